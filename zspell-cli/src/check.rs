@@ -0,0 +1,632 @@
+//! The `check` subcommand, which spellchecks files, and stdin as a live filter
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufRead, Write};
+use std::num::NonZeroUsize;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::{env, fs, thread};
+
+use anyhow::{bail, Context};
+use ignore::WalkBuilder;
+use zspell::{Dictionary, DocumentFilter, Session};
+
+use crate::cli::{CheckArgs, Cli, OutputFormat};
+use crate::config::Settings;
+use crate::diff::{ChangedLines, LineSet};
+use crate::filter::{filter_for_path, LineFilter};
+use crate::fix::{added_words_target, Fixer};
+use crate::output::{Found, Output};
+use crate::spelling::{load_dict, EXIT_DICTIONARY, EXIT_USAGE};
+
+/// Exit code when a file cannot be read or written, or added words cannot be
+/// saved. Results may be incomplete, so this wins over misspellings.
+const EXIT_IO: u8 = 4;
+
+/// The path that checks stdin instead of a file
+const STDIN_PATH: &str = "-";
+
+pub fn check_cli(cli: &Cli, args: &CheckArgs) -> ExitCode {
+    let settings = match Settings::new(cli) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{e:#}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let Some(dict_path) = settings.dict_path.as_deref() else {
+        eprintln!(
+            "Dictionary path not specified. Please specify with `-d /path/to/dic` or in a \
+            config file."
+        );
+        return ExitCode::from(EXIT_USAGE);
+    };
+
+    if args.summary && !matches!(settings.format, OutputFormat::Plain | OutputFormat::Json) {
+        eprintln!("`--summary` can only be printed in the plain or JSON format");
+        return ExitCode::from(EXIT_USAGE);
+    }
+
+    let read_stdin = args.paths.iter().any(|path| path == STDIN_PATH);
+    if read_stdin && args.fix {
+        eprintln!("`--fix` cannot be used with stdin ('{STDIN_PATH}')");
+        return ExitCode::from(EXIT_USAGE);
+    }
+    let paths: Vec<String> = args
+        .paths
+        .iter()
+        .filter(|path| *path != STDIN_PATH)
+        .cloned()
+        .collect();
+
+    let files = match collect_files(&paths, args) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{e:#}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let changed = match args
+        .diff_base
+        .as_deref()
+        .map(ChangedLines::from_git)
+        .transpose()
+    {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{e:#}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    // Files in different projects may use different project word lists, so keep
+    // one dictionary per word list, along with the words ignored with it. They
+    // are all loaded before checking starts.
+    let mut sessions: BTreeMap<Option<PathBuf>, Session> = BTreeMap::new();
+    let mut jobs = Vec::with_capacity(files.len());
+    for file in &files {
+        let lines = changed
+            .as_ref()
+            .map_or(LineSet::All, |changed| changed.for_file(file));
+        let dir = fs::canonicalize(file)
+            .ok()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+            .unwrap_or_default();
+        let words = settings.project_words(&dir);
+        if !lines.is_empty() {
+            if let Err(e) = session_for(&mut sessions, &words, dict_path, &settings) {
+                eprintln!("Error loading dictionary: {e:#}");
+                return ExitCode::from(EXIT_DICTIONARY);
+            }
+        }
+        jobs.push((file.as_path(), lines, words));
+    }
+
+    let mut total = 0;
+    let mut files_with_errors = 0;
+    let mut failed = false;
+
+    if args.fix {
+        let mut fixer = Fixer::new(&settings);
+        for (file, lines, words) in &jobs {
+            if lines.is_empty() {
+                continue;
+            }
+            let session = sessions.get_mut(words).unwrap();
+            let filter = filter_for_path(file, args.strings, &args.keys);
+            if let Err(e) = fixer.fix_file(file, session, &*filter, *lines) {
+                eprintln!("{e:#}");
+                failed = true;
+            }
+            if fixer.quit() {
+                break;
+            }
+        }
+
+        for session in sessions.values_mut() {
+            if let Err(e) = session.flush() {
+                eprintln!("unable to save added words: {e}");
+                failed = true;
+            }
+        }
+        eprintln!("fixed {} misspelling(s)", fixer.fixed());
+        return if failed {
+            ExitCode::from(EXIT_IO)
+        } else {
+            ExitCode::SUCCESS
+        };
+    }
+
+    let threads = args.jobs.map_or_else(
+        || thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        NonZeroUsize::get,
+    );
+    let mut progress = args.progress.then(|| ProgressLine::new(&files));
+    let mut output = Output::new(&settings, args.summary);
+    let suggestions = output.wants_suggestions();
+    let check = |(file, lines, words): &(&Path, LineSet, Option<PathBuf>)| {
+        if lines.is_empty() {
+            return Ok(FileReport::default());
+        }
+        let filter = filter_for_path(file, args.strings, &args.keys);
+        let dict = sessions[words].dictionary();
+        check_file(file, dict, &settings, &*filter, *lines, suggestions)
+    };
+    let mut write_error = None;
+    check_in_order(&jobs, threads, check, |(file, _, _), result| {
+        if let Some(progress) = progress.as_mut() {
+            progress.start_file();
+        }
+        match result {
+            Ok(report) => {
+                if !report.found.is_empty() {
+                    total += report.found.len();
+                    files_with_errors += 1;
+                    if let Some(progress) = progress.as_mut() {
+                        progress.clear();
+                    }
+                }
+                let display = file.display().to_string();
+                let mut stdout = io::stdout().lock();
+                if let Err(e) =
+                    output.report(&mut stdout, Some(&display), report.words, &report.found)
+                {
+                    write_error = Some(e);
+                    return ControlFlow::Break(());
+                }
+                if let Some(progress) = progress.as_mut() {
+                    progress.advance(report.bytes);
+                }
+            }
+            Err(e) => {
+                if let Some(progress) = progress.as_mut() {
+                    progress.clear();
+                }
+                eprintln!("{e:#}");
+                failed = true;
+            }
+        }
+        ControlFlow::Continue(())
+    });
+
+    if let Some(progress) = progress {
+        progress.finish();
+    }
+    if let Some(e) = write_error {
+        return write_failed(&e);
+    }
+
+    if read_stdin {
+        let words = settings.project_words(&env::current_dir().unwrap_or_default());
+        let session = match session_for(&mut sessions, &words, dict_path, &settings) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Error loading dictionary: {e:#}");
+                return ExitCode::from(EXIT_DICTIONARY);
+            }
+        };
+        match check_stdin(session.dictionary(), &settings, &mut output) {
+            Ok(0) => (),
+            Ok(count) => {
+                total += count;
+                files_with_errors += 1;
+            }
+            Err(e) => match e.downcast_ref::<io::Error>() {
+                Some(e) if e.kind() == io::ErrorKind::BrokenPipe => return write_failed(e),
+                _ => {
+                    eprintln!("{e:#}");
+                    failed = true;
+                }
+            },
+        }
+    }
+
+    if let Err(e) = output.finish(&mut io::stdout().lock()) {
+        return write_failed(&e);
+    }
+
+    eprintln!(
+        "found {total} misspelling(s) in {files_with_errors} of {} file(s)",
+        files.len() + usize::from(read_stdin)
+    );
+
+    if failed {
+        ExitCode::from(EXIT_IO)
+    } else if total > args.max_errors {
+        ExitCode::from(args.error_exit_code)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Report a failed write to stdout and return the exit code for it. Output
+/// closed early, as by `zspell check | head`, is not an error, so a broken pipe
+/// exits quietly.
+fn write_failed(e: &io::Error) -> ExitCode {
+    if e.kind() == io::ErrorKind::BrokenPipe {
+        return ExitCode::SUCCESS;
+    }
+    eprintln!("unable to write to stdout: {e}");
+    ExitCode::from(EXIT_IO)
+}
+
+/// Return the session for files that use the project word list `words`,
+/// loading its dictionary the first time
+fn session_for<'a>(
+    sessions: &'a mut BTreeMap<Option<PathBuf>, Session>,
+    words: &Option<PathBuf>,
+    dict_path: &Path,
+    settings: &Settings,
+) -> anyhow::Result<&'a mut Session> {
+    if !sessions.contains_key(words) {
+        let personal: Vec<&Path> = settings
+            .personal
+            .iter()
+            .chain(words.iter())
+            .map(PathBuf::as_path)
+            .collect();
+        let mut session = Session::new(load_dict(dict_path, &personal, &settings.skip)?);
+        if let Some(target) = added_words_target(settings, words.as_deref()) {
+            session = session.with_personal_file(target);
+        }
+        sessions.insert(words.clone(), session);
+    }
+    Ok(sessions.get_mut(words).unwrap())
+}
+
+/// Expand the paths given on the command line into a sorted list of files.
+///
+/// Files are used as-is, directories are searched recursively for files with
+/// one of the extensions in `args`, and anything else is treated as a glob
+/// pattern. Unless `args` says otherwise, the search skips hidden entries and
+/// those excluded by `.gitignore` or `.ignore` files.
+fn collect_files(paths: &[String], args: &CheckArgs) -> anyhow::Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+
+    for arg in paths {
+        let path = Path::new(arg);
+        if path.is_file() {
+            files.insert(path.to_path_buf());
+        } else if path.is_dir() {
+            let walk = WalkBuilder::new(path)
+                .hidden(!args.hidden)
+                .ignore(!args.no_ignore)
+                .git_ignore(!args.no_ignore)
+                .git_global(!args.no_ignore)
+                .git_exclude(!args.no_ignore)
+                .parents(!args.no_ignore)
+                .require_git(false)
+                // Even with `--hidden`, git's own files are never wanted
+                .filter_entry(|e| e.depth() == 0 || e.file_name() != ".git")
+                .build();
+            for entry in walk {
+                let entry = entry.with_context(|| format!("unable to read '{arg}'"))?;
+                let is_file = entry.file_type().is_some_and(|t| t.is_file());
+                if is_file && has_extension(entry.path(), &args.extensions) {
+                    files.insert(entry.into_path());
+                }
+            }
+        } else {
+            let matches =
+                glob::glob(arg).with_context(|| format!("invalid glob pattern '{arg}'"))?;
+            let before = files.len();
+            for entry in matches {
+                let entry = entry.with_context(|| format!("unable to read '{arg}'"))?;
+                if entry.is_file() {
+                    files.insert(entry);
+                }
+            }
+            if files.len() == before {
+                bail!("no files found matching '{arg}'");
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Misspellings found in one file by [`check_file`]
+#[derive(Debug, Default)]
+struct FileReport {
+    found: Vec<Found>,
+    /// The number of words checked
+    words: usize,
+    /// The size of the file
+    bytes: usize,
+}
+
+/// Check the given lines of a file, looking up suggestions for each
+/// misspelling if `suggestions` is set
+fn check_file(
+    path: &Path,
+    dict: &Dictionary,
+    settings: &Settings,
+    filter: &dyn DocumentFilter,
+    lines: LineSet,
+    suggestions: bool,
+) -> anyhow::Result<FileReport> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("unable to read '{}'", path.display()))?;
+    let mut filter = LineFilter::new(filter, &content);
+    let mut report = FileReport {
+        bytes: content.len(),
+        ..FileReport::default()
+    };
+    let mut line_start = 0;
+
+    for (line_idx, raw_line) in content.split_inclusive('\n').enumerate() {
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+        let words_before = filter.words();
+        let misspellings = filter.check(dict, line_start, line);
+        line_start += raw_line.len();
+        if !lines.contains(line_idx + 1) {
+            continue;
+        }
+        report.words += filter.words() - words_before;
+        for (idx, misspelled) in misspellings {
+            if settings.is_ignored(misspelled) {
+                continue;
+            }
+            let found = Found::new(line_idx + 1, line, idx, misspelled);
+            report.found.push(if suggestions {
+                found.with_suggestions(dict)
+            } else {
+                found
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Run `check` on each of `jobs` using up to `threads` threads, passing each job
+/// and its result to `report` in the order of `jobs` as they become available.
+/// No more jobs are started once `report` breaks.
+fn check_in_order<T, R>(
+    jobs: &[T],
+    threads: usize,
+    check: impl Fn(&T) -> R + Sync,
+    mut report: impl FnMut(&T, R) -> ControlFlow<()>,
+) where
+    T: Sync,
+    R: Send,
+{
+    let next_job = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..threads.min(jobs.len()) {
+            let tx = tx.clone();
+            let (next_job, check) = (&next_job, &check);
+            scope.spawn(move || loop {
+                let idx = next_job.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(idx) else {
+                    break;
+                };
+                if tx.send((idx, check(job))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        // Results that arrived before those of earlier jobs
+        let mut pending = BTreeMap::new();
+        let mut next_report = 0;
+        for (idx, result) in rx {
+            pending.insert(idx, result);
+            while let Some(result) = pending.remove(&next_report) {
+                if report(&jobs[next_report], result).is_break() {
+                    // Workers stop at their next job, or when sending to the
+                    // dropped receiver fails
+                    next_job.store(jobs.len(), Ordering::Relaxed);
+                    return;
+                }
+                next_report += 1;
+            }
+        }
+    });
+}
+
+/// Check lines from stdin as they arrive, printing the misspellings in each
+/// line before reading the next, so that `tail -f log | zspell check -` works
+/// as a live filter. Returns the number found.
+fn check_stdin(
+    dict: &Dictionary,
+    settings: &Settings,
+    output: &mut Output,
+) -> anyhow::Result<usize> {
+    let mut count = 0;
+    for (line_idx, line) in io::stdin().lock().lines().enumerate() {
+        let line = line.context("unable to read stdin")?;
+        let mut words = 0;
+        let found: Vec<Found> = dict
+            .check_indices_with_progress(&line, |p| words = p.words)
+            .filter(|(_, misspelled)| !settings.is_ignored(misspelled))
+            .map(|(idx, misspelled)| {
+                let found = Found::new(line_idx + 1, &line, idx, misspelled);
+                if output.wants_suggestions() {
+                    found.with_suggestions(dict)
+                } else {
+                    found
+                }
+            })
+            .collect();
+        count += found.len();
+        let mut stdout = io::stdout().lock();
+        output
+            .report(&mut stdout, None, words, &found)
+            .and_then(|()| stdout.flush())
+            .context("unable to write to stdout")?;
+    }
+    Ok(count)
+}
+
+/// Bytes checked between redraws of a [`ProgressLine`]
+const PROGRESS_INTERVAL: u64 = 64 * 1024;
+
+/// How far through its files `check` has got, redrawn in place on one line of
+/// stderr. Files are counted as their results are printed. Misspellings are
+/// printed to stdout, so the line is cleared before each file's misspellings
+/// and drawn again once more bytes have been checked.
+struct ProgressLine {
+    files: usize,
+    total_files: usize,
+    bytes: u64,
+    total_bytes: u64,
+    /// Bytes checked when the line was last drawn
+    drawn_at: u64,
+    /// Whether the line is currently shown
+    shown: bool,
+}
+
+impl ProgressLine {
+    fn new(files: &BTreeSet<PathBuf>) -> Self {
+        let total_bytes = files
+            .iter()
+            .filter_map(|file| fs::metadata(file).ok())
+            .map(|meta| meta.len())
+            .sum();
+        Self {
+            files: 0,
+            total_files: files.len(),
+            bytes: 0,
+            total_bytes,
+            drawn_at: 0,
+            shown: false,
+        }
+    }
+
+    fn start_file(&mut self) {
+        self.files += 1;
+        self.draw();
+    }
+
+    /// Count `bytes` more as checked, redrawing if enough have been
+    fn advance(&mut self, bytes: usize) {
+        self.bytes += bytes as u64;
+        if self.bytes - self.drawn_at >= PROGRESS_INTERVAL {
+            self.draw();
+        }
+    }
+
+    fn draw(&mut self) {
+        // Files may have changed since they were measured
+        let total_bytes = self.total_bytes.max(self.bytes);
+        let percent = (self.bytes * 100).checked_div(total_bytes).unwrap_or(100);
+        let mut stderr = io::stderr().lock();
+        _ = write!(
+            stderr,
+            "\r\x1b[Kchecking file {}/{}, {} of {} ({percent}%)",
+            self.files,
+            self.total_files,
+            format_bytes(self.bytes),
+            format_bytes(total_bytes),
+        );
+        _ = stderr.flush();
+        self.drawn_at = self.bytes;
+        self.shown = true;
+    }
+
+    fn clear(&mut self) {
+        if self.shown {
+            self.shown = false;
+            _ = write!(io::stderr(), "\r\x1b[K");
+        }
+    }
+
+    fn finish(mut self) {
+        self.clear();
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    const KIB: u64 = 1024;
+    const MIB: u64 = 1024 * KIB;
+    if bytes >= MIB {
+        format!("{:.1} MiB", bytes as f64 / MIB as f64)
+    } else if bytes >= KIB {
+        format!("{:.1} KiB", bytes as f64 / KIB as f64)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| extensions.iter().any(|x| x.eq_ignore_ascii_case(ext)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn check_in_order_keeps_order() {
+        // Later jobs finish first, but are reported in order
+        let jobs: Vec<u64> = (0..20).collect();
+        for threads in [1, 3, 64] {
+            let mut reported = Vec::new();
+            check_in_order(
+                &jobs,
+                threads,
+                |&job| {
+                    thread::sleep(Duration::from_millis(20 - job));
+                    job * 2
+                },
+                |_, result| {
+                    reported.push(result);
+                    ControlFlow::Continue(())
+                },
+            );
+            let expected: Vec<u64> = jobs.iter().map(|job| job * 2).collect();
+            assert_eq!(reported, expected);
+        }
+
+        let mut reported = Vec::new();
+        check_in_order(
+            &[] as &[u64],
+            4,
+            |&job| job,
+            |_, result| {
+                reported.push(result);
+                ControlFlow::Continue(())
+            },
+        );
+        assert!(reported.is_empty());
+    }
+
+    #[test]
+    fn check_in_order_stops_on_break() {
+        let jobs: Vec<u64> = (0..100).collect();
+        let checked = AtomicUsize::new(0);
+        let mut reported = Vec::new();
+        check_in_order(
+            &jobs,
+            2,
+            |&job| {
+                checked.fetch_add(1, Ordering::Relaxed);
+                thread::sleep(Duration::from_millis(5));
+                job
+            },
+            |_, result| {
+                reported.push(result);
+                if result == 2 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            },
+        );
+        assert_eq!(reported, [0, 1, 2]);
+        assert!(checked.into_inner() < jobs.len());
+    }
+}
@@ -1,6 +1,7 @@
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -9,8 +10,9 @@ pub struct Cli {
     pub file: Option<PathBuf>,
 
     /// Path to a dictionary file. Specify e.g. dictionaries/de_DE if
-    /// dictionaries/de_DE.aff and dictionaries/de_DE.dic exist
-    #[arg(short = 'd', long)]
+    /// dictionaries/de_DE.aff and dictionaries/de_DE.dic exist, or the path to a
+    /// LibreOffice `.oxt` extension or Mozilla `.xpi` add-on
+    #[arg(short = 'd', long, global = true)]
     pub dict_path: Option<String>,
 
     /// Whether to print misspelled words
@@ -41,6 +43,44 @@ pub struct Cli {
     #[arg(short = 't', long, default_value_t = false)]
     pub text_dictionary: bool,
 
+    /// Path to a personal dictionary, with one word per line
+    #[arg(short = 'p', long, global = true)]
+    pub personal: Option<PathBuf>,
+
+    /// Name of the project word list to look for in the current directory and
+    /// its parents (default `.zspell-words`). Pass an empty string to disable
+    #[arg(long, value_name = "NAME", global = true)]
+    pub words_file: Option<String>,
+
+    /// Do not report words that match this regular expression. May be given
+    /// more than once
+    #[arg(long, value_name = "REGEX", global = true)]
+    pub ignore: Vec<String>,
+
+    /// How to print misspelled words
+    #[arg(long, value_enum, global = true)]
+    pub format: Option<OutputFormat>,
+
+    /// Line to print for each misspelling with `--format template`, which this
+    /// implies. Placeholders are {file}, {line}, {col}, {end_col}, {word}, and
+    /// {suggestions}; use {{ and }} for braces and \t or \n for tabs and
+    /// newlines
+    #[arg(long, value_name = "TEMPLATE", global = true)]
+    pub template: Option<String>,
+
+    /// Read settings from this file instead of the user and project config files
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Do not read any config files
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with = "config",
+        global = true
+    )]
+    pub no_config: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -55,6 +95,25 @@ impl Cli {
     }
 }
 
+/// Output format for misspelled words
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One misspelled word per line
+    #[default]
+    Plain,
+    /// One JSON object per line, with the word and its line and column
+    Json,
+    /// A SARIF 2.1.0 log with a fix for each suggestion, for code scanning
+    /// tools. Only supported by `check`
+    Sarif,
+    /// GitHub Actions warning annotations with suggestions, so misspellings
+    /// are shown on pull request diffs. Only supported by `check`
+    Github,
+    /// One line per misspelling, following `--template`. Only supported by
+    /// `check`
+    Template,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Calculate levenshtein distance
@@ -69,4 +128,108 @@ pub enum Commands {
         #[arg(short, long, default_value_t = 1000)]
         limit: u32,
     },
+    /// Download a dictionary for a language and print the paths to its files
+    Fetch {
+        /// Language tag of the dictionary, e.g. en-US
+        lang: String,
+
+        /// URL of the index to locate dictionaries with
+        #[arg(long)]
+        index_url: Option<String>,
+
+        /// Directory to store dictionaries in, rather than the user cache
+        #[arg(long)]
+        cache_dir: Option<PathBuf>,
+
+        /// Download the dictionary even if it is already cached
+        #[arg(short, long, default_value_t = false)]
+        force: bool,
+    },
+    /// Check files, directories, and glob patterns, printing each misspelling
+    /// with its location
+    Check(CheckArgs),
+    /// Check an affix file and its word list for problems, printing each with
+    /// its line number
+    Lint(LintArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    /// Files, directories to search recursively, or glob patterns such as
+    /// 'docs/**/*.md'. Use '-' to check lines from stdin as they arrive, after
+    /// any files
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+
+    /// Also search hidden files and directories
+    #[arg(long, default_value_t = false)]
+    pub hidden: bool,
+
+    /// Also search files excluded by `.gitignore`, `.ignore`, and git's other
+    /// exclude files
+    #[arg(long, default_value_t = false)]
+    pub no_ignore: bool,
+
+    /// Extensions of files to check when searching directories
+    #[arg(
+        long = "ext",
+        value_name = "EXT",
+        value_delimiter = ',',
+        default_values_t = ["md", "markdown", "txt", "text", "rst", "adoc", "html", "htm", "tex"].map(String::from)
+    )]
+    pub extensions: Vec<String>,
+
+    /// Interactively choose a correction for each misspelling, rewriting
+    /// files in place
+    #[arg(long, default_value_t = false)]
+    pub fix: bool,
+
+    /// Only check lines that were added or changed since this git ref, e.g.
+    /// `origin/main`. Untracked files are checked in full
+    #[arg(long, value_name = "REF")]
+    pub diff_base: Option<String>,
+
+    /// Also check string literals in Rust files
+    #[arg(long, default_value_t = false)]
+    pub strings: bool,
+
+    /// Only check JSON and YAML values under keys matching this pattern, such
+    /// as 'title', '*_label', or 'menu.*'. Can be given more than once
+    #[arg(long = "key", value_name = "PATTERN")]
+    pub keys: Vec<String>,
+
+    /// Print totals instead of each misspelling: the number of words checked,
+    /// misspelled words ranked by how often they occur, and the files with the
+    /// most misspellings. Only the plain and JSON formats are supported
+    #[arg(long, default_value_t = false, conflicts_with = "fix")]
+    pub summary: bool,
+
+    /// Show how many files and bytes have been checked on stderr. Ignored with
+    /// `--fix`
+    #[arg(long, default_value_t = false)]
+    pub progress: bool,
+
+    /// Only fail if more than this many misspellings are found
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub max_errors: usize,
+
+    /// Exit code when more than `--max-errors` misspellings are found. Other
+    /// failures exit with 2 for invalid arguments, settings, or paths, 3 if the
+    /// dictionary cannot be loaded, and 4 if a file cannot be read or written
+    #[arg(long, value_name = "CODE", default_value_t = 1)]
+    pub error_exit_code: u8,
+
+    /// Number of files to check at once. Defaults to the number of CPUs.
+    /// Output is always in the same order. Ignored with `--fix`
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<NonZeroUsize>,
+}
+
+#[derive(Args, Debug)]
+pub struct LintArgs {
+    /// Path to the affix file, usually ending in `.aff`
+    pub aff: PathBuf,
+
+    /// Path to the word list, usually ending in `.dic`
+    pub dic: PathBuf,
 }
@@ -0,0 +1,287 @@
+//! Configuration files that provide defaults for command line options
+//!
+//! Settings are read from the user's config file (`~/.config/zspell/config.toml`
+//! on Linux) and then from the nearest `zspell.toml` in the current directory or
+//! its parents, with the project file taking precedence. Command line options
+//! override both.
+//!
+//! ```toml
+//! # Used if `-d` is not given. Relative paths are relative to the config file
+//! dictionary = "dictionaries/en_US"
+//! personal = "~/.hunspell_en_US"
+//! # Words matching any of these regular expressions are never reported
+//! ignore = ["^[A-Z0-9]+$", "^https?://"]
+//! # Text matching these regular expressions or literal strings is skipped before
+//! # it is split into words, so patterns may span hyphens and other separators
+//! skip = ['\b[A-Z]+-\d+\b', '\b[0-9a-f]{8}(-[0-9a-f]{4}){3}-[0-9a-f]{12}\b']
+//! skip_literal = ["wasm-bindgen"]
+//! format = "json"
+//! # Used with format = "template", in the same format as `--template`
+//! template = "{file}:{line}:{col}: {word} -> {suggestions}"
+//! # Name of the project word list (see below)
+//! words_file = ".zspell-words"
+//! ```
+//!
+//! A project can also keep a word list, `.zspell-words` by default, in its root
+//! directory. The nearest one to the current directory is merged into the
+//! personal dictionary, so that a team can share accepted jargon in version
+//! control.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::cli::{Cli, OutputFormat};
+use crate::output::Template;
+
+/// Name of the project-local configuration file
+pub const PROJECT_CONFIG_NAME: &str = "zspell.toml";
+/// Default name of the project word list
+pub const PROJECT_WORDS_NAME: &str = ".zspell-words";
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Dictionary path, in the same format as `-d`
+    pub dictionary: Option<PathBuf>,
+    /// Personal dictionary path
+    pub personal: Option<PathBuf>,
+    /// Regular expressions for words that should not be checked
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Regular expressions for text that should not be checked
+    #[serde(default)]
+    pub skip: Vec<String>,
+    /// Literal text that should not be checked
+    #[serde(default)]
+    pub skip_literal: Vec<String>,
+    /// Output format name, parsed the same way as `--format`
+    format: Option<String>,
+    /// Template for the `template` output format
+    pub template: Option<String>,
+    /// File name of the project word list
+    pub words_file: Option<String>,
+}
+
+impl Config {
+    /// Load the user and project config files, or only `explicit` if it is given
+    pub fn load(explicit: Option<&Path>) -> anyhow::Result<Self> {
+        if let Some(path) = explicit {
+            return Self::from_file(path);
+        }
+
+        let mut cfg = Self::default();
+        if let Some(path) = user_config_path().filter(|p| p.is_file()) {
+            cfg.merge(Self::from_file(&path)?);
+        }
+        let cwd = std::env::current_dir()?;
+        if let Some(path) = find_in_ancestors(&cwd, PROJECT_CONFIG_NAME) {
+            cfg.merge(Self::from_file(&path)?);
+        }
+        Ok(cfg)
+    }
+
+    /// Parse a single file, resolving paths relative to its directory
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("unable to read config file '{}'", path.display()))?;
+        let mut cfg: Self = toml::from_str(&content)
+            .with_context(|| format!("invalid config file '{}'", path.display()))?;
+
+        if let Some(fmt) = &cfg.format {
+            OutputFormat::from_str(fmt, true).map_err(|e| {
+                anyhow::anyhow!("invalid format in config file '{}': {e}", path.display())
+            })?;
+        }
+
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        cfg.dictionary = cfg.dictionary.map(|p| resolve_path(base, &p));
+        cfg.personal = cfg.personal.map(|p| resolve_path(base, &p));
+        Ok(cfg)
+    }
+
+    /// The configured output format, if any
+    pub fn format(&self) -> Option<OutputFormat> {
+        // Validated when loading
+        self.format
+            .as_deref()
+            .map(|fmt| OutputFormat::from_str(fmt, true).unwrap())
+    }
+
+    /// Apply settings from `other` on top of this config. Ignore and skip
+    /// patterns from both are kept.
+    fn merge(&mut self, other: Self) {
+        self.dictionary = other.dictionary.or(self.dictionary.take());
+        self.personal = other.personal.or(self.personal.take());
+        self.ignore.extend(other.ignore);
+        self.skip.extend(other.skip);
+        self.skip_literal.extend(other.skip_literal);
+        self.format = other.format.or(self.format.take());
+        self.template = other.template.or(self.template.take());
+        self.words_file = other.words_file.or(self.words_file.take());
+    }
+}
+
+/// Options resolved from the command line and config files
+#[derive(Debug)]
+pub struct Settings {
+    pub dict_path: Option<PathBuf>,
+    pub personal: Option<PathBuf>,
+    pub ignore: Vec<Regex>,
+    /// Regular expressions for text that the dictionary skips, with literals
+    /// already escaped
+    pub skip: Vec<String>,
+    pub format: OutputFormat,
+    /// Parsed template for [`OutputFormat::Template`]
+    pub template: Option<Template>,
+    /// File name of project word lists. An empty name disables them.
+    pub words_file: String,
+}
+
+impl Settings {
+    pub fn new(cli: &Cli) -> anyhow::Result<Self> {
+        let cfg = if cli.no_config {
+            Config::default()
+        } else {
+            Config::load(cli.config.as_deref())?
+        };
+
+        let ignore = cfg
+            .ignore
+            .iter()
+            .chain(cli.ignore.iter())
+            .map(|pat| Regex::new(pat).with_context(|| format!("invalid ignore pattern '{pat}'")))
+            .collect::<anyhow::Result<_>>()?;
+
+        let literals = cfg.skip_literal.iter().map(|lit| regex::escape(lit));
+        let skip = cfg.skip.iter().cloned().chain(literals).collect::<Vec<_>>();
+        for pat in &skip {
+            Regex::new(pat).with_context(|| format!("invalid skip pattern '{pat}'"))?;
+        }
+
+        // A template on the command line selects the template format, unless
+        // another format is given there too
+        let format = cli
+            .format
+            .or_else(|| cli.template.as_ref().map(|_| OutputFormat::Template))
+            .or_else(|| cfg.format())
+            .unwrap_or_default();
+        let template = cli
+            .template
+            .as_deref()
+            .or(cfg.template.as_deref())
+            .map(Template::parse)
+            .transpose()?;
+        if format == OutputFormat::Template && template.is_none() {
+            bail!("the template format needs `--template` or `template` in a config file");
+        }
+
+        Ok(Self {
+            format,
+            template,
+            dict_path: cli.dict_path.as_ref().map(PathBuf::from).or(cfg.dictionary),
+            personal: cli.personal.clone().or(cfg.personal),
+            words_file: cli
+                .words_file
+                .clone()
+                .or(cfg.words_file)
+                .unwrap_or_else(|| PROJECT_WORDS_NAME.to_owned()),
+            ignore,
+            skip,
+        })
+    }
+
+    /// Locate the project word list that applies to files in `dir`
+    pub fn project_words(&self, dir: &Path) -> Option<PathBuf> {
+        if self.words_file.is_empty() {
+            return None;
+        }
+        find_in_ancestors(dir, &self.words_file)
+    }
+
+    /// Whether `word` matches any ignore pattern
+    pub fn is_ignored(&self, word: &str) -> bool {
+        self.ignore.iter().any(|re| re.is_match(word))
+    }
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("zspell").join("config.toml"))
+}
+
+/// Find the nearest file named `name` in `dir` or any of its parents
+fn find_in_ancestors(dir: &Path, name: &str) -> Option<PathBuf> {
+    dir.ancestors().map(|d| d.join(name)).find(|p| p.is_file())
+}
+
+/// Expand `~` and make relative paths relative to `base`
+fn resolve_path(base: &Path, path: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    base.join(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn load_and_merge() {
+        let dir = tempdir().unwrap();
+        let user = dir.path().join("user.toml");
+        let project = dir.path().join("project").join(PROJECT_CONFIG_NAME);
+        fs::create_dir(project.parent().unwrap()).unwrap();
+        fs::write(
+            &user,
+            "dictionary = \"/usr/share/hunspell/en_US\"\npersonal = \"words.txt\"\nignore = [\"^[A-Z]+$\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            &project,
+            "dictionary = \"dicts/en_GB\"\nignore = [\"^x\"]\nformat = \"JSON\"\nskip = [\"a-b\"]\n",
+        )
+        .unwrap();
+
+        let mut cfg = Config::from_file(&user).unwrap();
+        assert_eq!(cfg.personal, Some(dir.path().join("words.txt")));
+        assert_eq!(cfg.format(), None);
+
+        cfg.merge(Config::from_file(&project).unwrap());
+        assert_eq!(
+            cfg.dictionary,
+            Some(dir.path().join("project").join("dicts/en_GB"))
+        );
+        assert_eq!(cfg.personal, Some(dir.path().join("words.txt")));
+        assert_eq!(cfg.ignore, ["^[A-Z]+$", "^x"]);
+        assert_eq!(cfg.skip, ["a-b"]);
+        assert_eq!(cfg.format(), Some(OutputFormat::Json));
+        assert_eq!(cfg.template, None);
+
+        assert_eq!(
+            find_in_ancestors(&dir.path().join("project").join("src"), PROJECT_CONFIG_NAME),
+            Some(project)
+        );
+    }
+
+    #[test]
+    fn invalid_config() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(PROJECT_CONFIG_NAME);
+
+        fs::write(&path, "dictionary = 1").unwrap();
+        assert!(Config::from_file(&path).is_err());
+        fs::write(&path, "dictionaries = []").unwrap();
+        assert!(Config::from_file(&path).is_err());
+        fs::write(&path, "format = \"xml\"").unwrap();
+        assert!(Config::from_file(&path).is_err());
+    }
+}
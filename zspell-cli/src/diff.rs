@@ -0,0 +1,174 @@
+//! Support for `zspell check --diff-base`, which only reports misspellings on
+//! lines that changed relative to a git ref
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context};
+
+/// Lines added or modified in the working tree since a base ref
+#[derive(Debug)]
+pub struct ChangedLines {
+    /// Changed line ranges (1-based, end exclusive) per file, with absolute paths
+    files: BTreeMap<PathBuf, Vec<Range<usize>>>,
+    /// Files that git does not track, which are new in their entirety
+    untracked: BTreeSet<PathBuf>,
+}
+
+/// The lines of a single file that should be checked
+#[derive(Clone, Copy, Debug)]
+pub enum LineSet<'a> {
+    All,
+    Ranges(&'a [Range<usize>]),
+}
+
+impl ChangedLines {
+    /// Ask git for the changes between `base` and the working tree of the
+    /// repository containing the current directory
+    pub fn from_git(base: &str) -> anyhow::Result<Self> {
+        let root = git(Path::new("."), &["rev-parse", "--show-toplevel"])?;
+        let root = fs::canonicalize(root.trim())
+            .with_context(|| format!("unable to locate git repository '{}'", root.trim()))?;
+        let diff = git(
+            &root,
+            &[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+                base,
+                "--",
+            ],
+        )?;
+        let untracked = git(&root, &["ls-files", "--others", "--exclude-standard"])?;
+
+        let mut ret = Self::parse_diff(&root, &diff);
+        ret.untracked = untracked.lines().map(|p| root.join(p)).collect();
+        Ok(ret)
+    }
+
+    /// Parse unified diff output with no context lines
+    fn parse_diff(root: &Path, diff: &str) -> Self {
+        let mut files: BTreeMap<PathBuf, Vec<Range<usize>>> = BTreeMap::new();
+        let mut current = None;
+
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                // Deleted files have no new side
+                current = path.strip_prefix("b/").map(|p| root.join(p));
+            } else if let Some(hunk) = line.strip_prefix("@@ ") {
+                let Some(path) = &current else { continue };
+                // Format: `@@ -old_start[,old_count] +new_start[,new_count] @@`
+                let Some(new) = hunk.split(' ').find_map(|s| s.strip_prefix('+')) else {
+                    continue;
+                };
+                let (start, count) = match new.split_once(',') {
+                    Some((start, count)) => (start.parse(), count.parse()),
+                    None => (new.parse(), Ok(1)),
+                };
+                if let (Ok(start), Ok(count)) = (start, count) {
+                    if count > 0 {
+                        files
+                            .entry(path.clone())
+                            .or_default()
+                            .push(start..start + count);
+                    }
+                }
+            }
+        }
+
+        Self {
+            files,
+            untracked: BTreeSet::new(),
+        }
+    }
+
+    /// The changed lines of `path`, which may be relative to the current
+    /// directory
+    pub fn for_file(&self, path: &Path) -> LineSet<'_> {
+        let Ok(path) = fs::canonicalize(path) else {
+            return LineSet::Ranges(&[]);
+        };
+        if self.untracked.contains(&path) {
+            return LineSet::All;
+        }
+        LineSet::Ranges(self.files.get(&path).map_or(&[], Vec::as_slice))
+    }
+}
+
+impl LineSet<'_> {
+    /// Whether the 1-based line `line` should be checked
+    pub fn contains(&self, line: usize) -> bool {
+        match self {
+            Self::All => true,
+            Self::Ranges(ranges) => ranges.iter().any(|r| r.contains(&line)),
+        }
+    }
+
+    /// Whether no lines should be checked
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Ranges(ranges) if ranges.is_empty())
+    }
+}
+
+/// Run git in `dir` and return its stdout
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .context("unable to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).context("git produced invalid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_diff() {
+        let diff = "\
+diff --git a/README.md b/README.md
+index 1111111..2222222 100644
+--- a/README.md
++++ b/README.md
+@@ -3 +3 @@ Title
+-old line
++new line
+@@ -10,0 +11,2 @@
++added
++lines
+@@ -20,2 +21,0 @@
+-removed
+-lines
+diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+        let root = Path::new("/repo");
+        let changed = ChangedLines::parse_diff(root, diff);
+        assert_eq!(
+            changed.files,
+            BTreeMap::from([(root.join("README.md"), vec![3..4, 11..13])])
+        );
+
+        let lines = LineSet::Ranges(&changed.files[&root.join("README.md")]);
+        let checked: Vec<usize> = (1..25).filter(|&n| lines.contains(n)).collect();
+        assert_eq!(checked, [3, 11, 12]);
+    }
+}
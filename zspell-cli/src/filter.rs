@@ -0,0 +1,523 @@
+//! Per-file-type filters that select the text that should be spellchecked, such
+//! as prose outside of code blocks in Markdown
+//!
+//! Filters come from [`zspell::filter`], plus one for Rust here. They return
+//! byte ranges into the whole file, which [`LineFilter`] splits by line so
+//! offsets into the checked text map directly back to the original line.
+
+use std::ops::Range;
+use std::path::Path;
+
+use zspell::filter::{self, DocumentFilter, Html, Json, Markdown, Plain, Yaml};
+use zspell::Dictionary;
+
+/// Keywords and common standard library names that are not checked in Rust
+/// identifiers
+const RUST_SKIP_IDENTS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "union", "bool", "char", "str", "usize", "isize", "std",
+    "core", "alloc", "Vec", "Ok", "Err", "Rc", "Arc", "Cow", "Fn", "FnMut", "FnOnce", "fmt", "io",
+];
+
+/// Pick the filter for a file from its extension. Files with an unknown
+/// extension are checked in full. `strings` also checks string literals in Rust,
+/// and `keys` limits the values checked in JSON and YAML.
+pub fn filter_for_path(path: &Path, strings: bool, keys: &[String]) -> Box<dyn DocumentFilter> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "rs" => Box::new(Rust { strings }),
+        "json" => Box::new(Json::new().with_keys(keys)),
+        "yaml" | "yml" => Box::new(Yaml::new().with_keys(keys)),
+        _ => filter::for_extension(&ext).unwrap_or_else(|| Box::new(Plain)),
+    }
+}
+
+/// The ranges a filter selected from a file, handed out line by line
+#[derive(Debug)]
+pub struct LineFilter {
+    ranges: Vec<Range<usize>>,
+    /// The first range that does not end before the current line
+    next: usize,
+    /// Words checked so far
+    words: usize,
+}
+
+impl LineFilter {
+    /// Run `filter` over the whole of `text`
+    pub fn new(filter: &dyn DocumentFilter, text: &str) -> Self {
+        Self {
+            ranges: filter.ranges(text),
+            next: 0,
+            words: 0,
+        }
+    }
+
+    /// Return the byte ranges of `line` that should be checked, given where the
+    /// line starts in the file. Lines must be passed in order.
+    pub fn filter(&mut self, line_start: usize, line: &str) -> Vec<Range<usize>> {
+        let line_end = line_start + line.len();
+        while self
+            .ranges
+            .get(self.next)
+            .is_some_and(|r| r.end <= line_start)
+        {
+            self.next += 1;
+        }
+
+        let mut out = Vec::new();
+        for range in self.ranges[self.next..]
+            .iter()
+            .take_while(|r| r.start < line_end)
+        {
+            push_range(
+                &mut out,
+                range.start.max(line_start) - line_start..range.end.min(line_end) - line_start,
+            );
+        }
+        out
+    }
+
+    /// Filter `line` and check the selected text, returning misspelled words
+    /// with their byte offset in `line`
+    pub fn check<'a>(
+        &mut self,
+        dict: &Dictionary,
+        line_start: usize,
+        line: &'a str,
+    ) -> Vec<(usize, &'a str)> {
+        let mut misspelled = Vec::new();
+        for range in self.filter(line_start, line) {
+            let mut words = 0;
+            let start = range.start;
+            misspelled.extend(
+                dict.check_indices_with_progress(&line[range], |p| words = p.words)
+                    .map(|(idx, word)| (start + idx, word)),
+            );
+            self.words += words;
+        }
+        misspelled
+    }
+
+    /// The number of words checked so far, correct or not
+    pub fn words(&self) -> usize {
+        self.words
+    }
+}
+
+/// Checks doc comments and identifiers, and optionally string literals. Doc
+/// comments are filtered as Markdown.
+#[derive(Debug)]
+struct Rust {
+    strings: bool,
+}
+
+impl DocumentFilter for Rust {
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut lexer = RustLexer {
+            strings: self.strings,
+            rust: RustState::Code,
+            docs: Vec::new(),
+        };
+        let mut ranges = Vec::new();
+        let mut docs = Vec::new();
+        let mut offset = 0;
+
+        for raw_line in text.split_inclusive('\n') {
+            let line = raw_line.trim_end_matches(['\n', '\r']);
+            let mut out = Vec::new();
+            lexer.filter_line(line, &mut out);
+            ranges.extend(out.into_iter().map(|r| offset + r.start..offset + r.end));
+            docs.extend(
+                lexer
+                    .docs
+                    .drain(..)
+                    .map(|r| offset + r.start..offset + r.end),
+            );
+            offset += raw_line.len();
+        }
+
+        ranges.extend(Markdown.then(Html).ranges_within(text, &docs));
+        ranges.sort_by_key(|r| r.start);
+        ranges
+    }
+}
+
+/// Lexes Rust line by line, keeping track of comments and strings that span
+/// lines
+#[derive(Debug)]
+struct RustLexer {
+    /// Whether to check string literals
+    strings: bool,
+    rust: RustState,
+    /// Ranges of doc comments in the current line
+    docs: Vec<Range<usize>>,
+}
+
+/// Rust lexer state that carries between lines
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum RustState {
+    #[default]
+    Code,
+    /// Inside a possibly nested block comment
+    Comment { depth: usize, doc: bool },
+    /// Inside a string literal. `hashes` is set for raw strings.
+    Str { hashes: Option<usize> },
+}
+
+impl RustLexer {
+    /// Add the code ranges of `line` that should be checked to `out`, and its
+    /// doc comments to `self.docs`
+    fn filter_line(&mut self, line: &str, out: &mut Vec<Range<usize>>) {
+        // Attributes are mostly names that are not words, like `cfg`
+        if self.rust == RustState::Code && line.trim_start().starts_with('#') {
+            return;
+        }
+
+        let mut i = 0;
+        while i < line.len() {
+            let rest = &line[i..];
+            match self.rust {
+                RustState::Comment { depth, doc } => {
+                    let open = rest.find("/*");
+                    let close = rest.find("*/");
+                    let end = match (open, close) {
+                        (Some(o), c) if c.map_or(true, |c| o < c) => {
+                            self.rust = RustState::Comment {
+                                depth: depth + 1,
+                                doc,
+                            };
+                            o
+                        }
+                        (_, Some(c)) => {
+                            self.rust = if depth == 1 {
+                                RustState::Code
+                            } else {
+                                RustState::Comment {
+                                    depth: depth - 1,
+                                    doc,
+                                }
+                            };
+                            c
+                        }
+                        _ => rest.len(),
+                    };
+                    if doc {
+                        push_range(&mut self.docs, i..i + end);
+                    }
+                    i += (end + 2).min(rest.len());
+                }
+                RustState::Str { hashes } => {
+                    i = self.rust_string(line, i, hashes, out);
+                }
+                RustState::Code => i = self.rust_code(line, i, out),
+            }
+        }
+    }
+
+    /// Lex code starting at `start`, returning the index to continue from
+    fn rust_code(&mut self, line: &str, start: usize, out: &mut Vec<Range<usize>>) -> usize {
+        let rest = &line[start..];
+        let Some(c) = rest.chars().next() else {
+            return start;
+        };
+
+        if rest.starts_with("//") {
+            let doc =
+                (rest.starts_with("///") && !rest.starts_with("////")) || rest.starts_with("//!");
+            if doc {
+                push_range(&mut self.docs, start + 3..line.len());
+            }
+            line.len()
+        } else if rest.starts_with("/*") {
+            let doc = (rest.starts_with("/**") && !rest.starts_with("/***"))
+                && !rest.starts_with("/**/")
+                || rest.starts_with("/*!");
+            self.rust = RustState::Comment { depth: 1, doc };
+            start + if doc { 3 } else { 2 }
+        } else if c == '"' {
+            self.rust = RustState::Str { hashes: None };
+            start + 1
+        } else if c == '\'' {
+            skip_char_or_lifetime(rest) + start
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+                .unwrap_or(rest.len());
+            let ident = &rest[..len];
+            let after = &rest[len..];
+            let hashes = after.len() - after.trim_start_matches('#').len();
+
+            if matches!(ident, "b" | "c") && after.starts_with('"') {
+                self.rust = RustState::Str { hashes: None };
+                start + len + 1
+            } else if matches!(ident, "r" | "br" | "cr") && after[hashes..].starts_with('"') {
+                self.rust = RustState::Str {
+                    hashes: Some(hashes),
+                };
+                start + len + hashes + 1
+            } else {
+                identifier_ranges(ident, start, out);
+                start + len
+            }
+        } else if c.is_ascii_digit() {
+            // Skip numbers along with suffixes like `1u8` or `0xff`
+            start
+                + rest
+                    .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '.'))
+                    .unwrap_or(rest.len())
+        } else {
+            start + c.len_utf8()
+        }
+    }
+
+    /// Lex a string literal starting at `start`, returning the index to continue
+    /// from
+    fn rust_string(
+        &mut self,
+        line: &str,
+        start: usize,
+        hashes: Option<usize>,
+        out: &mut Vec<Range<usize>>,
+    ) -> usize {
+        let mut out_tmp = Vec::new();
+        let end = if let Some(hashes) = hashes {
+            let terminator = format!("\"{}", "#".repeat(hashes));
+            line[start..].find(&terminator).map(|pos| {
+                push_range(&mut out_tmp, start..start + pos);
+                start + pos + terminator.len()
+            })
+        } else {
+            let mut seg_start = start;
+            let mut chars = line[start..].char_indices().map(|(i, c)| (i + start, c));
+            let mut end = None;
+            while let Some((pos, c)) = chars.next() {
+                if c == '"' {
+                    push_range(&mut out_tmp, seg_start..pos);
+                    end = Some(pos + 1);
+                    break;
+                } else if c == '\\' {
+                    push_range(&mut out_tmp, seg_start..pos);
+                    // Skip the escaped character, or all of `\u{...}`
+                    seg_start = match chars.next() {
+                        Some((_, 'u')) => line[pos..].find('}').map_or(line.len(), |p| pos + p + 1),
+                        Some((esc, ch)) => esc + ch.len_utf8(),
+                        None => line.len(),
+                    };
+                    while chars.clone().next().is_some_and(|(p, _)| p < seg_start) {
+                        chars.next();
+                    }
+                }
+            }
+            if end.is_none() {
+                push_range(&mut out_tmp, seg_start..line.len());
+            }
+            end
+        };
+
+        if end.is_some() {
+            self.rust = RustState::Code;
+        } else if hashes.is_some() {
+            push_range(&mut out_tmp, start..line.len());
+        }
+        if self.strings {
+            out.extend(out_tmp);
+        }
+        end.unwrap_or(line.len())
+    }
+}
+
+/// Skip a char literal like `'a'` or `'\n'`, or a lifetime like `'a`. Returns
+/// the length skipped.
+fn skip_char_or_lifetime(rest: &str) -> usize {
+    let mut chars = rest.char_indices().skip(1);
+    match chars.next() {
+        Some((_, '\\')) => rest[2..].find('\'').map_or(rest.len(), |p| p + 3),
+        Some((i, c)) if rest[i + c.len_utf8()..].starts_with('\'') => i + c.len_utf8() + 1,
+        Some(_) => rest[1..]
+            .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+            .map_or(rest.len(), |p| p + 1),
+        None => 1,
+    }
+}
+
+/// Split an identifier like `parse_HTTPHeader` into its words, adding their
+/// ranges to `out`. Keywords, single letters, and parts containing digits are
+/// skipped.
+fn identifier_ranges(ident: &str, offset: usize, out: &mut Vec<Range<usize>>) {
+    if RUST_SKIP_IDENTS.contains(&ident) {
+        return;
+    }
+
+    let chars: Vec<(usize, char)> = ident.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start = None;
+    for (n, &(i, c)) in chars.iter().enumerate() {
+        if c == '_' {
+            if let Some(s) = start.take() {
+                parts.push(s..i);
+            }
+            continue;
+        }
+        let Some(s) = start else {
+            start = Some(i);
+            continue;
+        };
+        let prev = chars[n - 1].1;
+        let next_lower = chars.get(n + 1).is_some_and(|&(_, nx)| nx.is_lowercase());
+        if c.is_uppercase() && (prev.is_lowercase() || prev.is_numeric() || next_lower) {
+            parts.push(s..i);
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        parts.push(s..ident.len());
+    }
+
+    for part in parts {
+        let word = &ident[part.clone()];
+        if word.chars().nth(1).is_some() && !word.chars().any(|c| c.is_numeric()) {
+            out.push(offset + part.start..offset + part.end);
+        }
+    }
+}
+
+fn push_range(out: &mut Vec<Range<usize>>, range: Range<usize>) {
+    if !range.is_empty() {
+        out.push(range);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run the filter for `path` over lines and return the selected text of each
+    fn apply(path: &str, strings: bool, input: &[&str]) -> Vec<Vec<String>> {
+        let text = input.join("\n");
+        let mut filter = LineFilter::new(&*filter_for_path(Path::new(path), strings, &[]), &text);
+        let mut line_start = 0;
+        input
+            .iter()
+            .map(|line| {
+                let selected = filter
+                    .filter(line_start, line)
+                    .into_iter()
+                    .map(|r| line[r].to_owned())
+                    .collect();
+                line_start += line.len() + 1;
+                selected
+            })
+            .collect()
+    }
+
+    #[test]
+    fn markdown_filter() {
+        let input = [
+            "Some `inline code` and <b>tags</b>",
+            "See https://exmaple.com/pth or [a lnk](http://x.y/z).",
+            "```rust",
+            "let misspeled = 1;",
+            "``",
+            "```",
+            "~~~~",
+            "```",
+            "~~~~~",
+            "back to txt",
+        ];
+        let output = apply("a.md", false, &input);
+
+        let expected: [&[&str]; 10] = [
+            &["Some ", " and ", "tags"],
+            &["See ", " or [a lnk](", ")."],
+            &[],
+            &[],
+            &[],
+            &[],
+            &[],
+            &[],
+            &[],
+            &["back to txt"],
+        ];
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn rust_filter() {
+        let input = [
+            "//! Crate `docs` here",
+            "#[derive(Debug)]",
+            "pub struct HTTPServerConfig<'a> {",
+            "    max_conections: u32, // plain comment",
+            "    name: &'a str,",
+            "}",
+            "/// Make a `Foo`:",
+            "/// ```",
+            "/// let wrongg = 1;",
+            "/// ```",
+            r#"fn greet(c: char) -> String { let s = "helo\nwrld"; format!("{s}{}", 'x') }"#,
+            r##"let raw = r#"raw "quoted" strng"#; /* block */ let b = b"byts";"##,
+            "/** Doc block",
+            "  spans lines */",
+            r#"let multi = "first"#,
+            r#"  secnd";"#,
+        ];
+
+        let expected: [&[&str]; 16] = [
+            &[" Crate ", " here"],
+            &[],
+            &["HTTP", "Server", "Config"],
+            &["max", "conections"],
+            &["name"],
+            &[],
+            &[" Make a ", ":"],
+            &[],
+            &[],
+            &[],
+            &["greet", "String", "format"],
+            &["raw"],
+            &[" Doc block"],
+            &["  spans lines "],
+            &["multi"],
+            &[],
+        ];
+        let output = apply("a.rs", false, &input);
+        assert_eq!(output, expected);
+
+        let output = apply("a.rs", true, &input);
+        assert_eq!(
+            output[10],
+            ["greet", "String", "helo", "wrld", "format", "{s}{}"]
+        );
+        assert_eq!(output[11], ["raw", "raw \"quoted\" strng", "byts"]);
+        assert_eq!(output[14], ["multi", "first"]);
+        assert_eq!(output[15], ["  secnd"]);
+    }
+
+    #[test]
+    fn file_types() {
+        let debug = |path: &str| format!("{:?}", filter_for_path(Path::new(path), false, &[]));
+        assert_eq!(
+            debug("a/README.MD"),
+            "Then { outer: Markdown, inner: Html }"
+        );
+        assert_eq!(debug("src/lib.rs"), "Rust { strings: false }");
+        assert_eq!(debug("paper.tex"), "Latex");
+        assert_eq!(debug("en.JSON"), "Json { keys: KeyPatterns([]) }");
+        assert_eq!(debug("notes.txt"), "Plain");
+        assert_eq!(debug("LICENSE"), "Plain");
+    }
+
+    #[test]
+    fn line_filter() {
+        // The one range from `Plain` is split between lines
+        let output = apply("a.txt", false, &["one two", "three", "", "four"]);
+        let expected: [&[&str]; 4] = [&["one two"], &["three"], &[], &["four"]];
+        assert_eq!(output, expected);
+    }
+}
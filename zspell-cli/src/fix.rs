@@ -0,0 +1,219 @@
+//! Interactive correction of misspellings for `zspell check --fix`
+//!
+//! Each misspelling is shown in context along with numbered suggestions, and
+//! the user can pick a replacement, ignore the word, or add it to a word list.
+//! Prompts are written to stderr and answers read from stdin, one per line.
+
+use std::borrow::Cow;
+use std::fs;
+use std::io::{self, Lines, StdinLock, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use zspell::{DocumentFilter, Session};
+
+use crate::config::Settings;
+use crate::diff::LineSet;
+use crate::filter::LineFilter;
+
+/// Maximum number of suggestions offered for a word
+const MAX_SUGGESTIONS: usize = 9;
+
+/// What to do with a misspelled word
+enum Action {
+    Keep,
+    Replace(String),
+}
+
+/// Keeps state across files for an interactive session
+pub struct Fixer<'s> {
+    settings: &'s Settings,
+    input: Lines<StdinLock<'static>>,
+    /// Set when the user asks to stop
+    quit: bool,
+    /// Number of words replaced so far
+    fixed: usize,
+}
+
+impl<'s> Fixer<'s> {
+    pub fn new(settings: &'s Settings) -> Self {
+        Self {
+            settings,
+            input: io::stdin().lines(),
+            quit: false,
+            fixed: 0,
+        }
+    }
+
+    /// Whether the user has asked to stop
+    pub fn quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Number of words replaced so far
+    pub fn fixed(&self) -> usize {
+        self.fixed
+    }
+
+    /// Walk through the misspellings on the given lines of a file and rewrite it
+    /// with any replacements. Words ignored with "ignore all" are kept in
+    /// `session`, which also saves added words to its personal word list.
+    pub fn fix_file(
+        &mut self,
+        path: &Path,
+        session: &mut Session,
+        filter: &dyn DocumentFilter,
+        lines: LineSet,
+    ) -> anyhow::Result<()> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("unable to read '{}'", path.display()))?;
+        let display = path.display().to_string();
+        let mut filter = LineFilter::new(filter, &content);
+        let mut output = String::with_capacity(content.len());
+        let fixed_before = self.fixed;
+        let mut line_start = 0;
+
+        for (line_idx, raw_line) in content.split_inclusive('\n').enumerate() {
+            let line = raw_line.trim_end_matches(['\n', '\r']);
+            let ending = &raw_line[line.len()..];
+            let misspelled: Vec<(usize, String)> = filter
+                .check(session.dictionary(), line_start, line)
+                .into_iter()
+                .filter(|(_, word)| !self.settings.is_ignored(word))
+                .map(|(idx, word)| (idx, word.to_owned()))
+                .collect();
+            line_start += raw_line.len();
+            if self.quit || !lines.contains(line_idx + 1) {
+                output.push_str(raw_line);
+                continue;
+            }
+
+            let mut replacements = Vec::new();
+            for (idx, word) in misspelled {
+                // Earlier answers may have accepted this word
+                if session.check_word(&word) {
+                    continue;
+                }
+                let location = format!("{display}:{}", line_idx + 1);
+                match self.prompt(session, &location, line, idx, &word)? {
+                    Action::Keep => (),
+                    Action::Replace(new) => replacements.push((idx..idx + word.len(), new)),
+                }
+                if self.quit {
+                    break;
+                }
+            }
+
+            self.fixed += replacements.len();
+            output.push_str(&zspell::apply_fixes(line, &replacements));
+            output.push_str(ending);
+        }
+
+        if self.fixed > fixed_before {
+            fs::write(path, output)
+                .with_context(|| format!("unable to write '{}'", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Show a misspelling and ask the user what to do with it
+    fn prompt(
+        &mut self,
+        session: &mut Session,
+        location: &str,
+        line: &str,
+        idx: usize,
+        word: &str,
+    ) -> anyhow::Result<Action> {
+        let suggestions: Vec<String> = session
+            .dictionary()
+            .entry(word)
+            .suggest_limit(MAX_SUGGESTIONS)
+            .unwrap_or_default()
+            .into_iter()
+            .map(Cow::into_owned)
+            .collect();
+
+        let mut stderr = io::stderr().lock();
+        let column = line[..idx].chars().count();
+        writeln!(stderr, "\n{location}:{}: {word}", column + 1)?;
+        writeln!(stderr, "    {}", line.replace('\t', " "))?;
+        writeln!(
+            stderr,
+            "    {}{}",
+            " ".repeat(column),
+            "^".repeat(word.chars().count())
+        )?;
+        for (i, sugg) in suggestions.iter().enumerate() {
+            write!(stderr, "  {}) {sugg}", i + 1)?;
+        }
+        if !suggestions.is_empty() {
+            writeln!(stderr)?;
+        }
+        writeln!(
+            stderr,
+            "  i) ignore  I) ignore all  a) add to dictionary  r) replace  q) save and quit"
+        )?;
+
+        loop {
+            write!(stderr, "> ")?;
+            stderr.flush()?;
+            let Some(answer) = self.next_line()? else {
+                self.quit = true;
+                return Ok(Action::Keep);
+            };
+
+            match answer.trim() {
+                "" | "i" => return Ok(Action::Keep),
+                "I" => {
+                    session.ignore_all(word);
+                    return Ok(Action::Keep);
+                }
+                "a" => {
+                    if session.personal_file().is_none() {
+                        writeln!(stderr, "no personal dictionary or project word list set")?;
+                        continue;
+                    }
+                    session.add_word(word)?;
+                    return Ok(Action::Keep);
+                }
+                "r" => {
+                    write!(stderr, "replace with: ")?;
+                    stderr.flush()?;
+                    match self.next_line()? {
+                        Some(new) if !new.trim().is_empty() => {
+                            return Ok(Action::Replace(new.trim().to_owned()))
+                        }
+                        _ => continue,
+                    }
+                }
+                "q" => {
+                    self.quit = true;
+                    return Ok(Action::Keep);
+                }
+                other => match other.parse::<usize>() {
+                    Ok(n) if (1..=suggestions.len()).contains(&n) => {
+                        return Ok(Action::Replace(suggestions[n - 1].clone()));
+                    }
+                    _ => writeln!(stderr, "unknown option '{other}'")?,
+                },
+            }
+        }
+    }
+
+    fn next_line(&mut self) -> io::Result<Option<String>> {
+        self.input.next().transpose()
+    }
+}
+
+/// Where added words are saved: the personal dictionary if one is set, else the
+/// nearest project word list, or a new one in the current directory
+pub fn added_words_target(settings: &Settings, found: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = settings.personal.as_deref().or(found) {
+        return Some(path.to_path_buf());
+    }
+    if settings.words_file.is_empty() {
+        return None;
+    }
+    Some(std::env::current_dir().ok()?.join(&settings.words_file))
+}
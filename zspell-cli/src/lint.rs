@@ -0,0 +1,171 @@
+//! The `lint` subcommand, which reports problems in an affix file and its word
+//! list
+
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+use std::str;
+
+use anyhow::Context;
+use zspell::error::BuildWarning;
+use zspell::{Encoding, ParseMode};
+
+use crate::cli::LintArgs;
+
+/// A problem found in one of the dictionary files
+#[derive(Debug)]
+struct Issue<'a> {
+    path: &'a Path,
+    /// 1-based line number, if the problem is on a single line
+    line: Option<u32>,
+    msg: String,
+}
+
+pub fn lint_cli(args: &LintArgs) -> ExitCode {
+    match lint(&args.aff, &args.dic) {
+        Ok(issues) if issues.is_empty() => ExitCode::SUCCESS,
+        Ok(issues) => {
+            for issue in &issues {
+                println!("{issue}");
+            }
+            eprintln!("found {} problem(s)", issues.len());
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("{e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Collect the problems in a pair of dictionary files, sorted by file and line
+fn lint<'a>(aff_path: &'a Path, dic_path: &'a Path) -> anyhow::Result<Vec<Issue<'a>>> {
+    let read = |p: &Path| fs::read(p).with_context(|| format!("unable to read '{}'", p.display()));
+    let aff_bytes = read(aff_path)?;
+    let dic_bytes = read(dic_path)?;
+
+    let declared = declared_encoding(&aff_bytes);
+    let mut issues: Vec<Issue> = [(aff_path, &aff_bytes), (dic_path, &dic_bytes)]
+        .into_iter()
+        .filter_map(|(path, bytes)| check_encoding(path, bytes, declared.as_deref()))
+        .collect();
+
+    // Mismatched encodings have already been reported, so keep going with
+    // whatever text can be decoded
+    let aff = String::from_utf8_lossy(&aff_bytes);
+    let dic = String::from_utf8_lossy(&dic_bytes);
+    let (dict, warnings) = zspell::builder()
+        .config_str(&aff)
+        .dict_str(&dic)
+        .parse_mode(ParseMode::Permissive)
+        .validate_dict(true)
+        .build_with_warnings()
+        .context("unable to build dictionary")?;
+
+    issues.extend(warnings.iter().filter_map(|w| match w {
+        BuildWarning::Parse(e) => Some(Issue {
+            path: aff_path,
+            line: w.line(),
+            msg: e.err().to_string(),
+        }),
+        BuildWarning::Deprecated { directive, .. } => Some(Issue {
+            path: aff_path,
+            line: directive_line(&aff, directive),
+            msg: w.to_string(),
+        }),
+        BuildWarning::Dict(e) => Some(Issue {
+            path: dic_path,
+            line: w.line(),
+            msg: e.to_string(),
+        }),
+        // Unsupported directives are not mistakes in the files
+        _ => None,
+    }));
+    issues.extend(dict.unused_affix_flags().into_iter().map(|flag| Issue {
+        path: aff_path,
+        line: rule_group_line(&aff, flag),
+        msg: format!("affix flag '{flag}' is not used by any word or affix"),
+    }));
+
+    issues.sort_by_key(|issue| (issue.path == dic_path, issue.line));
+    Ok(issues)
+}
+
+/// The charset named by the `SET` directive of an affix file, if it has one
+fn declared_encoding(aff: &[u8]) -> Option<String> {
+    aff.split(|&b| b == b'\n').find_map(|line| {
+        let line = String::from_utf8_lossy(line);
+        let mut fields = line.trim_start_matches('\u{feff}').split_whitespace();
+        if fields.next() == Some("SET") {
+            fields.next().map(str::to_owned)
+        } else {
+            None
+        }
+    })
+}
+
+/// Report the first line of `bytes` that does not fit the declared charset,
+/// along with how many others do not
+fn check_encoding<'a>(path: &'a Path, bytes: &[u8], declared: Option<&str>) -> Option<Issue<'a>> {
+    let (is_bad, mut msg): (fn(&[u8]) -> bool, String) =
+        match declared.map(|name| (name, Encoding::try_from(name))) {
+            // Unknown charsets are reported by the parser
+            Some((_, Err(_))) => return None,
+            Some((name, Ok(Encoding::Utf8))) => (
+                |line| str::from_utf8(line).is_err(),
+                format!("text is not valid UTF-8, but the affix file declares `SET {name}`"),
+            ),
+            Some((name, Ok(_))) => (
+                |line| !line.is_ascii() && str::from_utf8(line).is_ok(),
+                format!("text looks like UTF-8, but the affix file declares `SET {name}`"),
+            ),
+            None => (
+                |line| !line.is_ascii(),
+                "non-ASCII text, but the affix file has no `SET` directive".to_owned(),
+            ),
+        };
+
+    let mut bad_lines = bytes
+        .split(|&b| b == b'\n')
+        .enumerate()
+        .filter(|(_, line)| is_bad(line))
+        .map(|(idx, _)| idx + 1);
+    let first = bad_lines.next()?;
+    let more = bad_lines.count();
+    if more > 0 {
+        write!(msg, " ({more} more line(s))").unwrap();
+    }
+
+    Some(Issue {
+        path,
+        line: u32::try_from(first).ok(),
+        msg,
+    })
+}
+
+/// Line of the first use of `directive`
+fn directive_line(aff: &str, directive: &str) -> Option<u32> {
+    let idx = aff
+        .lines()
+        .position(|line| line.split_whitespace().next() == Some(directive))?;
+    u32::try_from(idx + 1).ok()
+}
+
+/// Line of the header of the first prefix or suffix group with `flag`
+fn rule_group_line(aff: &str, flag: &str) -> Option<u32> {
+    let idx = aff.lines().position(|line| {
+        let mut fields = line.split_whitespace();
+        matches!(fields.next(), Some("PFX" | "SFX")) && fields.next() == Some(flag)
+    })?;
+    u32::try_from(idx + 1).ok()
+}
+
+impl fmt::Display for Issue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{line}: {}", self.path.display(), self.msg),
+            None => write!(f, "{}: {}", self.path.display(), self.msg),
+        }
+    }
+}
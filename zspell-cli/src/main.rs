@@ -4,18 +4,26 @@ use std::process::ExitCode;
 
 use clap::Parser;
 
+mod check;
 mod cli;
-mod download;
+mod config;
+mod diff;
+mod filter;
+mod fix;
+mod lint;
+mod output;
 mod spelling;
+mod summary;
 
 use spelling::spellcheck_cli;
 use stringmetrics::levenshtein_limit;
+use zspell::fetch::Fetcher;
 
 fn main() -> ExitCode {
     let cli_parse = cli::Cli::parse();
     if let Err(e) = cli_parse.validate() {
         eprintln!("{e}");
-        return ExitCode::FAILURE;
+        return ExitCode::from(spelling::EXIT_USAGE);
     }
 
     if let Some(cli::Commands::Lev {
@@ -28,7 +36,41 @@ fn main() -> ExitCode {
         return ExitCode::SUCCESS;
     }
 
-    spellcheck_cli(&cli_parse);
+    if let Some(cli::Commands::Fetch {
+        lang,
+        index_url,
+        cache_dir,
+        force,
+    }) = &cli_parse.command
+    {
+        let mut fetcher = Fetcher::new().force(*force);
+        if let Some(url) = index_url {
+            fetcher = fetcher.index_url(url);
+        }
+        if let Some(dir) = cache_dir {
+            fetcher = fetcher.cache_dir(dir);
+        }
+
+        return match fetcher.fetch(lang) {
+            Ok(fetched) => {
+                println!("{}", fetched.aff_path().display());
+                println!("{}", fetched.dic_path().display());
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(cli::Commands::Check(args)) = &cli_parse.command {
+        return check::check_cli(&cli_parse, args);
+    }
+
+    if let Some(cli::Commands::Lint(args)) = &cli_parse.command {
+        return lint::lint_cli(args);
+    }
 
-    ExitCode::SUCCESS
+    spellcheck_cli(&cli_parse)
 }
@@ -0,0 +1,503 @@
+//! Output formats for the misspellings found by `check`
+//!
+//! Plain text, JSON, GitHub annotations, and user templates are printed one
+//! misspelling per line as each file is reported. SARIF is a single document,
+//! so its results are collected and printed once checking is done, as is a
+//! [`Summary`].
+
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::bail;
+use serde_json::{json, Value};
+use zspell::system::PKG_VERSION;
+use zspell::Dictionary;
+
+use crate::cli::OutputFormat;
+use crate::config::Settings;
+use crate::spelling::Misspelling;
+use crate::summary::Summary;
+
+/// Maximum number of suggestions included with a misspelling
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The SARIF rule that every misspelling is reported under
+const SARIF_RULE_ID: &str = "misspelled-word";
+
+/// A misspelling found by `check`
+#[derive(Debug)]
+pub struct Found {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    pub word: String,
+    /// Corrections for the word, best first. Only looked up for formats that
+    /// show them, see [`Output::wants_suggestions`]
+    pub suggestions: Vec<String>,
+}
+
+impl Found {
+    pub fn new(line: usize, line_text: &str, idx: usize, word: &str) -> Self {
+        Self {
+            line,
+            column: line_text[..idx].chars().count() + 1,
+            word: word.to_owned(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Look up suggestions for the word
+    pub fn with_suggestions(mut self, dict: &Dictionary) -> Self {
+        self.suggestions = dict
+            .entry(&self.word)
+            .suggest_limit(MAX_SUGGESTIONS)
+            .unwrap_or_default()
+            .into_iter()
+            .map(Cow::into_owned)
+            .collect();
+        self
+    }
+}
+
+/// Writes misspellings in the chosen [`OutputFormat`]
+#[derive(Debug)]
+pub struct Output {
+    format: OutputFormat,
+    template: Option<Template>,
+    /// SARIF results, printed by [`Output::finish`]
+    results: Vec<Value>,
+    /// Totals printed by [`Output::finish`] instead of each misspelling
+    summary: Option<Summary>,
+}
+
+impl Output {
+    /// With `summary`, only totals are printed, in the plain or JSON format
+    pub fn new(settings: &Settings, summary: bool) -> Self {
+        Self {
+            format: settings.format,
+            template: settings.template.clone(),
+            results: Vec::new(),
+            summary: summary.then(Summary::default),
+        }
+    }
+
+    /// Whether misspellings need their suggestions looked up before they are
+    /// reported
+    pub fn wants_suggestions(&self) -> bool {
+        if self.summary.is_some() {
+            return false;
+        }
+        match self.format {
+            OutputFormat::Plain | OutputFormat::Json => false,
+            OutputFormat::Sarif | OutputFormat::Github => true,
+            OutputFormat::Template => self
+                .template
+                .as_ref()
+                .is_some_and(Template::uses_suggestions),
+        }
+    }
+
+    /// Write the misspellings found in `file`, or in stdin if it is `None`,
+    /// where `words` words were checked. Misspellings from stdin are shown with
+    /// the file name `<stdin>`.
+    pub fn report(
+        &mut self,
+        out: &mut impl Write,
+        file: Option<&str>,
+        words: usize,
+        found: &[Found],
+    ) -> io::Result<()> {
+        if let Some(summary) = self.summary.as_mut() {
+            summary.add(file.unwrap_or("<stdin>"), words, found);
+            return Ok(());
+        }
+        for f in found {
+            match self.format {
+                OutputFormat::Plain => writeln!(
+                    out,
+                    "{}:{}:{}: {}",
+                    file.unwrap_or("<stdin>"),
+                    f.line,
+                    f.column,
+                    f.word
+                )?,
+                OutputFormat::Json => {
+                    let m = Misspelling {
+                        file,
+                        line: f.line,
+                        column: f.column,
+                        word: &f.word,
+                    };
+                    writeln!(out, "{}", serde_json::to_string(&m).unwrap())?;
+                }
+                OutputFormat::Github => writeln!(out, "{}", github_annotation(file, f))?,
+                OutputFormat::Sarif => self.results.push(sarif_result(file, f)),
+                OutputFormat::Template => {
+                    // Checked when loading settings
+                    let template = self
+                        .template
+                        .as_ref()
+                        .expect("template format without a template");
+                    writeln!(out, "{}", template.render(file, f))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write anything that can only be written once all misspellings have
+    /// been reported
+    pub fn finish(self, out: &mut impl Write) -> io::Result<()> {
+        if let Some(summary) = &self.summary {
+            return summary.write(out, self.format);
+        }
+        if self.format != OutputFormat::Sarif {
+            return Ok(());
+        }
+        let log = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "zspell",
+                        "version": PKG_VERSION,
+                        "informationUri": "https://github.com/pluots/zspell",
+                        "rules": [{
+                            "id": SARIF_RULE_ID,
+                            "name": "MisspelledWord",
+                            "shortDescription": { "text": "Misspelled word" },
+                            "fullDescription": {
+                                "text": "A word that is not in the dictionary, a personal \
+                                    dictionary, or the project word list."
+                            },
+                            "defaultConfiguration": { "level": "warning" },
+                        }],
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": self.results,
+            }],
+        });
+        serde_json::to_writer_pretty(&mut *out, &log)?;
+        writeln!(out)
+    }
+}
+
+/// A line of output with placeholders for the parts of a misspelling, given
+/// with `--template`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template(Vec<Piece>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Piece {
+    Text(String),
+    File,
+    Line,
+    Column,
+    EndColumn,
+    Word,
+    Suggestions,
+}
+
+impl Template {
+    /// Parse a template. `{name}` is a placeholder, `{{` and `}}` are literal
+    /// braces, and `\t`, `\n`, and `\\` are a tab, newline, and backslash.
+    pub fn parse(template: &str) -> anyhow::Result<Self> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let Some(end) = rest.find('}') else {
+                        bail!("unclosed '{{' in template '{template}'");
+                    };
+                    let piece = match &rest[..end] {
+                        "file" => Piece::File,
+                        "line" => Piece::Line,
+                        "col" => Piece::Column,
+                        "end_col" => Piece::EndColumn,
+                        "word" => Piece::Word,
+                        "suggestions" => Piece::Suggestions,
+                        name => bail!(
+                            "unknown placeholder '{{{name}}}' in template, expected one of \
+                            {{file}}, {{line}}, {{col}}, {{end_col}}, {{word}}, or {{suggestions}}"
+                        ),
+                    };
+                    chars = rest[end + 1..].chars();
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(piece);
+                }
+                '}' => bail!("unmatched '}}' in template '{template}', use '}}}}' for a brace"),
+                '\\' => match chars.clone().next() {
+                    Some('t') => {
+                        chars.next();
+                        text.push('\t');
+                    }
+                    Some('n') => {
+                        chars.next();
+                        text.push('\n');
+                    }
+                    Some('\\') => {
+                        chars.next();
+                        text.push('\\');
+                    }
+                    _ => text.push('\\'),
+                },
+                _ => text.push(ch),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Self(pieces))
+    }
+
+    fn uses_suggestions(&self) -> bool {
+        self.0.contains(&Piece::Suggestions)
+    }
+
+    /// Fill in the template for a misspelling. Suggestions are separated by
+    /// `, `, and misspellings from stdin have the file name `<stdin>`.
+    fn render(&self, file: Option<&str>, f: &Found) -> String {
+        let mut line = String::new();
+        for piece in &self.0 {
+            match piece {
+                Piece::Text(text) => line.push_str(text),
+                Piece::File => line.push_str(file.unwrap_or("<stdin>")),
+                Piece::Line => _ = write!(line, "{}", f.line),
+                Piece::Column => _ = write!(line, "{}", f.column),
+                Piece::EndColumn => _ = write!(line, "{}", f.column + f.word.chars().count()),
+                Piece::Word => line.push_str(&f.word),
+                Piece::Suggestions => line.push_str(&f.suggestions.join(", ")),
+            }
+        }
+        line
+    }
+}
+
+/// A SARIF result for one misspelling, with a fix for each suggestion.
+/// Consumers need a uri to place a result, so stdin gets the placeholder
+/// `stdin`.
+fn sarif_result(file: Option<&str>, f: &Found) -> Value {
+    let artifact = file.map_or_else(
+        || json!({ "uri": "stdin", "description": { "text": "stdin" } }),
+        |file| json!({ "uri": file_uri(file) }),
+    );
+    let region = json!({
+        "startLine": f.line,
+        "startColumn": f.column,
+        "endColumn": f.column + f.word.chars().count(),
+    });
+
+    let fixes: Vec<Value> = f
+        .suggestions
+        .iter()
+        .map(|suggestion| {
+            json!({
+                "description": { "text": format!("Replace with '{suggestion}'") },
+                "artifactChanges": [{
+                    "artifactLocation": artifact,
+                    "replacements": [{
+                        "deletedRegion": region,
+                        "insertedContent": { "text": suggestion },
+                    }],
+                }],
+            })
+        })
+        .collect();
+
+    json!({
+        "ruleId": SARIF_RULE_ID,
+        "ruleIndex": 0,
+        "level": "warning",
+        "message": { "text": message(f) },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": artifact,
+                "region": region,
+            }
+        }],
+        "fixes": fixes,
+    })
+}
+
+/// A GitHub Actions workflow command that annotates the misspelling. Lines from
+/// stdin have no file, so they are shown on the workflow run instead.
+fn github_annotation(file: Option<&str>, f: &Found) -> String {
+    let mut annotation = String::from("::warning ");
+    if let Some(file) = file {
+        let file = file.trim_start_matches("./");
+        _ = write!(annotation, "file={},", github_escape(file, true));
+    }
+    _ = write!(
+        annotation,
+        "line={},col={},endColumn={},title=Misspelled word::{}",
+        f.line,
+        f.column,
+        f.column + f.word.chars().count(),
+        github_escape(&message(f), false)
+    );
+    annotation
+}
+
+/// Escape text for a GitHub workflow command, either a property value or the
+/// message
+fn github_escape(s: &str, property: bool) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '%' => escaped.push_str("%25"),
+            '\r' => escaped.push_str("%0D"),
+            '\n' => escaped.push_str("%0A"),
+            ':' if property => escaped.push_str("%3A"),
+            ',' if property => escaped.push_str("%2C"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// The message shown for a misspelling, with its suggestions
+fn message(f: &Found) -> String {
+    let mut message = format!("'{}' may be misspelled.", f.word);
+    if !f.suggestions.is_empty() {
+        let suggestions: Vec<String> = f.suggestions.iter().map(|s| format!("'{s}'")).collect();
+        _ = write!(message, " Did you mean {}?", suggestions.join(", "));
+    }
+    message
+}
+
+/// The URI for a file as given on the command line. Relative paths stay
+/// relative, so tools resolve them against the root of the checkout.
+fn file_uri(file: &str) -> String {
+    let path = file.replace('\\', "/");
+    let path = path.trim_start_matches("./");
+    let mut uri = String::with_capacity(path.len());
+    if Path::new(file).is_absolute() {
+        uri.push_str("file://");
+        if !path.starts_with('/') {
+            // Windows drive letters
+            uri.push('/');
+        }
+    }
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+            uri.push(char::from(byte));
+        } else {
+            _ = write!(uri, "%{byte:02X}");
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_uri() {
+        assert_eq!(file_uri("docs/a.md"), "docs/a.md");
+        assert_eq!(file_uri("./docs/a b.md"), "docs/a%20b.md");
+        assert_eq!(file_uri("docs\\é.md"), "docs/%C3%A9.md");
+        #[cfg(unix)]
+        assert_eq!(file_uri("/home/me/a.md"), "file:///home/me/a.md");
+        #[cfg(windows)]
+        assert_eq!(file_uri("C:\\me\\a.md"), "file:///C:/me/a.md");
+    }
+
+    #[test]
+    fn test_github_annotation() {
+        let found = Found {
+            line: 2,
+            column: 5,
+            word: "bananna".to_owned(),
+            suggestions: vec!["banana".to_owned(), "bandana".to_owned()],
+        };
+        assert_eq!(
+            github_annotation(Some("./docs/a,b.md"), &found),
+            "::warning file=docs/a%2Cb.md,line=2,col=5,endColumn=12,title=Misspelled word::\
+            'bananna' may be misspelled. Did you mean 'banana', 'bandana'?"
+        );
+        assert_eq!(
+            github_annotation(None, &Found::new(1, "100% wrng", 5, "wrng")),
+            "::warning line=1,col=6,endColumn=10,title=Misspelled word::'wrng' may be misspelled."
+        );
+        assert_eq!(github_escape("a:b,c%\n", true), "a%3Ab%2Cc%25%0A");
+        assert_eq!(github_escape("a:b,c%\n", false), "a:b,c%25%0A");
+    }
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_template() {
+        let found = Found {
+            line: 2,
+            column: 5,
+            word: "bananna".to_owned(),
+            suggestions: vec!["banana".to_owned(), "bandana".to_owned()],
+        };
+        let template = Template::parse("{file}:{line}:{col}: {word} -> {suggestions}").unwrap();
+        assert!(template.uses_suggestions());
+        assert_eq!(
+            template.render(Some("a.md"), &found),
+            "a.md:2:5: bananna -> banana, bandana"
+        );
+
+        let template = Template::parse("{{{word}}}\\t{end_col}\\\\n\\x").unwrap();
+        assert!(!template.uses_suggestions());
+        assert_eq!(template.render(None, &found), "{bananna}\t12\\n\\x");
+        assert_eq!(Template::parse("").unwrap().render(None, &found), "");
+
+        for invalid in ["{words}", "{word", "word}", "{}"] {
+            assert!(Template::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_sarif_result() {
+        let found = Found {
+            line: 2,
+            column: 5,
+            word: "bananna".to_owned(),
+            suggestions: vec!["banana".to_owned()],
+        };
+        let result = sarif_result(Some("a.md"), &found);
+        assert_eq!(
+            result["message"]["text"],
+            "'bananna' may be misspelled. Did you mean 'banana'?"
+        );
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "a.md");
+        assert_eq!(
+            location["region"],
+            json!({ "startLine": 2, "startColumn": 5, "endColumn": 12 })
+        );
+        let replacement = &result["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["deletedRegion"], location["region"]);
+        assert_eq!(replacement["insertedContent"]["text"], "banana");
+    }
+
+    #[test]
+    fn test_sarif_result_stdin() {
+        let found = Found::new(1, "teh cat", 0, "teh");
+        let result = sarif_result(None, &found);
+        let artifact = &result["locations"][0]["physicalLocation"]["artifactLocation"];
+        assert_eq!(artifact["uri"], "stdin");
+        assert_eq!(artifact["description"]["text"], "stdin");
+    }
+}
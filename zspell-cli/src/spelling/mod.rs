@@ -1,14 +1,28 @@
 //! Helpers for CLI spelling features
 
+use std::ffi::OsStr;
 use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::{env, fs};
 
-use zspell::error::Error;
-use zspell::system::{create_dict_from_path, PKG_NAME, PKG_VERSION};
+use anyhow::Context;
+use serde::Serialize;
+use zspell::archive::{Oxt, Xpi};
+use zspell::error::BuildWarning;
+use zspell::system::{PKG_NAME, PKG_VERSION};
 use zspell::Dictionary;
 
-use crate::cli::Cli;
+use crate::cli::{Cli, OutputFormat};
+use crate::config::Settings;
+
+/// Exit code for invalid arguments, settings, or paths, the same as clap uses
+/// for arguments it cannot parse
+pub const EXIT_USAGE: u8 = 2;
+
+/// Exit code when a dictionary cannot be loaded
+pub const EXIT_DICTIONARY: u8 = 3;
 
 // A reminder that code is written by humans
 const SALUTATIONS: [&str; 9] = [
@@ -28,29 +42,46 @@ pub fn spellcheck_cli(cli: &Cli) -> ExitCode {
 
     io::stdout().flush().unwrap();
 
-    let dict_path = if let Some(v) = cli.dict_path.as_ref() {
-        v.as_str()
-    } else {
-        eprintln!("Dictionary path not specified. Please specify with `-d /path/to/dic`.");
-        return ExitCode::FAILURE;
+    let settings = match Settings::new(cli) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{e:#}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let Some(dict_path) = settings.dict_path.as_deref() else {
+        eprintln!(
+            "Dictionary path not specified. Please specify with `-d /path/to/dic` or in a \
+            config file."
+        );
+        return ExitCode::from(EXIT_USAGE);
     };
 
+    if !matches!(settings.format, OutputFormat::Plain | OutputFormat::Json) {
+        eprintln!("only the plain and JSON formats can be used without `zspell check`");
+        return ExitCode::from(EXIT_USAGE);
+    }
+
     let load_start = Instant::now();
-    let dict = match create_dict_from_path(dict_path) {
+    let cwd = env::current_dir().unwrap_or_default();
+    let project_words = settings.project_words(&cwd);
+    let personal: Vec<&Path> = settings
+        .personal
+        .iter()
+        .chain(project_words.iter())
+        .map(PathBuf::as_path)
+        .collect();
+    let loaded = load_dict(dict_path, &personal, &settings.skip);
+    let dict = match loaded {
         Ok(v) => v,
         Err(e) => {
-            match e {
-                Error::Io(e) => eprintln!("IO error: {e}"),
-                Error::Parse(e) => eprintln!("Error parsing: {e}"),
-                Error::Build(e) => eprintln!("Error building: {e}"),
-                Error::Regex(e) => eprintln!("Regex error: {e}"),
-                _ => unreachable!(),
-            };
-            return ExitCode::FAILURE;
+            eprintln!("Error loading dictionary: {e:#}");
+            return ExitCode::from(EXIT_DICTIONARY);
         }
     };
     let load_time = load_start.elapsed().as_secs_f32();
-    let wc = dict.wordlist().inner().len() + dict.wordlist_nosuggest().inner().len();
+    let wc = dict.wordlist().len() + dict.wordlist_nosuggest().len();
     eprintln!("loaded {wc} words in {load_time:.2}s. started session");
 
     if cli.generate_wordlist {
@@ -63,7 +94,7 @@ pub fn spellcheck_cli(cli: &Cli) -> ExitCode {
     } else if cli.stem {
         runner_stemming(&dict);
     } else {
-        runner_spellcheck(&dict);
+        runner_spellcheck(&dict, &settings);
     }
 
     // Quick RNG without external crates
@@ -78,10 +109,85 @@ pub fn spellcheck_cli(cli: &Cli) -> ExitCode {
     ExitCode::SUCCESS
 }
 
-fn runner_spellcheck(dict: &Dictionary) {
+/// Load a dictionary from a base path (`.aff` and `.dic` are appended) or from
+/// an `.oxt`/`.xpi` bundle. Any personal word lists are merged together.
+/// Text matching any of the `skip` regular expressions is not checked.
+pub fn load_dict(path: &Path, personal: &[&Path], skip: &[String]) -> anyhow::Result<Dictionary> {
+    let read = |p: &Path| {
+        fs::read_to_string(p).with_context(|| format!("unable to read '{}'", p.display()))
+    };
+
+    let (aff, dic) = match path.extension().and_then(OsStr::to_str) {
+        Some("oxt") => Oxt::open(path)?.read_files(None)?,
+        Some("xpi") => Xpi::open(path)?.read_files(None)?,
+        _ => {
+            let mut aff_path = path.as_os_str().to_owned();
+            let mut dic_path = aff_path.clone();
+            aff_path.push(".aff");
+            dic_path.push(".dic");
+            (read(aff_path.as_ref())?, read(dic_path.as_ref())?)
+        }
+    };
+
+    let mut personal_str = String::new();
+    for p in personal {
+        personal_str.push_str(&read(p)?);
+        personal_str.push('\n');
+    }
+
+    let mut builder = zspell::builder()
+        .config_str(&aff)
+        .dict_str(&dic)
+        .personal_str(&personal_str);
+    for pattern in skip {
+        builder = builder.ignore_regex(pattern);
+    }
+
+    let (dict, warnings) = builder.build_with_warnings()?;
+    for warning in &warnings {
+        if matches!(warning, BuildWarning::Deprecated { .. }) {
+            eprintln!("warning: {warning}");
+        }
+    }
+    Ok(dict)
+}
+
+/// A misspelling as printed in JSON output
+#[derive(Serialize)]
+pub struct Misspelling<'a> {
+    /// File the word was found in, if not checking stdin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<&'a str>,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    pub word: &'a str,
+}
+
+fn runner_spellcheck(dict: &Dictionary, settings: &Settings) {
+    let mut line_num = 0;
     stdin_interactive_runner(|line| {
-        for (_, misspelled) in dict.check_indices(&line) {
-            println!("{misspelled}");
+        line_num += 1;
+        for (idx, misspelled) in dict.check_indices(&line) {
+            if settings.is_ignored(misspelled) {
+                continue;
+            }
+            match settings.format {
+                OutputFormat::Plain => println!("{misspelled}"),
+                OutputFormat::Json => {
+                    let m = Misspelling {
+                        file: None,
+                        line: line_num,
+                        column: line[..idx].chars().count() + 1,
+                        word: misspelled,
+                    };
+                    println!("{}", serde_json::to_string(&m).unwrap());
+                }
+                OutputFormat::Sarif | OutputFormat::Github | OutputFormat::Template => {
+                    unreachable!("rejected by spellcheck_cli")
+                }
+            }
         }
     })
 }
@@ -119,8 +225,8 @@ fn runner_morph_analysis(dict: &Dictionary) {
         for entry in dict.entries(&line) {
             print!("{}", entry.word());
 
-            if let Some(morphs) = entry.analyze() {
-                for morph in morphs {
+            if let Some(analysis) = entry.analyze() {
+                for morph in analysis.morphs() {
                     print!(" {morph}");
                 }
             } else {
@@ -131,11 +237,12 @@ fn runner_morph_analysis(dict: &Dictionary) {
     })
 }
 
-/// Common runner interface that calls a function once per stdin line
-// FIXME: if not a tty, lock output once before writing
-fn stdin_interactive_runner<F>(f: F)
+/// Common runner interface that calls a function once per stdin line. Output
+/// is flushed after each line, so results show up as soon as a line is read
+/// when piping from something like `tail -f`.
+fn stdin_interactive_runner<F>(mut f: F)
 where
-    F: Fn(String),
+    F: FnMut(String),
 {
     let stdin = io::stdin();
     // This is a false positive, see clippy #9135
@@ -143,5 +250,6 @@ where
     for line in stdin.lock().lines() {
         let line_val = line.expect("received invalid input from IO!");
         f(line_val);
+        _ = io::stdout().flush();
     }
 }
@@ -0,0 +1,176 @@
+//! Totals across all files checked by `check --summary`, to see which words and
+//! files most need attention
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::cli::OutputFormat;
+use crate::output::Found;
+
+/// Counts of the words and misspellings seen in each file
+#[derive(Debug, Default)]
+pub struct Summary {
+    files: usize,
+    words: usize,
+    /// How many times each misspelled word was found
+    misspellings: BTreeMap<String, usize>,
+    /// Each file checked, with its number of misspellings
+    file_errors: Vec<(String, usize)>,
+}
+
+/// The summary as printed in JSON output
+#[derive(Serialize)]
+struct Report<'a> {
+    files_checked: usize,
+    words_checked: usize,
+    misspellings: usize,
+    /// Misspelled words, most frequent first
+    words: Vec<WordCount<'a>>,
+    /// Files with misspellings, those with the most first
+    files: Vec<FileCount<'a>>,
+}
+
+#[derive(Serialize)]
+struct WordCount<'a> {
+    word: &'a str,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct FileCount<'a> {
+    file: &'a str,
+    count: usize,
+}
+
+impl Summary {
+    /// Count `words` checked in `file` and the misspellings `found` in them.
+    /// Consecutive calls for the same file, such as for each line of stdin,
+    /// count it once.
+    pub fn add(&mut self, file: &str, words: usize, found: &[Found]) {
+        self.words += words;
+        for f in found {
+            *self.misspellings.entry(f.word.clone()).or_default() += 1;
+        }
+        match self.file_errors.last_mut() {
+            Some((last, count)) if last == file => *count += found.len(),
+            _ => {
+                self.files += 1;
+                self.file_errors.push((file.to_owned(), found.len()));
+            }
+        }
+    }
+
+    pub fn write(&self, out: &mut impl Write, format: OutputFormat) -> io::Result<()> {
+        let words = rank(self.misspellings.iter().map(|(w, &n)| (w.as_str(), n)));
+        let files = rank(self.file_errors.iter().map(|(f, n)| (f.as_str(), *n)));
+        let total: usize = self.misspellings.values().sum();
+
+        if format == OutputFormat::Json {
+            let report = Report {
+                files_checked: self.files,
+                words_checked: self.words,
+                misspellings: total,
+                words: words
+                    .iter()
+                    .map(|&(word, count)| WordCount { word, count })
+                    .collect(),
+                files: files
+                    .iter()
+                    .map(|&(file, count)| FileCount { file, count })
+                    .collect(),
+            };
+            return writeln!(out, "{}", serde_json::to_string(&report).unwrap());
+        }
+
+        writeln!(
+            out,
+            "checked {} word(s) in {} file(s), found {total} misspelling(s) of {} word(s) \
+            in {} file(s)",
+            self.words,
+            self.files,
+            words.len(),
+            files.len()
+        )?;
+        if !words.is_empty() {
+            writeln!(out, "\nmost frequent misspellings:")?;
+            write_ranked(out, &words)?;
+        }
+        if !files.is_empty() {
+            writeln!(out, "\nfiles with the most misspellings:")?;
+            write_ranked(out, &files)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sort by count, highest first, keeping the order of `items` for equal
+/// counts. Items with a count of zero are left out.
+fn rank<'a>(items: impl Iterator<Item = (&'a str, usize)>) -> Vec<(&'a str, usize)> {
+    let mut ranked: Vec<_> = items.filter(|&(_, count)| count > 0).collect();
+    ranked.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    ranked
+}
+
+fn write_ranked(out: &mut impl Write, ranked: &[(&str, usize)]) -> io::Result<()> {
+    let width = ranked
+        .first()
+        .map_or(0, |(_, count)| count.to_string().len());
+    for (name, count) in ranked {
+        writeln!(out, "  {count:>width$}  {name}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(words: &[&str]) -> Vec<Found> {
+        words.iter().map(|w| Found::new(1, w, 0, w)).collect()
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut summary = Summary::default();
+        summary.add("a.md", 10, &found(&["teh", "recieve"]));
+        summary.add("b.md", 5, &[]);
+        summary.add("c.md", 20, &found(&["teh", "teh", "adn"]));
+        summary.add("<stdin>", 1, &found(&["teh"]));
+        summary.add("<stdin>", 2, &found(&["adn"]));
+
+        let mut out = Vec::new();
+        summary.write(&mut out, OutputFormat::Plain).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "checked 38 word(s) in 4 file(s), found 7 misspelling(s) of 3 word(s) in 3 file(s)\n\
+            \n\
+            most frequent misspellings:\n  \
+            4  teh\n  \
+            2  adn\n  \
+            1  recieve\n\
+            \n\
+            files with the most misspellings:\n  \
+            3  c.md\n  \
+            2  a.md\n  \
+            2  <stdin>\n"
+        );
+
+        let mut out = Vec::new();
+        summary.write(&mut out, OutputFormat::Json).unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(report["files_checked"], 4);
+        assert_eq!(report["words_checked"], 38);
+        assert_eq!(report["misspellings"], 7);
+        assert_eq!(
+            report["words"][0],
+            serde_json::json!({ "word": "teh", "count": 4 })
+        );
+        assert_eq!(
+            report["files"][2],
+            serde_json::json!({ "file": "<stdin>", "count": 2 })
+        );
+        assert_eq!(report["files"].as_array().unwrap().len(), 3);
+    }
+}
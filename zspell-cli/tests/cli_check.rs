@@ -0,0 +1,622 @@
+//! Tests for the `check` subcommand
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::process::Stdio;
+
+use assert_cmd::cargo::CommandCargoExt;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+const DICT_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../zspell/tests/files/w1_eng_short"
+);
+
+#[test]
+fn check_dirs_and_globs() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    let docs = dir.path().join("docs");
+    fs::create_dir_all(docs.join("nested"))?;
+    fs::create_dir(dir.path().join(".hidden"))?;
+
+    fs::write(
+        docs.join("a.md"),
+        "banana `misspeled` pillow\n\n```\nrepitles\n```\nokay https://bananna.com reptiles\n",
+    )?;
+    fs::write(docs.join("nested").join("b.txt"), "okay rust repitles\n")?;
+    fs::write(docs.join("nested").join("c.rs"), "repitles\n")?;
+    fs::write(dir.path().join(".hidden").join("d.md"), "repitles\n")?;
+
+    // Directories are searched recursively, skipping hidden entries and unknown
+    // extensions
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", ".", "-d", DICT_PATH]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::diff("./docs/nested/b.txt:1:11: repitles\n"))
+        .stderr(predicate::str::contains(
+            "found 1 misspelling(s) in 1 of 2 file(s)",
+        ));
+
+    // Globs select files directly, and the extension list can be changed
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["-d", DICT_PATH, "--format", "json", "check", "docs/**/*.rs"]);
+    cmd.assert().failure().stdout(predicate::str::diff(
+        "{\"file\":\"docs/nested/c.rs\",\"line\":1,\"column\":1,\"word\":\"repitles\"}\n",
+    ));
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "docs", "--ext", "md", "-d", DICT_PATH]);
+    cmd.assert().success().stdout(predicate::str::diff(""));
+
+    // Patterns that match nothing are an error
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "*.adoc", "-d", DICT_PATH]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("no files found matching '*.adoc'"));
+
+    Ok(())
+}
+
+#[test]
+fn check_fix() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    let file = dir.path().join("a.txt");
+    fs::write(
+        &file,
+        "okay rustt pillow\r\nbanana zspell\nzspell repitles\nlast rustt",
+    )?;
+
+    // Pick a suggestion, add a word, type a replacement, then stop at EOF
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "a.txt", "--fix", "-d", DICT_PATH])
+        .write_stdin("1\na\nr\nreptiles\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff(""))
+        .stderr(predicate::str::contains("1) rust"))
+        .stderr(predicate::str::contains("fixed 2 misspelling(s)"));
+
+    assert_eq!(
+        fs::read_to_string(&file)?,
+        "okay rust pillow\r\nbanana zspell\nzspell reptiles\nlast rustt"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join(".zspell-words"))?,
+        "zspell\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn check_diff_base() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .current_dir(dir.path())
+            .args([
+                "-c",
+                "user.name=zspell",
+                "-c",
+                "user.email=zspell@example.com",
+            ])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    };
+
+    fs::write(dir.path().join("old.txt"), "repitles\nokay\nrustt\n")?;
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-qm", "initial"]);
+    fs::write(
+        dir.path().join("old.txt"),
+        "repitles\nokay bananna\nrustt\n",
+    )?;
+    fs::write(dir.path().join("new.txt"), "pillowz\n")?;
+
+    // Only the changed line and the untracked file are reported
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", ".", "--diff-base", "HEAD", "-d", DICT_PATH]);
+    cmd.assert().failure().stdout(predicate::str::diff(
+        "./new.txt:1:1: pillowz\n./old.txt:2:6: bananna\n",
+    ));
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", ".", "--diff-base", "no-such-ref", "-d", DICT_PATH]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("no-such-ref"));
+
+    Ok(())
+}
+
+#[test]
+fn check_rust_source() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(
+        dir.path().join("lib.rs"),
+        "/// okay banana `rustt` pillow\n\
+         fn pillowz_reptile(okay: u8) -> &'static str {\n    \"repitles\" // rustt\n}\n",
+    )?;
+
+    // Doc comments and identifiers are checked, but not code or plain comments
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "*.rs", "-d", DICT_PATH]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::diff("lib.rs:2:4: pillowz\n"));
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "*.rs", "--strings", "-d", DICT_PATH]);
+    cmd.assert().failure().stdout(predicate::str::diff(
+        "lib.rs:2:4: pillowz\nlib.rs:3:6: repitles\n",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn check_structured() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(
+        dir.path().join("strings.json"),
+        "{\"pillowz\": \"okay repitles\",\n \"id\": \"bananna\"}\n",
+    )?;
+    fs::write(
+        dir.path().join("strings.yml"),
+        "# pillowz\nmenu:\n  title: okay repitles\n  id: bananna\n",
+    )?;
+
+    // Only string values are checked, not keys or comments
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "strings.json", "strings.yml", "-d", DICT_PATH]);
+    cmd.assert().failure().stdout(predicate::str::diff(
+        "strings.json:1:19: repitles\nstrings.json:2:9: bananna\n\
+         strings.yml:3:15: repitles\nstrings.yml:4:7: bananna\n",
+    ));
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "strings.json", "strings.yml", "--key", "id"])
+        .args(["-d", DICT_PATH]);
+    cmd.assert().failure().stdout(predicate::str::diff(
+        "strings.json:2:9: bananna\nstrings.yml:4:7: bananna\n",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn check_exit_codes() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(dir.path().join("a.txt"), "okay repitles pillowz\n")?;
+    let check = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("zspell").unwrap();
+        cmd.current_dir(dir.path())
+            .env("XDG_CONFIG_HOME", user_cfg.path())
+            .arg("check")
+            .args(args);
+        cmd.assert()
+    };
+
+    // Misspellings within the budget pass
+    check(&["a.txt", "--max-errors", "2", "-d", DICT_PATH]).success();
+    check(&["a.txt", "--max-errors", "1", "-d", DICT_PATH]).code(1);
+    check(&["a.txt", "--error-exit-code", "10", "-d", DICT_PATH]).code(10);
+
+    // Other failures have their own codes
+    check(&["a.txt", "-d", "no/such/dict"])
+        .code(3)
+        .stderr(predicate::str::contains("Error loading dictionary"));
+    check(&["*.md", "-d", DICT_PATH]).code(2);
+    fs::write(dir.path().join("b.txt"), [0xff, 0xfe])?;
+    check(&["a.txt", "b.txt", "-d", DICT_PATH])
+        .code(4)
+        .stdout(predicate::str::contains("a.txt:1:6: repitles"));
+    check(&["a.txt", "--max-errors", "-1", "-d", DICT_PATH]).code(2);
+
+    Ok(())
+}
+
+#[test]
+fn check_jobs() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    let mut expected = String::new();
+    for idx in 0..30 {
+        fs::write(
+            dir.path().join(format!("{idx:02}.txt")),
+            "okay repitles\npillowz\n",
+        )?;
+        expected.push_str(&format!(
+            "./{idx:02}.txt:1:6: repitles\n./{idx:02}.txt:2:1: pillowz\n"
+        ));
+    }
+
+    // Output is grouped by file and in order, however many jobs run
+    for jobs in ["1", "4", "64"] {
+        let mut cmd = Command::cargo_bin("zspell")?;
+        cmd.current_dir(dir.path())
+            .env("XDG_CONFIG_HOME", user_cfg.path())
+            .args(["check", ".", "--jobs", jobs, "-d", DICT_PATH]);
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::diff(expected.clone()))
+            .stderr(predicate::str::contains(
+                "found 60 misspelling(s) in 30 of 30 file(s)",
+            ));
+    }
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", ".", "-j", "0", "-d", DICT_PATH]);
+    cmd.assert().failure().stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn check_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(dir.path().join("a.txt"), "pillowz\n")?;
+
+    // Stdin is checked after any files
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "-", "a.txt", "-d", DICT_PATH])
+        .write_stdin("okay repitles\n\nbananna\n");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::diff(
+            "a.txt:1:1: pillowz\n<stdin>:1:6: repitles\n<stdin>:3:1: bananna\n",
+        ))
+        .stderr(predicate::str::contains(
+            "found 3 misspelling(s) in 2 of 2 file(s)",
+        ));
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "-", "--fix", "-d", DICT_PATH]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with stdin"));
+
+    Ok(())
+}
+
+#[test]
+fn check_stdin_streaming() -> Result<(), Box<dyn std::error::Error>> {
+    let user_cfg = tempdir()?;
+    let mut child = std::process::Command::cargo_bin("zspell")?
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "-", "-d", DICT_PATH])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    // Each line's results are printed before stdin is closed
+    let mut line = String::new();
+    for (input, expected) in [
+        ("repitles\n", "<stdin>:1:1: repitles\n"),
+        ("okay\nbananna\n", "<stdin>:3:1: bananna\n"),
+    ] {
+        stdin.write_all(input.as_bytes())?;
+        stdin.flush()?;
+        line.clear();
+        stdout.read_line(&mut line)?;
+        assert_eq!(line, expected);
+    }
+
+    drop(stdin);
+    assert!(!child.wait()?.success());
+    Ok(())
+}
+
+#[test]
+fn check_progress() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(dir.path().join("a.txt"), "okay repitles\n")?;
+    fs::write(dir.path().join("b.txt"), "okay reptiles\n")?;
+
+    // Progress goes to stderr, leaving the misspellings on stdout unchanged
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", ".", "--progress", "-d", DICT_PATH]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::diff("./a.txt:1:6: repitles\n"))
+        .stderr(
+            predicate::str::contains("checking file 1/2, 0 B of 28 B (0%)")
+                .and(predicate::str::contains("checking file 2/2"))
+                .and(predicate::str::contains(
+                    "found 1 misspelling(s) in 1 of 2 file(s)",
+                )),
+        );
+
+    Ok(())
+}
+
+#[test]
+fn check_sarif() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::create_dir(dir.path().join("my docs"))?;
+    fs::write(
+        dir.path().join("my docs").join("a.txt"),
+        "okay\nrust rustt\n",
+    )?;
+    fs::write(dir.path().join("b.txt"), "okay reptiles\n")?;
+
+    // All results are printed as one document once checking is done
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", ".", "--format", "sarif", "-d", DICT_PATH]);
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let log: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "zspell");
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "misspelled-word");
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "misspelled-word");
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "my%20docs/a.txt");
+    assert_eq!(
+        location["region"],
+        serde_json::json!({ "startLine": 2, "startColumn": 6, "endColumn": 11 })
+    );
+    let fix = &results[0]["fixes"][0]["artifactChanges"][0]["replacements"][0];
+    assert_eq!(fix["insertedContent"]["text"], "rust");
+
+    // Without misspellings the document has no results
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "b.txt", "--format", "sarif", "-d", DICT_PATH]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let log: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(log["runs"][0]["results"], serde_json::json!([]));
+
+    // Results from stdin have a placeholder uri
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "-", "--format", "sarif", "-d", DICT_PATH])
+        .write_stdin("okay rustt\n");
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let log: serde_json::Value = serde_json::from_slice(&output)?;
+    let location = &log["runs"][0]["results"][0]["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "stdin");
+    assert_eq!(location["region"]["startColumn"], 6);
+
+    // Only `check` can write SARIF
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["--format", "sarif", "-d", DICT_PATH])
+        .write_stdin("repitles\n");
+    cmd.assert().code(2).stderr(predicate::str::contains(
+        "only the plain and JSON formats can be used without `zspell check`",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn check_github() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(dir.path().join("a.txt"), "okay\nrust rustt\n")?;
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", ".", "--format", "github", "-d", DICT_PATH]);
+    cmd.assert().failure().stdout(predicate::str::diff(
+        "::warning file=a.txt,line=2,col=6,endColumn=11,title=Misspelled word::\
+        'rustt' may be misspelled. Did you mean 'rust', 'rusts'?\n",
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn check_template() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(dir.path().join("a.txt"), "okay\nrust rustt\n")?;
+
+    // A template selects the template format
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "a.txt", "-d", DICT_PATH])
+        .args(["--template", "{file}:{line}:{col}: {word} -> {suggestions}"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::diff("a.txt:2:6: rustt -> rust, rusts\n"));
+
+    // It can also come from a config file
+    fs::write(
+        dir.path().join("zspell.toml"),
+        "format = \"template\"\ntemplate = '{word}\\t{line}'\n",
+    )?;
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "-", "-d", DICT_PATH])
+        .write_stdin("rustt\n");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::diff("rustt\t1\n"));
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "a.txt", "--no-config", "--format", "template"])
+        .args(["-d", DICT_PATH]);
+    cmd.assert().code(2).stderr(predicate::str::contains(
+        "the template format needs `--template`",
+    ));
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "a.txt", "--template", "{words}", "-d", DICT_PATH]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("unknown placeholder '{words}'"));
+
+    Ok(())
+}
+
+#[test]
+fn check_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(dir.path().join("a.txt"), "rustt okay\nrustt bananna\n")?;
+    fs::write(dir.path().join("b.txt"), "okay rust\n")?;
+    fs::write(dir.path().join("c.txt"), "bananna okay\n")?;
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", ".", "--summary", "-d", DICT_PATH]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::diff(
+            "checked 8 word(s) in 3 file(s), found 4 misspelling(s) of 2 word(s) in 2 file(s)\n\
+            \n\
+            most frequent misspellings:\n  \
+            2  bananna\n  \
+            2  rustt\n\
+            \n\
+            files with the most misspellings:\n  \
+            3  ./a.txt\n  \
+            1  ./c.txt\n",
+        ))
+        .stderr(predicate::str::contains(
+            "found 4 misspelling(s) in 2 of 3 file(s)",
+        ));
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["--format", "json", "check", "b.txt", "-", "--summary"])
+        .args(["-d", DICT_PATH])
+        .write_stdin("rustt\nokay rustt\n");
+    cmd.assert().failure().stdout(predicate::str::diff(
+        "{\"files_checked\":2,\"words_checked\":5,\"misspellings\":2,\
+        \"words\":[{\"word\":\"rustt\",\"count\":2}],\
+        \"files\":[{\"file\":\"<stdin>\",\"count\":2}]}\n",
+    ));
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args([
+            "check",
+            ".",
+            "--summary",
+            "--format",
+            "sarif",
+            "-d",
+            DICT_PATH,
+        ]);
+    cmd.assert().code(2);
+
+    Ok(())
+}
+
+#[test]
+fn check_ignore_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    for sub in ["docs", "target", "node_modules", ".github", ".git"] {
+        fs::create_dir(dir.path().join(sub))?;
+        fs::write(dir.path().join(sub).join("a.md"), "repitles\n")?;
+    }
+    fs::write(dir.path().join(".gitignore"), "/target\n")?;
+    fs::write(dir.path().join(".ignore"), "node_modules/\n")?;
+
+    let check = |extra: &[&str]| -> Result<_, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("zspell")?;
+        cmd.current_dir(dir.path())
+            .env("XDG_CONFIG_HOME", user_cfg.path())
+            .args(["check", ".", "-d", DICT_PATH, "--format", "template"])
+            .args(["--template", "{file}"])
+            .args(extra);
+        Ok(String::from_utf8(
+            cmd.assert().failure().get_output().stdout.clone(),
+        )?)
+    };
+
+    // Ignored and hidden directories are skipped, and `.git` always is
+    assert_eq!(check(&[])?, "./docs/a.md\n");
+    assert_eq!(check(&["--hidden"])?, "./.github/a.md\n./docs/a.md\n");
+    assert_eq!(
+        check(&["--no-ignore"])?,
+        "./docs/a.md\n./node_modules/a.md\n./target/a.md\n"
+    );
+    assert_eq!(
+        check(&["--hidden", "--no-ignore"])?,
+        "./.github/a.md\n./docs/a.md\n./node_modules/a.md\n./target/a.md\n"
+    );
+
+    // Paths given directly are always checked
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["check", "target", "-d", DICT_PATH]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::diff("target/a.md:1:1: repitles\n"));
+
+    Ok(())
+}
@@ -1 +1,130 @@
 //! Tests for the dictionary command line interface
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+const DICT_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../zspell/tests/files/w1_eng_short"
+);
+
+#[test]
+fn project_config() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(dir.path().join("words.txt"), "zspell\n")?;
+    fs::write(
+        dir.path().join("zspell.toml"),
+        format!(
+            "dictionary = '{DICT_PATH}'\npersonal = 'words.txt'\nignore = ['^[A-Z]+$']\nformat = 'json'\n"
+        ),
+    )?;
+    let subdir = dir.path().join("docs");
+    fs::create_dir(&subdir)?;
+
+    // The project config is found from a subdirectory
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(&subdir)
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .write_stdin("zspell bananas NASA\nrepitles pillow\n");
+    cmd.assert().success().stdout(predicate::str::diff(
+        "{\"line\":2,\"column\":1,\"word\":\"repitles\"}\n",
+    ));
+
+    // Command line options override the config
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(&subdir)
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["--format", "plain", "--ignore", "^rep"])
+        .write_stdin("zspell NASA repitles pillow\n");
+    cmd.assert().success().stdout(predicate::str::diff(""));
+
+    // Config files can be skipped entirely
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(&subdir)
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["--no-config", "-d", DICT_PATH])
+        .write_stdin("zspell NASA\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("zspell\nNASA\n"));
+
+    Ok(())
+}
+
+#[test]
+fn project_words() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let user_cfg = tempdir()?;
+    fs::write(dir.path().join(".zspell-words"), "kubectl\n")?;
+    fs::write(dir.path().join("words.txt"), "zspell\n")?;
+    let subdir = dir.path().join("docs");
+    fs::create_dir(&subdir)?;
+
+    // Project words are merged with the personal dictionary
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(&subdir)
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["-d", DICT_PATH, "-p", "../words.txt"])
+        .write_stdin("kubectl zspell kubernetes\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("kubernetes\n"));
+
+    // An empty name disables them
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(&subdir)
+        .env("XDG_CONFIG_HOME", user_cfg.path())
+        .args(["-d", DICT_PATH, "--words-file", ""])
+        .write_stdin("kubectl\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("kubectl\n"));
+
+    Ok(())
+}
+
+#[test]
+fn skip_patterns() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let cfg = dir.path().join("custom.toml");
+    fs::write(
+        &cfg,
+        format!("dictionary = '{DICT_PATH}'\nskip = ['\\b[A-Z]+-\\d+\\b']\nskip_literal = ['wasm-bindgen']\n"),
+    )?;
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.arg("--config")
+        .arg(&cfg)
+        .write_stdin("pillow JIRA-1234 wasm-bindgen repitles JIRA\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("repitles\nJIRA\n"));
+
+    fs::write(&cfg, "skip = ['(']\n")?;
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.arg("--config").arg(&cfg).write_stdin("");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid skip pattern"));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_config() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let cfg = dir.path().join("custom.toml");
+    fs::write(&cfg, "ignore = ['(']\n")?;
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.arg("--config").arg(&cfg).write_stdin("");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid ignore pattern"));
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Tests for the dictionary download command line interface
+
+use std::fs;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[test]
+fn fetch_cached() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    for ext in ["aff", "dic", "lic"] {
+        fs::write(dir.path().join(format!("de-AT.{ext}")), "")?;
+    }
+
+    // Everything is in the cache so this should not touch the index
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.arg("fetch")
+        .arg("de-AT")
+        .arg("--index-url")
+        .arg("http://127.0.0.1:1/index.json")
+        .arg("--cache-dir")
+        .arg(dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("de-AT.aff").and(predicate::str::contains("de-AT.dic")));
+
+    Ok(())
+}
+
+#[test]
+fn fetch_unreachable() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.arg("fetch")
+        .arg("de-AT")
+        .arg("--index-url")
+        .arg("http://127.0.0.1:1/index.json")
+        .arg("--cache-dir")
+        .arg(dir.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("request to"));
+
+    Ok(())
+}
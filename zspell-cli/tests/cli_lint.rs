@@ -0,0 +1,70 @@
+//! Tests for the `lint` subcommand
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[test]
+fn lint_reports_problems() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let aff = dir.path().join("test.aff");
+    let dic = dir.path().join("test.dic");
+
+    fs::write(
+        &aff,
+        "SET UTF-8\n\
+        SFX S Y 1\n\
+        SFX S 0 s .\n\
+        \n\
+        SFX D Y 1\n\
+        SFX D 0 ed .\n\
+        \n\
+        PFX S Y 1\n\
+        PFX S 0 re .\n\
+        \n\
+        MAP 1\n\
+        MAP (ab\n\
+        WORDLIST foo\n\
+        PSEUDOROOT N\n",
+    )?;
+    fs::write(&dic, b"3\ncat/S\ncaf\xe9\ncat/S\ndog/X\n")?;
+
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .args(["lint", "test.aff", "test.dic"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::diff(
+            "test.aff:5: affix flag 'D' is not used by any word or affix\n\
+            test.aff:8: affix flag 'S' is already defined\n\
+            test.aff:12: unclosed '(' in map entry\n\
+            test.aff:13: unknown directive 'WORDLIST'\n\
+            test.aff:14: directive 'PSEUDOROOT' is deprecated, use NEEDAFFIX\n\
+            test.dic:1: expected 3 entries but got 4\n\
+            test.dic:3: text is not valid UTF-8, but the affix file declares `SET UTF-8`\n\
+            test.dic:4: entry 'cat' repeats the entry on line 2\n\
+            test.dic:5: entry 'dog' has flag 'X', which the affix file does not define\n",
+        ))
+        .stderr(predicate::str::contains("found 9 problem(s)"));
+
+    // Text in the declared charset that is also valid UTF-8 is suspicious
+    fs::write(&aff, "SET ISO8859-1\nSFX S Y 1\nSFX S 0 s .\n")?;
+    fs::write(&dic, "3\ncat/S\ncafé\nnaïve\n")?;
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .args(["lint", "test.aff", "test.dic"]);
+    cmd.assert().failure().stdout(predicate::str::diff(
+        "test.dic:3: text looks like UTF-8, but the affix file declares `SET ISO8859-1` \
+        (1 more line(s))\n",
+    ));
+
+    fs::write(&aff, "SET UTF-8\nSFX S Y 1\nSFX S 0 s .\n")?;
+    let mut cmd = Command::cargo_bin("zspell")?;
+    cmd.current_dir(dir.path())
+        .args(["lint", "test.aff", "test.dic"]);
+    cmd.assert().success().stdout(predicate::str::diff(""));
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! Benchmarks for parsing affix files and expanding affixes, eagerly or on
+//! demand, using the `en_US` and `hu_HU` dictionaries. `hu_HU` has thousands
+//! of affix rules, so it stresses rule parsing and condition matching far more
+//! than `en_US`.
+//!
+//! Dictionaries that have not been downloaded with `update_dictionaries.py`
+//! are skipped.
+
+use std::fs;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use zspell::DictBuilder;
+
+const LANGS: &[&str] = &["en_US", "hu_HU"];
+
+/// The affix and dictionary files for `lang`, if they exist
+fn load(lang: &str) -> Option<(String, String)> {
+    let aff = fs::read_to_string(format!("../dictionaries/{lang}.aff")).ok()?;
+    let dic = fs::read_to_string(format!("../dictionaries/{lang}.dic")).ok()?;
+    Some((aff, dic))
+}
+
+/// Parse the affix file and compile its rules, with an empty word list
+pub fn bench_parse_affix(c: &mut Criterion) {
+    for (lang, (aff, _)) in LANGS.iter().filter_map(|lang| Some((lang, load(lang)?))) {
+        c.bench_function(&format!("Parse affix: {lang}"), |b| {
+            b.iter(|| {
+                black_box(
+                    DictBuilder::new()
+                        .config_str(black_box(&aff))
+                        .dict_str("")
+                        .build()
+                        .unwrap(),
+                )
+            })
+        });
+    }
+}
+
+/// Build the full dictionary, which checks every rule condition against every
+/// stem that has its flag
+pub fn bench_expand_affixes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Expand affixes");
+    group.sample_size(10);
+    for (lang, (aff, dic)) in LANGS.iter().filter_map(|lang| Some((lang, load(lang)?))) {
+        group.bench_function(*lang, |b| {
+            b.iter(|| {
+                black_box(
+                    DictBuilder::new()
+                        .config_str(black_box(&aff))
+                        .dict_str(black_box(&dic))
+                        .build()
+                        .unwrap(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Build the dictionary without expanding affixes, then check a sample of
+/// affixed words, which strips their affixes on demand
+pub fn bench_lazy_affixes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Lazy affixes");
+    group.sample_size(10);
+    for (lang, (aff, dic)) in LANGS.iter().filter_map(|lang| Some((lang, load(lang)?))) {
+        let build = || {
+            DictBuilder::new()
+                .config_str(&aff)
+                .dict_str(&dic)
+                .lazy_affixes(true)
+                .build()
+                .unwrap()
+        };
+        group.bench_function(format!("build {lang}"), |b| b.iter(|| black_box(build())));
+
+        // Every word of the eager expansion, so most lookups need stripping
+        let eager = DictBuilder::new()
+            .config_str(&aff)
+            .dict_str(&dic)
+            .build()
+            .unwrap();
+        let words: Vec<_> = eager
+            .word_forms()
+            .map(|form| form.word)
+            .step_by(50)
+            .collect();
+        let dict = build();
+        group.bench_function(format!("check {lang}"), |b| {
+            b.iter(|| {
+                for word in &words {
+                    black_box(dict.check_word(black_box(word)));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    affix,
+    bench_parse_affix,
+    bench_expand_affixes,
+    bench_lazy_affixes
+);
+criterion_main!(affix);
@@ -0,0 +1,57 @@
+//! Benchmarks for the spellcheck hot path. Unlike `dict_integration`, these only
+//! need the small test dictionary so they can always be run.
+
+use std::fs;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use zspell::{DictBuilder, Dictionary};
+
+const TEXT: &str = "Bananas and pines grow near the pillows. BANANA reptile Reptiles
+pined pillowed; Pine, PILLOW? misspelled Banana's bananna reptiles pine";
+
+fn fixture_dict() -> Dictionary {
+    let aff_content = fs::read_to_string("tests/files/w1_eng_short.aff").unwrap();
+    let dic_content = fs::read_to_string("tests/files/w1_eng_short.dic").unwrap();
+
+    DictBuilder::new()
+        .config_str(&aff_content)
+        .dict_str(&dic_content)
+        .build()
+        .unwrap()
+}
+
+pub fn bench_check_word(c: &mut Criterion) {
+    let dict = fixture_dict();
+
+    c.bench_function("Check word: lowercase", |b| {
+        b.iter(|| black_box(dict.check_word(black_box("reptiles"))))
+    });
+
+    c.bench_function("Check word: capitalized", |b| {
+        b.iter(|| black_box(dict.check_word(black_box("Reptiles"))))
+    });
+
+    c.bench_function("Check word: uppercase", |b| {
+        b.iter(|| black_box(dict.check_word(black_box("PILLOWS"))))
+    });
+
+    c.bench_function("Check word: non-ASCII incorrect", |b| {
+        b.iter(|| black_box(dict.check_word(black_box("Ünderstöod"))))
+    });
+}
+
+pub fn bench_check_text(c: &mut Criterion) {
+    let dict = fixture_dict();
+    let text = TEXT.repeat(100);
+
+    let mut group = c.benchmark_group("Check text");
+    group.throughput(Throughput::Bytes(text.len().try_into().unwrap()));
+    group.bench_function("check_indices", |b| {
+        b.iter(|| black_box(dict.check_indices(black_box(&text)).count()))
+    });
+    group.finish();
+}
+
+criterion_group!(check, bench_check_word, bench_check_text);
+criterion_main!(check);
@@ -0,0 +1,45 @@
+//! Benchmarks for matching affix rule conditions, which happens for every rule
+//! of every flag when affixes are expanded or stripped
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use zspell::bench::Condition;
+use zspell::RuleType;
+
+const WORDS: &[&str] = &[
+    "the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog", "happy", "carry", "study",
+    "employ", "play", "stay", "boy", "cherry", "fly", "wishes", "boxes", "buzz", "église",
+    "réseau", "über", "naïve",
+];
+
+const CONDITIONS: &[(&str, RuleType)] = &[
+    ("y", RuleType::Suffix),
+    ("[^aeiou]y", RuleType::Suffix),
+    ("[sxz]", RuleType::Suffix),
+    ("[^é]", RuleType::Suffix),
+    ("qu", RuleType::Prefix),
+    ("y.[sxz]", RuleType::Prefix),
+];
+
+/// Match each condition against a list of words, by character and by grapheme
+pub fn bench_conditions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Affix condition");
+    for (cond, kind) in CONDITIONS {
+        let by_char = Condition::new(cond, *kind).unwrap().unwrap();
+        let by_grapheme = by_char.to_graphemes();
+        for (mode, cond_obj) in [("chars", by_char), ("graphemes", by_grapheme)] {
+            group.bench_function(format!("{cond} {kind:?} {mode}"), |b| {
+                b.iter(|| {
+                    for word in WORDS {
+                        black_box(black_box(&cond_obj).matches(black_box(word)));
+                    }
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(condition, bench_conditions);
+criterion_main!(condition);
@@ -86,7 +86,9 @@ pub fn bench_parsers(c: &mut Criterion) {
     c.bench_function("Parse dict file", |b| {
         b.iter(|| {
             black_box(
-                DictEntry::parse_all(black_box(&dic_content), black_box(FlagType::Utf8)).unwrap(),
+                DictEntry::parse_numbered(black_box(&dic_content), black_box(FlagType::Utf8))
+                    .unwrap()
+                    .0,
             )
         })
     });
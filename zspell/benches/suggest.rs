@@ -0,0 +1,37 @@
+//! Benchmarks for suggestions, mostly the edit distance that scores every word
+//! in the list against a misspelling
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use zspell::bench::EditDistance;
+
+const TEXT: &str = "Bananas and pines grow near the pillows while reptiles bask in the
+afternoon sun. Accommodation for travellers is available throughout the region,
+although international visitors should expect considerable bureaucratic
+procedures, particularly regarding telecommunications and transportation";
+
+pub fn bench_edit_distance(c: &mut Criterion) {
+    let words: Vec<&str> = TEXT
+        .split(|ch: char| !ch.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let mut group = c.benchmark_group("Edit distance");
+    group.throughput(Throughput::Elements(words.len().try_into().unwrap()));
+    for misspelling in ["pilows", "acommodation", "telecomunications", "ünderstöod"] {
+        group.bench_function(misspelling, |b| {
+            b.iter(|| {
+                let distance = EditDistance::new(black_box(misspelling));
+                words
+                    .iter()
+                    .filter(|word| distance.within(word, 1).is_some())
+                    .count()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(suggest, bench_edit_distance);
+criterion_main!(suggest);
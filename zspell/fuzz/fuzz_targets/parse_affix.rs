@@ -0,0 +1,14 @@
+//! Parse affix files assembled from plausible lines
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zspell::fuzz::AffixFile;
+
+fuzz_target!(|aff: AffixFile| {
+    // Errors are fine, panics are not
+    let _ = zspell::builder()
+        .config_str(&aff.to_string())
+        .dict_str("")
+        .build();
+});
@@ -0,0 +1,22 @@
+//! Parse `.dic` files and expand them with the rules of an affix file
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zspell::fuzz::{AffixFile, DicFile};
+
+fuzz_target!(|input: (AffixFile, DicFile)| {
+    let (aff, dic) = input;
+    let dic = dic.to_string();
+    let Ok(dict) = zspell::builder()
+        .config_str(&aff.to_string())
+        .dict_str(&dic)
+        .build()
+    else {
+        return;
+    };
+
+    for line in dic.lines() {
+        dict.check(line);
+    }
+});
@@ -1,20 +1,62 @@
 //! Representation of an affix file
 
+mod condition;
+mod conv;
 mod node;
 mod parse;
 mod rule;
 mod types;
 
-use std::collections::BTreeMap;
-use std::sync::Arc;
-
-pub use self::parse::{affix_from_str, AffixNode, ParsedRuleGroup};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+pub use self::condition::Condition;
+pub use self::conv::{ConvTable, RepTable};
+#[cfg(feature = "unstable-bench")]
+pub use self::parse::affix_from_str;
+use self::parse::parse_affix;
+pub use self::parse::{AffixNode, ParseMode, ParsedRuleGroup};
 pub use self::types::{
-    CompoundPattern, CompoundSyllable, Conversion, Encoding, FlagType, PartOfSpeech, Phonetic,
-    RuleType,
+    CompoundPattern, CompoundRule, CompoundSyllable, Conversion, Encoding, FlagType, PartOfSpeech,
+    Phonetic, RuleElement, RuleType,
 };
 use crate::dict::{AfxRule, Flag, FlagValue};
-use crate::error::{BuildError, Error, ParseError};
+use crate::error::{BuildError, BuildWarning, Error, ParseError};
+use crate::trace;
+
+/// Directives that are parsed but that checking and suggestions do not honor
+/// yet, so dictionaries using them may accept or suggest the wrong words
+const UNSUPPORTED_DIRECTIVES: &[&str] = &[
+    // General
+    "IGNORE",
+    "AF",
+    "AM",
+    // Suggestions
+    "KEY",
+    "TRY",
+    "WARN",
+    "FORBIDWARN",
+    "MAXNGRAMSUGS",
+    "MAXDIFF",
+    "ONLYMAXDIFF",
+    "NOSPLITSUGS",
+    // Compounds
+    "COMPOUNDROOT",
+    "COMPOUNDFORBIDFLAG",
+    "COMPOUNDMORESUFFIXES",
+    "CHECKCOMPOUNDPATTERN",
+    // Affixes and other options
+    "KEEPCASE",
+    "SUBSTANDARD",
+    "WORDCHARS",
+    "CHECKSHARPS",
+    "OCONV",
+];
 
 /// A representation of an affix file
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -53,7 +95,7 @@ pub struct ParsedCfg {
     neighbor_keys: Vec<String>,
 
     // Rules for suggestion replacements to try
-    replacements: Vec<Conversion>,
+    replacements: RepTable,
 
     /// Suggest words that differ by 1 try character
     try_characters: String,
@@ -73,8 +115,9 @@ pub struct ParsedCfg {
     /// Whether to never suggest words with the warn flag (above)
     forbid_warn_words: bool,
 
-    /// Replace commonly misused letters, e.g. `u`/`ü`
-    maps: Vec<(char, char)>,
+    /// Groups of related characters that are commonly confused, e.g. `uúü`. A
+    /// group item may be more than one character, e.g. `ß(ss)`
+    maps: Vec<Vec<String>>,
 
     /// Phonetic replacements for similar words
     phonetics: Vec<Phonetic>,
@@ -108,9 +151,12 @@ pub struct ParsedCfg {
     afx_circumflex_flag: Option<Flag>,
     forbidden_word_flag: Option<Flag>,
     afx_full_strip: bool,
+    /// Whether conditions and strings to strip are handled by grapheme cluster,
+    /// see `DictBuilder::grapheme_affixes`
+    afx_graphemes: bool,
     afx_keep_case_flag: Option<Flag>,
-    input_conversions: Vec<Conversion>,
-    output_conversions: Vec<Conversion>,
+    input_conversions: ConvTable,
+    output_conversions: ConvTable,
     afx_needed_flag: Option<Flag>,
     afx_substandard_flag: Option<Flag>,
     afx_word_chars: String,
@@ -118,20 +164,27 @@ pub struct ParsedCfg {
     name: String,
     home_page: String,
     version: String,
+    /// Directives in the file from [`UNSUPPORTED_DIRECTIVES`], in order of
+    /// first appearance
+    unsupported: Vec<&'static str>,
+    /// Lines that were skipped because they could not be parsed
+    issues: Vec<ParseError>,
+    /// Deprecated directives in the file, as [`BuildWarning::Deprecated`]
+    deprecated: Vec<BuildWarning>,
 }
 
 /// Separated structure for compound rules
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CompoundConfig {
     /// Something like `-` to indicate whether both sides should be checked
-    /// Prefer COMPOUNDRULE instead
+    /// Prefer COMPOUNDRULE instead. Hunspell's default is `-`, `^-` and `-$`.
     break_separators: Vec<String>,
 
     /// Maximum compound word suggestions
     sug_max: u16,
 
     /// Regex-like rules for compound words
-    rules: Vec<String>,
+    rules: Vec<CompoundRule>,
 
     /// Minimum length of words used in a compound
     min_length: u16,
@@ -156,6 +209,7 @@ pub struct CompoundConfig {
     forbid_flag: Option<Flag>,
     more_suffixes: bool,
     root_flag: Option<Flag>,
+    /// Maximum number of words in a compound, 0 for no limit
     word_max: u16,
     forbid_dup: bool,
     forbid_repeat: bool,
@@ -164,8 +218,10 @@ pub struct CompoundConfig {
     simplify_triple: bool,
     forbid_pats: Vec<CompoundPattern>,
     force_upper_flag: Option<Flag>,
+    /// Syllable limit for compounds longer than `word_max`
     syllable: CompoundSyllable,
-    syllable_num: String,
+    /// Suffixes with these flags add to the syllable count
+    syllable_num: Vec<Flag>,
 }
 
 impl Default for ParsedCfg {
@@ -198,6 +254,7 @@ impl Default for ParsedCfg {
             afx_circumflex_flag: Default::default(),
             forbidden_word_flag: Default::default(),
             afx_full_strip: Default::default(),
+            afx_graphemes: Default::default(),
             afx_keep_case_flag: Default::default(),
             input_conversions: Default::default(),
             output_conversions: Default::default(),
@@ -208,6 +265,9 @@ impl Default for ParsedCfg {
             name: Default::default(),
             home_page: Default::default(),
             version: Default::default(),
+            unsupported: Default::default(),
+            issues: Default::default(),
+            deprecated: Default::default(),
         }
     }
 }
@@ -216,7 +276,7 @@ impl Default for CompoundConfig {
     #[allow(clippy::default_trait_access)]
     fn default() -> Self {
         Self {
-            break_separators: Default::default(),
+            break_separators: vec!["-".to_owned(), "^-".to_owned(), "-$".to_owned()],
             sug_max: 3,
             rules: Default::default(),
             min_length: 3,
@@ -243,11 +303,203 @@ impl Default for CompoundConfig {
     }
 }
 
+impl CompoundConfig {
+    /// Whether any of the flags that allow words in compounds are set
+    pub(crate) fn uses_flags(&self) -> bool {
+        self.flag.is_some()
+            || self.begin_flag.is_some()
+            || self.middle_flag.is_some()
+            || self.end_flag.is_some()
+    }
+
+    /// Patterns where words may be split so each part is checked, `BREAK`. A
+    /// leading `^` or trailing `$` anchors a pattern to the start or end.
+    pub(crate) fn break_separators(&self) -> &[String] {
+        &self.break_separators
+    }
+
+    /// Patterns of flags that compounds may follow, `COMPOUNDRULE`
+    pub(crate) fn rules(&self) -> &[CompoundRule] {
+        &self.rules
+    }
+
+    /// Flag for words and affixes that may only appear inside compounds,
+    /// `ONLYINCOMPOUND`
+    pub(crate) fn only_flag(&self) -> Option<Flag> {
+        self.only_flag
+    }
+
+    /// Minimum number of characters in each part of a compound, `COMPOUNDMIN`
+    pub(crate) fn min_length(&self) -> usize {
+        // Hunspell treats a minimum of 0 as 1
+        usize::from(self.min_length.max(1))
+    }
+
+    /// Maximum number of words in a compound, `COMPOUNDWORDMAX`. Unlimited if
+    /// not set.
+    pub(crate) fn word_max(&self) -> Option<usize> {
+        (self.word_max > 0).then_some(usize::from(self.word_max))
+    }
+
+    /// Whether a word may not be repeated in a compound, `CHECKCOMPOUNDDUP`
+    pub(crate) fn check_dup(&self) -> bool {
+        self.forbid_dup
+    }
+
+    /// Whether compounds that are a `REP` replacement away from a dictionary
+    /// word are rejected, `CHECKCOMPOUNDREP`
+    pub(crate) fn check_rep(&self) -> bool {
+        self.forbid_repeat
+    }
+
+    /// Whether uppercase letters are forbidden at part boundaries,
+    /// `CHECKCOMPOUNDCASE`
+    pub(crate) fn check_case(&self) -> bool {
+        self.check_case
+    }
+
+    /// Whether three identical letters are forbidden across part boundaries,
+    /// `CHECKCOMPOUNDTRIPLE`
+    pub(crate) fn check_triple(&self) -> bool {
+        self.check_triple
+    }
+
+    /// Whether a tripled letter at a boundary may be written as two,
+    /// `SIMPLIFIEDTRIPLE`
+    pub(crate) fn simplify_triple(&self) -> bool {
+        self.simplify_triple
+    }
+
+    /// Maximum number of syllables in a compound with more than `word_max`
+    /// parts, `COMPOUNDSYLLABLE`. Such compounds are rejected if not set.
+    pub(crate) fn syllable_max(&self) -> Option<usize> {
+        let count = self.syllable.count();
+        (count > 0).then_some(usize::from(count))
+    }
+
+    /// Number of vowels in `s`, used as its syllable count
+    pub(crate) fn count_syllables(&self, s: &str) -> usize {
+        s.chars().filter(|ch| self.syllable.is_vowel(*ch)).count()
+    }
+
+    /// Flags of suffixes that count towards the syllables of a compound,
+    /// `SYLLABLENUM`
+    pub(crate) fn syllable_num(&self) -> &[Flag] {
+        &self.syllable_num
+    }
+
+    /// Maximum number of compound suggestions, `MAXCPDSUGS`
+    #[cfg(feature = "unstable-suggestions")]
+    pub(crate) fn sug_max(&self) -> usize {
+        usize::from(self.sug_max)
+    }
+}
+
 impl ParsedCfg {
     pub fn flag_type(&self) -> FlagType {
         self.flag_type
     }
 
+    /// Lines that were skipped because they could not be parsed
+    pub(crate) fn issues(&self) -> &[ParseError] {
+        &self.issues
+    }
+
+    /// Deprecated directives in the file, each listed once
+    pub(crate) fn deprecated(&self) -> &[BuildWarning] {
+        &self.deprecated
+    }
+
+    /// Directives in the file that are parsed but not honored yet
+    pub(crate) fn unsupported(&self) -> &[&'static str] {
+        &self.unsupported
+    }
+
+    /// Language code from `LANG`, e.g. `en_US`
+    pub(crate) fn lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Conversions applied to input words, `ICONV`
+    pub(crate) fn input_conversions(&self) -> &ConvTable {
+        &self.input_conversions
+    }
+
+    /// Common misspellings from the `REP` table
+    pub(crate) fn replacements(&self) -> &RepTable {
+        &self.replacements
+    }
+
+    /// Whether suggestions keep the trailing dots of the word, `SUGSWITHDOTS`
+    #[cfg(feature = "unstable-suggestions")]
+    pub(crate) fn sugs_with_dots(&self) -> bool {
+        self.keep_term_dots
+    }
+
+    /// Rules from the `PHONE` table
+    pub(crate) fn phonetics(&self) -> &[Phonetic] {
+        &self.phonetics
+    }
+
+    /// Configuration for compound words
+    pub(crate) fn compound_config(&self) -> &CompoundConfig {
+        &self.compound_config
+    }
+
+    /// Whether affix rules may strip the entire stem, `FULLSTRIP`
+    pub(crate) fn full_strip(&self) -> bool {
+        self.afx_full_strip
+    }
+
+    /// Whether affix rules work on grapheme clusters rather than characters
+    pub(crate) fn graphemes(&self) -> bool {
+        self.afx_graphemes
+    }
+
+    /// Match affix conditions and strip strings by grapheme cluster
+    pub(crate) fn use_graphemes(&mut self) {
+        self.afx_graphemes = true;
+        for rule in self.afx_rule_groups.iter_mut().flat_map(|g| &mut g.rules) {
+            if let Some(cond) = &mut rule.condition {
+                *cond = cond.to_graphemes();
+            }
+        }
+    }
+
+    /// Whether prefixes may be stripped twice, `COMPLEXPREFIXES`
+    pub(crate) fn complex_prefixes(&self) -> bool {
+        self.complex_prefixes
+    }
+
+    /// Flags that keep an affixed word from standing on its own when they are an
+    /// affix's continuation flags, `NEEDAFFIX` and `CIRCUMFIX`
+    pub(crate) fn incomplete_affix_flags(&self) -> impl Iterator<Item = Flag> {
+        self.afx_needed_flag
+            .into_iter()
+            .chain(self.afx_circumflex_flag)
+    }
+
+    /// Flags with a meaning that are not in [`compile_flags`](Self::compile_flags):
+    /// those of `COMPOUNDRULE`s and `CIRCUMFIX`
+    pub(crate) fn other_flags(&self) -> impl Iterator<Item = Flag> + '_ {
+        self.compound_config
+            .rules
+            .iter()
+            .flat_map(|rule| rule.elements().iter().map(|el| el.flag()))
+            .chain(self.afx_circumflex_flag)
+    }
+
+    /// Whether `.dic` flags are numbers of `AF` aliases rather than flags
+    pub(crate) fn uses_affix_aliases(&self) -> bool {
+        !self.affix_alias.is_empty()
+    }
+
+    /// Groups of related characters from `MAP`
+    #[cfg(feature = "unstable-suggestions")]
+    pub(crate) fn maps(&self) -> &[Vec<String>] {
+        &self.maps
+    }
+
     /// Create a `Config` object from a string version of an affix file
     ///
     /// # Errors
@@ -256,15 +508,29 @@ impl ParsedCfg {
     /// invalid
     #[inline]
     pub fn load_from_str(s: &str) -> Result<Self, Error> {
-        Self::from_parsed(affix_from_str(s)?)
+        Self::load_with_mode(s, ParseMode::Strict)
+    }
+
+    /// Like [`load_from_str`](Self::load_from_str), but with a choice of how to
+    /// handle malformed directives
+    pub(crate) fn load_with_mode(s: &str, mode: ParseMode) -> Result<Self, Error> {
+        trace::enter_span!(DEBUG, "parse_affix", bytes = s.len(), ?mode);
+        let mut issues = Vec::new();
+        let nodes = parse_affix(s, mode, &mut issues)?;
+        let mut cfg = Self::from_parsed(nodes, mode)?;
+        issues.append(&mut cfg.issues);
+        cfg.issues = issues;
+        trace::event!(
+            DEBUG,
+            rule_groups = cfg.afx_rule_groups.len(),
+            "parsed affix file"
+        );
+        Ok(cfg)
     }
 
     #[allow(clippy::unnecessary_wraps)]
-    #[allow(clippy::too_many_lines)]
-    fn from_parsed(v: Vec<AffixNode>) -> Result<Self, Error> {
+    fn from_parsed(v: Vec<AffixNode>, mode: ParseMode) -> Result<Self, Error> {
         let mut res = Self::default();
-        let mut warnings: Vec<String> = Vec::new();
-
         if let Some(node) = v.iter().find(|node| matches!(node, AffixNode::FlagType(_))) {
             if let AffixNode::FlagType(v) = node {
                 res.flag_type = *v;
@@ -275,106 +541,143 @@ impl ParsedCfg {
 
         for node in v {
             let name_str = node.name_str();
-            match node {
-                AffixNode::Encoding(v) => res.encoding = v,
-                AffixNode::FlagType(_) => (),
-                AffixNode::ComplexPrefixes => res.complex_prefixes = true,
-                AffixNode::Language(v) => res.lang = v,
-                AffixNode::IgnoreChars(v) => res.ignore_chars = v,
-                AffixNode::AffixAlias(v) => res.affix_alias = v,
-                AffixNode::MorphAlias(v) => res.morph_alias = v,
-                AffixNode::NeighborKeys(v) => res.neighbor_keys = v,
-                AffixNode::TryCharacters(v) => res.try_characters = v,
-                AffixNode::NoSuggestFlag(v) => res.nosuggest_flag = Some(res.convert_flag(&v)?),
-                AffixNode::CompoundSugMax(v) => res.compound_config.sug_max = v,
-                AffixNode::NGramSugMax(v) => res.ngram_sug_max = v,
-                AffixNode::NGramDiffMax(v) => res.ngram_diff_max = v,
-                AffixNode::NGramLimitToDiffMax => res.ngram_limit_to_diff_max = true,
-                AffixNode::NoSplitSuggestions => res.no_split_suggestions = true,
-                AffixNode::KeepTermDots => res.keep_term_dots = true,
-                AffixNode::Replacement(v) => res.replacements = v,
-                AffixNode::Mapping(v) => res.maps = v,
-                AffixNode::Phonetic(v) => res.phonetics = v,
-                AffixNode::WarnRareFlag(v) => res.warn_rare_flag = Some(res.convert_flag(&v)?),
-                AffixNode::ForbidWarnWords => res.forbid_warn_words = true,
-                AffixNode::BreakSeparator(v) => res.compound_config.break_separators = v,
-                AffixNode::CompoundRule(v) => res.compound_config.rules = v,
-                AffixNode::CompoundMinLen(v) => res.compound_config.min_length = v,
-                AffixNode::CompoundFlag(v) => {
-                    res.compound_config.flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::CompoundBeginFlag(v) => {
-                    res.compound_config.begin_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::CompoundEndFlag(v) => {
-                    res.compound_config.end_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::CompoundMiddleFlag(v) => {
-                    res.compound_config.middle_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::CompoundOnlyFlag(v) => {
-                    res.compound_config.only_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::CompoundPermitFlag(v) => {
-                    res.compound_config.permit_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::CompoundForbidFlag(v) => {
-                    res.compound_config.forbid_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::CompoundMoreSuffixes => res.compound_config.more_suffixes = true,
-                AffixNode::CompoundRootFlag(v) => {
-                    res.compound_config.root_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::CompoundWordMax(v) => res.compound_config.word_max = v,
-                AffixNode::CompoundForbidDup => res.compound_config.forbid_dup = true,
-                AffixNode::CompoundForbidRepeat => res.compound_config.forbid_repeat = true,
-                AffixNode::CompoundCheckCase => res.compound_config.check_case = true,
-                AffixNode::CompoundCheckTriple => res.compound_config.check_triple = true,
-                AffixNode::CompoundSimplifyTriple => res.compound_config.simplify_triple = true,
-                AffixNode::CompoundForbidPats(v) => res.compound_config.forbid_pats = v,
-                AffixNode::CompoundForceUpFlag(v) => {
-                    res.compound_config.force_upper_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::CompoundSyllable(v) => res.compound_config.syllable = v,
-                AffixNode::SyllableNum(v) => res.compound_config.syllable_num = v,
-                AffixNode::Prefix(v) => res.afx_rule_groups.push(v),
-                AffixNode::Suffix(v) => res.afx_rule_groups.push(v),
-                AffixNode::AfxCircumfixFlag(v) => {
-                    res.afx_circumflex_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::ForbiddenWordFlag(v) => {
-                    res.forbidden_word_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::AfxFullStrip => res.afx_full_strip = true,
-                AffixNode::AfxKeepCaseFlag(v) => {
-                    res.afx_keep_case_flag = Some(res.convert_flag(&v)?);
-                }
-                AffixNode::AfxInputConversion(v) => res.input_conversions = v,
-                AffixNode::AfxOutputConversion(v) => res.output_conversions = v,
-                AffixNode::AfxLemmaPresentFlag(_) => {
-                    warnings.push(format!("flag {name_str} is deprecated"));
-                }
-                AffixNode::AfxNeededFlag(v) => res.afx_needed_flag = Some(res.convert_flag(&v)?),
-                AffixNode::AfxPseudoRootFlag(_) => {
-                    warnings.push(format!("flag {name_str} is deprecated"));
-                }
-                AffixNode::AfxSubstandardFlag(v) => {
-                    res.afx_substandard_flag = Some(res.convert_flag(&v)?);
+            if UNSUPPORTED_DIRECTIVES.contains(&name_str) && !res.unsupported.contains(&name_str) {
+                res.unsupported.push(name_str);
+            }
+            if let Err(e) = res.apply_node(node) {
+                match mode {
+                    ParseMode::Strict => return Err(e.into()),
+                    ParseMode::Permissive => res.issues.push(e),
                 }
-                AffixNode::AfxWordChars(v) => res.afx_word_chars = v,
-                AffixNode::AfxCheckSharps => res.afx_check_sharps = true,
-                AffixNode::Comment => (),
-                AffixNode::Name(v) => res.name = v,
-                AffixNode::HomePage(v) => res.home_page = v,
-                AffixNode::Version(v) => res.version = v,
             }
         }
 
-        for w in warnings {
-            eprintln!("warning: {w}");
+        Ok(res)
+    }
+
+    /// Apply one directive of the affix file
+    #[allow(clippy::too_many_lines)]
+    fn apply_node(&mut self, node: AffixNode) -> Result<(), ParseError> {
+        match node {
+            AffixNode::Encoding(v) => self.encoding = v,
+            AffixNode::FlagType(_) => (),
+            AffixNode::ComplexPrefixes => self.complex_prefixes = true,
+            AffixNode::Language(v) => self.lang = v,
+            AffixNode::IgnoreChars(v) => self.ignore_chars = v,
+            AffixNode::AffixAlias(v) => self.affix_alias = v,
+            AffixNode::MorphAlias(v) => self.morph_alias = v,
+            AffixNode::NeighborKeys(v) => self.neighbor_keys = v,
+            AffixNode::TryCharacters(v) => self.try_characters = v,
+            AffixNode::NoSuggestFlag(v) => self.nosuggest_flag = Some(self.convert_flag(&v)?),
+            AffixNode::CompoundSugMax(v) => self.compound_config.sug_max = v,
+            AffixNode::NGramSugMax(v) => self.ngram_sug_max = v,
+            AffixNode::NGramDiffMax(v) => self.ngram_diff_max = v,
+            AffixNode::NGramLimitToDiffMax => self.ngram_limit_to_diff_max = true,
+            AffixNode::NoSplitSuggestions => self.no_split_suggestions = true,
+            AffixNode::KeepTermDots => self.keep_term_dots = true,
+            AffixNode::Replacement(v) => self.replacements = RepTable::new(v),
+            AffixNode::Mapping(v) => self.maps = v,
+            AffixNode::Phonetic(v) => self.phonetics = v,
+            AffixNode::WarnRareFlag(v) => self.warn_rare_flag = Some(self.convert_flag(&v)?),
+            AffixNode::ForbidWarnWords => self.forbid_warn_words = true,
+            AffixNode::BreakSeparator(v) => self.compound_config.break_separators = v,
+            AffixNode::CompoundRule(v) => {
+                self.compound_config.rules = v
+                    .iter()
+                    .map(|rule| {
+                        CompoundRule::parse(rule, self.flag_type)
+                            .map_err(|e| ParseError::new_nospan(e, rule))
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            AffixNode::CompoundMinLen(v) => self.compound_config.min_length = v,
+            AffixNode::CompoundFlag(v) => {
+                self.compound_config.flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::CompoundBeginFlag(v) => {
+                self.compound_config.begin_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::CompoundEndFlag(v) => {
+                self.compound_config.end_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::CompoundMiddleFlag(v) => {
+                self.compound_config.middle_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::CompoundOnlyFlag(v) => {
+                self.compound_config.only_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::CompoundPermitFlag(v) => {
+                self.compound_config.permit_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::CompoundForbidFlag(v) => {
+                self.compound_config.forbid_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::CompoundMoreSuffixes => self.compound_config.more_suffixes = true,
+            AffixNode::CompoundRootFlag(v) => {
+                self.compound_config.root_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::CompoundWordMax(v) => self.compound_config.word_max = v,
+            AffixNode::CompoundForbidDup => self.compound_config.forbid_dup = true,
+            AffixNode::CompoundForbidRepeat => self.compound_config.forbid_repeat = true,
+            AffixNode::CompoundCheckCase => self.compound_config.check_case = true,
+            AffixNode::CompoundCheckTriple => self.compound_config.check_triple = true,
+            AffixNode::CompoundSimplifyTriple => self.compound_config.simplify_triple = true,
+            AffixNode::CompoundForbidPats(v) => self.compound_config.forbid_pats = v,
+            AffixNode::CompoundForceUpFlag(v) => {
+                self.compound_config.force_upper_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::CompoundSyllable(v) => self.compound_config.syllable = v,
+            AffixNode::SyllableNum(v) => {
+                self.compound_config.syllable_num = self
+                    .flag_type
+                    .parse_str(&v)
+                    .map_err(|e| ParseError::new_nospan(e, &v))?;
+            }
+            AffixNode::Prefix(v) => self.afx_rule_groups.push(v),
+            AffixNode::Suffix(v) => self.afx_rule_groups.push(v),
+            AffixNode::AfxCircumfixFlag(v) => {
+                self.afx_circumflex_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::ForbiddenWordFlag(v) => {
+                self.forbidden_word_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::AfxFullStrip => self.afx_full_strip = true,
+            AffixNode::AfxKeepCaseFlag(v) => {
+                self.afx_keep_case_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::AfxInputConversion(v) => self.input_conversions = ConvTable::new(v),
+            AffixNode::AfxOutputConversion(v) => self.output_conversions = ConvTable::new(v),
+            AffixNode::AfxLemmaPresentFlag(_) => {
+                self.push_deprecated("LEMMA_PRESENT", None);
+            }
+            AffixNode::AfxNeededFlag(v) => self.afx_needed_flag = Some(self.convert_flag(&v)?),
+            // `PSEUDOROOT` is the old name of `NEEDAFFIX`
+            AffixNode::AfxPseudoRootFlag(v) => {
+                self.push_deprecated("PSEUDOROOT", Some("NEEDAFFIX"));
+                self.afx_needed_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::AfxSubstandardFlag(v) => {
+                self.afx_substandard_flag = Some(self.convert_flag(&v)?);
+            }
+            AffixNode::AfxWordChars(v) => self.afx_word_chars = v,
+            AffixNode::AfxCheckSharps => self.afx_check_sharps = true,
+            AffixNode::Comment => (),
+            AffixNode::Name(v) => self.name = v,
+            AffixNode::HomePage(v) => self.home_page = v,
+            AffixNode::Version(v) => self.version = v,
         }
+        Ok(())
+    }
 
-        Ok(res)
+    /// Note a deprecated directive, unless it has been seen already
+    fn push_deprecated(&mut self, directive: &'static str, replacement: Option<&'static str>) {
+        let warning = BuildWarning::Deprecated {
+            directive,
+            replacement,
+        };
+        if !self.deprecated.contains(&warning) {
+            self.deprecated.push(warning);
+        }
     }
 
     /// Convert a string to the internal flag type
@@ -465,7 +768,7 @@ impl ParsedCfg {
                 .into());
             }
 
-            let rule = AfxRule::from_parsed_group(self, group);
+            let rule = AfxRule::from_parsed_group(self, group)?;
             affix_flags.insert(flag, FlagValue::Rule(Arc::new(rule)));
         }
 
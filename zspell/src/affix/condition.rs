@@ -0,0 +1,297 @@
+//! Affix rule conditions, such as the `[^aeiou]y` in `SFX A y ies [^aeiou]y`
+//!
+//! A condition is a sequence of single characters, `.` for any character, and
+//! bracketed groups `[abc]` or negated groups `[^abc]`. For a prefix it must
+//! match the start of a word, for a suffix the end.
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::mem;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::RuleType;
+use crate::error::ParseErrorKind;
+
+/// One unit of a condition, a character or a grapheme cluster
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum CondUnit<T> {
+    /// `.`
+    Any,
+    Is(T),
+    /// `[abc]`
+    OneOf(Box<[T]>),
+    /// `[^abc]`
+    NoneOf(Box<[T]>),
+}
+
+/// The units of a condition, matched against characters by default or grapheme
+/// clusters if the dictionary is built with
+/// [`DictBuilder::grapheme_affixes`](crate::DictBuilder::grapheme_affixes)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Units {
+    Chars(Box<[CondUnit<char>]>),
+    Graphemes(Box<[CondUnit<Box<str>>]>),
+}
+
+/// A compiled condition for a prefix or suffix rule
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Condition {
+    kind: RuleType,
+    units: Units,
+    /// The condition as written, to compile it again by grapheme
+    source: Box<str>,
+}
+
+impl<T> CondUnit<T> {
+    fn matches<U>(&self, item: &U) -> bool
+    where
+        T: Borrow<U>,
+        U: PartialEq + ?Sized,
+    {
+        match self {
+            Self::Any => true,
+            Self::Is(unit) => unit.borrow() == item,
+            Self::OneOf(units) => units.iter().any(|unit| unit.borrow() == item),
+            Self::NoneOf(units) => !units.iter().any(|unit| unit.borrow() == item),
+        }
+    }
+
+    fn map<U>(self, mut f: impl FnMut(T) -> U) -> CondUnit<U> {
+        let mut map_all = |units: Box<[T]>| units.into_vec().into_iter().map(&mut f).collect();
+        match self {
+            Self::Any => CondUnit::Any,
+            Self::Is(unit) => CondUnit::Is(f(unit)),
+            Self::OneOf(units) => CondUnit::OneOf(map_all(units)),
+            Self::NoneOf(units) => CondUnit::NoneOf(map_all(units)),
+        }
+    }
+
+    fn heap_size(&self) -> usize {
+        match self {
+            Self::OneOf(units) | Self::NoneOf(units) => mem::size_of_val(&**units),
+            Self::Any | Self::Is(_) => 0,
+        }
+    }
+}
+
+impl Condition {
+    /// Parse a condition for a rule of type `kind`. Returns `None` for `.`,
+    /// which matches everything.
+    ///
+    /// # Errors
+    ///
+    /// If a bracketed group is empty or not closed.
+    #[inline]
+    pub fn new(condition: &str, kind: RuleType) -> Result<Option<Self>, ParseErrorKind> {
+        if condition == "." {
+            return Ok(None);
+        }
+
+        let chars = condition
+            .char_indices()
+            .map(|(idx, ch)| &condition[idx..idx + ch.len_utf8()]);
+        let units = parse_units(condition, chars)?
+            .into_iter()
+            .map(|unit| unit.map(|s| s.chars().next().unwrap()))
+            .collect();
+
+        Ok(Some(Self {
+            kind,
+            units: Units::Chars(units),
+            source: condition.into(),
+        }))
+    }
+
+    /// This condition, matching grapheme clusters rather than characters. A
+    /// letter written with combining marks is then a single unit that `.` or a
+    /// group may match.
+    #[inline]
+    #[must_use]
+    pub fn to_graphemes(&self) -> Self {
+        let units = parse_units(&self.source, self.source.graphemes(true))
+            .expect("the condition was already parsed by character")
+            .into_iter()
+            .map(|unit| unit.map(Box::from))
+            .collect();
+
+        Self {
+            kind: self.kind,
+            units: Units::Graphemes(units),
+            source: self.source.clone(),
+        }
+    }
+
+    /// Whether the start (for prefixes) or end (for suffixes) of `word` meets
+    /// this condition
+    #[inline]
+    pub fn matches(&self, word: &str) -> bool {
+        match &self.units {
+            Units::Chars(units) => matches_units::<_, char, _>(self.kind, units, word.chars()),
+            Units::Graphemes(units) => {
+                matches_units::<_, str, _>(self.kind, units, word.graphemes(true))
+            }
+        }
+    }
+
+    /// Estimate the heap memory used by this condition
+    #[inline]
+    pub fn heap_size(&self) -> usize {
+        let units = match &self.units {
+            Units::Chars(units) => {
+                mem::size_of_val(&**units) + units.iter().map(CondUnit::heap_size).sum::<usize>()
+            }
+            Units::Graphemes(units) => {
+                mem::size_of_val(&**units)
+                    + units
+                        .iter()
+                        .map(|unit| {
+                            let strs = match unit {
+                                CondUnit::Is(s) => s.len(),
+                                CondUnit::OneOf(group) | CondUnit::NoneOf(group) => {
+                                    group.iter().map(|s| s.len()).sum()
+                                }
+                                CondUnit::Any => 0,
+                            };
+                            unit.heap_size() + strs
+                        })
+                        .sum::<usize>()
+            }
+        };
+        units + self.source.len()
+    }
+}
+
+/// Split a condition into `.`, single units, and bracketed groups, given the
+/// units of `condition` in order
+fn parse_units<'a>(
+    condition: &str,
+    mut units: impl Iterator<Item = &'a str>,
+) -> Result<Vec<CondUnit<&'a str>>, ParseErrorKind> {
+    let err = || ParseErrorKind::AffixCondition(condition.to_owned());
+    let mut ret = Vec::new();
+    while let Some(unit) = units.next() {
+        let cond = match unit {
+            "." => CondUnit::Any,
+            "[" => {
+                let mut group = Vec::new();
+                let mut negated = false;
+                loop {
+                    match units.next().ok_or_else(err)? {
+                        "]" => break,
+                        "^" if group.is_empty() && !negated => negated = true,
+                        unit => group.push(unit),
+                    }
+                }
+                if group.is_empty() {
+                    return Err(err());
+                }
+                if negated {
+                    CondUnit::NoneOf(group.into())
+                } else {
+                    CondUnit::OneOf(group.into())
+                }
+            }
+            _ => CondUnit::Is(unit),
+        };
+        ret.push(cond);
+    }
+    Ok(ret)
+}
+
+/// Match `units` against the start or end of a word's `items`
+fn matches_units<T, U, I>(kind: RuleType, units: &[CondUnit<T>], mut items: I) -> bool
+where
+    T: Borrow<U>,
+    U: PartialEq + ?Sized,
+    I: DoubleEndedIterator,
+    I::Item: Borrow<U>,
+{
+    match kind {
+        RuleType::Prefix => units.iter().all(|cond| {
+            items
+                .next()
+                .map_or(false, |item| cond.matches(item.borrow()))
+        }),
+        RuleType::Suffix => units.iter().rev().all(|cond| {
+            items
+                .next_back()
+                .map_or(false, |item| cond.matches(item.borrow()))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffix() {
+        let cond = Condition::new("[^aeiou]y", RuleType::Suffix)
+            .unwrap()
+            .unwrap();
+        assert!(cond.matches("xxxy"));
+        assert!(cond.matches("ry"));
+        assert!(!cond.matches("y"));
+        assert!(!cond.matches("xxxay"));
+        assert!(!cond.matches("xxxyxx"));
+    }
+
+    #[test]
+    fn test_prefix() {
+        let cond = Condition::new("y.[sxz]", RuleType::Prefix)
+            .unwrap()
+            .unwrap();
+        assert!(cond.matches("yasxx"));
+        assert!(cond.matches("yéz"));
+        assert!(!cond.matches("ya"));
+        assert!(!cond.matches("xasxx"));
+    }
+
+    #[test]
+    fn test_literal_characters() {
+        // Characters with a special meaning in regex are plain characters here
+        let cond = Condition::new("a-b*", RuleType::Suffix).unwrap().unwrap();
+        assert!(cond.matches("xa-b*"));
+        assert!(!cond.matches("xab"));
+        let cond = Condition::new("[a-]", RuleType::Suffix).unwrap().unwrap();
+        assert!(cond.matches("x-"));
+        assert!(!cond.matches("xb"));
+    }
+
+    #[test]
+    fn test_graphemes() {
+        // "á" as `a` and a combining acute accent
+        let cond = Condition::new("[^a\u{301}]", RuleType::Suffix)
+            .unwrap()
+            .unwrap();
+        assert!(!cond.matches("ba"));
+        assert!(!cond.matches("ba\u{301}"));
+
+        // By grapheme the group holds one letter, so a plain `a` is not in it
+        let cond = cond.to_graphemes();
+        assert!(cond.matches("ba"));
+        assert!(!cond.matches("ba\u{301}"));
+        assert!(cond.matches("bo\u{301}"));
+
+        // The accent counts as its own character unless grouped
+        let cond = Condition::new("x..", RuleType::Prefix).unwrap().unwrap();
+        assert!(cond.matches("xa\u{301}"));
+        let cond = cond.to_graphemes();
+        assert!(!cond.matches("xa\u{301}"));
+        assert!(cond.matches("xa\u{301}b"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        for cond in ["[ab", "a[b", "[]", "[^]"] {
+            assert_eq!(
+                Condition::new(cond, RuleType::Suffix),
+                Err(ParseErrorKind::AffixCondition(cond.to_owned()))
+            );
+        }
+        assert_eq!(Condition::new(".", RuleType::Prefix), Ok(None));
+    }
+}
@@ -0,0 +1,195 @@
+//! Conversion and replacement tables (`ICONV`, `OCONV`, and `REP`), which are
+//! matched with an Aho-Corasick automaton built when the affix file is loaded
+//! rather than by scanning the word once per pair
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use aho_corasick::{AhoCorasick, MatchKind};
+
+use super::Conversion;
+
+/// A table of conversions such as `ICONV`. Wherever inputs overlap, the one
+/// that starts first wins, then the longest.
+#[derive(Clone, Debug, Default)]
+pub struct ConvTable {
+    convs: Vec<Conversion>,
+    /// Output for each pattern of `matcher`
+    outputs: Vec<Box<str>>,
+    /// `None` if there is nothing to convert
+    matcher: Option<AhoCorasick>,
+}
+
+/// One entry of a `REP` table, with its anchors removed and `_` turned into a
+/// space
+#[derive(Clone, Debug)]
+struct Rep {
+    output: Box<str>,
+    at_start: bool,
+    at_end: bool,
+}
+
+/// The `REP` table of common misspellings. Any entry may apply at any place
+/// that its input occurs, including places that overlap.
+#[derive(Clone, Debug, Default)]
+pub struct RepTable {
+    convs: Vec<Conversion>,
+    /// Entry for each pattern of `matcher`
+    reps: Vec<Rep>,
+    /// `None` if there are no usable entries
+    matcher: Option<AhoCorasick>,
+}
+
+impl ConvTable {
+    pub fn new(convs: Vec<Conversion>) -> Self {
+        let mut patterns: Vec<&str> = Vec::new();
+        let mut outputs: Vec<Box<str>> = Vec::new();
+        for conv in convs.iter().filter(|conv| !conv.input().is_empty()) {
+            // If an input is repeated, the last conversion for it is used
+            if let Some(idx) = patterns.iter().position(|pat| *pat == conv.input()) {
+                outputs[idx] = conv.output().into();
+            } else {
+                patterns.push(conv.input());
+                outputs.push(conv.output().into());
+            }
+        }
+        let matcher = build_matcher(&patterns, MatchKind::LeftmostLongest);
+
+        Self {
+            convs,
+            outputs,
+            matcher,
+        }
+    }
+
+    /// Apply the conversions to `word`, only allocating if anything changed
+    pub fn convert<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        let Some(matcher) = &self.matcher else {
+            return Cow::Borrowed(word);
+        };
+        let mut found = matcher.find_iter(word).peekable();
+        if found.peek().is_none() {
+            return Cow::Borrowed(word);
+        }
+
+        let mut out = String::with_capacity(word.len());
+        let mut last = 0;
+        for mat in found {
+            out.push_str(&word[last..mat.start()]);
+            out.push_str(&self.outputs[mat.pattern()]);
+            last = mat.end();
+        }
+        out.push_str(&word[last..]);
+        Cow::Owned(out)
+    }
+}
+
+impl RepTable {
+    pub fn new(convs: Vec<Conversion>) -> Self {
+        let mut patterns = Vec::new();
+        let mut reps = Vec::new();
+        for conv in &convs {
+            // `^` and `$` anchor the pattern, `_` stands for a space
+            let input = conv.input();
+            let at_start = input.starts_with('^');
+            let at_end = input.len() > 1 && input.ends_with('$');
+            let input = input.trim_start_matches('^').trim_end_matches('$');
+            if input.is_empty() {
+                continue;
+            }
+            patterns.push(input.replace('_', " "));
+            reps.push(Rep {
+                output: conv.output().replace('_', " ").into(),
+                at_start,
+                at_end,
+            });
+        }
+        let matcher = build_matcher(&patterns, MatchKind::Standard);
+
+        Self {
+            convs,
+            reps,
+            matcher,
+        }
+    }
+
+    /// Every word made by applying one entry at one place in `word`
+    pub fn candidates<'a>(&'a self, word: &'a str) -> impl Iterator<Item = String> + 'a {
+        self.matcher
+            .iter()
+            .flat_map(move |matcher| matcher.find_overlapping_iter(word))
+            .filter_map(move |mat| {
+                let rep = &self.reps[mat.pattern()];
+                if (rep.at_start && mat.start() != 0) || (rep.at_end && mat.end() != word.len()) {
+                    return None;
+                }
+                let (head, tail) = (&word[..mat.start()], &word[mat.end()..]);
+                let mut cand = String::with_capacity(head.len() + rep.output.len() + tail.len());
+                cand.push_str(head);
+                cand.push_str(&rep.output);
+                cand.push_str(tail);
+                Some(cand)
+            })
+    }
+}
+
+/// Build an automaton for `patterns`, or `None` if there are none
+fn build_matcher<P: AsRef<[u8]>>(patterns: &[P], kind: MatchKind) -> Option<AhoCorasick> {
+    if patterns.is_empty() {
+        return None;
+    }
+    let matcher = AhoCorasick::builder()
+        .match_kind(kind)
+        .build(patterns)
+        .expect("affix tables are far below the automaton size limits");
+    Some(matcher)
+}
+
+/* Tables are equal if they were built from the same entries */
+
+impl PartialEq for ConvTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.convs == other.convs
+    }
+}
+
+impl Eq for ConvTable {}
+
+impl PartialEq for RepTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.convs == other.convs
+    }
+}
+
+impl Eq for RepTable {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn test_rep_candidates() {
+        let table = RepTable::new(vec![
+            Conversion::new("aa", "a", false),
+            Conversion::new("aa", "e", false),
+            Conversion::new("^x", "y", false),
+            Conversion::new("z$", "s", false),
+            Conversion::new("a_b", "ab", false),
+        ]);
+
+        let mut cands: Vec<_> = table.candidates("aaa").collect();
+        cands.sort_unstable();
+        // Overlapping places and repeated inputs are all tried
+        assert_eq!(cands, ["aa", "aa", "ae", "ea"]);
+
+        assert_eq!(table.candidates("xax").collect::<Vec<_>>(), ["yax"]);
+        assert_eq!(table.candidates("zaz").collect::<Vec<_>>(), ["zas"]);
+        assert_eq!(table.candidates("a b").collect::<Vec<_>>(), ["ab"]);
+        assert_eq!(table.candidates("bcd").count(), 0);
+        assert_eq!(RepTable::default().candidates("aaa").count(), 0);
+    }
+}
@@ -1,5 +1,8 @@
 //! Parser representations of an affix file
 
+use alloc::string::String;
+use alloc::vec::Vec;
+
 use super::ParsedRuleGroup;
 use crate::affix::{CompoundPattern, CompoundSyllable, Conversion, Encoding, FlagType, Phonetic};
 
@@ -51,7 +54,7 @@ pub enum AffixNode {
     /// `REP`
     Replacement(Vec<Conversion>),
     /// `MAP`
-    Mapping(Vec<(char, char)>),
+    Mapping(Vec<Vec<String>>),
     /// `PHONE`
     Phonetic(Vec<Phonetic>),
     /// `WARN`
@@ -72,7 +75,7 @@ pub enum AffixNode {
     CompoundFlag(String),
     /// `COMPOUNDBEGIN`
     CompoundBeginFlag(String),
-    /// `COMPOUNDLAST`
+    /// `COMPOUNDEND`, or `COMPOUNDLAST`
     CompoundEndFlag(String),
     /// `COMPOUNDMIDDLE`
     CompoundMiddleFlag(String),
@@ -181,7 +184,7 @@ impl AffixNode {
             AffixNode::CompoundMinLen(_) => "COMPOUNDMIN",
             AffixNode::CompoundFlag(_) => "COMPOUNDFLAG",
             AffixNode::CompoundBeginFlag(_) => "COMPOUNDBEGIN",
-            AffixNode::CompoundEndFlag(_) => "COMPOUNDLAST",
+            AffixNode::CompoundEndFlag(_) => "COMPOUNDEND",
             AffixNode::CompoundMiddleFlag(_) => "COMPOUNDMIDDLE",
             AffixNode::CompoundOnlyFlag(_) => "ONLYINCOMPOUND",
             AffixNode::CompoundPermitFlag(_) => "COMPOUNDPERMITFLAG",
@@ -2,13 +2,15 @@
 //!
 //! Contains various munchers for all possible affix keys
 
-use std::num::ParseIntError;
-use std::str::FromStr;
-use std::sync::Arc;
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::num::ParseIntError;
+use core::str::FromStr;
 
-use lazy_static::lazy_static;
 pub use node::AffixNode;
-use regex::Regex;
 pub use rule::{ParsedRule, ParsedRuleGroup};
 
 use super::{node, rule};
@@ -32,25 +34,6 @@ const LINE_TERMINATORS: [char; 2] = ['\r', '\n'];
 /// - `Err(e)`: error while parsing
 type ParseResult<'a> = Result<Option<(AffixNode, &'a str, u32)>, ParseError>;
 
-lazy_static! {
-    static ref RE_AFX_RULE_HEADER: Regex = Regex::new(
-        r"(?x)
-        ^(?P<flag>\S+)\s+
-        (?P<xprod>\w+)\s+
-        (?P<num>\d+)$"
-    )
-    .unwrap();
-    static ref RE_AFX_RULE_BODY: Regex = Regex::new(
-        r"(?x)
-        ^(?P<flag>\S+)\s+
-        (?P<strip_chars>\S+)\s+
-        (?P<affix>\S+)\s+
-        (?P<condition>\S+)
-        (?:$|\s+(?P<morph>.+)$)"
-    )
-    .unwrap();
-}
-
 /*
     Parser Helpers
 */
@@ -212,12 +195,8 @@ where
         return Ok(None);
     };
 
-    let header_caps = RE_AFX_RULE_HEADER
-        .captures(work)
+    let (flag, xprod, count) = split_afx_header(work)
         .ok_or_else(|| ParseError::new_nospan(ParseErrorKind::AffixHeader, work))?;
-    let count: u32 = header_caps.name("num").unwrap().as_str().parse().unwrap();
-    let flag = header_caps.name("flag").unwrap().as_str();
-    let xprod = header_caps.name("xprod").unwrap().as_str();
     let can_combine = parse_xprod(xprod)?;
     let kind: RuleType = key.try_into().unwrap();
 
@@ -229,27 +208,25 @@ where
         match line_splitter(residual, key) {
             Some((content, resid)) => {
                 residual = resid;
-                let line_groups = RE_AFX_RULE_BODY.captures(content).ok_or_else(|| {
+                let fields = split_afx_body(content).ok_or_else(|| {
                     ParseError::new_nocol(ParseErrorKind::AffixBody, content, nlines)
                 })?;
 
-                let line_flag = line_groups.name("flag").unwrap().as_str();
-                if line_flag != flag {
+                if fields.flag != flag {
                     return Err(ParseError::new_nocol(
                         ParseErrorKind::AffixFlagMismatch(flag.to_owned()),
                         content,
                         nlines,
                     ));
                 }
-                let strip = line_groups.name("strip_chars").unwrap().as_str();
-                let affix = line_groups.name("affix").unwrap().as_str();
-                let cond = line_groups.name("condition").unwrap().as_str();
-                let morph_info = line_groups.name("morph").map_or_else(Vec::new, |m| {
-                    MorphInfo::many_from_str(m.as_str()).map(Arc::new).collect()
+                let morph_info = fields.morph.map_or_else(Vec::new, |morph| {
+                    MorphInfo::many_from_str(morph).map(Arc::new).collect()
                 });
 
-                let push = ParsedRule::new_parse(kind, affix, strip, cond, morph_info)
-                    .map_err(|e| ParseError::new_nocol(e, cond, nlines))?;
+                let cond = fields.condition;
+                let push =
+                    ParsedRule::new_parse(kind, fields.affix, fields.strip, cond, morph_info)
+                        .map_err(|e| ParseError::new_nocol(e, cond, nlines))?;
 
                 rules.push(push);
             }
@@ -273,6 +250,52 @@ where
     Ok(Some((f(ret), residual, nlines)))
 }
 
+/// Split an affix table header, `flag cross_product count`
+fn split_afx_header(s: &str) -> Option<(&str, &str, u32)> {
+    let mut fields = s.split_whitespace();
+    let (flag, xprod, count) = (fields.next()?, fields.next()?, fields.next()?);
+    let is_word = xprod.chars().all(|ch| ch.is_alphanumeric() || ch == '_');
+    if fields.next().is_some() || !is_word || !count.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((flag, xprod, count.parse().ok()?))
+}
+
+/// The fields of an affix rule line
+struct AfxRuleFields<'a> {
+    flag: &'a str,
+    strip: &'a str,
+    affix: &'a str,
+    condition: &'a str,
+    /// Everything after the condition
+    morph: Option<&'a str>,
+}
+
+/// Split an affix rule line, `flag strip affix condition [morph info...]`
+fn split_afx_body(s: &str) -> Option<AfxRuleFields<'_>> {
+    /// Take the next whitespace-separated field from `s`
+    fn next_field<'a>(s: &mut &'a str) -> Option<&'a str> {
+        let trimmed = s.trim_start();
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        *s = &trimmed[end..];
+        (end > 0).then(|| &trimmed[..end])
+    }
+
+    let mut rest = s;
+    let flag = next_field(&mut rest)?;
+    let strip = next_field(&mut rest)?;
+    let affix = next_field(&mut rest)?;
+    let condition = next_field(&mut rest)?;
+    let morph = rest.trim();
+    Some(AfxRuleFields {
+        flag,
+        strip,
+        affix,
+        condition,
+        morph: (!morph.is_empty()).then_some(morph),
+    })
+}
+
 /// Create a table error at line `idx + 1`
 fn table_count_err(ctx: &str, expected: u32, line_no: u32) -> ParseError {
     ParseError::new_nocol(
@@ -428,20 +451,43 @@ fn parse_mapping(s: &str) -> ParseResult {
     table_parser(s, "MAP", |v| {
         let mut res = Vec::new();
         for (i, item) in v.iter().enumerate() {
-            // Expect two chars
-            let mut chars = item.chars().fuse();
-            let c1 = chars.next();
-            let c2 = chars.next();
-            let push = c1.zip(c2).ok_or_else(|| {
-                let ecount = [c1, c2].iter().filter(|c| c.is_some()).count();
-                ParseError::new_nocol(ParseErrorKind::Char(2, ecount), item, i + 1)
-            })?;
-
-            res.push(push);
+            let group = parse_map_group(item).map_err(|e| ParseError::new_nocol(e, item, i + 1))?;
+            res.push(group);
         }
         Ok(AffixNode::Mapping(res))
     })
 }
+
+/// Split a single `MAP` entry into its related items. Each character is its own
+/// item, unless it is part of a parenthesized group like `(ss)`.
+fn parse_map_group(s: &str) -> Result<Vec<String>, ParseErrorKind> {
+    let mut res = Vec::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '(' {
+            let mut group = String::new();
+            loop {
+                match chars.next() {
+                    Some(')') => break,
+                    Some(gc) => group.push(gc),
+                    None => return Err(ParseErrorKind::MapGroupUnclosed),
+                }
+            }
+            if !group.is_empty() {
+                res.push(group);
+            }
+        } else {
+            res.push(c.to_string());
+        }
+    }
+
+    if res.len() < 2 {
+        return Err(ParseErrorKind::Char(2, res.len()));
+    }
+
+    Ok(res)
+}
 fn parse_phonetic(s: &str) -> ParseResult {
     table_parser(s, "PHONE", |v| {
         let mut res = Vec::new();
@@ -477,7 +523,7 @@ fn parse_compound_rule(s: &str) -> ParseResult {
         for (i, item) in v.iter().enumerate() {
             check_contains_whitespace(item).map_err(|e| e.add_offset_ret(i + 1, 0))?;
         }
-        Ok(AffixNode::BreakSeparator(v))
+        Ok(AffixNode::CompoundRule(v))
     })
 }
 fn parse_compound_min_length(s: &str) -> ParseResult {
@@ -490,6 +536,11 @@ fn parse_compound_begin_flag(s: &str) -> ParseResult {
     flag_parser(s, "COMPOUNDBEGIN", AffixNode::CompoundBeginFlag)
 }
 fn parse_compound_end_flag(s: &str) -> ParseResult {
+    // `COMPOUNDLAST` is the older name of `COMPOUNDEND`
+    let node = flag_parser(s, "COMPOUNDEND", AffixNode::CompoundEndFlag)?;
+    if node.is_some() {
+        return Ok(node);
+    }
     flag_parser(s, "COMPOUNDLAST", AffixNode::CompoundEndFlag)
 }
 fn parse_compound_middle_flag(s: &str) -> ParseResult {
@@ -697,40 +748,169 @@ const ALL_PARSERS: [for<'a> fn(&'a str) -> ParseResult; 61] = [
     parse_version,
 ];
 
+/// How to handle malformed directives in an affix file, see
+/// [`DictBuilder::parse_mode`](crate::DictBuilder::parse_mode)
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ParseMode {
+    /// Fail on the first malformed directive
+    #[default]
+    Strict,
+    /// Skip malformed directives and keep going, recording each as an issue.
+    /// Only the first prefix or suffix group with a given flag is kept.
+    Permissive,
+}
+
 /// Main parser entrypoint
 #[inline]
 #[allow(clippy::missing_errors_doc)]
+#[cfg(any(test, feature = "unstable-bench"))]
 pub fn affix_from_str(s: &str) -> Result<Vec<AffixNode>, ParseError> {
+    parse_affix(s, ParseMode::Strict, &mut Vec::new())
+}
+
+/// Parse an affix file. Lines that are not a known directive are skipped and
+/// added to `issues`. In [`ParseMode::Permissive`], so are the lines of
+/// malformed directives and prefix or suffix groups that reuse an earlier
+/// group's flag; otherwise those are an error.
+pub fn parse_affix(
+    s: &str,
+    mode: ParseMode,
+    issues: &mut Vec<ParseError>,
+) -> Result<Vec<AffixNode>, ParseError> {
     let mut working = s;
     let mut ret: Vec<AffixNode> = Vec::new();
     let mut nlines: u32 = 1;
+    let mut rule_flags: BTreeSet<String> = BTreeSet::new();
 
     'outer: while !working.is_empty() {
         for parse_fn in &ALL_PARSERS {
-            let tmp = parse_fn(working).map_err(|e| e.add_offset_ret(nlines, 0))?;
-            if let Some((node, residual, nl)) = tmp {
-                nlines += nl;
-                ret.push(node);
-                let tmp = munch_newline(residual).map_err(|e| e.add_offset_ret(nlines, 0))?;
-                if let Some(resid) = tmp {
-                    nlines += 1;
-                    working = resid;
+            let tmp = match parse_fn(working) {
+                Ok(tmp) => tmp,
+                Err(e) => {
+                    let e = e.add_offset_ret(nlines, 0);
+                    // Errors past the first line are in the rows of a table,
+                    // which are skipped along with its header
+                    let in_table = e.span().map_or(false, |span| span.start().line() > nlines);
+                    let rows = if in_table { table_rows(working) } else { 0 };
+                    recover(mode, issues, e)?;
+                    for _ in 0..rows {
+                        working = skip_line(working).strip_prefix('\n').unwrap_or_default();
+                        nlines += 1;
+                    }
+                    working = skip_line(working);
                     continue 'outer;
                 }
-                // End of string, done parsing
-                break 'outer;
+            };
+            if let Some((node, residual, nl)) = tmp {
+                match &node {
+                    AffixNode::Prefix(group) | AffixNode::Suffix(group)
+                        if mode == ParseMode::Permissive
+                            && !rule_flags.insert(group.flag.clone()) =>
+                    {
+                        let line =
+                            &working[..working.find(LINE_TERMINATORS).unwrap_or(working.len())];
+                        issues.push(ParseError::new_nocol(
+                            ParseErrorKind::DuplicateAffixFlag(group.flag.clone()),
+                            line,
+                            nlines,
+                        ));
+                    }
+                    _ => ret.push(node),
+                }
+                nlines += nl;
+                working = match munch_newline(residual) {
+                    Ok(Some(resid)) => {
+                        nlines += 1;
+                        resid
+                    }
+                    // End of string, done parsing
+                    Ok(None) => break 'outer,
+                    Err(e) => {
+                        recover(mode, issues, e.add_offset_ret(nlines, 0))?;
+                        skip_line(residual)
+                    }
+                };
+                continue 'outer;
             }
         }
 
-        if working.starts_with('\n') {
+        // No directive starts here. Skip newlines and indentation, or else the
+        // rest of the line, which holds something that is not a directive.
+        if let Some(rest) = working.strip_prefix('\n') {
             nlines += 1;
+            working = rest;
+        } else if let Some(rest) = working
+            .strip_prefix(|c: char| c.is_whitespace() || c == '\u{feff}')
+            .map(|rest| rest.trim_start_matches(|c: char| c != '\n' && c.is_whitespace()))
+        {
+            working = rest;
+        } else {
+            let line = &working[..working.find(LINE_TERMINATORS).unwrap_or(working.len())];
+            let directive = line.split_whitespace().next().unwrap_or(line);
+            issues.push(ParseError::new_nocol(
+                ParseErrorKind::UnknownDirective(directive.to_owned()),
+                line,
+                nlines,
+            ));
+            working = &working[line.len()..];
         }
-        working = &working[1..];
     }
 
     Ok(ret)
 }
 
+/// Fail with `err` in strict mode, otherwise record it and carry on
+fn recover(
+    mode: ParseMode,
+    issues: &mut Vec<ParseError>,
+    err: ParseError,
+) -> Result<(), ParseError> {
+    match mode {
+        ParseMode::Strict => Err(err),
+        ParseMode::Permissive => {
+            issues.push(err);
+            Ok(())
+        }
+    }
+}
+
+/// Count the rows of the table whose header starts `s`: up to as many as the
+/// header declares, for as long as they start with the same key
+fn table_rows(s: &str) -> u32 {
+    let mut lines = s.split('\n');
+    let header: Vec<&str> = lines
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect();
+    // Affix rows also repeat the flag, and the count follows the cross product
+    let affix = matches!(header.first(), Some(&("PFX" | "SFX")));
+    let key_len = if affix { 2 } else { 1 };
+    let Some(count) = header
+        .get(key_len + usize::from(affix))
+        .and_then(|count| count.parse().ok())
+    else {
+        return 0;
+    };
+
+    let key = &header[..key_len];
+    let rows = lines
+        .take(count)
+        .take_while(|line| {
+            line.split_whitespace()
+                .take(key_len)
+                .eq(key.iter().copied())
+        })
+        .count();
+    u32::try_from(rows).unwrap_or(u32::MAX)
+}
+
+/// `s` without its first line, keeping the newline that ends it
+fn skip_line(s: &str) -> &str {
+    &s[s.find('\n').unwrap_or(s.len())..]
+}
+
 #[cfg(test)]
 #[path = "tests_parse.rs"]
 mod tests;
@@ -1,8 +1,10 @@
-use std::sync::Arc;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 
-use super::RuleType;
-use crate::error::ParseErrorKind;
-use crate::helpers::{compile_re_pattern, ReWrapper};
+use super::{Condition, RuleType};
+use crate::error::{ParseError, ParseErrorKind};
 use crate::morph::MorphInfo;
 use crate::Error;
 
@@ -30,11 +32,14 @@ pub struct ParsedRule {
     pub(crate) affix: String,
     /// Characters to remove from the beginning or end
     pub(crate) strip: Option<Arc<str>>,
-    /// Regex-based rule for when this rule is true. `None` indicates `.`, i.e.,
+    /// Condition for when this rule applies. `None` indicates `.`, i.e.,
     /// always true
-    pub(crate) condition: Option<ReWrapper>,
+    pub(crate) condition: Option<Condition>,
     /// Morphological information
     pub(crate) morph_info: Vec<Arc<MorphInfo>>,
+    /// Continuation flags written after a `/` in the affix, which allow further
+    /// affixes to be applied to the result
+    pub(crate) cont_flags: Option<String>,
 }
 
 impl ParsedRule {
@@ -46,38 +51,17 @@ impl ParsedRule {
         condition: Option<&str>,
         morph_info: Vec<Arc<MorphInfo>>,
     ) -> Result<Self, Error> {
-        let cond_re = match condition {
-            Some(c) => compile_re_pattern(c, kind)?,
+        let cond = match condition {
+            Some(c) => Condition::new(c, kind).map_err(|e| ParseError::new_nospan(e, c))?,
             None => None,
         };
 
         Ok(Self {
             strip: strip.map(Into::into),
             affix: affix.to_owned(),
-            condition: cond_re,
-            morph_info,
-        })
-    }
-
-    /// Same as `new` but don't modify the regex string
-    #[allow(unused)]
-    pub(crate) fn new_raw_re(
-        kind: RuleType,
-        affix: &str,
-        strip: Option<&str>,
-        condition: Option<&str>,
-        morph_info: Vec<Arc<MorphInfo>>,
-    ) -> Result<Self, Error> {
-        let cond_re = match condition {
-            Some(c) => Some(ReWrapper::new(c)?),
-            None => None,
-        };
-
-        Ok(Self {
-            strip: strip.map(Into::into),
-            affix: affix.to_owned(),
-            condition: cond_re,
+            condition: cond,
             morph_info,
+            cont_flags: None,
         })
     }
 
@@ -89,7 +73,11 @@ impl ParsedRule {
         condition: &str,
         morph_info: Vec<Arc<MorphInfo>>,
     ) -> Result<Self, ParseErrorKind> {
-        let cond_re = compile_re_pattern(condition, kind)?;
+        let cond = Condition::new(condition, kind)?;
+        let (affix, cont_flags) = match affix.split_once('/') {
+            Some((afx, flags)) => (afx, Some(flags.to_owned())),
+            None => (affix, None),
+        };
         let strip_chars = if strip == "0" {
             None
         } else {
@@ -99,8 +87,9 @@ impl ParsedRule {
         Ok(Self {
             strip: strip_chars,
             affix: affix.to_owned(),
-            condition: cond_re,
+            condition: cond,
             morph_info,
+            cont_flags,
         })
     }
 }
@@ -1,8 +1,52 @@
 //! Affix tests
 
+use std::borrow::Cow;
+
 use super::*;
 
 #[test]
 fn test_flagtype_convert_ok() {
     assert_eq!(FlagType::Ascii.str_to_flag("T"), Ok(Flag(84)));
 }
+
+#[test]
+fn test_compound_rule_parse() {
+    let rule = CompoundRule::parse("n*1t?", FlagType::Utf8).unwrap();
+    let elems: Vec<_> = rule
+        .elements()
+        .iter()
+        .map(|e| (e.flag(), e.repeat(), e.optional()))
+        .collect();
+    assert_eq!(
+        elems,
+        [
+            (Flag('n'.into()), true, true),
+            (Flag('1'.into()), false, false),
+            (Flag('t'.into()), false, true),
+        ]
+    );
+
+    let rule = CompoundRule::parse("(aa)*(bb)", FlagType::Long).unwrap();
+    assert_eq!(rule.elements().len(), 2);
+    assert!(rule.elements()[0].repeat());
+
+    assert!(CompoundRule::parse("*n", FlagType::Utf8).is_err());
+    assert!(CompoundRule::parse("(aa", FlagType::Long).is_err());
+}
+
+#[test]
+fn test_convert_all() {
+    let convs = ConvTable::new(vec![
+        Conversion::new("a", "b", false),
+        Conversion::new("aa", "c", false),
+        Conversion::new("’", "'", false),
+    ]);
+    assert!(matches!(convs.convert("xyz"), Cow::Borrowed("xyz")));
+    // The longest match wins, and replacements are not converted again
+    assert_eq!(convs.convert("aaab"), "cbb");
+    assert_eq!(convs.convert("l’a"), "l'b");
+    assert!(matches!(
+        ConvTable::default().convert("aaab"),
+        Cow::Borrowed("aaab")
+    ));
+}
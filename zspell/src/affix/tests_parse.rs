@@ -151,6 +151,25 @@ fn test_table_parser_ok() {
     assert_eq!(parse_replacement(s), Ok(Some((expected, "", 3))));
 }
 
+#[test]
+fn test_parse_mapping() {
+    let s = "MAP 3\nMAP uúü\nMAP ß(ss)\nMAP (ij)ĳ";
+    let expected = AffixNode::Mapping(vec![
+        vec!["u".to_owned(), "ú".to_owned(), "ü".to_owned()],
+        vec!["ß".to_owned(), "ss".to_owned()],
+        vec!["ij".to_owned(), "ĳ".to_owned()],
+    ]);
+    assert_eq!(parse_mapping(s), Ok(Some((expected, "", 3))));
+}
+
+#[test]
+fn test_parse_mapping_err() {
+    let s = "MAP 2\nMAP ab\nMAP ß(ss";
+    let res = parse_mapping(s).unwrap_err();
+    assert_eq!(res.err(), &ParseErrorKind::MapGroupUnclosed);
+    assert_eq!(res.span().unwrap(), &Span::new(2, 0));
+}
+
 #[test]
 fn test_afx_table_parser_err() {
     // check line offset count
@@ -205,7 +224,7 @@ fn test_full_parse() {
             kind: RuleType::Prefix,
             can_combine: false,
             rules: vec![
-                ParsedRule::new_raw_re(
+                ParsedRule::new(
                     RuleType::Prefix,
                     "ar",
                     None,
@@ -220,8 +239,7 @@ fn test_full_parse() {
                     ],
                 )
                 .unwrap(),
-                ParsedRule::new_raw_re(RuleType::Prefix, "br", None, Some("^a.*$"), Vec::new())
-                    .unwrap(),
+                ParsedRule::new(RuleType::Prefix, "br", None, Some("a"), Vec::new()).unwrap(),
             ],
         }),
         AffixNode::Suffix(ParsedRuleGroup {
@@ -229,9 +247,8 @@ fn test_full_parse() {
             kind: RuleType::Suffix,
             can_combine: true,
             rules: vec![
-                ParsedRule::new_raw_re(RuleType::Suffix, "ar", None, None, Vec::new()).unwrap(),
-                ParsedRule::new_raw_re(RuleType::Suffix, "br", None, Some("^.*[^a]$"), Vec::new())
-                    .unwrap(),
+                ParsedRule::new(RuleType::Suffix, "ar", None, None, Vec::new()).unwrap(),
+                ParsedRule::new(RuleType::Suffix, "br", None, Some("[^a]"), Vec::new()).unwrap(),
             ],
         }),
         AffixNode::Replacement(vec![
@@ -244,6 +261,108 @@ fn test_full_parse() {
     assert_eq!(affix_from_str(SAMPLE_AFX_OK), Ok(expected));
 }
 
+#[test]
+fn test_skip_unrecognized_multibyte() {
+    // Unrecognized lines are skipped whole, which must not split a multibyte
+    // character
+    assert_eq!(
+        affix_from_str("é\nFULLSTRIP"),
+        Ok(vec![AffixNode::AfxFullStrip])
+    );
+}
+
+#[test]
+fn test_skip_unrecognized_lines() {
+    let mut issues = Vec::new();
+    let s = "\u{feff}SET UTF-8\r\n  FULLSTRIP\nFOO KEY abc\n\n\tKEY qwerty\n";
+    assert_eq!(
+        parse_affix(s, ParseMode::Strict, &mut issues),
+        Ok(vec![
+            AffixNode::Encoding(Encoding::Utf8),
+            AffixNode::AfxFullStrip,
+            // The `KEY` in the middle of an unknown line is not a directive
+            AffixNode::NeighborKeys(vec!["qwerty".to_owned()]),
+        ])
+    );
+    assert_eq!(
+        issues,
+        [ParseError::new_nocol(
+            ParseErrorKind::UnknownDirective("FOO".to_owned()),
+            "FOO KEY abc",
+            3
+        )]
+    );
+}
+
+#[test]
+fn test_permissive() {
+    let s = "COMPOUNDMIN x\nFULLSTRIP extra\nCOMPOUNDMIN 2\nFULLSTRIP";
+
+    let mut issues = Vec::new();
+    let err = parse_affix(s, ParseMode::Strict, &mut issues).unwrap_err();
+    assert_eq!(err.span().unwrap(), &Span::new(1, 0));
+
+    let mut issues = Vec::new();
+    assert_eq!(
+        parse_affix(s, ParseMode::Permissive, &mut issues),
+        Ok(vec![AffixNode::CompoundMinLen(2), AffixNode::AfxFullStrip])
+    );
+    let spans: Vec<_> = issues.iter().map(|issue| issue.span().unwrap()).collect();
+    assert_eq!(spans, [&Span::new(1, 0), &Span::new(2, 0)]);
+}
+
+#[test]
+fn test_permissive_tables() {
+    // A bad row skips the whole table, while a short table keeps the line after
+    // it
+    let s = "MAP 2\nMAP (ab\nMAP cd\nCOMPOUNDMIN 2\nREP 2\nREP a b\nCOMPOUNDMIN 3\n";
+
+    let mut issues = Vec::new();
+    assert_eq!(
+        parse_affix(s, ParseMode::Permissive, &mut issues),
+        Ok(vec![
+            AffixNode::CompoundMinLen(2),
+            AffixNode::CompoundMinLen(3)
+        ])
+    );
+    let kinds: Vec<_> = issues.iter().map(ParseError::err).collect();
+    assert_eq!(
+        kinds,
+        [
+            &ParseErrorKind::MapGroupUnclosed,
+            &ParseErrorKind::TableCount {
+                expected: 2,
+                actual: 1
+            }
+        ]
+    );
+    let spans: Vec<_> = issues.iter().map(|issue| issue.span().unwrap()).collect();
+    assert_eq!(spans, [&Span::new(2, 0), &Span::new(7, 0)]);
+}
+
+#[test]
+fn test_permissive_duplicate_flag() {
+    let s = "SFX A Y 1\nSFX A 0 s .\nPFX B Y 1\nPFX B 0 un .\nPFX A Y 1\nPFX A 0 re .\n";
+
+    let mut issues = Vec::new();
+    assert_eq!(
+        parse_affix(s, ParseMode::Strict, &mut issues)
+            .unwrap()
+            .len(),
+        3
+    );
+    assert!(issues.is_empty());
+
+    let nodes = parse_affix(s, ParseMode::Permissive, &mut issues).unwrap();
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(
+        issues[0].err(),
+        &ParseErrorKind::DuplicateAffixFlag("A".to_owned())
+    );
+    assert_eq!(issues[0].span().unwrap(), &Span::new(5, 0));
+}
+
 #[test]
 fn test_large_file_parse() {
     let mut aff_path = workspace_root();
@@ -1,30 +1,20 @@
 //! Type representations for affix file contents
 
-use std::fmt::{self, Display};
-use std::str::FromStr;
-
-use lazy_static::lazy_static;
-use regex::Regex;
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::str::FromStr;
 
 use crate::dict::Flag;
 use crate::error::ParseErrorKind;
 use crate::morph::MorphStr;
 
-lazy_static! {
-    static ref RE_COMPOUND_PATTERN: Regex = Regex::new(
-        r"(?x)
-        ^(?P<endchars>\w+)
-        (?:/(?P<endflags>\w+))?\s+
-        (?P<beginchars>\w+)
-        (?:/(?P<beginflag>\w+))?
-        (?P<replacement>\s\w+)?$"
-    )
-    .unwrap();
-}
-
 /// A possible encoding type
 #[non_exhaustive]
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Encoding {
     /// UTF-8 encoding
     Utf8,
@@ -51,6 +41,7 @@ pub enum Encoding {
 /// We represent all flag types as a u32 and provide methods of conversion
 #[non_exhaustive]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FlagType {
     /// Single-character ASCII flags (default, single byte)
     Ascii,
@@ -191,14 +182,19 @@ pub struct CompoundSyllable {
     vowels: String,
 }
 
+/// The kind of an affix rule
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RuleType {
+    /// A `PFX` rule
     Prefix,
+    /// A `SFX` rule
     Suffix,
 }
 
 /// Representation of a part of speech
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PartOfSpeech {
     Noun,
     Verb,
@@ -220,6 +216,20 @@ pub struct Phonetic {
     replace: String,
 }
 
+/// A `COMPOUNDRULE`: the flags of the words in a compound, in order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompoundRule(Vec<RuleElement>);
+
+/// One flag of a [`CompoundRule`], which may be repeated or optional
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RuleElement {
+    flag: Flag,
+    /// Followed by `*`, may match any number of words
+    repeat: bool,
+    /// Followed by `*` or `?`, may match no words
+    optional: bool,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CompoundPattern {
     endchars: String,
@@ -239,6 +249,89 @@ impl Phonetic {
             replace: replace.to_owned(),
         }
     }
+
+    /// The pattern to match, including any `(..)`, `-`, `<`, priority, `^`, or
+    /// `$` modifiers
+    pub(crate) fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The replacement, where `_` means an empty string
+    pub(crate) fn replace(&self) -> &str {
+        &self.replace
+    }
+}
+
+impl CompoundSyllable {
+    /// The maximum number of syllables
+    pub(crate) fn count(&self) -> u16 {
+        self.count
+    }
+
+    /// Whether `ch` is one of the vowels
+    pub(crate) fn is_vowel(&self, ch: char) -> bool {
+        self.vowels.contains(ch)
+    }
+}
+
+impl CompoundRule {
+    /// Parse a rule such as `n*1t`. Flags that are more than one character are
+    /// written in parentheses, e.g. `(aa)*(bb)`.
+    pub(crate) fn parse(value: &str, flag_type: FlagType) -> Result<Self, ParseErrorKind> {
+        let mut ret = Vec::new();
+        let mut chars = value.char_indices();
+
+        while let Some((idx, ch)) = chars.next() {
+            let flag_str = match ch {
+                '(' => {
+                    let start = idx + 1;
+                    let end = chars
+                        .by_ref()
+                        .find(|(_, ch)| *ch == ')')
+                        .ok_or(ParseErrorKind::CompoundRule)?
+                        .0;
+                    &value[start..end]
+                }
+                '*' | '?' => {
+                    let last: &mut RuleElement =
+                        ret.last_mut().ok_or(ParseErrorKind::CompoundRule)?;
+                    if last.optional {
+                        return Err(ParseErrorKind::CompoundRule);
+                    }
+                    last.repeat = ch == '*';
+                    last.optional = true;
+                    continue;
+                }
+                _ => &value[idx..idx + ch.len_utf8()],
+            };
+
+            ret.push(RuleElement {
+                flag: flag_type.str_to_flag(flag_str)?,
+                repeat: false,
+                optional: false,
+            });
+        }
+
+        Ok(Self(ret))
+    }
+
+    pub(crate) fn elements(&self) -> &[RuleElement] {
+        &self.0
+    }
+}
+
+impl RuleElement {
+    pub(crate) fn flag(self) -> Flag {
+        self.flag
+    }
+
+    pub(crate) fn repeat(self) -> bool {
+        self.repeat
+    }
+
+    pub(crate) fn optional(self) -> bool {
+        self.optional
+    }
 }
 
 impl Conversion {
@@ -262,6 +355,16 @@ impl Conversion {
             bidirectional,
         })
     }
+
+    /// The text to replace
+    pub(crate) fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The replacement text
+    pub(crate) fn output(&self) -> &str {
+        &self.output
+    }
 }
 
 /* Trait implementations */
@@ -269,6 +372,7 @@ impl Conversion {
 impl TryFrom<&str> for Encoding {
     type Error = ParseErrorKind;
 
+    #[inline]
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value.to_ascii_lowercase().as_str() {
             "utf-8" => Ok(Self::Utf8),
@@ -319,7 +423,7 @@ impl FromStr for FlagType {
 
 impl Display for FlagType {
     #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let s: &str = self.into();
         write!(f, "{s}")?;
         Ok(())
@@ -357,15 +461,34 @@ impl TryFrom<&str> for CompoundPattern {
     type Error = ParseErrorKind;
 
     fn try_from(value: &str) -> Result<Self, ParseErrorKind> {
-        let caps = RE_COMPOUND_PATTERN
-            .captures(value)
-            .ok_or(ParseErrorKind::CompoundPattern)?;
+        /// Split `chars/flag`, where both are words and the flag is optional
+        fn chars_flag(s: &str) -> Option<(String, Option<String>)> {
+            let is_word =
+                |s: &str| !s.is_empty() && s.chars().all(|ch| ch.is_alphanumeric() || ch == '_');
+            let (chars, flag) = match s.split_once('/') {
+                Some((chars, flag)) => (chars, Some(flag)),
+                None => (s, None),
+            };
+            (is_word(chars) && flag.map_or(true, is_word))
+                .then(|| (chars.to_owned(), flag.map(ToOwned::to_owned)))
+        }
+
+        let mut fields = value.split_whitespace();
+        let (Some(end), Some(begin)) = (fields.next(), fields.next()) else {
+            return Err(ParseErrorKind::CompoundPattern);
+        };
+        let replacement = fields.next();
+        let (Some((endchars, endflag)), Some((beginchars, beginflag)), None) =
+            (chars_flag(end), chars_flag(begin), fields.next())
+        else {
+            return Err(ParseErrorKind::CompoundPattern);
+        };
         Ok(Self {
-            endchars: caps.name("endchars").unwrap().as_str().to_owned(),
-            endflag: caps.name("endflag").map(|m| m.as_str().to_owned()),
-            beginchars: caps.name("beginchars").unwrap().as_str().to_owned(),
-            beginflag: caps.name("beginflag").map(|m| m.as_str().to_owned()),
-            replacement: caps.name("replacement").map(|m| m.as_str().to_owned()),
+            endchars,
+            endflag,
+            beginchars,
+            beginflag,
+            replacement: replacement.map(ToOwned::to_owned),
         })
     }
 }
@@ -403,7 +526,8 @@ impl From<&str> for PartOfSpeech {
             "preposition" => Self::Preposition,
             "conjunction" => Self::Conjunction,
             "interjection" => Self::Interjection,
-            val => Self::Other(val.into()),
+            // Keep the original casing so unknown values round trip
+            _ => Self::Other(value.into()),
         }
     }
 }
@@ -429,6 +553,7 @@ impl fmt::Display for PartOfSpeech {
 impl TryFrom<&str> for RuleType {
     type Error = String;
 
+    #[inline]
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let ret = match value.to_lowercase().as_str() {
             "pfx" => Self::Prefix,
@@ -440,6 +565,7 @@ impl TryFrom<&str> for RuleType {
 }
 
 impl Default for Encoding {
+    #[inline]
     fn default() -> Self {
         Self::Utf8
     }
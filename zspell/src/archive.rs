@@ -0,0 +1,499 @@
+//! Load dictionaries from zipped bundles (requires feature `archive`)
+//!
+//! LibreOffice and OpenOffice distribute dictionaries as `.oxt` extensions,
+//! which are zip archives containing one or more `.aff`/`.dic` pairs alongside
+//! a manifest that describes them. Firefox and Thunderbird dictionary add-ons
+//! are `.xpi` files with a similar layout. [`Oxt`] and [`Xpi`] read these
+//! bundles directly so the files do not need to be extracted first.
+//!
+//! ```no_run
+//! use zspell::archive::Oxt;
+//!
+//! let mut oxt = Oxt::open("dict-en.oxt").unwrap();
+//!
+//! for bundled in oxt.dictionaries() {
+//!     println!("{}: {}", bundled.dic_path(), bundled.locales().join(", "));
+//! }
+//!
+//! let dict = oxt.build(Some("en-GB")).unwrap();
+//! assert!(dict.check("colour"));
+//! ```
+//!
+//! Note that the `.aff` and `.dic` files must be UTF-8 encoded.
+
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::error::{ArchiveError, Error, IoError};
+use crate::{DictBuilder, Dictionary};
+
+/// Location of the manifest within an `.oxt` extension
+const MANIFEST_PATH: &str = "META-INF/manifest.xml";
+/// Location of the manifest within an `.xpi` add-on
+const XPI_MANIFEST_PATH: &str = "manifest.json";
+/// Directory that legacy `.xpi` add-ons store dictionaries in
+const XPI_DICT_DIR: &str = "dictionaries/";
+/// Media type of manifest entries that may register dictionaries
+const CONFIG_MEDIA_TYPE: &str = "application/vnd.sun.star.configuration-data";
+/// Placeholder for the directory of the configuration file in `Locations`
+const ORIGIN: &str = "%origin%";
+
+/// A spellcheck dictionary contained in a bundle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundledDict {
+    aff_path: String,
+    dic_path: String,
+    locales: Vec<String>,
+}
+
+impl BundledDict {
+    /// Path of the `.aff` file within the archive
+    #[inline]
+    pub fn aff_path(&self) -> &str {
+        &self.aff_path
+    }
+
+    /// Path of the `.dic` file within the archive
+    #[inline]
+    pub fn dic_path(&self) -> &str {
+        &self.dic_path
+    }
+
+    /// Language tags this dictionary is registered for, e.g. `en-US`
+    #[inline]
+    pub fn locales(&self) -> &[String] {
+        &self.locales
+    }
+
+    /// Whether `locale` is one of this dictionary's locales. Comparison ignores
+    /// case and treats `_` and `-` as equivalent.
+    #[inline]
+    pub fn matches_locale(&self, locale: &str) -> bool {
+        let norm = |s: &str| s.replace('_', "-").to_ascii_lowercase();
+        let locale = norm(locale);
+        self.locales.iter().any(|l| norm(l) == locale)
+    }
+}
+
+/// A LibreOffice/OpenOffice `.oxt` dictionary extension.
+///
+/// See the [module-level documentation](crate::archive) for an example.
+#[derive(Debug)]
+pub struct Oxt<R> {
+    zip: ZipArchive<R>,
+    dicts: Vec<BundledDict>,
+}
+
+impl Oxt<File> {
+    /// Open an extension from a file path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not a zip archive, or
+    /// does not contain any spellcheck dictionaries.
+    #[inline]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| IoError::new(&path.to_string_lossy(), e.kind()))?;
+        Self::from_reader(file)
+    }
+}
+
+impl<R: Read + Seek> Oxt<R> {
+    /// Read an extension from anything that can be read and seeked, such as a
+    /// `Cursor<Vec<u8>>`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a zip archive or does not contain any
+    /// spellcheck dictionaries.
+    #[inline]
+    pub fn from_reader(reader: R) -> Result<Self, Error> {
+        let mut zip = ZipArchive::new(reader).map_err(zip_error)?;
+        let manifest = read_string(&mut zip, MANIFEST_PATH)?;
+
+        let mut dicts = Vec::new();
+        for cfg_path in manifest_config_paths(&manifest) {
+            let xcu = read_string(&mut zip, &cfg_path)?;
+            let origin = cfg_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+            dicts.extend(xcu_spell_dicts(&xcu, origin));
+        }
+
+        if dicts.is_empty() {
+            return Err(ArchiveError::NoDictionary.into());
+        }
+
+        Ok(Self { zip, dicts })
+    }
+
+    /// All spellcheck dictionaries listed in the extension
+    #[inline]
+    pub fn dictionaries(&self) -> &[BundledDict] {
+        &self.dicts
+    }
+
+    /// Read the `.aff` and `.dic` contents for a locale, or for the first
+    /// dictionary if `locale` is `None`. This is useful to further configure a
+    /// [`DictBuilder`] before building.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no dictionary matches `locale`, or if its files are
+    /// missing or not valid UTF-8.
+    #[inline]
+    pub fn read_files(&mut self, locale: Option<&str>) -> Result<(String, String), Error> {
+        read_pair(&mut self.zip, &self.dicts, locale)
+    }
+
+    /// Build a [`Dictionary`] for a locale, or for the first dictionary in the
+    /// extension if `locale` is `None`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the files cannot be read (see
+    /// [`read_files`](Self::read_files)) or the dictionary fails to build.
+    #[inline]
+    pub fn build(&mut self, locale: Option<&str>) -> Result<Dictionary, Error> {
+        let (aff, dic) = self.read_files(locale)?;
+        DictBuilder::new().config_str(&aff).dict_str(&dic).build()
+    }
+}
+
+/// A Mozilla `.xpi` dictionary add-on, as used by Firefox and Thunderbird.
+///
+/// The dictionaries are located using the `dictionaries` key of the add-on's
+/// `manifest.json`. Legacy add-ons without this key are supported by looking for
+/// `.aff`/`.dic` pairs in the `dictionaries` directory, using the file name as
+/// the locale.
+#[derive(Debug)]
+pub struct Xpi<R> {
+    zip: ZipArchive<R>,
+    dicts: Vec<BundledDict>,
+}
+
+impl Xpi<File> {
+    /// Open an add-on from a file path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not a zip archive, or
+    /// does not contain any spellcheck dictionaries.
+    #[inline]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| IoError::new(&path.to_string_lossy(), e.kind()))?;
+        Self::from_reader(file)
+    }
+}
+
+impl<R: Read + Seek> Xpi<R> {
+    /// Read an add-on from anything that can be read and seeked
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a zip archive or does not contain any
+    /// spellcheck dictionaries.
+    #[inline]
+    pub fn from_reader(reader: R) -> Result<Self, Error> {
+        let mut zip = ZipArchive::new(reader).map_err(zip_error)?;
+
+        let manifest = match read_string(&mut zip, XPI_MANIFEST_PATH) {
+            Ok(m) => Some(m),
+            Err(Error::Archive(ArchiveError::MissingFile(_))) => None,
+            Err(e) => return Err(e),
+        };
+        let mut dicts: Vec<BundledDict> = manifest
+            .as_deref()
+            .and_then(|m| json_string_map(m, "dictionaries"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(locale, dic_path)| {
+                let dic_path = dic_path.trim_start_matches("./");
+                Some(BundledDict {
+                    aff_path: format!("{}.aff", strip_extension(dic_path, "dic")?),
+                    dic_path: dic_path.to_owned(),
+                    locales: vec![locale],
+                })
+            })
+            .collect();
+
+        if dicts.is_empty() {
+            dicts = xpi_legacy_dicts(&zip);
+        }
+        if dicts.is_empty() {
+            return Err(ArchiveError::NoDictionary.into());
+        }
+
+        Ok(Self { zip, dicts })
+    }
+
+    /// All spellcheck dictionaries listed in the add-on
+    #[inline]
+    pub fn dictionaries(&self) -> &[BundledDict] {
+        &self.dicts
+    }
+
+    /// Read the `.aff` and `.dic` contents for a locale, or for the first
+    /// dictionary if `locale` is `None`. See [`Oxt::read_files`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no dictionary matches `locale`, or if its files are
+    /// missing or not valid UTF-8.
+    #[inline]
+    pub fn read_files(&mut self, locale: Option<&str>) -> Result<(String, String), Error> {
+        read_pair(&mut self.zip, &self.dicts, locale)
+    }
+
+    /// Build a [`Dictionary`] for a locale, or for the first dictionary in the
+    /// add-on if `locale` is `None`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the files cannot be read or the dictionary fails to
+    /// build.
+    #[inline]
+    pub fn build(&mut self, locale: Option<&str>) -> Result<Dictionary, Error> {
+        let (aff, dic) = self.read_files(locale)?;
+        DictBuilder::new().config_str(&aff).dict_str(&dic).build()
+    }
+}
+
+/// Read the files of the dictionary matching `locale`, or the first one
+fn read_pair<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    dicts: &[BundledDict],
+    locale: Option<&str>,
+) -> Result<(String, String), Error> {
+    let bundled = match locale {
+        Some(loc) => dicts
+            .iter()
+            .find(|d| d.matches_locale(loc))
+            .ok_or_else(|| ArchiveError::LocaleNotFound(loc.to_owned()))?,
+        None => &dicts[0],
+    };
+
+    let aff = read_string(zip, &bundled.aff_path)?;
+    let dic = read_string(zip, &bundled.dic_path)?;
+    Ok((aff, dic))
+}
+
+/// Read a file from the archive to a string
+fn read_string<R: Read + Seek>(zip: &mut ZipArchive<R>, path: &str) -> Result<String, Error> {
+    let mut file = zip.by_name(path).map_err(|e| match e {
+        ZipError::FileNotFound => ArchiveError::MissingFile(path.to_owned()).into(),
+        e => zip_error(e),
+    })?;
+    let mut buf = String::new();
+    file.read_to_string(&mut buf)
+        .map_err(|e| IoError::new(path, e.kind()))?;
+    Ok(buf)
+}
+
+fn zip_error(e: ZipError) -> Error {
+    match e {
+        ZipError::Io(e) => IoError::new("archive", e.kind()).into(),
+        e => ArchiveError::Zip(e.to_string()).into(),
+    }
+}
+
+/// Get the paths of all configuration files registered in `manifest.xml`
+fn manifest_config_paths(manifest: &str) -> Vec<String> {
+    xml_tags(manifest, "manifest:file-entry")
+        .filter(|tag| xml_attr(tag, "manifest:media-type") == Some(CONFIG_MEDIA_TYPE))
+        .filter_map(|tag| xml_attr(tag, "manifest:full-path"))
+        .map(|path| path.trim_start_matches("./").to_owned())
+        .collect()
+}
+
+/// Find `.dic` files in the dictionary directory that have a matching `.aff`
+fn xpi_legacy_dicts<R: Read + Seek>(zip: &ZipArchive<R>) -> Vec<BundledDict> {
+    let names: Vec<&str> = zip.file_names().collect();
+    let mut dicts: Vec<BundledDict> = names
+        .iter()
+        .filter_map(|name| {
+            let stem = strip_extension(name.strip_prefix(XPI_DICT_DIR)?, "dic")?;
+            let aff_path = format!("{XPI_DICT_DIR}{stem}.aff");
+            names.contains(&aff_path.as_str()).then(|| BundledDict {
+                aff_path,
+                dic_path: (*name).to_owned(),
+                locales: vec![stem.to_owned()],
+            })
+        })
+        .collect();
+    // Archive order is arbitrary, so sort to make the default dictionary stable
+    dicts.sort_unstable_by(|a, b| a.dic_path.cmp(&b.dic_path));
+    dicts
+}
+
+/// Remove a case-insensitive file extension
+fn strip_extension<'a>(path: &'a str, ext: &str) -> Option<&'a str> {
+    let (stem, found) = path.rsplit_once('.')?;
+    found.eq_ignore_ascii_case(ext).then_some(stem)
+}
+
+/// Locate spellcheck dictionaries in a `.xcu` configuration file. A dictionary
+/// is a `node` element with child properties like the following:
+///
+/// ```text
+/// <prop oor:name="Locations" oor:type="oor:string-list">
+///   <value>%origin%/en_US.aff %origin%/en_US.dic</value>
+/// </prop>
+/// <prop oor:name="Format" oor:type="xs:string">
+///   <value>DICT_SPELL</value>
+/// </prop>
+/// <prop oor:name="Locales" oor:type="oor:string-list">
+///   <value>en-US en-PH</value>
+/// </prop>
+/// ```
+fn xcu_spell_dicts<'a>(xcu: &'a str, origin: &'a str) -> impl Iterator<Item = BundledDict> + 'a {
+    // Dictionary nodes are the innermost ones, so the text between one `<node`
+    // and the next contains all properties of at most one dictionary
+    xcu.split("<node").skip(1).filter_map(move |node| {
+        if xcu_prop(node, "Format")? != "DICT_SPELL" {
+            return None;
+        }
+
+        let mut aff_path = None;
+        let mut dic_path = None;
+        for loc in xcu_prop(node, "Locations")?.split_whitespace() {
+            let loc = loc
+                .strip_prefix(ORIGIN)
+                .unwrap_or(loc)
+                .trim_start_matches('/');
+            let full = if origin.is_empty() {
+                loc.to_owned()
+            } else {
+                format!("{origin}/{loc}")
+            };
+            if strip_extension(loc, "aff").is_some() {
+                aff_path = Some(full);
+            } else if strip_extension(loc, "dic").is_some() {
+                dic_path = Some(full);
+            }
+        }
+
+        let locales = xcu_prop(node, "Locales")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(ToOwned::to_owned)
+            .collect();
+
+        Some(BundledDict {
+            aff_path: aff_path?,
+            dic_path: dic_path?,
+            locales,
+        })
+    })
+}
+
+/// Get the `<value>` contents of the property named `name`
+fn xcu_prop<'a>(node: &'a str, name: &str) -> Option<&'a str> {
+    node.split("<prop").skip(1).find_map(|prop| {
+        let (tag, body) = prop.split_once('>')?;
+        if xml_attr(tag, "oor:name") != Some(name) {
+            return None;
+        }
+        let body = &body[..body.find("</prop>")?];
+        let start = body.find("<value>")? + "<value>".len();
+        let end = body[start..].find("</value>")? + start;
+        Some(body[start..end].trim())
+    })
+}
+
+/// Iterate the contents of all opening tags named `name` (everything between
+/// `<name` and `>`)
+fn xml_tags<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    xml.match_indices('<').filter_map(move |(idx, _)| {
+        let rest = xml[idx + 1..].strip_prefix(name)?;
+        if !rest.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            return None;
+        }
+        Some(&rest[..rest.find('>')?])
+    })
+}
+
+/// Parse the object at `key` in a JSON document, if it exists and only contains
+/// string values. This is just enough JSON to read `manifest.json` without
+/// pulling in a full parser.
+fn json_string_map(json: &str, key: &str) -> Option<Vec<(String, String)>> {
+    let quoted = format!("\"{key}\"");
+    json.match_indices(&quoted).find_map(|(idx, _)| {
+        let after_key = json[idx + quoted.len()..].trim_start().strip_prefix(':')?;
+        let mut rest = after_key.trim_start().strip_prefix('{')?.trim_start();
+        let mut entries = Vec::new();
+
+        if rest.starts_with('}') {
+            return Some(entries);
+        }
+
+        loop {
+            let (k, r) = json_string(rest)?;
+            let r = r.trim_start().strip_prefix(':')?.trim_start();
+            let (v, r) = json_string(r)?;
+            entries.push((k, v));
+
+            let r = r.trim_start();
+            if r.starts_with('}') {
+                return Some(entries);
+            }
+            rest = r.strip_prefix(',')?.trim_start();
+        }
+    })
+}
+
+/// Parse a JSON string literal at the start of `s`, returning its unescaped value
+/// and the remaining input
+fn json_string(s: &str) -> Option<(String, &str)> {
+    let mut chars = s.strip_prefix('"')?.char_indices();
+    let mut ret = String::new();
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            '"' => return Some((ret, &s[idx + 2..])),
+            '\\' => match chars.next()?.1 {
+                'n' => ret.push('\n'),
+                't' => ret.push('\t'),
+                'r' => ret.push('\r'),
+                'b' => ret.push('\u{8}'),
+                'f' => ret.push('\u{c}'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                    ret.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                c => ret.push(c),
+            },
+            c => ret.push(c),
+        }
+    }
+    None
+}
+
+/// Get the value of an attribute within a tag
+fn xml_attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(idx) = rest.find(name) {
+        let preceded_by_space = rest[..idx].ends_with(char::is_whitespace) || idx == 0;
+        rest = &rest[idx + name.len()..];
+        if !preceded_by_space {
+            continue;
+        }
+        let Some(val) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let val = val.trim_start();
+        let quote = val.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let val = &val[1..];
+        return val.find(quote).map(|end| &val[..end]);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,236 @@
+//! Tests for the `archive` module
+
+use std::fs;
+use std::io::{Cursor, Write};
+
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use super::*;
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="http://openoffice.org/2001/manifest">
+    <manifest:file-entry manifest:media-type="application/vnd.sun.star.configuration-data"
+                         manifest:full-path="dictionaries.xcu"/>
+    <manifest:file-entry manifest:media-type="application/vnd.sun.star.package-bundle-description"
+                         manifest:full-path="package-description.txt"/>
+</manifest:manifest>
+"#;
+
+const XCU: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<oor:component-data xmlns:oor="http://openoffice.org/2001/registry" xmlns:xs="http://www.w3.org/2001/XMLSchema" oor:name="Linguistic" oor:package="org.openoffice.Office">
+ <node oor:name="ServiceManager">
+    <node oor:name="Dictionaries">
+        <node oor:name="HunSpellDic_en_US" oor:op="fuse">
+            <prop oor:name="Locations" oor:type="oor:string-list">
+                <value>%origin%/dicts/en_US.aff %origin%/dicts/en_US.dic</value>
+            </prop>
+            <prop oor:name="Format" oor:type="xs:string">
+                <value>DICT_SPELL</value>
+            </prop>
+            <prop oor:name="Locales" oor:type="oor:string-list">
+                <value>en-US en-PH</value>
+            </prop>
+        </node>
+        <node oor:name="HyphDic_en_US" oor:op="fuse">
+            <prop oor:name="Locations" oor:type="oor:string-list">
+                <value>%origin%/hyph_en_US.dic</value>
+            </prop>
+            <prop oor:name="Format" oor:type="xs:string">
+                <value>DICT_HYPH</value>
+            </prop>
+            <prop oor:name="Locales" oor:type="oor:string-list">
+                <value>en-US</value>
+            </prop>
+        </node>
+        <node oor:name="HunSpellDic_en_GB" oor:op="fuse">
+            <prop oor:name="Locations" oor:type="oor:string-list">
+                <value>%origin%/en_GB.aff %origin%/en_GB.dic</value>
+            </prop>
+            <prop oor:name="Format" oor:type="xs:string">
+                <value>DICT_SPELL</value>
+            </prop>
+            <prop oor:name="Locales" oor:type="oor:string-list">
+                <value>en-GB</value>
+            </prop>
+        </node>
+    </node>
+ </node>
+</oor:component-data>
+"#;
+
+/// Create an in-memory zip with the given files
+fn make_zip(files: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in files {
+        zip.start_file(*name, FileOptions::default()).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    let mut cursor = zip.finish().unwrap();
+    cursor.set_position(0);
+    cursor
+}
+
+#[test]
+fn test_oxt_dictionaries() {
+    let aff = fs::read_to_string("tests/files/w1_eng_short.aff").unwrap();
+    let dic = fs::read_to_string("tests/files/w1_eng_short.dic").unwrap();
+    let data = make_zip(&[
+        ("META-INF/manifest.xml", MANIFEST),
+        ("dictionaries.xcu", XCU),
+        ("dicts/en_US.aff", &aff),
+        ("dicts/en_US.dic", &dic),
+        ("en_GB.aff", ""),
+        ("en_GB.dic", "1\ncolour\n"),
+    ]);
+
+    let mut oxt = Oxt::from_reader(data).unwrap();
+    let expected = [
+        BundledDict {
+            aff_path: "dicts/en_US.aff".to_owned(),
+            dic_path: "dicts/en_US.dic".to_owned(),
+            locales: vec!["en-US".to_owned(), "en-PH".to_owned()],
+        },
+        BundledDict {
+            aff_path: "en_GB.aff".to_owned(),
+            dic_path: "en_GB.dic".to_owned(),
+            locales: vec!["en-GB".to_owned()],
+        },
+    ];
+    assert_eq!(oxt.dictionaries(), expected);
+
+    let dict = oxt.build(None).unwrap();
+    assert!(dict.check("reptiles pillow bananas"));
+
+    // Locale lookup is case and separator insensitive
+    let dict = oxt.build(Some("en_gb")).unwrap();
+    assert!(dict.check("colour"));
+    assert!(!dict.check("reptiles"));
+
+    assert_eq!(
+        oxt.build(Some("de-DE")).unwrap_err(),
+        ArchiveError::LocaleNotFound("de-DE".to_owned()).into()
+    );
+}
+
+#[test]
+fn test_oxt_errors() {
+    let no_manifest = make_zip(&[("dictionaries.xcu", XCU)]);
+    assert_eq!(
+        Oxt::from_reader(no_manifest).unwrap_err(),
+        ArchiveError::MissingFile(MANIFEST_PATH.to_owned()).into()
+    );
+
+    let no_dicts = make_zip(&[
+        ("META-INF/manifest.xml", MANIFEST),
+        ("dictionaries.xcu", "<oor:component-data/>"),
+    ]);
+    assert_eq!(
+        Oxt::from_reader(no_dicts).unwrap_err(),
+        ArchiveError::NoDictionary.into()
+    );
+
+    let missing_dic = make_zip(&[
+        ("META-INF/manifest.xml", MANIFEST),
+        ("dictionaries.xcu", XCU),
+        ("en_GB.aff", ""),
+    ]);
+    let mut oxt = Oxt::from_reader(missing_dic).unwrap();
+    assert_eq!(
+        oxt.build(Some("en-GB")).unwrap_err(),
+        ArchiveError::MissingFile("en_GB.dic".to_owned()).into()
+    );
+
+    assert!(matches!(
+        Oxt::from_reader(Cursor::new(b"not a zip".to_vec())),
+        Err(Error::Archive(ArchiveError::Zip(_)))
+    ));
+}
+
+#[test]
+fn test_xml_attr() {
+    let tag = r#" manifest:media-type='text/plain' xmanifest:full-path="a" manifest:full-path = "b.xcu" "#;
+    assert_eq!(xml_attr(tag, "manifest:media-type"), Some("text/plain"));
+    assert_eq!(xml_attr(tag, "manifest:full-path"), Some("b.xcu"));
+    assert_eq!(xml_attr(tag, "full-path"), None);
+}
+
+#[test]
+fn test_xpi_manifest() {
+    let manifest = r#"{
+  "manifest_version": 2,
+  "name": "English (Great Britain) Dictionary",
+  "browser_specific_settings": { "gecko": { "id": "en-GB@dictionaries.addons.mozilla.org" } },
+  "dictionaries": {
+    "en-GB": "dictionaries\/en-GB.dic",
+    "en-AU": "./en_AU.DIC"
+  }
+}"#;
+    let data = make_zip(&[
+        ("manifest.json", manifest),
+        ("dictionaries/en-GB.aff", ""),
+        ("dictionaries/en-GB.dic", "1\ncolour\n"),
+    ]);
+
+    let mut xpi = Xpi::from_reader(data).unwrap();
+    let expected = [
+        BundledDict {
+            aff_path: "dictionaries/en-GB.aff".to_owned(),
+            dic_path: "dictionaries/en-GB.dic".to_owned(),
+            locales: vec!["en-GB".to_owned()],
+        },
+        BundledDict {
+            aff_path: "en_AU.aff".to_owned(),
+            dic_path: "en_AU.DIC".to_owned(),
+            locales: vec!["en-AU".to_owned()],
+        },
+    ];
+    assert_eq!(xpi.dictionaries(), expected);
+
+    let dict = xpi.build(None).unwrap();
+    assert!(dict.check("colour"));
+    assert_eq!(
+        xpi.build(Some("en-AU")).unwrap_err(),
+        ArchiveError::MissingFile("en_AU.aff".to_owned()).into()
+    );
+}
+
+#[test]
+fn test_xpi_legacy() {
+    let data = make_zip(&[
+        ("install.rdf", "<RDF/>"),
+        ("dictionaries/fr.dic", "1\nbonjour\n"),
+        ("dictionaries/fr.aff", ""),
+        ("dictionaries/de.dic", "1\nhallo\n"),
+        ("dictionaries/README.txt", ""),
+        ("dictionaries/it.dic", "1\nciao\n"),
+    ]);
+
+    let mut xpi = Xpi::from_reader(data).unwrap();
+    let locales: Vec<_> = xpi
+        .dictionaries()
+        .iter()
+        .map(BundledDict::locales)
+        .collect();
+    assert_eq!(locales, [["fr"]]);
+    assert!(xpi.build(Some("fr")).unwrap().check("bonjour"));
+
+    let empty = make_zip(&[("manifest.json", r#"{"dictionaries": {}}"#)]);
+    assert_eq!(
+        Xpi::from_reader(empty).unwrap_err(),
+        ArchiveError::NoDictionary.into()
+    );
+}
+
+#[test]
+fn test_json_string_map() {
+    let json = r#"{"name": "dictionaries", "dictionaries" : {"a\"b": "é\\x", "c":"d"}}"#;
+    assert_eq!(
+        json_string_map(json, "dictionaries"),
+        Some(vec![
+            ("a\"b".to_owned(), "é\\x".to_owned()),
+            ("c".to_owned(), "d".to_owned())
+        ])
+    );
+    assert_eq!(json_string_map(json, "missing"), None);
+}
@@ -0,0 +1,51 @@
+//! Cancellation of long-running checks, see [`CancelToken`]
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A flag that stops document checks and suggestions that were started with it.
+///
+/// Clones share the same flag, so one clone can be handed to a background
+/// check while another is kept to cancel it, e.g. when an editor's buffer
+/// changes and the results would be stale.
+///
+/// ```
+/// use zspell::CancelToken;
+///
+/// let dict = zspell::builder()
+///     .config_str("")
+///     .dict_str("2\nthe\ncat")
+///     .build()
+///     .unwrap();
+///
+/// let cancel = CancelToken::new();
+/// let mut errors = dict.check_indices_cancellable("teh cat sta", &cancel);
+/// assert_eq!(errors.next(), Some((0, "teh")));
+///
+/// cancel.cancel();
+/// assert_eq!(errors.next(), None);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a token that is not cancelled
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop any work using this token or one of its clones. Work stops at the
+    /// next word, not immediately.
+    #[inline]
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called on this token or one of
+    /// its clones
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,230 @@
+//! Case mapping that follows the language of the dictionary
+//!
+//! Turkish and related languages have a dotted and a dotless `i` in both cases,
+//! so `i` uppercases to `İ` and `I` lowercases to `ı`. Unicode's default mapping
+//! pairs `i` with `I` instead, which would make most words with an `i` fail to
+//! match when they are written in capitals.
+
+use alloc::string::String;
+#[cfg(feature = "unstable-suggestions")]
+use core::cmp::Ordering;
+
+#[cfg(feature = "icu")]
+use icu_casemap::titlecase::{LeadingAdjustment, TitlecaseOptions, TrailingCase};
+#[cfg(feature = "icu")]
+use icu_casemap::CaseMapper;
+#[cfg(all(feature = "icu", feature = "unstable-suggestions"))]
+use icu_collator::{Collator, CollatorOptions};
+#[cfg(all(feature = "icu", feature = "unstable-suggestions"))]
+use icu_locid::Locale;
+#[cfg(feature = "icu")]
+use icu_locid::{langid, LanguageIdentifier};
+
+#[cfg(feature = "icu")]
+const CASE_MAPPER: CaseMapper = CaseMapper::new();
+
+/// Case mapping rules, chosen from the `LANG` of an affix file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Casing {
+    /// Unicode's default mapping
+    #[default]
+    Default,
+    /// Turkish, Azeri, and Crimean Tatar, where `i`/`İ` and `ı`/`I` are pairs
+    Turkic,
+}
+
+impl Casing {
+    /// The casing for a language code such as `tr_TR` or `az`
+    pub fn from_lang(lang: &str) -> Self {
+        let code = lang.split(['_', '-']).next().unwrap_or_default();
+        if ["tr", "az", "crh"]
+            .iter()
+            .any(|turkic| code.eq_ignore_ascii_case(turkic))
+        {
+            Self::Turkic
+        } else {
+            Self::Default
+        }
+    }
+
+    /// Whether lowercasing `word` differs from Unicode's default mapping, in
+    /// which case [`to_lowercase`](Self::to_lowercase) must be used
+    pub fn lowers_specially(self, word: &str) -> bool {
+        match self {
+            Self::Default => false,
+            Self::Turkic => word.contains(['I', 'İ']),
+        }
+    }
+
+    /// `word` in lowercase
+    pub fn to_lowercase(self, word: &str) -> String {
+        #[cfg(feature = "icu")]
+        {
+            CASE_MAPPER.lowercase_to_string(word, &self.langid())
+        }
+        #[cfg(not(feature = "icu"))]
+        match self {
+            Self::Turkic if self.lowers_specially(word) => {
+                word.replace('I', "ı").replace('İ', "i").to_lowercase()
+            }
+            _ => word.to_lowercase(),
+        }
+    }
+
+    /// `word` in uppercase
+    pub fn to_uppercase(self, word: &str) -> String {
+        #[cfg(feature = "icu")]
+        {
+            CASE_MAPPER.uppercase_to_string(word, &self.langid())
+        }
+        #[cfg(not(feature = "icu"))]
+        match self {
+            Self::Turkic if word.contains('i') => word.replace('i', "İ").to_uppercase(),
+            _ => word.to_uppercase(),
+        }
+    }
+
+    /// `word` with its first letter in uppercase and the rest unchanged. With
+    /// the `icu` feature the first letter is put in titlecase, so a digraph
+    /// such as "ǆ" becomes "ǅ" rather than "Ǆ".
+    pub fn capitalize(self, word: &str) -> String {
+        #[cfg(feature = "icu")]
+        {
+            let mut options = TitlecaseOptions::default();
+            options.leading_adjustment = LeadingAdjustment::None;
+            options.trailing_case = TrailingCase::Unchanged;
+            CASE_MAPPER.titlecase_segment_with_only_case_data_to_string(
+                word,
+                &self.langid(),
+                options,
+            )
+        }
+        #[cfg(not(feature = "icu"))]
+        {
+            let mut chars = word.chars();
+            let Some(first) = chars.next() else {
+                return String::new();
+            };
+            let mut ret = String::with_capacity(word.len() + 1);
+            match (self, first) {
+                (Self::Turkic, 'i') => ret.push('İ'),
+                _ => ret.extend(first.to_uppercase()),
+            }
+            ret.push_str(chars.as_str());
+            ret
+        }
+    }
+
+    /// `word` with differences of case removed, to compare words regardless of
+    /// case. With the `icu` feature this is full Unicode case folding, which
+    /// also matches "ß" with "ss" and "ς" with "σ"; otherwise it is the same
+    /// as [`to_lowercase`](Self::to_lowercase).
+    pub fn fold(self, word: &str) -> String {
+        #[cfg(feature = "icu")]
+        match self {
+            Self::Default => CASE_MAPPER.fold_string(word),
+            Self::Turkic => CASE_MAPPER.fold_turkic_string(word),
+        }
+        #[cfg(not(feature = "icu"))]
+        self.to_lowercase(word)
+    }
+
+    /// The language that ICU should use for case mapping. Its special cases for
+    /// Turkish also hold for the other Turkic languages.
+    #[cfg(feature = "icu")]
+    fn langid(self) -> LanguageIdentifier {
+        match self {
+            Self::Default => LanguageIdentifier::UND,
+            Self::Turkic => langid!("tr"),
+        }
+    }
+}
+
+/// Orders suggestions that are otherwise equally good. With the `icu` feature
+/// this follows the alphabet of the dictionary's language, otherwise it is by
+/// code point.
+#[cfg(feature = "unstable-suggestions")]
+pub struct Collation {
+    #[cfg(feature = "icu")]
+    collator: Option<Collator>,
+}
+
+#[cfg(feature = "unstable-suggestions")]
+impl Collation {
+    /// The ordering for a language code such as `de_DE`
+    #[cfg_attr(not(feature = "icu"), allow(unused_variables))]
+    pub fn new(lang: &str) -> Self {
+        #[cfg(feature = "icu")]
+        {
+            let locale =
+                Locale::try_from_bytes(lang.replace('_', "-").as_bytes()).unwrap_or(Locale::UND);
+            let collator = Collator::try_new(&(&locale).into(), CollatorOptions::new()).ok();
+            Self { collator }
+        }
+        #[cfg(not(feature = "icu"))]
+        Self {}
+    }
+
+    /// Compare two words in this order
+    #[cfg_attr(not(feature = "icu"), allow(clippy::unused_self))]
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        #[cfg(feature = "icu")]
+        if let Some(collator) = &self.collator {
+            return collator.compare(a, b);
+        }
+        a.cmp(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_lang() {
+        assert_eq!(Casing::from_lang("tr_TR"), Casing::Turkic);
+        assert_eq!(Casing::from_lang("az-Latn"), Casing::Turkic);
+        assert_eq!(Casing::from_lang("crh_UA"), Casing::Turkic);
+        assert_eq!(Casing::from_lang("en_US"), Casing::Default);
+        assert_eq!(Casing::from_lang("tru"), Casing::Default);
+        assert_eq!(Casing::from_lang(""), Casing::Default);
+    }
+
+    #[test]
+    fn test_turkic() {
+        let casing = Casing::Turkic;
+        assert_eq!(casing.to_lowercase("ISTANBUL"), "ıstanbul");
+        assert_eq!(casing.to_lowercase("İSTANBUL"), "istanbul");
+        assert_eq!(casing.to_uppercase("istanbul ılık"), "İSTANBUL ILIK");
+        assert_eq!(casing.capitalize("izmir"), "İzmir");
+        assert_eq!(casing.capitalize("ılık"), "Ilık");
+
+        // Unicode's default mapping gives a combining dot for `İ`
+        assert_eq!(Casing::Default.to_lowercase("İ"), "i\u{307}");
+        assert_eq!(Casing::Default.to_uppercase("i"), "I");
+        assert_eq!(Casing::Default.capitalize(""), "");
+    }
+
+    #[test]
+    #[cfg(feature = "icu")]
+    fn test_icu() {
+        let casing = Casing::Default;
+        assert_eq!(casing.capitalize("ǆemper"), "ǅemper");
+        assert_eq!(casing.fold("Straße"), casing.fold("STRASSE"));
+        // Final sigma
+        assert_eq!(casing.fold("ΣΟΦΟΣ"), casing.fold("σοφο\u{3c2}"));
+        assert_eq!(Casing::Turkic.fold("İSTANBUL"), "istanbul");
+    }
+
+    #[test]
+    #[cfg(feature = "unstable-suggestions")]
+    fn test_collation() {
+        let mut words = ["zb", "äb", "ab"];
+        words.sort_by(|a, b| Collation::new("de_DE").compare(a, b));
+        if cfg!(feature = "icu") {
+            assert_eq!(words, ["ab", "äb", "zb"]);
+        } else {
+            assert_eq!(words, ["ab", "zb", "äb"]);
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! One entry point for checking documents, see [`Checker`]
+
+#[cfg(feature = "unstable-suggestions")]
+use alloc::borrow::Cow;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::filter::{DocumentFilter, Plain};
+#[cfg(feature = "regex")]
+use crate::helpers::ReWrapper;
+use crate::segment::{IgnoredText, SegmenterHook};
+use crate::{Dictionary, Segmenter, Session};
+
+/// Dictionaries, a document filter, text to skip, and the words a user has
+/// ignored or added, put together behind one builder.
+///
+/// The first dictionary splits text into words and receives words added with
+/// [`add_word`](Self::add_word). Any other dictionaries are consulted for the
+/// words it rejects, so a word is only misspelled if no dictionary accepts
+/// it. This suits documents that mix languages, or a general dictionary with
+/// one of technical terms.
+///
+/// ```
+/// use zspell::filter::Markdown;
+/// use zspell::Checker;
+///
+/// let en = zspell::builder()
+///     .config_str("")
+///     .dict_str("4\nthe\ncat\nsat\non")
+///     .build()
+///     .unwrap();
+/// let terms = zspell::builder()
+///     .config_str("")
+///     .dict_str("1\nzspell")
+///     .build()
+///     .unwrap();
+///
+/// let mut checker = Checker::builder(en)
+///     .dictionary(terms)
+///     .filter(Markdown)
+///     .ignore_literal("JIRA-")
+///     .build()
+///     .unwrap();
+///
+/// let text = "teh cat sat on `teh` zspell JIRA-12 mat";
+/// assert_eq!(checker.check("notes.md", text), [(0, "teh"), (36, "mat")]);
+///
+/// checker.ignore_once("notes.md", 0, "teh");
+/// checker.add_word("mat").unwrap();
+/// assert!(checker.check("notes.md", text).is_empty());
+/// ```
+#[derive(Debug)]
+pub struct Checker {
+    session: Session,
+    /// Dictionaries consulted for words the first one rejects
+    others: Vec<Dictionary>,
+    filter: Box<dyn DocumentFilter>,
+    ignore: IgnoredText,
+}
+
+/// Options for a [`Checker`], created by [`Checker::builder`]
+#[must_use]
+#[derive(Debug)]
+pub struct CheckerBuilder {
+    dicts: Vec<Dictionary>,
+    segmenter: SegmenterHook,
+    filter: Option<Box<dyn DocumentFilter>>,
+    ignore_literals: Vec<String>,
+    #[cfg(feature = "regex")]
+    ignore_patterns: Vec<String>,
+    ignore_words: Vec<String>,
+    #[cfg(feature = "std")]
+    personal_file: Option<PathBuf>,
+}
+
+impl Checker {
+    /// Start building a checker around `dict`, which splits text into words
+    #[inline]
+    pub fn builder(dict: Dictionary) -> CheckerBuilder {
+        CheckerBuilder {
+            dicts: alloc::vec![dict],
+            segmenter: SegmenterHook::default(),
+            filter: None,
+            ignore_literals: Vec::new(),
+            #[cfg(feature = "regex")]
+            ignore_patterns: Vec::new(),
+            ignore_words: Vec::new(),
+            #[cfg(feature = "std")]
+            personal_file: None,
+        }
+    }
+
+    /// Check the text that the checker's filter selects from document `doc`,
+    /// returning each misspelled word with its byte index in `text`. `doc`
+    /// names the document for [`ignore_once`](Self::ignore_once).
+    #[inline]
+    pub fn check<'a>(&self, doc: &str, text: &'a str) -> Vec<(usize, &'a str)> {
+        self.check_with_filter(doc, text, &*self.filter)
+    }
+
+    /// Like [`check`](Self::check), but select the text to check with
+    /// `filter`, e.g. one picked by [`filter::for_extension`] for each file.
+    ///
+    /// [`filter::for_extension`]: crate::filter::for_extension
+    #[inline]
+    pub fn check_with_filter<'a>(
+        &self,
+        doc: &str,
+        text: &'a str,
+        filter: &dyn DocumentFilter,
+    ) -> Vec<(usize, &'a str)> {
+        let skip = self.ignore.spans(text);
+        let mut ret = Vec::new();
+        for range in filter.ranges(text) {
+            let start = range.start;
+            let errors = self
+                .session
+                .dictionary()
+                .check_indices(&text[range])
+                .map(|(idx, word)| (start + idx, word))
+                .filter(|&(idx, word)| {
+                    !overlaps(&skip, idx..idx + word.len())
+                        && !self.session.is_ignored_at(doc, idx, word)
+                        && !self.others.iter().any(|dict| dict.check_word(word))
+                });
+            ret.extend(errors);
+        }
+        ret
+    }
+
+    /// Whether `word` is accepted by any dictionary or ignored everywhere
+    #[inline]
+    pub fn check_word(&self, word: &str) -> bool {
+        self.session.check_word(word) || self.others.iter().any(|dict| dict.check_word(word))
+    }
+
+    /// Suggestions for `word` from each dictionary in turn, without repeats.
+    /// Empty if the word is correct. Feature gated behind
+    /// `unstable-suggestions`.
+    #[inline]
+    #[cfg(feature = "unstable-suggestions")]
+    pub fn suggest(&self, word: &str) -> Vec<Cow<'_, str>> {
+        if self.check_word(word) {
+            return Vec::new();
+        }
+        let mut ret: Vec<Cow<str>> = Vec::new();
+        for dict in self.dictionaries() {
+            for sug in dict.entry(word).suggest().unwrap_or_default() {
+                if !ret.contains(&sug) {
+                    ret.push(sug);
+                }
+            }
+        }
+        ret
+    }
+
+    /// Ignore `word` wherever it appears, see [`Session::ignore_all`]
+    #[inline]
+    pub fn ignore_all(&mut self, word: &str) {
+        self.session.ignore_all(word);
+    }
+
+    /// Ignore `word` at byte index `idx` of document `doc`, as returned by
+    /// [`check`](Self::check). See [`Session::ignore_once`].
+    #[inline]
+    pub fn ignore_once(&mut self, doc: &str, idx: usize, word: &str) {
+        self.session.ignore_once(doc, idx, word);
+    }
+
+    /// Accept `word` from now on by adding it to the first dictionary, and
+    /// save it to the personal word list if there is one. See
+    /// [`Session::add_word`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the personal word list could not be written.
+    #[inline]
+    pub fn add_word(&mut self, word: &str) -> Result<bool, Error> {
+        self.session.add_word(word)
+    }
+
+    /// The dictionaries, in the order they were given
+    #[inline]
+    pub fn dictionaries(&self) -> impl Iterator<Item = &Dictionary> {
+        core::iter::once(self.session.dictionary()).chain(&self.others)
+    }
+
+    /// The words ignored and added so far, e.g. to save added words with
+    /// [`Session::flush`]
+    #[inline]
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// The words ignored and added so far, e.g. to forget a closed document
+    /// with [`Session::forget_document`]
+    #[inline]
+    pub fn session_mut(&mut self) -> &mut Session {
+        &mut self.session
+    }
+}
+
+impl CheckerBuilder {
+    /// Also accept the words in `dict`. May be called more than once.
+    #[inline]
+    pub fn dictionary(mut self, dict: Dictionary) -> Self {
+        self.dicts.push(dict);
+        self
+    }
+
+    /// Split runs of scripts without spaces into words with `segmenter`,
+    /// replacing any that the dictionaries were built with. See
+    /// [`DictBuilder::segmenter`](crate::DictBuilder::segmenter).
+    #[inline]
+    pub fn segmenter(mut self, segmenter: impl Segmenter + 'static) -> Self {
+        self.segmenter = SegmenterHook::new(Arc::new(segmenter));
+        self
+    }
+
+    /// Only check the text that `filter` selects, such as the prose of a
+    /// Markdown file. By default all text is checked.
+    #[inline]
+    pub fn filter(mut self, filter: impl DocumentFilter + 'static) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Skip occurrences of `text`, like
+    /// [`DictBuilder::ignore_literal`](crate::DictBuilder::ignore_literal). May
+    /// be called more than once.
+    #[inline]
+    pub fn ignore_literal(mut self, text: &str) -> Self {
+        self.ignore_literals.push(text.to_owned());
+        self
+    }
+
+    /// Skip text matching the regular expression `pattern`, like
+    /// [`DictBuilder::ignore_regex`](crate::DictBuilder::ignore_regex). May be
+    /// called more than once. Requires the `regex` feature.
+    #[inline]
+    #[cfg(feature = "regex")]
+    pub fn ignore_regex(mut self, pattern: &str) -> Self {
+        self.ignore_patterns.push(pattern.to_owned());
+        self
+    }
+
+    /// Start with `word` ignored everywhere, see [`Checker::ignore_all`]
+    #[inline]
+    pub fn ignore_word(mut self, word: &str) -> Self {
+        self.ignore_words.push(word.to_owned());
+        self
+    }
+
+    /// Save words added with [`Checker::add_word`] to the personal word list
+    /// at `path`, see [`Session::with_personal_file`]
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn personal_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.personal_file = Some(path.into());
+        self
+    }
+
+    /// Create the checker
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an ignore pattern is not a valid regular
+    /// expression.
+    #[inline]
+    pub fn build(self) -> Result<Checker, Error> {
+        let ignore = IgnoredText::new(&self.ignore_literals);
+        #[cfg(feature = "regex")]
+        let ignore = if self.ignore_patterns.is_empty() {
+            ignore
+        } else {
+            for pattern in &self.ignore_patterns {
+                ReWrapper::new(pattern)?;
+            }
+            let joined: Vec<_> = self
+                .ignore_patterns
+                .iter()
+                .map(|pattern| alloc::format!("(?:{pattern})"))
+                .collect();
+            ignore.with_pattern(ReWrapper::new(&joined.join("|"))?)
+        };
+
+        let mut dicts = self.dicts;
+        if self.segmenter.get().is_some() {
+            for dict in &mut dicts {
+                dict.set_segmenter(self.segmenter.clone());
+            }
+        }
+        let others = dicts.split_off(1);
+        let mut session = Session::new(dicts.pop().unwrap());
+        #[cfg(feature = "std")]
+        if let Some(path) = self.personal_file {
+            session = session.with_personal_file(path);
+        }
+        for word in &self.ignore_words {
+            session.ignore_all(word);
+        }
+
+        Ok(Checker {
+            session,
+            others,
+            filter: self.filter.unwrap_or_else(|| Box::new(Plain)),
+            ignore,
+        })
+    }
+}
+
+/// Whether `range` overlaps any of `spans`
+fn overlaps(spans: &[Range<usize>], range: Range<usize>) -> bool {
+    spans
+        .iter()
+        .any(|span| span.start < range.end && range.start < span.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filter::Html;
+
+    fn dict(words: &str) -> Dictionary {
+        crate::builder()
+            .config_str("")
+            .dict_str(words)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dictionaries() {
+        let checker = Checker::builder(dict("2\nthe\ncat"))
+            .dictionary(dict("1\nkatze"))
+            .dictionary(dict("1\nchat"))
+            .build()
+            .unwrap();
+        assert_eq!(checker.check("a", "the katze chat hund"), [(15, "hund")]);
+        assert!(checker.check_word("chat"));
+        assert!(!checker.check_word("hund"));
+        assert_eq!(checker.dictionaries().count(), 3);
+    }
+
+    #[test]
+    fn test_filter_and_ignore() {
+        let mut checker = Checker::builder(dict("2\nthe\ncat"))
+            .filter(Html)
+            .ignore_literal("wasm-bindgen")
+            .ignore_word("kat")
+            .build()
+            .unwrap();
+        let text = "<p>teh wasm-bindgen kat</p><b>teh</b>";
+        assert_eq!(checker.check("a", text), [(3, "teh"), (30, "teh")]);
+        // Indices from filtered text can be ignored once
+        checker.ignore_once("a", 30, "teh");
+        assert_eq!(checker.check("a", text), [(3, "teh")]);
+        assert_eq!(checker.check("b", text).len(), 2);
+        // Another filter can be given for one call
+        assert_eq!(
+            checker.check_with_filter("a", "<teh>", &Plain),
+            [(1, "teh")]
+        );
+
+        assert!(checker.add_word("teh").unwrap());
+        assert!(checker.check("a", text).is_empty());
+        assert!(checker.session().dictionary().check_word("teh"));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_ignore_regex() {
+        let checker = Checker::builder(dict("1\nsee"))
+            .ignore_regex(r"\b[A-Z]+-\d+\b")
+            .build()
+            .unwrap();
+        assert_eq!(checker.check("a", "see ABC-12 ABC"), [(11, "ABC")]);
+        assert!(Checker::builder(dict("1\nsee"))
+            .ignore_regex("(")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "unstable-suggestions")]
+    fn test_suggest() {
+        let checker = Checker::builder(dict("1\ncat"))
+            .dictionary(dict("2\ncar\ncat"))
+            .build()
+            .unwrap();
+        assert_eq!(checker.suggest("caz"), ["cat", "car"]);
+        assert!(checker.suggest("car").is_empty());
+    }
+}
@@ -1,32 +1,77 @@
 //! Main datastructure module with entrypoints for checking
 
+mod breaks;
+mod compound;
+mod elision;
+mod explain;
 mod flags;
+mod fold;
+#[cfg(feature = "unstable-suggestions")]
+mod inflect;
+mod intern;
 mod meta;
 mod parse;
 mod rule;
 mod rules_apply;
 mod rules_reverse;
+mod stats;
+mod validate;
+
+use alloc::borrow::Cow;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "unstable-suggestions")]
+use core::cmp::Reverse;
+use core::fmt;
+#[cfg(feature = "unstable-suggestions")]
+use core::time::Duration;
 
-use std::collections::BTreeMap;
-use std::fmt;
-use std::sync::Arc;
-
-use hashbrown::{HashMap, HashSet};
-use stringmetrics::try_levenshtein;
-use unicode_segmentation::UnicodeSegmentation;
 use xxhash_rust::xxh32::xxh32;
 
+pub use self::explain::{CaseMatch, Explanation, Hint, ListKind, Reason};
 pub use self::flags::{Flag, FlagValue};
+use self::fold::FoldedIndex;
+use self::intern::Interner;
+pub use self::meta::{AffixInfo, StemInfo, WordForm};
 use self::meta::{Meta, PersonalMeta, Source};
 pub use self::parse::DictEntry;
 use self::parse::PersonalEntry;
 pub use self::rule::AfxRule;
-use self::rules_apply::{create_affixed_word_map, word_splitter};
-use crate::affix::{CompiledFlags, FlagType};
-use crate::error::{BuildError, Error};
-use crate::helpers::StrWrapper;
-use crate::morph::MorphInfo;
-use crate::ParsedCfg;
+use self::rule::AfxRulePattern;
+use self::rules_apply::{create_affixed_word_map, Target, Twofold};
+use self::rules_reverse::{Derivation, LazyAffixes, LazyRoot, MetaRef};
+pub use self::stats::DictStats;
+use crate::affix::{CompiledFlags, FlagType, ParseMode};
+use crate::cancel::CancelToken;
+use crate::casing::Casing;
+#[cfg(feature = "unstable-suggestions")]
+use crate::casing::Collation;
+use crate::error::{BuildError, BuildWarning, DictWarning, Error, ParseError};
+#[cfg(feature = "regex")]
+use crate::helpers::ReWrapper;
+use crate::helpers::{is_number, FxHashMap, LowerBuf};
+use crate::morph::{Analysis, MorphInfo, MorphSource, MorphTag};
+use crate::normalize::Normalization;
+use crate::numeric::{is_numeric_token, is_roman_numeral};
+use crate::phonet::PhoneTable;
+use crate::progress::{Progress, WithProgress};
+use crate::segment::{IgnoredText, Segmenter, SegmenterHook, Words};
+#[cfg(feature = "unstable-suggestions")]
+use crate::suggestions::{
+    Deadline, EditDistance, PhoneticCoder, PhoneticSuggestions, SuggestionCache,
+    SuggestionCacheStats, MAX_PHONETIC_SUGGESTIONS, MAX_STEM_SUGGESTIONS, MAX_SUGGESTIONS,
+};
+use crate::{trace, ParsedCfg};
+
+/// Smallest share of a [`Dictionary::check_all`] batch worth a thread of its
+/// own, below which starting the thread costs more than it saves
+#[cfg(feature = "std")]
+const MIN_WORDS_PER_THREAD: usize = 4096;
 
 /// Main dictionary object used for spellchecking, suggestions, and analysis.
 ///
@@ -48,9 +93,38 @@ use crate::ParsedCfg;
 /// More powerful use for things such as stemming, morphological analysis, or (unstable)
 /// suggestions will want to use the entry API via [`entry`](Self::entry) or
 /// [`entries`](Self::entries).
+///
+/// Cloning a `Dictionary` is cheap: the wordlists and other compiled data are
+/// shared behind an [`Arc`], so each thread of a server can be handed its own
+/// clone without copying the data.
 #[must_use]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Dictionary {
+    /// Compiled wordlists and configuration, shared among clones
+    data: Arc<DictData>,
+    /// Segmenter for scripts without spaces, if any
+    segmenter: SegmenterHook,
+    /// Normalization to apply to input words
+    normalization: Normalization,
+    /// Accept numbers, times, and dates that are not in the dictionary
+    accept_numeric: bool,
+    /// Accept Roman numerals that are not in the dictionary
+    accept_roman: bool,
+    /// Check the parts of words split at `BREAK` separators
+    check_break_parts: bool,
+    /// Text to skip when checking documents
+    ignore: IgnoredText,
+    /// Cache of suggestion results, if enabled
+    #[cfg(feature = "unstable-suggestions")]
+    suggestion_cache: Option<Arc<SuggestionCache>>,
+    /// How to find suggestions that sound like a misspelling
+    #[cfg(feature = "unstable-suggestions")]
+    phonetic_suggestions: PhoneticSuggestions,
+}
+
+/// The heavy parts of a [`Dictionary`], which are shared among its clones
+#[derive(Clone, Debug, PartialEq)]
+struct DictData {
     /// General word list of words that are accepted and suggested. Note that it
     /// may make sense in the future to include non-suggest words here too.
     wordlist: WordList,
@@ -58,22 +132,55 @@ pub struct Dictionary {
     wordlist_nosuggest: WordList,
     /// Words forbidden by the personal dictionary, i.e. do not accept as correct
     wordlist_forbidden: WordList,
+    /// Words that are only accepted as part of a compound, `ONLYINCOMPOUND`
+    wordlist_compound_only: WordList,
 
     /* the following few types are used to store  meta information */
     /// A list of all stem words
-    stems: HashSet<Arc<str>>,
+    stems: Interner<str>,
     /// Flags and rules that apply to affixes
     affix_flags: BTreeMap<Flag, FlagValue>,
     /// Flags that apply to other flags or rules
     /// Possible morphs
-    morphs: HashSet<Arc<MorphInfo>>,
+    morphs: Interner<MorphInfo>,
+    /// Distinct sets of morphs attached to dictionary entries. Most entries share
+    /// a set with many others (often the empty set), so these are deduplicated.
+    morph_sets: Interner<[Arc<MorphInfo>]>,
+    /// Distinct sets of flags attached to dictionary entries, deduplicated like
+    /// `morph_sets`
+    flag_sets: Interner<[Flag]>,
     /// Type of flags to expect in our file
     flag_type: FlagType,
+    /// Rules from the `PHONE` table, if there is one
+    phone_table: Option<PhoneTable>,
     /// Affix configuration file. This will also hold references where our `meta`
     /// object points
     // FIXME: we don't need to store the whole `Config` here. It would be better
     // to replace with information that is relevant
     parsed_config: Box<ParsedCfg>,
+    /// How often each word occurs, used to rank suggestions
+    #[cfg(feature = "unstable-suggestions")]
+    frequencies: FxHashMap<Box<str>, u64>,
+    /// Roots to derive affixed words from when they are looked up, if affixes
+    /// are not expanded when building
+    lazy: Option<LazyAffixes>,
+    /// Spellings of words with uppercase letters by their lowercase form, if
+    /// words are checked case-insensitively
+    folded: Option<FoldedIndex>,
+    /// Case mapping for the language of the dictionary
+    casing: Casing,
+    /// Problems found in the word list, if it was validated
+    dict_warnings: Vec<DictWarning>,
+}
+
+/// The wordlist that a dictionary entry and the words created from it are
+/// stored in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EntryList {
+    Words,
+    NoSuggest,
+    Forbidden,
+    CompoundOnly,
 }
 
 // Check API
@@ -81,24 +188,33 @@ impl Dictionary {
     /// Create a new empty dictionary with default config
     #[inline]
     fn new(cfg: ParsedCfg) -> Result<Self, Error> {
-        // FIXME: what do we do with rule flags?
-        let CompiledFlags {
-            affix_flags,
-            rule_flags: _,
-        } = cfg.compile_flags()?;
-
         Ok(Self {
-            wordlist: WordList::new(),
-            wordlist_nosuggest: WordList::new(),
-            wordlist_forbidden: WordList::new(),
-            stems: HashSet::new(),
-            morphs: HashSet::new(),
-            affix_flags,
-            flag_type: cfg.flag_type(),
-            parsed_config: Box::new(cfg),
+            data: Arc::new(DictData::new(cfg)?),
+            segmenter: SegmenterHook::default(),
+            normalization: Normalization::None,
+            accept_numeric: false,
+            accept_roman: false,
+            check_break_parts: false,
+            ignore: IgnoredText::default(),
+            #[cfg(feature = "unstable-suggestions")]
+            suggestion_cache: None,
+            #[cfg(feature = "unstable-suggestions")]
+            phonetic_suggestions: PhoneticSuggestions::default(),
         })
     }
 
+    /// Get mutable access to the shared data, copying it first if it is shared
+    /// with another clone
+    fn data_mut(&mut self) -> &mut DictData {
+        // Cached suggestions may no longer be valid, and the cache may be shared
+        // with clones that do not have this update
+        #[cfg(feature = "unstable-suggestions")]
+        if let Some(cache) = &mut self.suggestion_cache {
+            *cache = Arc::new(cache.new_empty());
+        }
+        Arc::make_mut(&mut self.data)
+    }
+
     /// Check that an entire string contains only words that are spelled
     /// correctly, returns `true` if so.
     ///
@@ -122,7 +238,7 @@ impl Dictionary {
     /// ```
     #[inline]
     pub fn check(&self, input: &str) -> bool {
-        input.unicode_words().all(|w| self.check_word(w))
+        self.check_indices(input).next().is_none()
     }
 
     /// Check that a single word is spelled correctly, returns `true` if so
@@ -147,13 +263,172 @@ impl Dictionary {
     /// ```
     #[inline]
     pub fn check_word(&self, word: &str) -> bool {
+        trace::enter_span!(TRACE, "check_word", word);
+        let correct = self.check_whole(word) || self.check_breaks(word, 0);
+        trace::event!(TRACE, correct);
+        correct
+    }
+
+    /// Check many words at once, returning whether each is correct. This is
+    /// meant for pipelines that have already split their text into words;
+    /// each item is checked like [`check_word`](Self::check_word).
+    ///
+    /// Repeated words are only looked up once, and with the `std` feature large
+    /// batches are split across threads.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\nthe\ncat")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     dict.check_all(["the", "cat", "teh", "the"]),
+    ///     [true, true, false, true]
+    /// );
+    /// ```
+    #[inline]
+    pub fn check_all<'a, I>(&self, words: I) -> Vec<bool>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let words: Vec<&str> = words.into_iter().collect();
+        self.check_batch(&words)
+    }
+
+    /// Like [`check_all`](Self::check_all), but only returns the misspelled
+    /// words, along with their positions in `words`.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\nthe\ncat")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let errors = dict.check_all_errors(["the", "cat", "teh", "the", "cta"]);
+    /// assert_eq!(errors, [(2, "teh"), (4, "cta")]);
+    /// ```
+    #[inline]
+    pub fn check_all_errors<'a, I>(&self, words: I) -> Vec<(usize, &'a str)>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let words: Vec<&str> = words.into_iter().collect();
+        let correct = self.check_batch(&words);
+        words
+            .into_iter()
+            .zip(correct)
+            .enumerate()
+            .filter(|(_, (_, correct))| !correct)
+            .map(|(idx, (word, _))| (idx, word))
+            .collect()
+    }
+
+    /// Check a batch of words, on several threads if it is large enough
+    fn check_batch(&self, words: &[&str]) -> Vec<bool> {
+        trace::enter_span!(TRACE, "check_batch", words = words.len());
+
+        #[cfg(feature = "std")]
+        {
+            let threads = std::thread::available_parallelism()
+                .map_or(1, core::num::NonZeroUsize::get)
+                .min(words.len() / MIN_WORDS_PER_THREAD);
+            if threads > 1 {
+                let chunk_len = (words.len() + threads - 1) / threads;
+                return std::thread::scope(|scope| {
+                    // Every thread must be started before any is joined
+                    #[allow(clippy::needless_collect)]
+                    let handles: Vec<_> = words
+                        .chunks(chunk_len)
+                        .map(|chunk| scope.spawn(|| self.check_serial(chunk)))
+                        .collect();
+                    handles
+                        .into_iter()
+                        .flat_map(|handle| handle.join().expect("word checks do not panic"))
+                        .collect()
+                });
+            }
+        }
+
+        self.check_serial(words)
+    }
+
+    /// Check a batch of words on this thread, looking each distinct word up once
+    fn check_serial(&self, words: &[&str]) -> Vec<bool> {
+        let mut seen: FxHashMap<&str, bool> = FxHashMap::default();
+        words
+            .iter()
+            .map(|word| {
+                *seen
+                    .entry(word)
+                    .or_insert_with(|| self.check_whole(word) || self.check_breaks(word, 0))
+            })
+            .collect()
+    }
+
+    /// Check a word without splitting it at `BREAK` separators
+    fn check_whole(&self, word: &str) -> bool {
+        let word = self.prepare(word);
+        let word = word.as_ref();
+        self.check_normalized(word)
+            || without_dots(word).map_or(false, |(dotless, abbrev)| {
+                [dotless, abbrev]
+                    .into_iter()
+                    .any(|form| !self.case_kept(form) && self.check_normalized(form))
+            })
+    }
+
+    /// Whether `word` is only in the dictionary in another case, as entries
+    /// flagged `KEEPCASE`. Such words are not accepted when trailing dots are
+    /// removed or kept.
+    fn case_kept(&self, word: &str) -> bool {
+        let mut buf = self.data.lower_buf();
+        let lower = buf.lower(word);
+        self.data.keepcase_only(word, lower)
+    }
+
+    /// Normalize an input word and apply `ICONV`, before it is looked up
+    fn prepare<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        let convs = self.data.parsed_config.input_conversions();
+        match self.normalization.apply(word) {
+            Cow::Borrowed(word) => convs.convert(word),
+            Cow::Owned(word) => Cow::Owned(convs.convert(&word).into_owned()),
+        }
+    }
+
+    /// Check a word that has already been normalized, in any casing if the
+    /// dictionary is case-insensitive
+    fn check_normalized(&self, word: &str) -> bool {
+        self.check_cased(word)
+            || self.data.folded.as_ref().map_or(false, |folded| {
+                folded
+                    .spellings(word)
+                    .any(|spelling| self.check_cased(spelling))
+            })
+    }
+
+    /// Check a normalized word with the usual case rules
+    fn check_cased(&self, word: &str) -> bool {
         // FIXME: we should make sure there are no overlaps among our wordlists
-        let lower = word.to_lowercase();
-        (!self.wordlist_forbidden.0.contains_key(word))
-            && (self.wordlist.0.contains_key(word)
-                || self.wordlist.0.contains_key(lower.as_str())
-                || self.wordlist_nosuggest.0.contains_key(word)
-                || self.wordlist_nosuggest.0.contains_key(lower.as_str()))
+        let mut buf = self.data.lower_buf();
+        let lower = buf.lower(word);
+        if self.data.wordlist_forbidden.0.contains_key(word) {
+            return false;
+        }
+        if self.data.wordlist.contains_either(word, lower)
+            || self.data.wordlist_nosuggest.contains_either(word, lower)
+        {
+            return true;
+        }
+
+        let (_, derived) = self.data.derive_either(word, lower);
+        !rules_reverse::any_forbidden(&derived)
+            && (!rules_reverse::accepted(derived).is_empty()
+                || self.is_accepted_number(word)
+                || self.data.compound_parts(word).is_some()
+                || self.data.elision_parts(word).is_some())
     }
 
     /// Check words in a string, returning a list of the start and end indices
@@ -199,240 +474,1137 @@ impl Dictionary {
         &'d self,
         input: &'a str,
     ) -> impl Iterator<Item = (usize, &'a str)> + 'd {
-        word_splitter(input).filter(|(_idx, w)| !self.check_word(w))
+        self.check_indices_inner(input, None)
     }
 
-    /// Helper for `locate_word` that allows setting the index
-    fn locate_word_inner<'d, 's>(&'d self, word: &'s str, index: usize) -> WordEntry<'d, 's> {
-        let lower = word.to_lowercase();
-
-        let ctx = if self.wordlist_forbidden.0.contains_key(word)
-            || self.wordlist_forbidden.0.contains_key(lower.as_str())
-        {
-            WordCtx::Incorrect { forbidden: true }
-        } else if let Some((matched, meta)) = self.wordlist.0.get_key_value(word) {
-            WordCtx::Correct {
-                matched,
-                meta_list: meta,
-            }
-        } else if let Some((matched, meta)) = self.wordlist.0.get_key_value(lower.as_str()) {
-            WordCtx::Correct {
-                matched,
-                meta_list: meta,
-            }
-        } else if let Some((matched, meta)) = self.wordlist_nosuggest.0.get_key_value(word) {
-            WordCtx::Correct {
-                matched,
-                meta_list: meta,
-            }
-        } else if let Some((matched, meta)) =
-            self.wordlist_nosuggest.0.get_key_value(lower.as_str())
+    /// Like [`check`](Self::check), but stops once `cancel` is cancelled.
+    /// Returns `None` if that happened before an incorrect word was found or
+    /// the whole input was checked.
+    ///
+    /// ```
+    /// use zspell::CancelToken;
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\nthe\ncat")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let cancel = CancelToken::new();
+    /// assert_eq!(dict.check_cancellable("the cat", &cancel), Some(true));
+    /// cancel.cancel();
+    /// assert_eq!(dict.check_cancellable("the cat", &cancel), None);
+    /// ```
+    #[inline]
+    pub fn check_cancellable(&self, input: &str, cancel: &CancelToken) -> Option<bool> {
+        if self
+            .check_indices_inner(input, Some(cancel))
+            .next()
+            .is_some()
         {
-            WordCtx::Correct {
-                matched,
-                meta_list: meta,
-            }
+            Some(false)
+        } else if cancel.is_cancelled() {
+            None
         } else {
-            WordCtx::Incorrect { forbidden: false }
-        };
-
-        WordEntry {
-            word,
-            index,
-            dict: self,
-            context: ctx,
+            Some(true)
         }
     }
 
-    /// Return an iterator over entries for each word in a sentence.
+    /// Like [`check_indices`](Self::check_indices), but the iterator ends once
+    /// `cancel` is cancelled. The token is checked before each word.
+    #[inline]
+    pub fn check_indices_cancellable<'a: 'd, 'd>(
+        &'d self,
+        input: &'a str,
+        cancel: &'d CancelToken,
+    ) -> impl Iterator<Item = (usize, &'a str)> + 'd {
+        self.check_indices_inner(input, Some(cancel))
+    }
+
+    /// Like [`check_indices`](Self::check_indices), but calls `progress` as
+    /// the input is checked so that long documents can show a progress bar.
     ///
-    /// This can be used to stem or analyze words that are spelled correctly, or provide
-    /// suggestions for incorrect words. See [`WordEntry`] for more information.
+    /// `progress` is called each time another 64 kilobytes of the input have
+    /// been checked, and once more with [`Progress::is_done`] when the iterator
+    /// is exhausted. It runs on the thread that drives the iterator, so it
+    /// should be cheap or hand the value off elsewhere.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\nthe\ncat")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut reports = Vec::new();
+    /// let errors: Vec<_> = dict
+    ///     .check_indices_with_progress("teh cat", |p| reports.push(p))
+    ///     .collect();
+    ///
+    /// assert_eq!(errors, [(0, "teh")]);
+    /// assert_eq!(reports.len(), 1);
+    /// assert!(reports[0].is_done());
+    /// assert_eq!(reports[0].words, 2);
+    /// ```
     #[inline]
-    pub fn entries<'d, 's>(&'d self, input: &'s str) -> impl Iterator<Item = WordEntry<'d, 's>> {
-        word_splitter(input).map(|(idx, word)| self.locate_word_inner(word, idx))
+    pub fn check_indices_with_progress<'a: 'd, 'd, F>(
+        &'d self,
+        input: &'a str,
+        progress: F,
+    ) -> impl Iterator<Item = (usize, &'a str)> + 'd
+    where
+        F: FnMut(Progress) + 'd,
+    {
+        let words = WithProgress::new(self.words(input), input.len(), progress);
+        self.check_words(input, words)
     }
 
-    /// Return an entry for a single word.
+    /// Find words that repeat the word right before them, such as the second
+    /// "the" in "the the". Returns the byte index and text of each repetition.
     ///
-    /// This can be used to stem or analyze words that are spelled correctly, or provide
-    /// suggestions for incorrect words. See [`WordEntry`] for more information.
+    /// Words are split like [`check_indices`](Self::check_indices) does, and
+    /// are compared case-insensitively. They only count as repeated if nothing
+    /// but whitespace is between them, so "that, that" and "1 1" are not
+    /// reported. Some repetitions are correct, like "had had", so callers may
+    /// want to present these as warnings rather than errors.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("3\nthe\ncat\nthat")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let input = "The the cat saw that, that cat\ncat";
+    /// let repeated: Vec<_> = dict.repeated_words(input).collect();
+    /// assert_eq!(repeated, [(4, "the"), (31, "cat")]);
+    /// ```
     #[inline]
-    pub fn entry<'d, 's>(&'d self, word: &'s str) -> WordEntry<'d, 's> {
-        self.locate_word_inner(word, 0)
+    pub fn repeated_words<'a: 'd, 'd>(
+        &'d self,
+        input: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a str)> + 'd {
+        let casing = self.data.casing;
+        let mut prev: Option<(usize, &'a str)> = None;
+        self.words(input).filter(move |&(idx, word)| {
+            let repeated = prev.map_or(false, |(prev_idx, prev_word)| {
+                // Words from a segmenter may not be in order
+                let gap = input
+                    .get(prev_idx + prev_word.len()..idx)
+                    .unwrap_or_default();
+                !gap.is_empty()
+                    && gap.chars().all(char::is_whitespace)
+                    && word.chars().any(char::is_alphabetic)
+                    && casing.fold(prev_word) == casing.fold(word)
+            });
+            prev = Some((idx, word));
+            repeated
+        })
     }
 
-    /// Return a reference to the internal wordlist
+    fn check_indices_inner<'a: 'd, 'd>(
+        &'d self,
+        input: &'a str,
+        cancel: Option<&'d CancelToken>,
+    ) -> impl Iterator<Item = (usize, &'a str)> + 'd {
+        let words = self
+            .words(input)
+            .take_while(move |_| !cancel.map_or(false, CancelToken::is_cancelled));
+        self.check_words(input, words)
+    }
+
+    /// Errors among `words`, which were split from `input`
+    fn check_words<'a: 'd, 'd>(
+        &'d self,
+        input: &'a str,
+        words: impl Iterator<Item = (usize, &'a str)> + 'd,
+    ) -> impl Iterator<Item = (usize, &'a str)> + 'd {
+        words
+            .map(|(idx, w)| (idx, self.with_abbrev_dot(input, idx, w)))
+            .filter(|(_idx, w)| !self.check_word(w))
+            .flat_map(|(idx, w)| self.word_errors(idx, w))
+    }
+
+    /// Replace the segmenter set with [`DictBuilder::segmenter`]
+    pub(crate) fn set_segmenter(&mut self, segmenter: SegmenterHook) {
+        self.segmenter = segmenter;
+    }
+
+    /// Return the single most likely spelling of `word`: the word itself if it
+    /// is correct, otherwise its best suggestion, or `None` if there are no
+    /// suggestions. Feature gated behind `unstable-suggestions`.
+    ///
+    /// Capitalization of the input is kept, so `Pne` is corrected to `Pine`.
+    ///
+    /// ```
+    /// # #![cfg(feature = "unstable-suggestions")]
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\npine\napple")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(dict.correct("apple").as_deref(), Some("apple"));
+    /// assert_eq!(dict.correct("appel").as_deref(), None);
+    /// assert_eq!(dict.correct("pne").as_deref(), Some("pine"));
+    /// assert_eq!(dict.correct("Pne").as_deref(), Some("Pine"));
+    /// assert_eq!(dict.correct("PNE").as_deref(), Some("PINE"));
+    /// ```
     #[inline]
-    #[doc(hidden)]
-    pub fn wordlist(&self) -> &WordList {
-        &self.wordlist
+    #[cfg(feature = "unstable-suggestions")]
+    pub fn correct(&self, word: &str) -> Option<String> {
+        let entry = self.entry(word);
+        if entry.correct() {
+            return Some(word.to_owned());
+        }
+        if let Some(best) = entry.suggest().and_then(|s| s.into_iter().next()) {
+            return Some(best.into_owned());
+        }
+
+        // The wordlist is mostly lowercase, so retry without capitals and then
+        // restore them
+        let casing = self.data.casing;
+        let lower = casing.to_lowercase(word);
+        if lower == word {
+            return None;
+        }
+        let best = self.entry(&lower).suggest()?.into_iter().next()?;
+        if word.chars().all(|c| !c.is_lowercase()) {
+            Some(casing.to_uppercase(&best))
+        } else if matches!(word.chars().next(), Some(c) if c.is_uppercase()) {
+            Some(casing.capitalize(&best))
+        } else {
+            Some(best.into_owned())
+        }
     }
 
-    /// Return a reference to the internal nosuggest wordlist
+    /// Hit and miss statistics for the suggestion cache, or `None` if it was not
+    /// enabled with [`DictBuilder::suggestion_cache`]. Feature gated behind
+    /// `unstable-suggestions`.
     #[inline]
-    #[doc(hidden)]
-    pub fn wordlist_nosuggest(&self) -> &WordList {
-        &self.wordlist_nosuggest
+    #[cfg(feature = "unstable-suggestions")]
+    pub fn suggestion_cache_stats(&self) -> Option<SuggestionCacheStats> {
+        self.suggestion_cache.as_ref().map(|cache| cache.stats())
     }
 
-    /// Return a reference to the internal forbidden wordlist
+    /// The Unicode normalization applied to words before lookup, see
+    /// [`DictBuilder::normalization`]
     #[inline]
-    #[doc(hidden)]
-    pub fn wordlist_forbidden(&self) -> &WordList {
-        &self.wordlist_forbidden
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
     }
-}
 
-/// Internal config API
-impl Dictionary {
-    /// Create a vector of words from a single root word by applying rules in
-    /// this affix. Does not check if the flag is valid.
+    /// Directives in the affix file that were parsed but that checking and
+    /// suggestions do not honor yet, such as `KEEPCASE` or `CHECKSHARPS`, in the
+    /// order they first appear. If this is not empty, the dictionary may accept
+    /// or suggest some words that Hunspell would not, or the other way around.
     ///
-    /// May contain duplicates, does not contain the original word
-    fn create_affixed_words(&mut self, stem: &str, flags: &[Flag], morph: &[Arc<MorphInfo>]) {
-        let mut prefix_rules = Vec::new();
-        let mut suffix_rules = Vec::new();
-
-        let stem: &Arc<str> = self
-            .stems
-            .get_or_insert_with(&StrWrapper::new(stem), |s: &StrWrapper| Arc::from(s.0));
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("KEEPCASE K\nCOMPOUNDFLAG C\nTRY abc")
+    ///     .dict_str("1\nword")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(dict.unsupported_options(), ["KEEPCASE", "TRY"]);
+    /// ```
+    #[inline]
+    pub fn unsupported_options(&self) -> &[&'static str] {
+        self.data.parsed_config.unsupported()
+    }
 
-        let mut add_stem = true;
-        let mut forbid = false;
-        let mut nosuggest = false;
+    /// Problems in the affix file that were skipped while building. These are
+    /// lines that do not start with a known directive and, with
+    /// [`ParseMode::Permissive`], malformed directives and repeated affix
+    /// flags.
+    ///
+    /// ```
+    /// use zspell::error::ParseErrorKind;
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str("SET UTF-8\nNOTADIRECTIVE 1\n")
+    ///     .dict_str("1\nword")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let issues = dict.parse_issues();
+    /// assert_eq!(issues.len(), 1);
+    /// assert_eq!(
+    ///     issues[0].err(),
+    ///     &ParseErrorKind::UnknownDirective("NOTADIRECTIVE".to_owned())
+    /// );
+    /// ```
+    #[inline]
+    pub fn parse_issues(&self) -> &[ParseError] {
+        self.data.parsed_config.issues()
+    }
 
-        for flag in flags {
-            if self.affix_flags.get(flag).is_none() {
-                // FIXME: we get stuck on compound rules
-                continue;
-            }
+    /// Problems in the word list, in the order of the lines they are on. These
+    /// are flags that the affix file does not define, with
+    /// [`ParseMode::Permissive`], and the problems found by
+    /// [`DictBuilder::validate_dict`].
+    #[inline]
+    pub fn dict_warnings(&self) -> &[DictWarning] {
+        &self.data.dict_warnings
+    }
 
-            match self.affix_flags.get(flag).unwrap() {
-                FlagValue::ForbiddenWord => forbid = true,
-                FlagValue::NoSuggest => nosuggest = true,
-                FlagValue::Rule(rule) => {
-                    if rule.is_pfx() {
-                        prefix_rules.push(rule);
-                    } else {
-                        suffix_rules.push(rule);
-                    }
-                }
-                // Don't add the stem to the dictionary
-                FlagValue::AfxNeeded => add_stem = false,
-                _ => {
-                    // FIXME: should be unimplemented
-                    // unimplemented!()
-                    // eprintln!("unexpected flag {}", flag);
-                }
+    /// Flags of the affix rules that no dictionary entry and no other rule
+    /// refers to, as written in the affix file. These rules never produce a
+    /// word, which usually means that a flag was mistyped.
+    ///
+    /// ```
+    /// let aff = "SFX S Y 1\nSFX S 0 s .\n\nSFX D Y 1\nSFX D 0 ed .\n";
+    /// let dict = zspell::builder()
+    ///     .config_str(aff)
+    ///     .dict_str("1\ncat/S")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(dict.unused_affix_flags(), ["D"]);
+    /// ```
+    #[inline]
+    pub fn unused_affix_flags(&self) -> Vec<&str> {
+        let data = &self.data;
+        let mut used: BTreeSet<Flag> = data.flag_sets.iter().flatten().copied().collect();
+        for value in data.affix_flags.values() {
+            if let FlagValue::Rule(rule) = value {
+                used.extend(rule.patterns().iter().flat_map(AfxRulePattern::cont_flags));
             }
         }
 
-        // Forbid trumps nosuggest
-        let dest = if forbid {
-            &mut self.wordlist_forbidden
-        } else if nosuggest {
-            &mut self.wordlist_nosuggest
-        } else {
-            &mut self.wordlist
-        };
-
-        let dict_meta = if add_stem {
-            #[cfg(not(box_from_slice_has_clone_bound))]
-            let morph = morph.to_owned(); // create a temporary vec if < 1.71
-            let meta = Meta::new(stem.clone(), Source::Dict(morph.into()));
-            let meta_vec = dest.0.entry_ref(stem.as_ref()).or_insert_with(Vec::new);
-            meta_vec.push(Meta::clone(&meta));
-            Some(meta)
-        } else {
-            None
-        };
-
-        create_affixed_word_map(stem, &prefix_rules, &suffix_rules, dict_meta.as_ref(), dest);
-        prefix_rules.clear();
-        suffix_rules.clear();
+        data.affix_flags
+            .iter()
+            .filter_map(|(flag, value)| match value {
+                FlagValue::Rule(rule) if !used.contains(flag) => Some(rule.ident()),
+                _ => None,
+            })
+            .collect()
     }
 
-    /// Update the internal wordlist and forbidden wordlist from a dictionary
-    /// file string
-    fn parse_update_wordlist(&mut self, source: &str) -> Result<(), Error> {
-        // FIXME: this could potentially be lazy, I don't think we need to collect to a Vec
-        let entries = DictEntry::parse_all(source, self.flag_type)?;
-        self.update_wordlist(&entries);
-        Ok(())
+    /// Compute the phonetic key of `word` using the dictionary's `PHONE` table,
+    /// or return `None` if it does not have one.
+    ///
+    /// Words that sound alike have the same key, so this can be used to group
+    /// similar user input or compare it against dictionary words. Keys are case
+    /// insensitive.
+    ///
+    /// ```
+    /// let aff = "
+    /// PHONE 6
+    /// PHONE PH F
+    /// PHONE F F
+    /// PHONE O O
+    /// PHONE N N
+    /// PHONE E _
+    /// PHONE T T
+    /// ";
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str(aff)
+    ///     .dict_str("1\nphone")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(dict.phonetic_key("phone").as_deref(), Some("FON"));
+    /// assert_eq!(dict.phonetic_key("fone"), dict.phonetic_key("Phone"));
+    /// ```
+    #[inline]
+    pub fn phonetic_key(&self, word: &str) -> Option<String> {
+        let table = self.data.phone_table.as_ref()?;
+        let word = self.prepare(word);
+        Some(table.key(&self.data.casing.to_uppercase(&word)))
     }
 
-    /// Update internal wordlists from dictionary entries
-    fn update_wordlist(&mut self, entries: &[DictEntry]) {
-        // the en dictionary has about 3 words per entry, German has 8ish
-        self.wordlist.0.reserve(entries.len() * 5);
+    /// The phonetic algorithm used for suggestions, if any
+    #[cfg(feature = "unstable-suggestions")]
+    fn phonetic_coder(&self) -> Option<PhoneticCoder<'_>> {
+        let table = self
+            .data
+            .phone_table
+            .as_ref()
+            .map(|table| PhoneticCoder::Table(table, self.data.casing));
+        match self.phonetic_suggestions {
+            PhoneticSuggestions::Disabled => None,
+            PhoneticSuggestions::PhoneTable => table,
+            PhoneticSuggestions::DoubleMetaphone => table.or(Some(PhoneticCoder::DoubleMetaphone)),
+        }
+    }
 
-        // PERF: try moving flags outside of loop
-        for entry in entries {
-            let DictEntry { stem, flags, morph } = entry;
+    /// Split a string into words that should be checked
+    fn words<'a, 'd>(&'d self, input: &'a str) -> Words<'a, 'd> {
+        Words::new(
+            input,
+            self.segmenter.get(),
+            self.accept_numeric,
+            self.break_separators(),
+        )
+        .skip_ignored(&self.ignore)
+    }
 
-            self.create_affixed_words(stem, flags, morph);
+    /// Word boundaries drop the final dot of abbreviations such as "etc.", so
+    /// keep it if the dictionary has the word at `idx` of `input` with it
+    fn with_abbrev_dot<'a>(&self, input: &'a str, idx: usize, word: &'a str) -> &'a str {
+        let end = idx + word.len();
+        if !input[end..].starts_with('.') {
+            return word;
+        }
+        let dotted = &input[idx..=end];
+        let normalized = self.prepare(dotted);
+        let mut buf = self.data.lower_buf();
+        let lower = buf.lower(&normalized);
+        let in_dict = self.data.wordlist.contains_either(&normalized, lower)
+            || self
+                .data
+                .wordlist_nosuggest
+                .contains_either(&normalized, lower)
+            || !rules_reverse::accepted(self.data.derive_either(&normalized, lower).1).is_empty();
+        if in_dict && !self.data.keepcase_only(&normalized, lower) {
+            dotted
+        } else {
+            word
         }
     }
 
+    /// Whether `word` is accepted as a number without being in the dictionary
+    fn is_accepted_number(&self, word: &str) -> bool {
+        is_number(word)
+            || (self.accept_numeric && is_numeric_token(word))
+            || (self.accept_roman && is_roman_numeral(word))
+    }
+
+    /// Helper for `locate_word` that allows setting the index
+    fn locate_word_inner<'d, 's>(&'d self, word: &'s str, index: usize) -> WordEntry<'d, 's> {
+        let normalized = self.prepare(word);
+        let lookup = normalized.as_ref();
+        let mut ctx = self.word_ctx(lookup);
+        if matches!(ctx, WordCtx::Incorrect { forbidden: false }) {
+            if let Some((dotless, abbrev)) = without_dots(lookup) {
+                for form in [dotless, abbrev] {
+                    if !self.case_kept(form) {
+                        ctx = self.word_ctx(form);
+                    }
+                    if ctx.is_correct() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        WordEntry {
+            word,
+            index,
+            dict: self,
+            context: ctx,
+            cancel: None,
+        }
+    }
+
+    /// Find what the dictionary knows about a normalized word, in any casing if
+    /// the dictionary is case-insensitive
+    fn word_ctx(&self, lookup: &str) -> WordCtx<'_> {
+        let ctx = self.word_ctx_cased(lookup);
+        let Some(folded) = &self.data.folded else {
+            return ctx;
+        };
+        if !matches!(ctx, WordCtx::Incorrect { forbidden: false }) {
+            return ctx;
+        }
+        folded
+            .spellings(lookup)
+            .map(|spelling| self.word_ctx_cased(spelling))
+            .find(WordCtx::is_correct)
+            .unwrap_or(ctx)
+    }
+
+    /// Find what the dictionary knows about a normalized word with the usual
+    /// case rules
+    fn word_ctx_cased(&self, lookup: &str) -> WordCtx<'_> {
+        let mut buf = self.data.lower_buf();
+        let lower = buf.lower(lookup);
+
+        if self.data.wordlist_forbidden.contains_either(lookup, lower) {
+            return WordCtx::Incorrect { forbidden: true };
+        }
+        if let Some((matched, meta_list)) = self
+            .data
+            .wordlist
+            .get_either(lookup, lower)
+            .or_else(|| self.data.wordlist_nosuggest.get_either(lookup, lower))
+        {
+            return WordCtx::Correct { matched, meta_list };
+        }
+
+        let (matched, derived) = self.data.derive_either(lookup, lower);
+        if rules_reverse::any_forbidden(&derived) {
+            return WordCtx::Incorrect { forbidden: true };
+        }
+        let derivations = rules_reverse::accepted(derived);
+
+        if !derivations.is_empty() {
+            WordCtx::Derived {
+                matched: matched.into(),
+                derivations,
+            }
+        } else if self.is_accepted_number(lookup) {
+            WordCtx::Number
+        } else if let Some(parts) = self
+            .data
+            .compound_parts(lookup)
+            .or_else(|| self.data.elision_parts(lookup))
+        {
+            WordCtx::Compound { parts }
+        } else {
+            WordCtx::Incorrect { forbidden: false }
+        }
+    }
+
+    /// Return an iterator over entries for each word in a sentence.
+    ///
+    /// This can be used to stem or analyze words that are spelled correctly, or provide
+    /// suggestions for incorrect words. See [`WordEntry`] for more information.
+    ///
+    /// If [`DictBuilder::check_break_parts`] is set, words such as "well-known"
+    /// that are not in the dictionary get an entry for each part.
+    #[inline]
+    pub fn entries<'d, 's>(&'d self, input: &'s str) -> impl Iterator<Item = WordEntry<'d, 's>> {
+        self.entries_inner(input, None)
+    }
+
+    /// Like [`entries`](Self::entries), but the iterator ends once `cancel` is
+    /// cancelled. The token is checked before each word, and suggestions for the
+    /// returned entries also stop early, returning what was found so far.
+    #[inline]
+    pub fn entries_cancellable<'d, 's>(
+        &'d self,
+        input: &'s str,
+        cancel: &'d CancelToken,
+    ) -> impl Iterator<Item = WordEntry<'d, 's>> {
+        self.entries_inner(input, Some(cancel))
+    }
+
+    fn entries_inner<'d, 's>(
+        &'d self,
+        input: &'s str,
+        cancel: Option<&'d CancelToken>,
+    ) -> impl Iterator<Item = WordEntry<'d, 's>> {
+        let cancelled = move || cancel.map_or(false, CancelToken::is_cancelled);
+        self.words(input)
+            .take_while(move |_| !cancelled())
+            .flat_map(move |(idx, word)| {
+                let word = self.with_abbrev_dot(input, idx, word);
+                let entry = self.locate_word_inner(word, idx).with_cancel(cancel);
+                let parts = if entry.correct() || self.break_separators().is_empty() {
+                    Vec::new()
+                } else {
+                    self.break_parts(word)
+                };
+                let split = parts.len() > 1;
+                core::iter::once(entry).filter(move |_| !split).chain(
+                    parts
+                        .into_iter()
+                        .filter(move |_| split)
+                        .map(move |(offset, part)| {
+                            self.locate_word_inner(part, idx + offset)
+                                .with_cancel(cancel)
+                        }),
+                )
+            })
+    }
+
+    /// Return an entry for a single word.
+    ///
+    /// This can be used to stem or analyze words that are spelled correctly, or provide
+    /// suggestions for incorrect words. See [`WordEntry`] for more information.
+    #[inline]
+    pub fn entry<'d, 's>(&'d self, word: &'s str) -> WordEntry<'d, 's> {
+        self.locate_word_inner(word, 0)
+    }
+
+    /// Iterate over every accepted word, including all affixed forms, along with
+    /// the root it was created from and the affix rules that were applied. This
+    /// can be used to audit what a dictionary's rules generate.
+    ///
+    /// Words are returned in no particular order. A word that can be created in
+    /// more than one way is returned once for each. Forbidden words are not
+    /// included.
+    ///
+    /// ```
+    /// let aff = "
+    /// PFX U Y 1
+    /// PFX U 0 un .
+    ///
+    /// SFX S Y 1
+    /// SFX S 0 s .
+    /// ";
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str(aff)
+    ///     .dict_str("1\nlock/US")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut forms: Vec<(&str, &str, Vec<&str>)> = dict
+    ///     .word_forms()
+    ///     .map(|f| (f.word, f.root, f.affixes.iter().map(|a| a.flag).collect()))
+    ///     .collect();
+    /// forms.sort();
+    ///
+    /// assert_eq!(
+    ///     forms,
+    ///     [
+    ///         ("lock", "lock", vec![]),
+    ///         ("locks", "lock", vec!["S"]),
+    ///         ("unlock", "lock", vec!["U"]),
+    ///         ("unlocks", "lock", vec!["U", "S"]),
+    ///     ]
+    /// );
+    /// ```
+    #[inline]
+    pub fn word_forms(&self) -> impl Iterator<Item = WordForm<'_>> {
+        [
+            &self.data.wordlist,
+            &self.data.wordlist_nosuggest,
+            &self.data.wordlist_compound_only,
+        ]
+        .into_iter()
+        .flat_map(|wordlist| wordlist.0.iter())
+        .flat_map(|(word, metas)| WordForm::from_metas(word, metas))
+    }
+
+    /// Return every way that `word` is created from a root and a chain of affix
+    /// rules. This can be used to debug why an unexpected word is accepted.
+    ///
+    /// The word is looked up like it is when checking, so a capitalized word may
+    /// match a lowercase entry. Words that are accepted for other reasons, such
+    /// as compounds or numbers, have no derivations.
+    ///
+    /// ```
+    /// let aff = "
+    /// SFX S Y 1
+    /// SFX S 0 s .
+    ///
+    /// SFX T Y 1
+    /// SFX T 0 ts .
+    /// ";
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str(aff)
+    ///     .dict_str("2\nbat/S\nba/T")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut derivations: Vec<(&str, Vec<&str>)> = dict
+    ///     .derivations("Bats")
+    ///     .into_iter()
+    ///     .map(|d| (d.root, d.affixes.iter().map(|a| a.flag).collect()))
+    ///     .collect();
+    /// derivations.sort();
+    ///
+    /// assert_eq!(derivations, [("ba", vec!["T"]), ("bat", vec!["S"])]);
+    /// assert!(dict.derivations("bta").is_empty());
+    /// ```
+    #[inline]
+    pub fn derivations(&self, word: &str) -> Vec<WordForm<'_>> {
+        let word = self.prepare(word);
+        let mut buf = self.data.lower_buf();
+        let lower = buf.lower(&word);
+
+        [
+            &self.data.wordlist,
+            &self.data.wordlist_nosuggest,
+            &self.data.wordlist_compound_only,
+        ]
+        .into_iter()
+        .filter_map(|wordlist| wordlist.get_either(&word, lower))
+        .flat_map(|(matched, metas)| WordForm::from_metas(matched, metas))
+        .collect()
+    }
+
+    /// Words that are accepted and may be suggested, including those from the
+    /// personal dictionary
+    #[inline]
+    pub fn wordlist(&self) -> &WordList {
+        &self.data.wordlist
+    }
+
+    /// Words that are accepted but never suggested, `NOSUGGEST`
+    #[inline]
+    pub fn wordlist_nosuggest(&self) -> &WordList {
+        &self.data.wordlist_nosuggest
+    }
+
+    /// Words that are never accepted, from `FORBIDDENWORD` or the personal
+    /// dictionary
+    #[inline]
+    pub fn wordlist_forbidden(&self) -> &WordList {
+        &self.data.wordlist_forbidden
+    }
+
+    /// Words that are only accepted as part of a compound, `ONLYINCOMPOUND`
+    #[inline]
+    pub fn wordlist_compound_only(&self) -> &WordList {
+        &self.data.wordlist_compound_only
+    }
+}
+
+// Runtime update API
+impl Dictionary {
+    /// Add a word to the dictionary so it is accepted by future checks. Returns
+    /// `false` if the word was already in the wordlist.
+    ///
+    /// Only the exact word is added; no affix rules are applied to it. If the word
+    /// was forbidden, it is no longer.
+    ///
+    /// The compiled wordlists are shared among clones of a `Dictionary`, so the
+    /// first update made to a clone copies them. To update a dictionary that is
+    /// being read by other threads, see [`SharedDictionary`](crate::SharedDictionary).
+    ///
+    /// ```
+    /// let mut dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("1\nfoo")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(!dict.check_word("zspell"));
+    /// assert!(dict.add_word("zspell"));
+    /// assert!(dict.check_word("zspell"));
+    /// assert!(!dict.add_word("foo"));
+    /// ```
+    #[inline]
+    pub fn add_word(&mut self, word: &str) -> bool {
+        let word = self.prepare(word).into_owned();
+        let data = &self.data;
+        if data.wordlist.0.contains_key(word.as_str())
+            && !data.wordlist_forbidden.0.contains_key(word.as_str())
+        {
+            return false;
+        }
+        self.data_mut().add_runtime_word(&word)
+    }
+
+    /// Remove a word from the dictionary so it is no longer accepted. Returns
+    /// `false` if the word was not in the wordlist.
+    ///
+    /// As with [`add_word`](Self::add_word), this only affects the exact word;
+    /// other forms created from it by affix rules are still accepted.
+    ///
+    /// ```
+    /// let mut dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("1\nfoo")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(dict.remove_word("foo"));
+    /// assert!(!dict.check_word("foo"));
+    /// assert!(!dict.remove_word("foo"));
+    /// ```
+    #[inline]
+    pub fn remove_word(&mut self, word: &str) -> bool {
+        let word = self.prepare(word);
+        let data = &self.data;
+        if !data.wordlist.0.contains_key(word.as_ref())
+            && !data.wordlist_nosuggest.0.contains_key(word.as_ref())
+        {
+            return false;
+        }
+        let data = self.data_mut();
+        let removed = data.wordlist.0.remove(word.as_ref()).is_some();
+        data.wordlist_nosuggest.0.remove(word.as_ref()).is_some() || removed
+    }
+}
+
+/// Internal config API
+impl DictData {
+    fn new(cfg: ParsedCfg) -> Result<Self, Error> {
+        // FIXME: what do we do with rule flags?
+        let CompiledFlags {
+            affix_flags,
+            rule_flags: _,
+        } = cfg.compile_flags()?;
+        let casing = Casing::from_lang(cfg.lang());
+
+        Ok(Self {
+            wordlist: WordList::new(),
+            wordlist_nosuggest: WordList::new(),
+            wordlist_compound_only: WordList::new(),
+            wordlist_forbidden: WordList::new(),
+            stems: Interner::new(),
+            morphs: Interner::new(),
+            morph_sets: Interner::new(),
+            flag_sets: Interner::new(),
+            affix_flags,
+            flag_type: cfg.flag_type(),
+            phone_table: PhoneTable::new(cfg.phonetics()),
+            parsed_config: Box::new(cfg),
+            #[cfg(feature = "unstable-suggestions")]
+            frequencies: FxHashMap::default(),
+            lazy: None,
+            folded: None,
+            casing,
+            dict_warnings: Vec::new(),
+        })
+    }
+
+    /// Create a vector of words from a single root word by applying rules in
+    /// this affix. Does not check if the flag is valid.
+    ///
+    /// May contain duplicates, does not contain the original word
+    fn create_affixed_words(&mut self, stem: &str, flags: &[Flag], morph: &[Arc<MorphInfo>]) {
+        let mut prefix_rules = Vec::new();
+        let mut suffix_rules = Vec::new();
+
+        let stem = self.stems.intern(stem);
+        let flag_set = self.flag_sets.intern(flags);
+
+        let twofold = Twofold {
+            affix_flags: &self.affix_flags,
+            kind: self.twofold_kind(),
+        };
+        let mut add_stem = true;
+        let mut forbid = false;
+        let mut nosuggest = false;
+        let mut compound_only = false;
+
+        for flag in flags {
+            if self.affix_flags.get(flag).is_none() {
+                // FIXME: we get stuck on compound rules
+                continue;
+            }
+
+            match self.affix_flags.get(flag).unwrap() {
+                FlagValue::ForbiddenWord => forbid = true,
+                FlagValue::NoSuggest => nosuggest = true,
+                FlagValue::CompoundOnly => compound_only = true,
+                FlagValue::Rule(rule) => {
+                    if rule.is_pfx() {
+                        prefix_rules.push(rule);
+                    } else {
+                        suffix_rules.push(rule);
+                    }
+                }
+                // Don't add the stem to the dictionary
+                FlagValue::AfxNeeded => add_stem = false,
+                _ => {
+                    // FIXME: should be unimplemented
+                    // unimplemented!()
+                    // eprintln!("unexpected flag {}", flag);
+                }
+            }
+        }
+
+        // A zero affix leaves the stem unchanged, which counts as the affix that
+        // `NEEDAFFIX` asks for
+        if !add_stem {
+            add_stem = prefix_rules
+                .iter()
+                .chain(&suffix_rules)
+                .any(|rule| rule.patterns().iter().any(|pat| pat.is_zero_affix(&stem)));
+        }
+
+        let dict_meta = if add_stem {
+            // Share both the individual morphs and the entire set with other entries
+            let morph: Vec<_> = morph
+                .iter()
+                .map(|m| self.morphs.intern_with(m, |_| Arc::clone(m)))
+                .collect();
+            let morph = self.morph_sets.intern(&morph);
+            Some(Meta::new(
+                Arc::clone(&stem),
+                Arc::clone(&flag_set),
+                Source::Dict(morph),
+            ))
+        } else {
+            None
+        };
+
+        // Forbid trumps compound only, which trumps nosuggest
+        let list = if forbid {
+            EntryList::Forbidden
+        } else if compound_only {
+            EntryList::CompoundOnly
+        } else if nosuggest {
+            EntryList::NoSuggest
+        } else {
+            EntryList::Words
+        };
+
+        // Forms with an `ONLYINCOMPOUND` affix go to their own list unless
+        // forbidden
+        let compound_only_dest = &mut self.wordlist_compound_only;
+        let (dest, compound_only_dest) = match list {
+            EntryList::Forbidden => (&mut self.wordlist_forbidden, None),
+            EntryList::CompoundOnly => (compound_only_dest, None),
+            EntryList::NoSuggest => (&mut self.wordlist_nosuggest, Some(compound_only_dest)),
+            EntryList::Words => (&mut self.wordlist, Some(compound_only_dest)),
+        };
+
+        if let Some(meta) = &dict_meta {
+            dest.0
+                .entry_ref(stem.as_ref())
+                .or_insert_with(|| Vec::with_capacity(1))
+                .push(Meta::clone(meta));
+        }
+
+        // Affixed forms are derived from the root when they are looked up
+        if let Some(lazy) = &mut self.lazy {
+            if !(prefix_rules.is_empty() && suffix_rules.is_empty()) {
+                lazy.add_root(LazyRoot::new(stem, flag_set, dict_meta, list));
+            }
+            return;
+        }
+
+        let target = Target {
+            stem: &stem,
+            flags: &flag_set,
+            dict_meta: dict_meta.as_ref(),
+            dest,
+            compound_only_dest,
+        };
+        create_affixed_word_map(target, &prefix_rules, &suffix_rules, Some(twofold));
+        prefix_rules.clear();
+        suffix_rules.clear();
+    }
+
+    /// Update the internal wordlist and forbidden wordlist from a dictionary
+    /// file string. The parsed entries are returned so the personal dictionary
+    /// can refer to them.
+    fn parse_update_wordlist(
+        &mut self,
+        source: &str,
+        mode: ParseMode,
+    ) -> Result<Vec<DictEntry>, Error> {
+        let (entries, line_nums) = {
+            trace::enter_span!(DEBUG, "parse_dict", bytes = source.len());
+            DictEntry::parse_numbered(source, self.flag_type)?
+        };
+        trace::event!(DEBUG, entries = entries.len(), "parsed dictionary file");
+        self.check_flags(&entries, &line_nums, mode)?;
+        self.update_wordlist(&entries);
+        Ok(entries)
+    }
+
+    /// Number of `flags` that refer to affix rules
+    fn rule_flag_count(&self, flags: &[Flag]) -> usize {
+        flags
+            .iter()
+            .filter(|flag| matches!(self.affix_flags.get(flag), Some(FlagValue::Rule(_))))
+            .count()
+    }
+
+    /// Update internal wordlists from dictionary entries
+    fn update_wordlist(&mut self, entries: &[DictEntry]) {
+        trace::enter_span!(DEBUG, "expand_affixes", entries = entries.len());
+        // Size the maps up front rather than rehashing while expanding. Each
+        // entry is stored once, and most rule flags add about one form.
+        let words = match &mut self.lazy {
+            Some(lazy) => {
+                lazy.reserve(entries.len());
+                entries.len()
+            }
+            None => entries
+                .iter()
+                .map(|entry| 1 + self.rule_flag_count(&entry.flags))
+                .sum(),
+        };
+        self.wordlist.0.reserve(words);
+        self.stems.reserve(entries.len());
+
+        // PERF: try moving flags outside of loop
+        for entry in entries {
+            let DictEntry { stem, flags, morph } = entry;
+
+            self.create_affixed_words(stem, flags, morph);
+        }
+
+        trace::event!(
+            DEBUG,
+            words = self.wordlist.0.len(),
+            nosuggest = self.wordlist_nosuggest.0.len(),
+            forbidden = self.wordlist_forbidden.0.len(),
+            compound_only = self.wordlist_compound_only.0.len(),
+            "expanded affixes"
+        );
+    }
+
     #[allow(clippy::unnecessary_wraps)] // parsing may become fallible
     fn parse_update_personal(&mut self, source: &str, dict: &[DictEntry]) -> Result<(), Error> {
+        trace::enter_span!(DEBUG, "parse_personal", bytes = source.len());
         let entries = PersonalEntry::parse_all(source);
+        trace::event!(DEBUG, entries = entries.len(), "parsed personal dictionary");
         self.update_personal(entries, dict);
         Ok(())
     }
 
     /// Must happen after `update_wordlist`
-    fn update_personal(&mut self, entries: Vec<PersonalEntry>, _dict: &[DictEntry]) {
-        // FIXME: don't take `dict` as an argument, use our existing hashmaps
+    fn update_personal(&mut self, entries: Vec<PersonalEntry>, dict: &[DictEntry]) {
+        // FIXME: don't take `dict` as an argument, use our existing hashmaps. This
+        // needs flags to be stored in the wordlist.
         self.wordlist.0.reserve(entries.len() * 2);
-        for entry in entries {
-            if let Some(_friend) = &entry.friend {
-                // FIXME:friends Find the friend in our dictionary, find its source affixes
-                // let flags = dict.iter().find(|d| &d.stem() == friend).map(|d| &d.flags);
-            } else {
-                let stem_arc: Arc<str> = self.stems.get_or_insert(entry.stem).clone();
-                let meta = PersonalMeta::new(None, self.get_or_insert_morphs(&entry.morph));
-                let source = Source::Personal(Arc::new(meta));
-                let meta = Meta::new(Arc::clone(&stem_arc), source);
-
-                // Select the correct word to work with
-                let hmap = if entry.forbid {
-                    &mut self.wordlist_forbidden.0
-                } else {
-                    &mut self.wordlist.0
-                };
 
-                // Add our word, update its meta
-                let extra_vec: &mut Vec<Meta> = hmap
-                    .entry_ref(stem_arc.as_ref())
-                    .or_insert_with(|| Vec::with_capacity(1));
-                extra_vec.push(meta);
+        // Only index the dictionary if something needs to look up a root word
+        let mut roots: FxHashMap<&str, &DictEntry> = FxHashMap::default();
+        if entries.iter().any(|entry| entry.friend.is_some()) {
+            for dict_entry in dict {
+                roots.entry(dict_entry.stem.as_ref()).or_insert(dict_entry);
             }
         }
+
+        for entry in entries {
+            // `word/root` adds `word` with the same affixes as `root`. Forbidden
+            // words never get affixes.
+            let root = entry
+                .friend
+                .as_deref()
+                .filter(|_| !entry.forbid)
+                .and_then(|friend| roots.get(friend));
+
+            if let Some(root) = root {
+                let morph = self.get_or_insert_morphs(&entry.morph);
+                self.create_affixed_words(&entry.stem, &root.flags, &morph);
+                continue;
+            }
+
+            // Without a known root, the word is added on its own
+            let stem_arc = self
+                .stems
+                .intern_with(&entry.stem, |_| Arc::clone(&entry.stem));
+            let friend = entry.friend.as_deref().map(Arc::from);
+            let meta = PersonalMeta::new(friend, self.get_or_insert_morphs(&entry.morph));
+            let source = Source::Personal(Arc::new(meta));
+            let meta = Meta::new(Arc::clone(&stem_arc), self.flag_sets.intern(&[]), source);
+
+            // Select the correct word to work with
+            let hmap = if entry.forbid {
+                &mut self.wordlist_forbidden.0
+            } else {
+                &mut self.wordlist.0
+            };
+
+            // Add our word, update its meta
+            let extra_vec: &mut Vec<Meta> = hmap
+                .entry_ref(stem_arc.as_ref())
+                .or_insert_with(|| Vec::with_capacity(1));
+            extra_vec.push(meta);
+        }
     }
 
     /// For each morph in the slice: find it or insert it in our hashset, return
     /// a vector of references to the newly inserted (or found) items
     fn get_or_insert_morphs(&mut self, morphs: &[MorphInfo]) -> Vec<Arc<MorphInfo>> {
-        let mut ret: Vec<Arc<MorphInfo>> = Vec::with_capacity(morphs.len());
-        for morph in morphs {
-            ret.push(
-                self.morphs
-                    .get_or_insert_with(morph, |m| Arc::new(m.clone()))
-                    .clone(),
-            );
+        morphs
+            .iter()
+            .map(|morph| self.morphs.intern_with(morph, |m| Arc::new(m.clone())))
+            .collect()
+    }
+
+    /// Add a single word that was not in the original sources, returning whether
+    /// anything changed
+    fn add_runtime_word(&mut self, word: &str) -> bool {
+        let unforbidden = self.wordlist_forbidden.0.remove(word).is_some();
+        if self.wordlist.0.contains_key(word) {
+            return unforbidden;
         }
-        ret
+
+        let stem = self.stems.intern(word);
+        let flags = self.flag_sets.intern(&[]);
+        let source = Source::Personal(Arc::new(PersonalMeta::new(None, Vec::new())));
+        if let Some(folded) = &mut self.folded {
+            folded.insert(word);
+        }
+        self.wordlist
+            .0
+            .entry_ref(word)
+            .or_insert_with(|| Vec::with_capacity(1))
+            .push(Meta::new(stem, flags, source));
+        true
+    }
+
+    /// Load word counts from a frequency file
+    #[cfg(feature = "unstable-suggestions")]
+    fn parse_update_frequencies(&mut self, source: &str) -> Result<(), Error> {
+        trace::enter_span!(DEBUG, "parse_frequencies", bytes = source.len());
+        let entries = parse::parse_frequencies(source)?;
+        trace::event!(DEBUG, entries = entries.len(), "parsed frequency file");
+        self.frequencies.reserve(entries.len());
+        for (word, count) in entries {
+            *self.frequencies.entry_ref(word).or_insert(0) += count;
+        }
+        Ok(())
+    }
+
+    /// How often a word occurs according to the frequency file, checking its
+    /// lowercase form if the word itself is not listed. Unlisted words have a
+    /// frequency of 0.
+    #[cfg(feature = "unstable-suggestions")]
+    fn frequency(&self, word: &str) -> u64 {
+        if let Some(count) = self.frequencies.get(word) {
+            return *count;
+        }
+        let mut buf = self.lower_buf();
+        buf.lower(word)
+            .and_then(|lower| self.frequencies.get(lower))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Scratch space to lowercase words in the dictionary's language
+    fn lower_buf(&self) -> LowerBuf {
+        LowerBuf::new(self.casing)
+    }
+
+    /// Whether `flags` include `KEEPCASE`
+    fn keeps_case(&self, flags: &[Flag]) -> bool {
+        flags
+            .iter()
+            .any(|flag| matches!(self.affix_flags.get(flag), Some(FlagValue::AfxKeepCase)))
+    }
+
+    /// Whether `word` is not in the dictionary as written, and its lowercase
+    /// form `lower` only matches entries flagged `KEEPCASE`
+    fn keepcase_only(&self, word: &str, lower: Option<&str>) -> bool {
+        let Some(lower) = lower else {
+            return false;
+        };
+        let lists = [&self.wordlist, &self.wordlist_nosuggest];
+        if lists.iter().any(|list| list.0.contains_key(word)) {
+            return false;
+        }
+        let mut metas = lists
+            .iter()
+            .filter_map(|list| list.0.get(lower))
+            .flatten()
+            .peekable();
+        if metas.peek().is_some() {
+            return metas.all(|meta| self.keeps_case(meta.flags()));
+        }
+        let (matched, derived) = self.derive_either(word, Some(lower));
+        let derived = rules_reverse::accepted(derived);
+        matched != word && !derived.is_empty() && derived.iter().all(|d| self.keeps_case(d.flags()))
+    }
+
+    /// The order of equally good suggestions in the dictionary's language
+    #[cfg(feature = "unstable-suggestions")]
+    fn collation(&self) -> Collation {
+        Collation::new(self.parsed_config.lang())
     }
 
     /// Free as much memory as possible when we know we won't be using it anymore
     fn shrink_storage(&mut self) {
-        self.wordlist.0.shrink_to_fit();
-        self.wordlist_nosuggest.0.shrink_to_fit();
-        self.wordlist_forbidden.0.shrink_to_fit();
+        self.wordlist.shrink_to_fit();
+        self.wordlist_nosuggest.shrink_to_fit();
+        self.wordlist_forbidden.shrink_to_fit();
+        self.wordlist_compound_only.shrink_to_fit();
         self.stems.shrink_to_fit();
         self.morphs.shrink_to_fit();
+        self.morph_sets.shrink_to_fit();
+        self.flag_sets.shrink_to_fit();
+        #[cfg(feature = "unstable-suggestions")]
+        self.frequencies.shrink_to_fit();
+        if let Some(lazy) = &mut self.lazy {
+            lazy.shrink_to_fit();
+        }
     }
 }
 
@@ -447,6 +1619,8 @@ pub struct WordEntry<'dict, 'word> {
     index: usize,
     dict: &'dict Dictionary,
     context: WordCtx<'dict>,
+    /// Stops suggestions for entries from [`Dictionary::entries_cancellable`]
+    cancel: Option<&'dict CancelToken>,
 }
 
 impl<'dict, 'word> fmt::Debug for WordEntry<'dict, 'word> {
@@ -471,12 +1645,55 @@ enum WordCtx<'dict> {
         /// Meta located in the dictionary
         meta_list: &'dict [Meta],
     },
+    /// The word is an affixed form of dictionary entries that was derived when it
+    /// was looked up, see [`DictBuilder::lazy_affixes`]
+    Derived {
+        /// The value that was derived, which is the word or its lowercase form
+        matched: Box<str>,
+        derivations: Vec<Derivation<'dict>>,
+    },
+    /// The word is not in the dictionary but is a valid compound of words that
+    /// are, or an elided word such as "l'arbre"
+    Compound {
+        /// The dictionary entries that make up the compound
+        parts: Vec<&'dict str>,
+    },
+    /// The word is not in the dictionary but is an accepted number, such as a
+    /// plain number or an enabled Roman numeral
+    Number,
     Incorrect {
         /// True if the word was located in a forbidden dictionary
         forbidden: bool,
     },
 }
 
+impl<'dict> WordCtx<'dict> {
+    fn is_correct(&self) -> bool {
+        matches!(
+            self,
+            WordCtx::Correct { .. }
+                | WordCtx::Derived { .. }
+                | WordCtx::Compound { .. }
+                | WordCtx::Number
+        )
+    }
+
+    /// The metas of a word that was found in or derived from the dictionary
+    fn metas(&self) -> Option<impl Iterator<Item = MetaRef<'_, 'dict>>> {
+        let (stored, derived): (&'dict [Meta], &[Derivation<'dict>]) = match self {
+            WordCtx::Correct { meta_list, .. } => (meta_list, &[]),
+            WordCtx::Derived { derivations, .. } => (&[], derivations),
+            WordCtx::Compound { .. } | WordCtx::Number | WordCtx::Incorrect { .. } => return None,
+        };
+        Some(
+            stored
+                .iter()
+                .map(MetaRef::Stored)
+                .chain(MetaRef::from_derivations(derived)),
+        )
+    }
+}
+
 impl<'dict, 'word> WordEntry<'dict, 'word> {
     /// Return true if the word is spelled correctly.
     ///
@@ -484,7 +1701,7 @@ impl<'dict, 'word> WordEntry<'dict, 'word> {
     /// [`Dictionary::check`] or related functions.
     #[inline]
     pub fn correct(&self) -> bool {
-        matches!(self.context, WordCtx::Correct { .. })
+        self.context.is_correct()
     }
 
     /// The input word that was checked.
@@ -514,9 +1731,34 @@ impl<'dict, 'word> WordEntry<'dict, 'word> {
     /// the same as `input` but not always).
     #[inline]
     pub fn matched_entry(&self) -> Option<&str> {
-        match self.context {
+        match &self.context {
             WordCtx::Correct { matched, .. } => Some(matched),
-            WordCtx::Incorrect { .. } => None,
+            WordCtx::Derived { matched, .. } => Some(matched),
+            WordCtx::Compound { .. } | WordCtx::Number | WordCtx::Incorrect { .. } => None,
+        }
+    }
+
+    /// If the word was accepted as a compound, return the dictionary entries
+    /// that it is made of. An elided word such as "l'arbre" is made of the
+    /// entries "l'" and "arbre".
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("COMPOUNDFLAG Y")
+    ///     .dict_str("2\nsun/Y\nflower/Y")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let entry = dict.entry("sunflower");
+    /// assert!(entry.correct());
+    /// assert_eq!(entry.compound_parts(), Some(&["sun", "flower"][..]));
+    /// assert_eq!(dict.entry("sun").compound_parts(), None);
+    /// ```
+    #[inline]
+    pub fn compound_parts(&self) -> Option<&[&'dict str]> {
+        match &self.context {
+            WordCtx::Compound { parts } => Some(parts),
+            _ => None,
         }
     }
 
@@ -578,18 +1820,12 @@ impl<'dict, 'word> WordEntry<'dict, 'word> {
         // call. Needs benchmarking.
         let mut visited: Vec<u32> = Vec::new();
 
-        let WordCtx::Correct {
-            matched: _,
-            meta_list,
-        } = self.context
-        else {
-            return None;
-        };
+        let metas = self.context.metas()?;
 
-        let ret = meta_list.iter().flat_map(|meta| {
+        let ret = metas.flat_map(|meta| {
             // Combine the main stem with every stem provided by morphs
-            let stem = std::iter::once(meta.stem());
-            let morph_stems = meta.source().morphs().filter_map(|morph| match morph {
+            let stem = core::iter::once(meta.stem());
+            let morph_stems = meta.morphs().filter_map(|morph| match morph {
                 MorphInfo::Stem(v) => Some(v.as_ref()),
                 _ => None,
             });
@@ -611,13 +1847,88 @@ impl<'dict, 'word> WordEntry<'dict, 'word> {
         Some(ret)
     }
 
+    /// Return the stems of a word along with the prefix and suffix rules that were
+    /// stripped to reach each one. Returns `None` if the word is not correct.
+    ///
+    /// This is the same information as [`stems`](Self::stems), but keeps track of
+    /// where each stem came from, which is useful for things like corpus annotation.
+    ///
+    /// ```
+    /// use zspell::{DictBuilder, RuleType};
+    ///
+    /// let affix_str = "
+    /// PFX P Y 1
+    /// PFX P 0 un .
+    ///
+    /// SFX X Y 1
+    /// SFX X 0 able .
+    /// ";
+    /// let dict_str = "
+    /// drink/PX
+    /// ";
+    ///
+    /// let dict = DictBuilder::new()
+    ///     .config_str(affix_str)
+    ///     .dict_str(dict_str)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let stems = dict.entry("drink").stems_with_affixes().unwrap();
+    /// assert_eq!(stems.len(), 1);
+    /// assert_eq!(stems[0].stem, "drink");
+    /// assert!(stems[0].affixes.is_empty());
+    ///
+    /// let stems = dict.entry("undrinkable").stems_with_affixes().unwrap();
+    /// assert_eq!(stems.len(), 1);
+    /// assert_eq!(stems[0].stem, "drink");
+    ///
+    /// let affixes = &stems[0].affixes;
+    /// assert_eq!(affixes[0].kind, RuleType::Prefix);
+    /// assert_eq!(affixes[0].flag, "P");
+    /// assert_eq!(affixes[1].kind, RuleType::Suffix);
+    /// assert_eq!(affixes[1].flag, "X");
+    /// assert_eq!(affixes[1].affix, "able");
+    /// ```
+    #[inline]
+    pub fn stems_with_affixes(&self) -> Option<Vec<StemInfo<'dict>>> {
+        let matched = self.matched_entry()?;
+        let metas = self.context.metas()?;
+
+        let mut ret: Vec<StemInfo> = Vec::new();
+
+        for meta in metas {
+            let affixes: Vec<_> = meta
+                .affixes()
+                .map(|(rule, pat_idx)| AffixInfo::new(rule, pat_idx))
+                .collect();
+
+            // Affixed words also carry the meta of their stem's dictionary entry,
+            // which does not describe this word
+            if affixes.is_empty() && meta.base_stem() != matched {
+                continue;
+            }
+
+            let info = StemInfo {
+                stem: meta.stem(),
+                affixes,
+            };
+
+            if !ret.contains(&info) {
+                ret.push(info);
+            }
+        }
+
+        Some(ret)
+    }
+
     /// Return morphological analysis information about a word if found, `None` otherwise
     ///
     /// Like with [`stems`](Self::stems), this is most useful with nonstandard dictionaries that
-    /// include morphological information.
+    /// include morphological information. Tags are grouped by stem and record whether they
+    /// came from the dictionary entry or an affix rule; see [`Analysis`] for details.
     ///
     /// ```
-    /// use zspell::{DictBuilder, MorphInfo, PartOfSpeech};
+    /// use zspell::{DictBuilder, MorphInfo, MorphSource, PartOfSpeech};
     ///
     /// let affix_str = "
     /// SFX X Y 1
@@ -637,67 +1948,470 @@ impl<'dict, 'word> WordEntry<'dict, 'word> {
     /// let deriv_sfx = MorphInfo::DerivSfx("able".into());
     ///
     /// let entry = dict.entry("drink");
-    /// let stems: Vec<_> = entry.analyze().unwrap().collect();
-    /// assert_eq!(stems, [&verb_pos]);
+    /// let morphs: Vec<_> = entry.analyze().unwrap().morphs().collect();
+    /// assert_eq!(morphs, [&verb_pos]);
     ///
     /// let entry = dict.entry("drinkable");
-    /// let stems: Vec<_> = entry.analyze().unwrap().collect();
-    /// assert_eq!(stems, [&deriv_sfx, &verb_pos]);
+    /// let analysis = entry.analyze().unwrap();
+    /// let morphs: Vec<_> = analysis.morphs().collect();
+    /// assert_eq!(morphs, [&deriv_sfx, &verb_pos]);
+    ///
+    /// // Both tags belong to the same stem, but only one came from an affix
+    /// let stem = &analysis.stems()[0];
+    /// assert_eq!(stem.stem, "drink");
+    /// assert!(matches!(&stem.tags[0].source, MorphSource::Affix(afx) if afx.flag == "X"));
+    /// assert_eq!(stem.tags[1].source, MorphSource::Entry);
+    /// ```
+    #[inline]
+    pub fn analyze(&self) -> Option<Analysis<'dict>> {
+        let metas = self.context.metas()?;
+
+        let mut ret = Analysis::new(self.index);
+
+        for meta in metas {
+            let stem = meta.stem();
+            ret.add_stem(stem);
+
+            let mut affixes = meta.affixes().peekable();
+            if affixes.peek().is_none() {
+                for info in meta.morphs() {
+                    let source = MorphSource::Entry;
+                    ret.push(stem, MorphTag { info, source });
+                }
+                continue;
+            }
+
+            for (rule, pat_idx) in affixes {
+                for info in rule.patterns()[pat_idx].morph_info() {
+                    let source = MorphSource::Affix(AffixInfo::new(rule, pat_idx));
+                    ret.push(stem, MorphTag { info, source });
+                }
+            }
+        }
+
+        Some(ret)
+    }
+
+    /// Suggest replacements for a word. Feature gated behind `unstable-suggestions`.
+    ///
+    /// If the word is correct, this will return `None`. Otherwise, it will return an
+    /// iterator over suggested words.
+    ///
+    /// Words that differ only by characters listed together in a `MAP` entry (e.g.
+    /// `uber` and `über` for `MAP uü`) are suggested first. Up to four other
+    /// forms of the stem that the word seems to be an inflection of follow, such
+    /// as `run` and `ran` for `runned`, then words with a small edit distance. Up to two words that sound alike are then added,
+    /// see [`DictBuilder::phonetic_suggestions`]. If the dictionary allows
+    /// compounds, up to
+    /// `MAXCPDSUGS` compounds with their first or last part corrected come last;
+    /// these are not in the wordlist so they are returned as owned strings.
+    ///
+    /// This function is unstable because it has performance issues. We are
+    /// going to try to speed up the algorithm significantly.
+    // PERF: bench with par_iter
+    #[inline]
+    #[cfg(feature = "unstable-suggestions")]
+    pub fn suggest(&self) -> Option<Vec<Cow<'dict, str>>> {
+        self.suggest_impl(None, None)
+    }
+
+    /// Suggest at most `limit` replacements for a word, stopping the search as
+    /// soon as that many are found. Feature gated behind `unstable-suggestions`.
+    ///
+    /// This bounds the time taken for each word, which is useful for checking as
+    /// the user types. Since the search ends early, the results are not always
+    /// the best ones that [`suggest`](Self::suggest) would return, and they are
+    /// not added to the suggestion cache.
+    ///
+    /// ```
+    /// # #![cfg(feature = "unstable-suggestions")]
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("4\ncat\ncar\ncap\ncan")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(dict.entry("caz").suggest_limit(2).unwrap().len(), 2);
+    /// assert_eq!(dict.entry("cat").suggest_limit(2), None);
+    /// ```
+    #[inline]
+    #[cfg(feature = "unstable-suggestions")]
+    pub fn suggest_limit(&self, limit: usize) -> Option<Vec<Cow<'dict, str>>> {
+        self.suggest_impl(Some(limit), None)
+    }
+
+    /// Suggest replacements for a word, spending at most about `budget` on the
+    /// search. Feature gated behind `unstable-suggestions`.
+    ///
+    /// When the time runs out, the suggestions found so far are returned, best
+    /// first, and the remaining passes are skipped. This gives a hard cap per
+    /// word for interactive use, such as 20 ms in a word processor. Results of
+    /// a search that was cut short are not added to the suggestion cache.
+    ///
+    /// ```
+    /// # #![cfg(feature = "unstable-suggestions")]
+    /// use std::time::Duration;
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("4\ncat\ncar\ncap\ncan")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let budget = Duration::from_millis(20);
+    /// assert_eq!(dict.entry("caz").suggest_within(budget).unwrap().len(), 4);
+    /// assert_eq!(dict.entry("cat").suggest_within(budget), None);
     /// ```
     #[inline]
-    pub fn analyze(&self) -> Option<impl Iterator<Item = &MorphInfo>> {
-        let WordCtx::Correct { meta_list, .. } = self.context else {
+    #[cfg(feature = "unstable-suggestions")]
+    pub fn suggest_within(&self, budget: Duration) -> Option<Vec<Cow<'dict, str>>> {
+        self.suggest_impl(None, Some(budget))
+    }
+
+    /// Set the token that stops suggestions for this entry
+    fn with_cancel(mut self, cancel: Option<&'dict CancelToken>) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Shared implementation of suggestions. With no limit, the whole wordlist
+    /// is searched and the results are cached unless `budget` runs out.
+    #[cfg(feature = "unstable-suggestions")]
+    fn suggest_impl(
+        &self,
+        limit: Option<usize>,
+        budget: Option<Duration>,
+    ) -> Option<Vec<Cow<'dict, str>>> {
+        trace::enter_span!(DEBUG, "suggest", word = self.word, ?limit, ?budget);
+        let deadline = Deadline::new(budget, self.cancel);
+        if self.correct() {
+            trace::event!(DEBUG, "word is correct, no suggestions");
             return None;
         };
-        let ret = meta_list.iter().flat_map(|meta| meta.source().morphs());
-        Some(ret)
+
+        // Like Hunspell, suggest for the word without trailing dots. They are
+        // only added back with `SUGSWITHDOTS`.
+        let word = self.dict.prepare(self.word);
+        let (word, dots) = match without_dots(&word) {
+            Some((dotless, _)) => (dotless, &word[dotless.len()..]),
+            None => (word.as_ref(), ""),
+        };
+        let ret = self.suggest_word(word, limit, &deadline);
+        if dots.is_empty() || !self.dict.data.parsed_config.sugs_with_dots() {
+            return Some(ret);
+        }
+        Some(
+            ret.into_iter()
+                .map(|sug| Cow::Owned(format!("{sug}{dots}")))
+                .collect(),
+        )
+    }
+
+    /// Find suggestions for a normalized word
+    #[cfg(feature = "unstable-suggestions")]
+    fn suggest_word(
+        &self,
+        word: &str,
+        limit: Option<usize>,
+        deadline: &Deadline<'_>,
+    ) -> Vec<Cow<'dict, str>> {
+        let wordlist = &self.dict.data.wordlist.0;
+        let cache = self.dict.suggestion_cache.as_deref();
+        let max_count = limit.unwrap_or(MAX_SUGGESTIONS);
+
+        // Cached results are owned by the cache, so look them back up in the
+        // wordlist to get references with the right lifetime. Compounds are not
+        // in the wordlist and need to be copied.
+        let cached = cache.and_then(|cache| {
+            cache.get(word, |cached| {
+                cached
+                    .iter()
+                    .map(|s| match wordlist.get_key_value(s.as_ref()) {
+                        Some((key, _)) => Cow::Borrowed(key.as_ref()),
+                        None => Cow::Owned(s.to_string()),
+                    })
+                    .take(max_count)
+                    .collect::<Vec<Cow<'dict, str>>>()
+            })
+        });
+        if let Some(cached) = cached {
+            trace::event!(DEBUG, count = cached.len(), "suggestions from cache");
+            return cached;
+        }
+
+        let mut suggestions: Vec<&'dict str> = Vec::new();
+        let data = &self.dict.data;
+
+        crate::suggestions::map_related(word, data.parsed_config.maps(), |cand| {
+            if let Some((key, _)) = wordlist.get_key_value(cand) {
+                if !suggestions.contains(&key.as_ref()) {
+                    suggestions.push(key);
+                }
+            }
+        });
+        // Prefer common words among those at the same distance
+        suggestions.sort_by_key(|s| Reverse(data.frequency(s)));
+
+        // Other forms of the same stem fix inflection errors such as "runned",
+        // which are often too far off for edit distance
+        for form in data.stem_suggestions(word, MAX_STEM_SUGGESTIONS.min(max_count), deadline) {
+            if !suggestions.contains(&form) {
+                suggestions.push(form);
+            }
+        }
+
+        // With a limit, stop scanning the wordlist once we have enough
+        let remaining = limit.map_or(usize::MAX, |l| l.saturating_sub(suggestions.len()));
+        let distance = EditDistance::new(word);
+        let mut by_distance: Vec<(u32, &'dict str)> = wordlist
+            .keys()
+            .take_while(|_| !deadline.expired())
+            .filter_map(|key| distance.within(key, 1).map(|dist| (dist, key.as_ref())))
+            .filter(|(_lim, key)| !suggestions.contains(key))
+            .take(remaining)
+            .collect();
+        let collation = data.collation();
+        by_distance.sort_unstable_by(|(dist_a, a), (dist_b, b)| {
+            dist_a
+                .cmp(dist_b)
+                .then_with(|| data.frequency(b).cmp(&data.frequency(a)))
+                .then_with(|| collation.compare(a, b))
+        });
+
+        suggestions.extend(by_distance.iter().map(|(_k, v)| *v));
+
+        // Words that sound alike catch misspellings that are too far off for edit
+        // distance. This scans the whole wordlist, so skip it if a limited search
+        // already has enough.
+        let mut phonetic: Vec<(u32, &'dict str)> = Vec::new();
+        if let Some(coder) = self.dict.phonetic_coder() {
+            if (limit.is_none() || suggestions.len() < max_count) && !deadline.expired() {
+                let codes = coder.codes(word);
+                phonetic = wordlist
+                    .keys()
+                    .take_while(|_| !deadline.expired())
+                    .map(AsRef::as_ref)
+                    .filter(|key| !suggestions.contains(key))
+                    .filter(|key| coder.sounds_like(&codes, key))
+                    .map(|key| (distance.distance(key), key))
+                    .collect();
+                phonetic.sort_unstable_by(|(dist_a, a), (dist_b, b)| {
+                    dist_a
+                        .cmp(dist_b)
+                        .then_with(|| data.frequency(b).cmp(&data.frequency(a)))
+                        .then_with(|| collation.compare(a, b))
+                });
+                phonetic.truncate(MAX_PHONETIC_SUGGESTIONS.min(max_count));
+            }
+        }
+
+        // Leave room for phonetic and compound suggestions, which are bounded
+        // separately
+        let compound_max = data.parsed_config.compound_config().sug_max();
+        let compound_max = compound_max.min(max_count - phonetic.len());
+        let compounds = data.compound_suggestions(word, compound_max, deadline);
+        suggestions.truncate(max_count - phonetic.len() - compounds.len());
+        trace::event!(
+            DEBUG,
+            similar = suggestions.len(),
+            phonetic = phonetic.len(),
+            compound = compounds.len(),
+            cut_short = deadline.was_hit(),
+            "found suggestions"
+        );
+        suggestions.extend(phonetic.iter().map(|(_dist, v)| *v));
+
+        if let (Some(cache), None, false) = (cache, limit, deadline.was_hit()) {
+            let all: Vec<&str> = suggestions
+                .iter()
+                .copied()
+                .chain(compounds.iter().map(String::as_str))
+                .collect();
+            cache.insert(word, &all);
+        }
+
+        let mut ret: Vec<Cow<'dict, str>> = suggestions.into_iter().map(Cow::Borrowed).collect();
+        ret.extend(compounds.into_iter().map(Cow::Owned));
+        ret
+    }
+}
+
+/// If `word` ends with dots, return it without them and with a single dot.
+/// Like Hunspell, such a word is correct if either form is, the latter for
+/// abbreviations such as "etc." in the dictionary.
+fn without_dots(word: &str) -> Option<(&str, &str)> {
+    let dotless = word.trim_end_matches('.');
+    (dotless.len() < word.len() && !dotless.is_empty()).then(|| (dotless, &word[..=dotless.len()]))
+}
+
+/// A set of words accepted by a [`Dictionary`], including affixed forms,
+/// along with how each word was created.
+///
+/// A dictionary keeps separate lists for words that may be suggested, words
+/// that are never suggested, forbidden words, and words that may only be used
+/// in compounds, see [`Dictionary::wordlist`] and the methods that follow it.
+/// Lists can be combined with set operations and exported as a `.dic` file.
+///
+/// ```
+/// use zspell::WordList;
+///
+/// let aff = "SFX S Y 1\nSFX S 0 s .\n";
+/// let dict = zspell::builder()
+///     .config_str(aff)
+///     .dict_str("2\ncat/S\ndog")
+///     .build()
+///     .unwrap();
+///
+/// let words = dict.wordlist();
+/// assert_eq!(words.len(), 3);
+/// assert!(words.contains("cats"));
+///
+/// // Lists can also be created from any words, e.g. those of a document
+/// let seen: WordList = ["cat", "cats", "mouse"].into_iter().collect();
+/// assert_eq!(words.difference(&seen).to_dic(), "1\ndog\n");
+/// assert_eq!(seen.difference(words).to_dic(), "1\nmouse\n");
+/// ```
+///
+/// With [`DictBuilder::lazy_affixes`], affixed forms are not expanded, so the
+/// lists only hold words as they are written in the dictionary.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WordList(FxHashMap<Box<str>, Vec<Meta>>);
+
+impl WordList {
+    fn new() -> Self {
+        Self(FxHashMap::default())
+    }
+
+    /// **UNSTABLE** Get a reference to the internal map. This is behind the
+    /// `zspell-unstable` marker as the internal format may change
+    #[inline]
+    #[cfg_attr(feature = "zspell-unstable", visibility::make(pub))]
+    pub(crate) fn inner(&self) -> &FxHashMap<Box<str>, Vec<Meta>> {
+        &self.0
+    }
+
+    /// Number of words in the list
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the list has no words
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether `word` is in the list, exactly as given. Unlike
+    /// [`Dictionary::check_word`], no case folding or other processing is done.
+    #[inline]
+    pub fn contains(&self, word: &str) -> bool {
+        self.0.contains_key(word)
+    }
+
+    /// Iterate over the words in the list, in no particular order
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(AsRef::as_ref)
+    }
+
+    /// Words that are in either list. Words that are in both keep how they were
+    /// created in `self`.
+    #[inline]
+    #[must_use]
+    pub fn union(&self, other: &WordList) -> WordList {
+        let mut ret = self.clone();
+        ret.0.reserve(other.len());
+        for (word, metas) in &other.0 {
+            ret.0
+                .entry_ref(word.as_ref())
+                .or_insert_with(|| metas.clone());
+        }
+        ret
+    }
+
+    /// Words that are in both lists, keeping how they were created in `self`
+    #[inline]
+    #[must_use]
+    pub fn intersection(&self, other: &WordList) -> WordList {
+        self.filtered(|word| other.contains(word))
+    }
+
+    /// Words that are in `self` but not in `other`
+    #[inline]
+    #[must_use]
+    pub fn difference(&self, other: &WordList) -> WordList {
+        self.filtered(|word| !other.contains(word))
+    }
+
+    /// Write the list as a `.dic` file without flags: the number of words,
+    /// followed by one word per line in sorted order. This can be loaded again
+    /// with an empty affix file.
+    #[inline]
+    pub fn to_dic(&self) -> String {
+        let mut words: Vec<&str> = self.iter().collect();
+        words.sort_unstable();
+
+        let len = words.iter().map(|word| word.len() + 1).sum::<usize>() + 20;
+        let mut ret = String::with_capacity(len);
+        ret.push_str(&words.len().to_string());
+        ret.push('\n');
+        for word in words {
+            ret.push_str(word);
+            ret.push('\n');
+        }
+        ret
     }
 
-    /// Suggest replacements for a word. Feature gated behind `unstable-suggestions`.
-    ///
-    /// If the word is correct, this will return `None`. Otherwise, it will return an
-    /// iterator over suggested words.
-    ///
-    /// This function is unstable because it has performance issues. We are
-    /// going to try to speed up the algorithm significantly.
-    // PERF: bench with par_iter
-    #[inline]
-    #[cfg(feature = "unstable-suggestions")]
-    pub fn suggest(&self) -> Option<Vec<&str>> {
-        if self.correct() {
-            return None;
-        };
+    /// Words for which `keep` returns true
+    fn filtered(&self, mut keep: impl FnMut(&str) -> bool) -> WordList {
+        Self(
+            self.0
+                .iter()
+                .filter(|(word, _)| keep(word))
+                .map(|(word, metas)| (word.clone(), metas.clone()))
+                .collect(),
+        )
+    }
 
-        let mut suggestions: Vec<(u32, &str)> = self
-            .dict
-            .wordlist
-            .0
-            .keys()
-            .filter_map(|key| try_levenshtein(key, self.word, 1).map(|lim| (lim, key.as_ref())))
-            .collect();
-        suggestions.sort_unstable_by_key(|(k, _v)| *k);
-        Some(suggestions.iter().take(10).map(|(_k, v)| *v).collect())
+    /// Release unused capacity, including that of each entry's meta list. A
+    /// `Vec` grows to hold four items on its first push, but most words only
+    /// ever have one.
+    fn shrink_to_fit(&mut self) {
+        for metas in self.0.values_mut() {
+            metas.shrink_to_fit();
+        }
+        self.0.shrink_to_fit();
     }
-}
 
-/// The internal representation of a wordlist.
-///
-/// Currently contains a `HashMap<String, Vec<Meta>>`
-#[doc(hidden)]
-#[derive(Clone, Debug, PartialEq)]
-pub struct WordList(HashMap<Box<str>, Vec<Meta>>);
+    /// Check whether `word` or, if provided, its lowercase form is in the list
+    fn contains_either(&self, word: &str, lower: Option<&str>) -> bool {
+        self.0.contains_key(word) || lower.map_or(false, |l| self.0.contains_key(l))
+    }
 
-impl WordList {
-    fn new() -> Self {
-        Self(HashMap::new())
+    /// Look up `word`, falling back to its lowercase form if provided
+    fn get_either(&self, word: &str, lower: Option<&str>) -> Option<(&str, &[Meta])> {
+        self.0
+            .get_key_value(word)
+            .or_else(|| self.0.get_key_value(lower?))
+            .map(|(k, v)| (k.as_ref(), v.as_slice()))
     }
+}
 
-    /// **UNSTABLE** Get a reference to the internal map. This is behind the
-    /// `zspell-unstable` marker as the internal format may change
+/// Collect words that did not come from a dictionary, e.g. to compare them
+/// with a dictionary's lists. Each word is its own stem.
+impl<'a> FromIterator<&'a str> for WordList {
     #[inline]
-    #[cfg_attr(feature = "zspell-unstable", visibility::make(pub))]
-    pub(crate) fn inner(&self) -> &HashMap<Box<str>, Vec<Meta>> {
-        &self.0
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let no_flags: Arc<[Flag]> = Arc::from([]);
+        let mut ret = Self::new();
+        for word in iter {
+            ret.0.entry_ref(word).or_insert_with(|| {
+                let meta = Meta::new(Arc::from(word), Arc::clone(&no_flags), Source::Raw);
+                vec![meta]
+            });
+        }
+        ret
     }
 }
 
@@ -711,6 +2425,25 @@ pub struct DictBuilder<'a> {
     cfg_src: Option<&'a str>,
     dict_src: Option<&'a str>,
     personal_src: Option<&'a str>,
+    segmenter: SegmenterHook,
+    normalization: Normalization,
+    accept_numeric: bool,
+    accept_roman: bool,
+    check_break_parts: bool,
+    case_insensitive: bool,
+    lazy_affixes: bool,
+    grapheme_affixes: bool,
+    parse_mode: ParseMode,
+    validate_dict: bool,
+    ignore_literals: Vec<String>,
+    #[cfg(feature = "regex")]
+    ignore_patterns: Vec<String>,
+    #[cfg(feature = "unstable-suggestions")]
+    suggestion_cache: Option<usize>,
+    #[cfg(feature = "unstable-suggestions")]
+    frequency_src: Option<&'a str>,
+    #[cfg(feature = "unstable-suggestions")]
+    phonetic_suggestions: PhoneticSuggestions,
 }
 
 impl<'a> DictBuilder<'a> {
@@ -722,6 +2455,25 @@ impl<'a> DictBuilder<'a> {
             cfg_src: None,
             dict_src: None,
             personal_src: None,
+            segmenter: SegmenterHook::default(),
+            normalization: Normalization::None,
+            accept_numeric: false,
+            accept_roman: false,
+            check_break_parts: false,
+            case_insensitive: false,
+            lazy_affixes: false,
+            grapheme_affixes: false,
+            parse_mode: ParseMode::Strict,
+            validate_dict: false,
+            ignore_literals: Vec::new(),
+            #[cfg(feature = "regex")]
+            ignore_patterns: Vec::new(),
+            #[cfg(feature = "unstable-suggestions")]
+            suggestion_cache: None,
+            #[cfg(feature = "unstable-suggestions")]
+            frequency_src: None,
+            #[cfg(feature = "unstable-suggestions")]
+            phonetic_suggestions: PhoneticSuggestions::default(),
         }
     }
 
@@ -749,13 +2501,438 @@ impl<'a> DictBuilder<'a> {
         self
     }
 
-    /// Load a personal dictionary file from a string
+    /// Load a personal dictionary file from a string.
+    ///
+    /// This uses the same format as Hunspell, so existing files such as
+    /// `~/.hunspell_en_US` can be used as-is. Each line holds one of:
+    ///
+    /// - `word`: accept `word`
+    /// - `word/root`: accept `word` along with the affixed forms that `root` from
+    ///   the main dictionary allows
+    /// - `*word`: reject `word`, even if the main dictionary accepts it
+    ///
+    /// An optional first line naming the dictionary (e.g. `en_US`) is ignored.
     #[inline]
     pub fn personal_str(mut self, personal: &'a str) -> Self {
         self.personal_src = Some(personal);
         self
     }
 
+    /// Use a [`Segmenter`] to split text in scripts that do not put spaces between
+    /// words, such as Thai or Japanese. Without a segmenter, runs of these scripts
+    /// are skipped when checking.
+    #[inline]
+    pub fn segmenter(mut self, segmenter: impl Segmenter + 'static) -> Self {
+        self.segmenter = SegmenterHook::new(Arc::new(segmenter));
+        self
+    }
+
+    /// Normalize words to the given Unicode form before looking them up. Affix and
+    /// dictionary sources given as strings are normalized when the dictionary is
+    /// built.
+    ///
+    /// ```
+    /// use zspell::{DictBuilder, Normalization};
+    ///
+    /// let dict = DictBuilder::new()
+    ///     .config_str("SET UTF-8")
+    ///     .dict_str("1\ncaf\u{e9}")
+    ///     .normalization(Normalization::Nfc)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // "e" followed by a combining acute accent
+    /// assert!(dict.check_word("cafe\u{301}"));
+    /// ```
+    #[inline]
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Accept numbers, times, and dates as correct even if they are not in the
+    /// dictionary. This avoids flagging every figure in technical documents.
+    ///
+    /// Plain numbers such as `1,000.5` are always accepted, like in Hunspell.
+    /// With this option, signed numbers, locale separators such as `1'000`,
+    /// times such as `12:30`, and ISO 8601 dates such as `2024-01-31` or
+    /// `2024-01-31T12:30Z` are also accepted. Document checking keeps times and
+    /// dates together as one word.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\nat\non")
+    ///     .accept_numeric(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(dict.check("at 12:30 on 2024-01-31"));
+    /// assert!(dict.check_word("1'000"));
+    /// assert!(!dict.check_word("12:61"));
+    /// ```
+    #[inline]
+    pub fn accept_numeric(mut self, accept: bool) -> Self {
+        self.accept_numeric = accept;
+        self
+    }
+
+    /// Accept Roman numerals such as "XIV" or "MMXXIV" as correct even if they
+    /// are not in the dictionary, as found in headings and legal text.
+    ///
+    /// Only uppercase numerals in their standard form are accepted, so "IIII"
+    /// is still flagged. Lowercase numerals are not accepted since many of them,
+    /// such as "mix" or "liv", could be misspellings.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\nchapter\nsection")
+    ///     .accept_roman_numerals(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(dict.check("chapter XIV section MMXXIV"));
+    /// assert!(!dict.check_word("XIIV"));
+    /// assert!(!dict.check_word("xiv"));
+    /// ```
+    #[inline]
+    pub fn accept_roman_numerals(mut self, accept: bool) -> Self {
+        self.accept_roman = accept;
+        self
+    }
+
+    /// Check words such as "well-known" in parts if they are not in the
+    /// dictionary as a whole. Words are split at the `BREAK` separators of the
+    /// affix file, which default to a hyphen.
+    ///
+    /// Document checking keeps words joined by separators together, and reports
+    /// only the misspelled parts of them. Without this option, each part is
+    /// checked as its own word and `check_word` does not split words.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("3\nwell\nknown\ne-mail")
+    ///     .check_break_parts(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(dict.check_word("well-known"));
+    /// assert!(dict.check_word("e-mail"));
+    ///
+    /// let errors: Vec<_> = dict.check_indices("well-known wel-known").collect();
+    /// assert_eq!(errors, [(11, "wel")]);
+    /// ```
+    #[inline]
+    pub fn check_break_parts(mut self, check: bool) -> Self {
+        self.check_break_parts = check;
+        self
+    }
+
+    /// Accept a dictionary word, or an affixed form of one, in any casing. This
+    /// is useful for text where case says little about spelling, such as posts
+    /// written in all caps or with random capitals.
+    ///
+    /// By default, casing follows Hunspell: words that are lowercase in the
+    /// dictionary may be capitalized, but "Paris" is not accepted as "paris" and
+    /// "iPhone" is not accepted as "IPHONE". With this option both are, at the
+    /// cost of an index of the dictionary words that have uppercase letters.
+    /// Words joined into compounds still follow the usual rules.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("3\nParis\niPhone\nNASA")
+    ///     .case_insensitive(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(dict.check("PARIS paris iphone IPHONE nasa"));
+    /// assert!(!dict.check_word("pariss"));
+    /// ```
+    #[inline]
+    pub fn case_insensitive(mut self, insensitive: bool) -> Self {
+        self.case_insensitive = insensitive;
+        self
+    }
+
+    /// Derive affixed words from their roots when they are looked up, rather
+    /// than creating every affixed form while building, as Hunspell does.
+    ///
+    /// Building is much faster and the dictionary takes far less memory,
+    /// especially for languages with many affix rules such as Hungarian, but
+    /// looking up a word that is not listed in the dictionary means trying to
+    /// strip each affix that it may end or start with. Checking and the
+    /// [`WordEntry`] API give the same results either way.
+    ///
+    /// Some features only know about the listed words, not their affixed forms:
+    /// compounds and elided words may only be made of listed words, suggestions
+    /// are only listed words, [`Dictionary::word_forms`],
+    /// [`Dictionary::derivations`] and [`Dictionary::remove_word`] only see
+    /// listed words, and [`Dictionary::stats`] only counts them.
+    ///
+    /// ```
+    /// let aff = "
+    /// PFX U Y 1
+    /// PFX U 0 un .
+    ///
+    /// SFX S Y 1
+    /// SFX S 0 s .
+    /// ";
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str(aff)
+    ///     .dict_str("1\nlock/US")
+    ///     .lazy_affixes(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(dict.check("lock locks unlocks"));
+    /// assert!(!dict.check_word("unlockss"));
+    /// assert_eq!(dict.stats().words, 1);
+    ///
+    /// let stems = dict.entry("unlocks").stems_with_affixes().unwrap();
+    /// assert_eq!(stems[0].stem, "lock");
+    /// ```
+    #[inline]
+    pub fn lazy_affixes(mut self, lazy: bool) -> Self {
+        self.lazy_affixes = lazy;
+        self
+    }
+
+    /// Match affix conditions and strip strings by grapheme cluster rather than
+    /// by character. Off by default, which is what Hunspell does.
+    ///
+    /// In scripts written with combining marks, such as Hindi or decomposed
+    /// Vietnamese, a character is often only part of a letter. With this
+    /// option, `.` and `[...]` in a condition match a whole letter with its
+    /// marks, groups may list such letters, and a rule does not apply if
+    /// stripping would separate a letter from its marks.
+    ///
+    /// ```
+    /// // `a` followed by a combining acute accent, "á"
+    /// let aff = "PFX P Y 1\nPFX P a x a\n";
+    /// let dic = "1\na\u{301}b/P";
+    ///
+    /// let by_char = zspell::builder()
+    ///     .config_str(aff)
+    ///     .dict_str(dic)
+    ///     .build()
+    ///     .unwrap();
+    /// // The `a` is stripped, leaving its accent on the `x`
+    /// assert!(by_char.check_word("x\u{301}b"));
+    ///
+    /// let by_grapheme = zspell::builder()
+    ///     .config_str(aff)
+    ///     .dict_str(dic)
+    ///     .grapheme_affixes(true)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(!by_grapheme.check_word("x\u{301}b"));
+    /// ```
+    #[inline]
+    pub fn grapheme_affixes(mut self, graphemes: bool) -> Self {
+        self.grapheme_affixes = graphemes;
+        self
+    }
+
+    /// How to handle malformed directives in the affix file. By default this is
+    /// [`ParseMode::Strict`], and building fails with the first error. With
+    /// [`ParseMode::Permissive`], malformed directives and repeated affix
+    /// flags are skipped and listed by [`Dictionary::parse_issues`], and flags
+    /// in the word list that the affix file does not define are listed by
+    /// [`Dictionary::dict_warnings`].
+    ///
+    /// ```
+    /// use zspell::ParseMode;
+    ///
+    /// let aff = "SFX S Y 1\nSFX S 0 s .\nCOMPOUNDMIN many\n";
+    /// let builder = zspell::builder().config_str(aff).dict_str("1\ncat/S");
+    /// assert!(builder.clone().build().is_err());
+    ///
+    /// let dict = builder.parse_mode(ParseMode::Permissive).build().unwrap();
+    /// assert!(dict.check("cat cats"));
+    /// assert_eq!(dict.parse_issues().len(), 1);
+    /// ```
+    #[inline]
+    pub fn parse_mode(mut self, mode: ParseMode) -> Self {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Check the word list for duplicate entries and an entry count that does
+    /// not match. These do not stop the dictionary from being built, but are
+    /// listed by [`Dictionary::dict_warnings`]. Off by default, since it takes
+    /// a second pass over the word list.
+    ///
+    /// ```
+    /// use zspell::error::DictWarning;
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str("SFX S Y 1\nSFX S 0 s .\n")
+    ///     .dict_str("2\ncat/S\ndog/S\ncat/S")
+    ///     .validate_dict(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     dict.dict_warnings()[1],
+    ///     DictWarning::DuplicateEntry {
+    ///         word: "cat".to_owned(),
+    ///         line: 4,
+    ///         first_line: 2
+    ///     }
+    /// );
+    /// assert_eq!(dict.dict_warnings().len(), 2);
+    /// ```
+    #[inline]
+    pub fn validate_dict(mut self, validate: bool) -> Self {
+        self.validate_dict = validate;
+        self
+    }
+
+    /// Skip occurrences of `text` when checking documents, such as project
+    /// names or identifiers like "wasm-bindgen". This may be called more than
+    /// once to add more text.
+    ///
+    /// Text is matched against the whole input rather than single words, so it
+    /// may span characters such as `-` that separate words. Any word that
+    /// overlaps a match is skipped.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("1\nuse")
+    ///     .ignore_literal("wasm-bindgen")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(dict.check("use wasm-bindgen"));
+    /// ```
+    #[inline]
+    pub fn ignore_literal(mut self, text: &str) -> Self {
+        self.ignore_literals.push(text.to_owned());
+        self
+    }
+
+    /// Skip text matching the regular expression `pattern` when checking
+    /// documents, such as hex strings, ticket IDs like "JIRA-1234", or UUIDs.
+    /// This may be called more than once to add more patterns. Requires the
+    /// `regex` feature.
+    ///
+    /// Matches are handled as for [`ignore_literal`](Self::ignore_literal). An
+    /// invalid pattern is reported when the dictionary is built.
+    ///
+    /// ```
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\nsee\nfor")
+    ///     .ignore_regex(r"\b[A-Z]+-\d+\b")
+    ///     .ignore_regex(r"\b0x[0-9a-fA-F]+\b")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(dict.check("see JIRA-1234 for 0xDEADBEEF"));
+    /// assert!(!dict.check("see JIRA for"));
+    ///
+    /// let res = zspell::builder().config_str("").dict_str("").ignore_regex("(").build();
+    /// assert!(res.is_err());
+    /// ```
+    #[inline]
+    #[cfg(feature = "regex")]
+    pub fn ignore_regex(mut self, pattern: &str) -> Self {
+        self.ignore_patterns.push(pattern.to_owned());
+        self
+    }
+
+    /// Cache the suggestions for up to `capacity` misspelled words, so that
+    /// repeated requests for the same word are answered without searching the
+    /// wordlist again. Feature gated behind `unstable-suggestions`.
+    ///
+    /// The least recently used word is evicted when the cache is full. Clones of
+    /// the dictionary share the cache, and [`Dictionary::suggestion_cache_stats`]
+    /// reports its hit and miss counts.
+    ///
+    /// ```
+    /// # #![cfg(feature = "unstable-suggestions")]
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("1\nworld")
+    ///     .suggestion_cache(100)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let first = dict.entry("wrld").suggest();
+    /// let second = dict.entry("wrld").suggest();
+    /// assert_eq!(first, second);
+    ///
+    /// let stats = dict.suggestion_cache_stats().unwrap();
+    /// assert_eq!((stats.hits, stats.misses), (1, 1));
+    /// ```
+    #[inline]
+    #[cfg(feature = "unstable-suggestions")]
+    pub fn suggestion_cache(mut self, capacity: usize) -> Self {
+        self.suggestion_cache = Some(capacity);
+        self
+    }
+
+    /// Add word frequency data, used to rank suggestions so that common words come
+    /// before rare ones at the same edit distance. Feature gated behind
+    /// `unstable-suggestions`.
+    ///
+    /// Each line should contain a word and its count separated by whitespace, e.g.
+    /// `the 23135851162`. Words that are not listed are treated as the rarest.
+    ///
+    /// ```
+    /// # #![cfg(feature = "unstable-suggestions")]
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("3\ncat\ncar\ncap")
+    ///     .frequency_str("car 5000\ncat 300\ncap 12")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(dict.entry("caz").suggest().unwrap(), ["car", "cat", "cap"]);
+    /// ```
+    #[inline]
+    #[cfg(feature = "unstable-suggestions")]
+    pub fn frequency_str(mut self, frequencies: &'a str) -> Self {
+        self.frequency_src = Some(frequencies);
+        self
+    }
+
+    /// Choose how suggestions find words that sound like a misspelling. Feature
+    /// gated behind `unstable-suggestions`.
+    ///
+    /// By default, the dictionary's `PHONE` table is used if it has one.
+    /// [`PhoneticSuggestions::DoubleMetaphone`] falls back to a built-in
+    /// algorithm for English dictionaries without one.
+    ///
+    /// ```
+    /// # #![cfg(feature = "unstable-suggestions")]
+    /// use zspell::PhoneticSuggestions;
+    ///
+    /// let builder = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\nknowledge\nknow");
+    ///
+    /// let dict = builder.clone().build().unwrap();
+    /// assert!(dict.entry("nolij").suggest().unwrap().is_empty());
+    ///
+    /// let dict = builder
+    ///     .phonetic_suggestions(PhoneticSuggestions::DoubleMetaphone)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(dict.entry("nolij").suggest().unwrap(), ["knowledge"]);
+    /// ```
+    #[inline]
+    #[cfg(feature = "unstable-suggestions")]
+    pub fn phonetic_suggestions(mut self, phonetic: PhoneticSuggestions) -> Self {
+        self.phonetic_suggestions = phonetic;
+        self
+    }
+
     /// Consume this builder and return a `Dictionary`
     ///
     /// # Errors
@@ -765,32 +2942,173 @@ impl<'a> DictBuilder<'a> {
     // PERF: parallize parsing of affix & dict files
     #[inline]
     pub fn build(self) -> Result<Dictionary, Error> {
+        trace::enter_span!(DEBUG, "build_dictionary");
         if self.cfg.is_some() && self.cfg_src.is_some() {
             return Err(Error::Build(BuildError::BuilderCfgSpecTwice));
         }
 
-        let cfg = if let Some(c) = self.cfg {
+        let norm = self.normalization;
+
+        let mut cfg = if let Some(c) = self.cfg {
             c
         } else if let Some(cs) = self.cfg_src {
-            ParsedCfg::load_from_str(cs)?
+            ParsedCfg::load_with_mode(&norm.apply(cs), self.parse_mode)?
         } else {
             return Err(Error::Build(BuildError::BuilderCfgUnspecified));
         };
+        if self.grapheme_affixes {
+            cfg.use_graphemes();
+        }
+
+        let ignore = IgnoredText::new(&self.ignore_literals);
+        #[cfg(feature = "regex")]
+        let ignore = if self.ignore_patterns.is_empty() {
+            ignore
+        } else {
+            // Check each pattern on its own so errors point at the right one
+            for pattern in &self.ignore_patterns {
+                ReWrapper::new(pattern)?;
+            }
+            let joined: Vec<_> = self
+                .ignore_patterns
+                .iter()
+                .map(|pattern| format!("(?:{pattern})"))
+                .collect();
+            ignore.with_pattern(ReWrapper::new(&joined.join("|"))?)
+        };
 
         let mut dict = Dictionary::new(cfg)?;
+        dict.segmenter = self.segmenter;
+        dict.normalization = norm;
+        dict.accept_numeric = self.accept_numeric;
+        dict.accept_roman = self.accept_roman;
+        dict.check_break_parts = self.check_break_parts;
+        dict.ignore = ignore;
+        #[cfg(feature = "unstable-suggestions")]
+        {
+            dict.suggestion_cache = self
+                .suggestion_cache
+                .map(|cap| Arc::new(SuggestionCache::new(cap)));
+            dict.phonetic_suggestions = self.phonetic_suggestions;
+        }
 
-        if let Some(wl) = self.dict_src {
-            dict.parse_update_wordlist(wl)?;
+        let data = dict.data_mut();
+        if self.lazy_affixes {
+            data.lazy = Some(LazyAffixes::new(data));
         }
 
+        let entries = match self.dict_src {
+            Some(wl) => {
+                let wl = norm.apply(wl);
+                let entries = data.parse_update_wordlist(&wl, self.parse_mode)?;
+                if self.validate_dict {
+                    let mut warnings = data.validate_wordlist(&wl);
+                    data.dict_warnings.append(&mut warnings);
+                    data.dict_warnings.sort_by_key(DictWarning::line);
+                }
+                entries
+            }
+            None => Vec::new(),
+        };
+
         if let Some(wl) = self.personal_src {
-            dict.parse_update_personal(wl, &[])?;
+            data.parse_update_personal(&norm.apply(wl), &entries)?;
+        }
+
+        #[cfg(feature = "unstable-suggestions")]
+        if let Some(freq) = self.frequency_src {
+            data.parse_update_frequencies(&norm.apply(freq))?;
         }
 
-        dict.shrink_storage();
+        if self.case_insensitive {
+            data.build_folded();
+        }
+
+        data.shrink_storage();
+        trace::event!(DEBUG, heap_bytes = data.heap_size(), "built dictionary");
+
+        Ok(dict)
+    }
 
+    /// Like [`build`](Self::build), but calls `report` with each directive in
+    /// the affix file that is not honored yet, so that users can be warned that
+    /// the dictionary will not behave fully correctly. See
+    /// [`Dictionary::unsupported_options`].
+    ///
+    /// ```
+    /// let mut unsupported = Vec::new();
+    /// let dict = zspell::builder()
+    ///     .config_str("CHECKSHARPS\nWORDCHARS 0123456789")
+    ///     .dict_str("1\nword")
+    ///     .build_with_unsupported(|directive| unsupported.push(directive))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(unsupported, ["CHECKSHARPS", "WORDCHARS"]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The same as [`build`](Self::build).
+    #[inline]
+    pub fn build_with_unsupported(
+        self,
+        mut report: impl FnMut(&'static str),
+    ) -> Result<Dictionary, Error> {
+        let dict = self.build()?;
+        for directive in dict.unsupported_options() {
+            report(directive);
+        }
         Ok(dict)
     }
+
+    /// Like [`build`](Self::build), but also return the issues that did not
+    /// stop the dictionary from being built: unsupported and deprecated
+    /// directives, lines of the affix file that were skipped with
+    /// [`ParseMode::Permissive`], and problems in the word list. Directives
+    /// come first, then lines of the affix file, then those of the word list.
+    ///
+    /// ```
+    /// use zspell::error::BuildWarning;
+    /// use zspell::ParseMode;
+    ///
+    /// let (dict, warnings) = zspell::builder()
+    ///     .config_str("PSEUDOROOT X\nFOO bar")
+    ///     .dict_str("2\nword\nword")
+    ///     .parse_mode(ParseMode::Permissive)
+    ///     .validate_dict(true)
+    ///     .build_with_warnings()
+    ///     .unwrap();
+    ///
+    /// assert!(dict.check_word("word"));
+    /// assert_eq!(
+    ///     warnings[0],
+    ///     BuildWarning::Deprecated {
+    ///         directive: "PSEUDOROOT",
+    ///         replacement: Some("NEEDAFFIX")
+    ///     }
+    /// );
+    /// assert!(matches!(warnings[1], BuildWarning::Parse(_)));
+    /// assert!(matches!(warnings[2], BuildWarning::Dict(_)));
+    /// assert_eq!(warnings.len(), 3);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The same as [`build`](Self::build).
+    #[inline]
+    pub fn build_with_warnings(self) -> Result<(Dictionary, Vec<BuildWarning>), Error> {
+        let dict = self.build()?;
+        let cfg = &dict.data.parsed_config;
+        let warnings = cfg
+            .unsupported()
+            .iter()
+            .map(|&directive| BuildWarning::Unsupported(directive))
+            .chain(cfg.deprecated().iter().cloned())
+            .chain(cfg.issues().iter().cloned().map(BuildWarning::Parse))
+            .chain(dict.dict_warnings().iter().cloned().map(BuildWarning::Dict))
+            .collect();
+        Ok((dict, warnings))
+    }
 }
 
 impl<'a> Default for DictBuilder<'a> {
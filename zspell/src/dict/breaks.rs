@@ -0,0 +1,101 @@
+//! Checking words in parts, split at `BREAK` separators
+//!
+//! With [`DictBuilder::check_break_parts`](crate::DictBuilder::check_break_parts),
+//! a word such as "well-known" that is not in the dictionary is accepted if
+//! each of "well" and "known" is. Misspelled parts are reported on their own.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::Dictionary;
+use crate::segment::is_inner_break;
+
+/// How many times a word may be split, as in Hunspell
+const MAX_BREAK_DEPTH: usize = 10;
+
+impl Dictionary {
+    /// `BREAK` separators to split words at, if enabled
+    pub(super) fn break_separators(&self) -> &[String] {
+        if self.check_break_parts {
+            self.data.parsed_config.compound_config().break_separators()
+        } else {
+            &[]
+        }
+    }
+
+    /// Whether `word` is correct once split at `BREAK` separators. Like
+    /// Hunspell, only the first and last occurrence of each separator is tried.
+    pub(super) fn check_breaks(&self, word: &str, depth: usize) -> bool {
+        if depth >= MAX_BREAK_DEPTH {
+            return false;
+        }
+        let check = |part: &str| {
+            !part.is_empty() && (self.check_whole(part) || self.check_breaks(part, depth + 1))
+        };
+
+        self.break_separators().iter().any(|sep| {
+            if is_inner_break(sep) {
+                return [word.find(sep.as_str()), word.rfind(sep.as_str())]
+                    .into_iter()
+                    .flatten()
+                    .any(|idx| check(&word[..idx]) && check(&word[idx + sep.len()..]));
+            }
+            let nonempty = |pat: &&str| !pat.is_empty();
+            sep.strip_prefix('^')
+                .filter(nonempty)
+                .and_then(|pat| word.strip_prefix(pat))
+                .or_else(|| {
+                    sep.strip_suffix('$')
+                        .filter(nonempty)
+                        .and_then(|pat| word.strip_suffix(pat))
+                })
+                .map_or(false, check)
+        })
+    }
+
+    /// Split `word` at every `BREAK` separator that may appear between words,
+    /// returning each part with its byte offset
+    pub(super) fn break_parts<'a>(&self, word: &'a str) -> Vec<(usize, &'a str)> {
+        let mut parts = Vec::new();
+        let mut start = 0;
+        let mut idx = 0;
+        while let Some(ch) = word[idx..].chars().next() {
+            let rest = &word[idx..];
+            let Some(sep) = self
+                .break_separators()
+                .iter()
+                .filter(|sep| is_inner_break(sep) && rest.starts_with(sep.as_str()))
+                .max_by_key(|sep| sep.len())
+            else {
+                idx += ch.len_utf8();
+                continue;
+            };
+            if idx > start {
+                parts.push((start, &word[start..idx]));
+            }
+            idx += sep.len();
+            start = idx;
+        }
+        if start < word.len() {
+            parts.push((start, &word[start..]));
+        }
+        parts
+    }
+
+    /// The misspelled parts of an incorrect `word` at byte offset `index`, or
+    /// the whole word if they cannot be narrowed down
+    pub(super) fn word_errors<'a>(&self, index: usize, word: &'a str) -> Vec<(usize, &'a str)> {
+        let errors: Vec<_> = self
+            .break_parts(word)
+            .into_iter()
+            .filter(|(_, part)| !self.check_word(part))
+            .map(|(offset, part)| (index + offset, part))
+            .collect();
+        if errors.is_empty() {
+            vec![(index, word)]
+        } else {
+            errors
+        }
+    }
+}
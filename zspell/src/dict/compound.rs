@@ -0,0 +1,523 @@
+//! Checking of compound words, which are made of several dictionary words joined
+//! together
+//!
+//! Words may appear in a compound if they have `COMPOUNDFLAG`, or
+//! `COMPOUNDBEGIN`/`COMPOUNDMIDDLE`/`COMPOUNDEND` for the matching position.
+//! These flags may also come from the continuation flags of an affix. Each part
+//! must be at least `COMPOUNDMIN` characters long, and there may be at most
+//! `COMPOUNDWORDMAX` parts. Words with `ONLYINCOMPOUND` are only accepted here.
+//!
+//! The boundaries between parts are restricted by `CHECKCOMPOUNDDUP`,
+//! `CHECKCOMPOUNDCASE` and `CHECKCOMPOUNDTRIPLE`, and `SIMPLIFIEDTRIPLE` allows
+//! a tripled letter to be written as two. With `CHECKCOMPOUNDREP`, compounds
+//! that are a `REP` replacement away from a dictionary word are rejected.
+//! Compounds whose last part has `FORCEUCASE` must be capitalized.
+//!
+//! Compounds may also follow a `COMPOUNDRULE`, a pattern of the flags of their
+//! parts. This is how dictionaries accept ordinal numbers such as "21st".
+//!
+//! `COMPOUNDSYLLABLE` allows compounds with more than `COMPOUNDWORDMAX` parts if
+//! they are short enough, counting vowels as syllables.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ptr;
+
+use super::rule::AfxRule;
+use super::{DictData, Flag, FlagValue, Meta, Source};
+use crate::affix::RuleElement;
+use crate::helpers::within_one_edit;
+#[cfg(feature = "unstable-suggestions")]
+use crate::suggestions::Deadline;
+
+/// Where a part appears within a compound
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Position {
+    Begin,
+    Middle,
+    End,
+}
+
+impl DictData {
+    /// If `word` is a valid compound, return the dictionary entries that make it
+    /// up
+    pub(super) fn compound_parts(&self, word: &str) -> Option<Vec<&str>> {
+        self.flag_compound_parts(word)
+            .or_else(|| self.rule_compound_parts(word))
+    }
+
+    /// Split `word` into parts allowed by the compound flags
+    fn flag_compound_parts(&self, word: &str) -> Option<Vec<&str>> {
+        if !self.parsed_config.compound_config().uses_flags() {
+            return None;
+        }
+        let mut parts = Vec::new();
+        if !self.split_compound(word, true, true, &mut parts) {
+            return None;
+        }
+        let lower_start = word.chars().next().map_or(false, char::is_lowercase);
+        if lower_start && parts.last().map_or(false, |last| self.forces_upper(last)) {
+            return None;
+        }
+        // Like Hunspell, longer compounds are more likely to be an accident, so
+        // reject them if they are a typo of a dictionary word
+        if parts.len() >= 3 && self.is_typo(word) {
+            return None;
+        }
+        if self.parsed_config.compound_config().check_rep() && self.is_rep_typo(word) {
+            return None;
+        }
+        Some(parts)
+    }
+
+    /// Whether applying a `REP` replacement to `word` gives a dictionary word
+    fn is_rep_typo(&self, word: &str) -> bool {
+        let mut buf = self.lower_buf();
+        self.parsed_config
+            .replacements()
+            .candidates(word)
+            .any(|cand| {
+                let lower = buf.lower(&cand);
+                !self.wordlist_forbidden.contains_either(&cand, lower)
+                    && (self.wordlist.contains_either(&cand, lower)
+                        || self.wordlist_nosuggest.contains_either(&cand, lower))
+            })
+    }
+
+    /// Split `word` into parts whose flags match one of the `COMPOUNDRULE`s
+    fn rule_compound_parts(&self, word: &str) -> Option<Vec<&str>> {
+        let mut parts = Vec::new();
+        self.parsed_config
+            .compound_config()
+            .rules()
+            .iter()
+            .any(|rule| {
+                parts.clear();
+                self.split_rule(rule.elements(), word, &mut parts)
+            })
+            .then_some(parts)
+    }
+
+    /// Try to split `rest` into parts matching the rule elements in `pattern`,
+    /// pushing them to `parts`. A compound needs at least two parts.
+    fn split_rule<'a>(
+        &'a self,
+        pattern: &[RuleElement],
+        rest: &str,
+        parts: &mut Vec<&'a str>,
+    ) -> bool {
+        if rest.is_empty() {
+            return parts.len() >= 2 && pattern.iter().all(|elem| elem.optional());
+        }
+        let Some((&elem, tail)) = pattern.split_first() else {
+            return false;
+        };
+        if elem.optional() && self.split_rule(tail, rest, parts) {
+            return true;
+        }
+
+        // A repeated element stays at the start of the pattern until skipped
+        let next = if elem.repeat() { pattern } else { tail };
+        let min = self.parsed_config.compound_config().min_length();
+        for (count, (idx, ch)) in rest.char_indices().enumerate() {
+            if count + 1 < min {
+                continue;
+            }
+            let end = idx + ch.len_utf8();
+            let Some(matched) = self.lookup_rule_part(&rest[..end], elem.flag()) else {
+                continue;
+            };
+            parts.push(matched);
+            if self.split_rule(next, &rest[end..], parts) {
+                return true;
+            }
+            parts.pop();
+        }
+
+        false
+    }
+
+    /// Find the dictionary entry for `part` if it has `flag`
+    fn lookup_rule_part(&self, part: &str, flag: Flag) -> Option<&str> {
+        let mut buf = self.lower_buf();
+        let lower = buf.lower(part);
+        if self.wordlist_forbidden.contains_either(part, lower) {
+            return None;
+        }
+        let (matched, metas) = self
+            .wordlist
+            .get_either(part, lower)
+            .or_else(|| self.wordlist_nosuggest.get_either(part, lower))
+            .or_else(|| self.wordlist_compound_only.get_either(part, lower))?;
+        metas
+            .iter()
+            .any(|meta| {
+                // See `part_allowed`
+                if matches!(meta.source(), Source::Dict(_)) && meta.base_stem() != matched {
+                    return false;
+                }
+                meta.flags().contains(&flag)
+            })
+            .then_some(matched)
+    }
+
+    /// Whether `word` is one edit away from a dictionary word
+    fn is_typo(&self, word: &str) -> bool {
+        let len = word.chars().count();
+        self.wordlist
+            .0
+            .keys()
+            .any(|key| key.chars().count().abs_diff(len) <= 1 && within_one_edit(key, word))
+    }
+
+    /// Try to split `rest` into parts that may appear in a compound, pushing them
+    /// to `parts`. `first` is set if `rest` starts the compound and `complete` if
+    /// it ends it; a complete compound needs at least two parts.
+    fn split_compound<'a>(
+        &'a self,
+        rest: &str,
+        first: bool,
+        complete: bool,
+        parts: &mut Vec<&'a str>,
+    ) -> bool {
+        let min = self.parsed_config.compound_config().min_length();
+        let len = rest.chars().count();
+        if len < min {
+            return false;
+        }
+        // Parts that may still be added, including the one for `rest`. With
+        // `COMPOUNDSYLLABLE`, longer compounds are checked once complete.
+        let cfg = self.parsed_config.compound_config();
+        let max_parts = cfg.word_max().filter(|_| cfg.syllable_max().is_none());
+        let parts_left = max_parts.map_or(usize::MAX, |max| max.saturating_sub(parts.len()));
+        if parts_left == 0 {
+            return false;
+        }
+
+        // Use all of `rest` as the last part
+        let last_pos = if complete {
+            Position::End
+        } else if first {
+            Position::Begin
+        } else {
+            Position::Middle
+        };
+        if !(first && complete) {
+            if let Some(matched) = self.lookup_part(rest, last_pos) {
+                if !self.is_dup(parts, matched) {
+                    parts.push(matched);
+                    if !complete || self.within_syllable_max(parts) {
+                        return true;
+                    }
+                    parts.pop();
+                }
+            }
+        }
+
+        // Splitting further would need at least two more parts
+        if parts_left == 1 {
+            return false;
+        }
+        let pos = if first {
+            Position::Begin
+        } else {
+            Position::Middle
+        };
+        for (count, (idx, _)) in rest.char_indices().enumerate().skip(min) {
+            if len - count < min {
+                break;
+            }
+            let (left, right) = rest.split_at(idx);
+            let Some(matched) = self.lookup_part(left, pos) else {
+                continue;
+            };
+            if self.is_dup(parts, matched) {
+                continue;
+            }
+            parts.push(matched);
+            if self.boundary_allowed(left, right, false)
+                && self.split_compound(right, false, complete, parts)
+            {
+                return true;
+            }
+            if let Some(restored) = self.restore_triple(left, right) {
+                if self.boundary_allowed(left, &restored, true)
+                    && self.split_compound(&restored, false, complete, parts)
+                {
+                    return true;
+                }
+            }
+            parts.pop();
+        }
+
+        false
+    }
+
+    /// Whether a compound with more than `COMPOUNDWORDMAX` parts has at most as
+    /// many syllables as `COMPOUNDSYLLABLE` allows
+    fn within_syllable_max(&self, parts: &[&str]) -> bool {
+        let cfg = self.parsed_config.compound_config();
+        let (Some(word_max), Some(syllable_max)) = (cfg.word_max(), cfg.syllable_max()) else {
+            return true;
+        };
+        parts.len() <= word_max
+            || parts.iter().map(|part| self.syllables(part)).sum::<usize>() <= syllable_max
+    }
+
+    /// Syllables in the entry `part`. Like Hunspell, the last suffix is not
+    /// counted unless its flag is in `SYLLABLENUM`.
+    fn syllables(&self, part: &str) -> usize {
+        let cfg = self.parsed_config.compound_config();
+        let total = cfg.count_syllables(part);
+        let Some(metas) = self.part_metas(part) else {
+            return total;
+        };
+        metas
+            .iter()
+            .map(|meta| {
+                let suffix = meta
+                    .source()
+                    .affixes()
+                    .filter(|(rule, _)| !rule.is_pfx())
+                    .last();
+                match suffix {
+                    Some((rule, pat_idx)) if !self.counts_syllables(rule) => {
+                        let affix = rule.patterns()[pat_idx].affix();
+                        total.saturating_sub(cfg.count_syllables(affix))
+                    }
+                    _ => total,
+                }
+            })
+            .min()
+            .unwrap_or(total)
+    }
+
+    /// Whether `rule` has one of the `SYLLABLENUM` flags
+    fn counts_syllables(&self, rule: &AfxRule) -> bool {
+        let cfg = self.parsed_config.compound_config();
+        cfg.syllable_num().iter().any(|flag| {
+            matches!(self.affix_flags.get(flag), Some(FlagValue::Rule(r)) if ptr::eq(r.as_ref(), rule))
+        })
+    }
+
+    /// Whether `matched` would repeat the previous part, with `CHECKCOMPOUNDDUP`
+    fn is_dup(&self, parts: &[&str], matched: &str) -> bool {
+        self.parsed_config.compound_config().check_dup() && parts.last() == Some(&matched)
+    }
+
+    /// Whether the boundary between the end of `left` and the start of `right`
+    /// is allowed. `simplified` is set if a tripled letter was written as two.
+    fn boundary_allowed(&self, left: &str, right: &str, simplified: bool) -> bool {
+        let cfg = self.parsed_config.compound_config();
+        let (Some(before), Some(after)) = (left.chars().next_back(), right.chars().next()) else {
+            return true;
+        };
+
+        // Fully uppercase words are checked like their lowercase form
+        let all_upper = !left.chars().chain(right.chars()).any(char::is_lowercase);
+        if cfg.check_case()
+            && !all_upper
+            && (before.is_uppercase() || after.is_uppercase())
+            && before != '-'
+            && after != '-'
+        {
+            return false;
+        }
+
+        if cfg.check_triple() && !simplified {
+            let mut end = left.chars().rev();
+            let mut start = right.chars();
+            let tripled =
+                (before == after) && (end.nth(1) == Some(before) || start.nth(1) == Some(after));
+            if tripled {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// With `SIMPLIFIEDTRIPLE`, if `left` ends with a doubled letter, the part
+    /// in `right` may have lost its first letter to it. Return that part.
+    fn restore_triple(&self, left: &str, right: &str) -> Option<String> {
+        if !self.parsed_config.compound_config().simplify_triple() {
+            return None;
+        }
+        let mut end = left.chars().rev();
+        let last = end.next()?;
+        (end.next() == Some(last)).then(|| format!("{last}{right}"))
+    }
+
+    /// Find the dictionary entry for `part` if it may appear at `pos`
+    fn lookup_part(&self, part: &str, pos: Position) -> Option<&str> {
+        let mut buf = self.lower_buf();
+        let lower = buf.lower(part);
+        if self.wordlist_forbidden.contains_either(part, lower) {
+            return None;
+        }
+        let (matched, metas) = self
+            .wordlist
+            .get_either(part, lower)
+            .or_else(|| self.wordlist_nosuggest.get_either(part, lower))
+            .or_else(|| self.wordlist_compound_only.get_either(part, lower))?;
+        self.part_allowed(matched, metas, pos).then_some(matched)
+    }
+
+    /// The metas of the entry `part`, which was returned by `lookup_part`
+    fn part_metas(&self, part: &str) -> Option<&[Meta]> {
+        self.wordlist
+            .0
+            .get(part)
+            .or_else(|| self.wordlist_nosuggest.0.get(part))
+            .or_else(|| self.wordlist_compound_only.0.get(part))
+            .map(Vec::as_slice)
+    }
+
+    /// Whether any meta of the entry `matched` allows it at `pos`. Affixed forms
+    /// may only have prefixes at the start of a compound and suffixes at the end,
+    /// unless the affix has `COMPOUNDPERMITFLAG`.
+    fn part_allowed(&self, matched: &str, metas: &[Meta], pos: Position) -> bool {
+        metas.iter().any(|meta| {
+            // Affixed words also carry the meta of their stem's dictionary entry,
+            // which does not describe this word
+            if matches!(meta.source(), Source::Dict(_)) && meta.base_stem() != matched {
+                return false;
+            }
+            let affix_flags = meta
+                .source()
+                .affixes()
+                .flat_map(|(rule, pat_idx)| rule.patterns()[pat_idx].cont_flags());
+            self.flags_allow(meta.flags().iter().chain(affix_flags), pos)
+                && meta.source().affixes().all(|(rule, pat_idx)| {
+                    let permit = rule.patterns()[pat_idx].cont_flags().iter().any(|flag| {
+                        matches!(self.affix_flags.get(flag), Some(FlagValue::CompoundPermit))
+                    });
+                    if permit {
+                        true
+                    } else if rule.is_pfx() {
+                        pos == Position::Begin
+                    } else {
+                        pos == Position::End
+                    }
+                })
+        })
+    }
+
+    /// Whether the flags of an entry allow it at `pos`
+    fn flags_allow<'a>(&self, mut flags: impl Iterator<Item = &'a Flag>, pos: Position) -> bool {
+        flags.any(|flag| match self.affix_flags.get(flag) {
+            Some(FlagValue::Compound) => true,
+            Some(FlagValue::CompoundBegin) => pos == Position::Begin,
+            Some(FlagValue::CompoundMiddle) => pos == Position::Middle,
+            Some(FlagValue::CompoundEnd) => pos == Position::End,
+            _ => false,
+        })
+    }
+
+    /// Whether the entry `part` has `FORCEUCASE`, so a compound ending with it
+    /// must be capitalized
+    fn forces_upper(&self, part: &str) -> bool {
+        let Some(metas) = self.part_metas(part) else {
+            return false;
+        };
+        metas
+            .iter()
+            .flat_map(Meta::flags)
+            .any(|flag| matches!(self.affix_flags.get(flag), Some(FlagValue::CompoundForceUp)))
+    }
+
+    /// Suggest corrections for a misspelled compound by replacing its first or
+    /// last part with a similar word, returning at most `max`. Each split point
+    /// scans the wordlist, so this stops early once `deadline` passes.
+    #[cfg(feature = "unstable-suggestions")]
+    pub(super) fn compound_suggestions(
+        &self,
+        word: &str,
+        max: usize,
+        deadline: &Deadline<'_>,
+    ) -> Vec<String> {
+        let mut ret = Vec::new();
+        if max == 0 || !self.parsed_config.compound_config().uses_flags() || deadline.expired() {
+            return ret;
+        }
+
+        // If the compound is only missing the capital that `FORCEUCASE` needs,
+        // that is the fix
+        let mut parts = Vec::new();
+        if self.split_compound(word, true, true, &mut parts) {
+            let upper = self.casing.capitalize(word);
+            if upper != word && parts.last().map_or(false, |last| self.forces_upper(last)) {
+                ret.push(upper);
+                return ret;
+            }
+        }
+
+        for (idx, _) in word.char_indices().skip(1) {
+            if deadline.expired() {
+                break;
+            }
+            let (head, tail) = word.split_at(idx);
+
+            // Keep the start and fix the last part
+            parts.clear();
+            if self.split_compound(head, true, false, &mut parts) {
+                for cand in self.similar_parts(tail, Position::End) {
+                    self.push_compound_suggestion(format!("{head}{cand}"), &mut ret);
+                }
+            }
+
+            // Keep the end and fix the first part
+            parts.clear();
+            if self.split_compound(tail, false, true, &mut parts) {
+                for cand in self.similar_parts(head, Position::Begin) {
+                    self.push_compound_suggestion(format!("{cand}{tail}"), &mut ret);
+                }
+            }
+
+            if ret.len() >= max {
+                break;
+            }
+        }
+
+        ret.truncate(max);
+        ret
+    }
+
+    /// Entries within an edit distance of one from `part` that may appear at
+    /// `pos`, sorted so results do not depend on hash order
+    #[cfg(feature = "unstable-suggestions")]
+    fn similar_parts(&self, part: &str, pos: Position) -> Vec<&str> {
+        let min = self.parsed_config.compound_config().min_length();
+        let mut ret: Vec<&str> = self
+            .wordlist
+            .0
+            .iter()
+            .filter(|(key, _)| key.as_ref() != part && key.chars().count() >= min)
+            .filter(|(key, _)| within_one_edit(key, part))
+            .filter(|(key, metas)| self.part_allowed(key, metas, pos))
+            .map(|(key, _)| key.as_ref())
+            .collect();
+        ret.sort_unstable();
+        ret
+    }
+
+    /// Add `cand` to `dest` if it is a valid compound, capitalizing it if its last
+    /// part requires
+    #[cfg(feature = "unstable-suggestions")]
+    fn push_compound_suggestion(&self, cand: String, dest: &mut Vec<String>) {
+        let mut parts = Vec::new();
+        if !self.split_compound(&cand, true, true, &mut parts) {
+            return;
+        }
+
+        let cand = match parts.last() {
+            Some(last) if self.forces_upper(last) => self.casing.capitalize(&cand),
+            _ => cand,
+        };
+
+        if !dest.contains(&cand) {
+            dest.push(cand);
+        }
+    }
+}
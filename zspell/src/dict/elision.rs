@@ -0,0 +1,41 @@
+//! Checking of elided words such as "l'arbre" or "dell'acqua"
+//!
+//! Languages like French and Italian drop a vowel before a word that starts
+//! with one, joining the two with an apostrophe. If the dictionary has the part
+//! up to the apostrophe (e.g. "l'") and the word after it, the whole is
+//! accepted.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::DictData;
+
+/// Apostrophes that may end an elided part
+const APOSTROPHES: [char; 2] = ['\'', '’'];
+
+impl DictData {
+    /// If `word` is an elided part followed by a word, return the dictionary
+    /// entries for both
+    pub(super) fn elision_parts(&self, word: &str) -> Option<Vec<&str>> {
+        word.match_indices(APOSTROPHES.as_slice())
+            .map(|(idx, apos)| idx + apos.len())
+            .filter(|&split| split < word.len())
+            .find_map(|split| {
+                let (head, tail) = word.split_at(split);
+                Some(vec![self.lookup_simple(head)?, self.lookup_simple(tail)?])
+            })
+    }
+
+    /// Find the dictionary entry for a word that is not forbidden
+    fn lookup_simple(&self, word: &str) -> Option<&str> {
+        let mut buf = self.lower_buf();
+        let lower = buf.lower(word);
+        if self.wordlist_forbidden.contains_either(word, lower) {
+            return None;
+        }
+        self.wordlist
+            .get_either(word, lower)
+            .or_else(|| self.wordlist_nosuggest.get_either(word, lower))
+            .map(|(matched, _)| matched)
+    }
+}
@@ -0,0 +1,308 @@
+//! Tracing why a word is accepted or rejected, see [`WordEntry::explain`]
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::rules_reverse::{self, Derivation};
+use super::{without_dots, DictData, Dictionary, EntryList, WordEntry};
+
+/// How a word was checked, created by [`WordEntry::explain`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Explanation<'dict> {
+    /// Whether the word is accepted, which matches [`Dictionary::check_word`]
+    pub correct: bool,
+    /// The form of the word that decided the result. This is after
+    /// normalization and `ICONV`, and may have trailing dots removed.
+    pub lookup: String,
+    /// How the word was accepted, or why it was rejected
+    pub reason: Reason<'dict>,
+}
+
+/// The way a word was accepted or rejected, see [`Explanation`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Reason<'dict> {
+    /// The word is in a wordlist
+    Listed {
+        /// The entry that matched
+        matched: &'dict str,
+        /// The wordlist that the entry is in
+        list: ListKind,
+        /// Whether the entry matched as given or in lowercase
+        case: CaseMatch,
+    },
+    /// The word is an affixed form of dictionary entries that was derived when
+    /// it was looked up, see [`DictBuilder::lazy_affixes`](crate::DictBuilder::lazy_affixes)
+    Derived {
+        /// The entries that the word is created from
+        roots: Vec<&'dict str>,
+        /// The wordlist that the word belongs to
+        list: ListKind,
+        /// Whether the word was derived as given or in lowercase
+        case: CaseMatch,
+    },
+    /// The word is an accepted number
+    Number,
+    /// The word is a compound of these dictionary entries
+    Compound { parts: Vec<&'dict str> },
+    /// The word is an elided part and a word, such as "l'arbre"
+    Elision { parts: Vec<&'dict str> },
+    /// The word was split at `BREAK` separators and each of these parts was
+    /// accepted, see [`DictBuilder::check_break_parts`](crate::DictBuilder::check_break_parts)
+    Break { parts: Vec<String> },
+    /// The word is forbidden
+    Forbidden {
+        /// The forbidden entry that matched
+        matched: &'dict str,
+    },
+    /// The word was not found. `hints` lists the closest reasons it may have
+    /// been expected to pass, if any.
+    NotFound { hints: Vec<Hint<'dict>> },
+}
+
+/// A wordlist that accepted a word, see [`Reason::Listed`]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ListKind {
+    /// Words that are accepted and suggested, including the personal dictionary
+    Words,
+    /// Words that are accepted but never suggested, `NOSUGGEST`
+    NoSuggest,
+}
+
+/// How the case of a word matched a wordlist entry, see [`Reason::Listed`]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CaseMatch {
+    /// The word matched exactly
+    Exact,
+    /// The lowercase form of the word matched
+    Lowercase,
+}
+
+/// A near miss for a word that was not found, see [`Reason::NotFound`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Hint<'dict> {
+    /// The word may only be used inside compounds, `ONLYINCOMPOUND`
+    OnlyInCompound { matched: &'dict str },
+    /// The dictionary has the word with different capitalization
+    Capitalization { matched: &'dict str },
+    /// The word splits into these dictionary entries, but the compound rules do
+    /// not allow them to be joined
+    CompoundRejected { parts: Vec<&'dict str> },
+    /// The word was split at `BREAK` separators, but these parts are misspelled
+    MisspelledParts { parts: Vec<String> },
+}
+
+impl<'dict> WordEntry<'dict, '_> {
+    /// Trace how this word is checked: the wordlist and case that matched, or
+    /// whether it was accepted as a compound, number, or in parts split at
+    /// `BREAK` separators. For rejected words, near misses such as a different
+    /// capitalization are listed. This is meant for debugging dictionaries.
+    ///
+    /// ```
+    /// use zspell::{CaseMatch, Hint, ListKind, Reason};
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("2\nhello\nParis")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let explanation = dict.entry("Hello").explain();
+    /// assert!(explanation.correct);
+    /// assert_eq!(
+    ///     explanation.reason,
+    ///     Reason::Listed {
+    ///         matched: "hello",
+    ///         list: ListKind::Words,
+    ///         case: CaseMatch::Lowercase
+    ///     }
+    /// );
+    ///
+    /// let explanation = dict.entry("paris").explain();
+    /// assert!(!explanation.correct);
+    /// assert_eq!(
+    ///     explanation.reason,
+    ///     Reason::NotFound {
+    ///         hints: vec![Hint::Capitalization { matched: "Paris" }]
+    ///     }
+    /// );
+    /// ```
+    #[inline]
+    pub fn explain(&self) -> Explanation<'dict> {
+        let dict: &'dict Dictionary = self.dict;
+        let prepared = dict.prepare(self.word);
+        let forms = without_dots(&prepared).map_or([None, None], |(dotless, abbrev)| {
+            [Some(dotless), Some(abbrev)]
+        });
+
+        let mut first = None;
+        for form in core::iter::once(prepared.as_ref()).chain(forms.into_iter().flatten()) {
+            let reason = dict.lookup_reason(form);
+            let stop = !matches!(reason, Reason::NotFound { .. });
+            let explanation = Explanation {
+                correct: !matches!(reason, Reason::NotFound { .. } | Reason::Forbidden { .. }),
+                lookup: form.to_owned(),
+                reason,
+            };
+            if stop {
+                return explanation;
+            }
+            first.get_or_insert(explanation);
+        }
+        let mut explanation = first.expect("the word itself is always looked up");
+
+        let parts = dict.break_parts(self.word);
+        if parts.len() > 1 {
+            if dict.check_breaks(self.word, 0) {
+                explanation.correct = true;
+                explanation.reason = Reason::Break {
+                    parts: parts.iter().map(|(_, part)| (*part).to_owned()).collect(),
+                };
+                return explanation;
+            }
+            let misspelled: Vec<_> = dict
+                .word_errors(0, self.word)
+                .into_iter()
+                .map(|(_, part)| part.to_owned())
+                .collect();
+            explanation.reason = Reason::NotFound {
+                hints: vec![Hint::MisspelledParts { parts: misspelled }],
+            };
+            return explanation;
+        }
+
+        explanation.reason = Reason::NotFound {
+            hints: dict.data.hints(&explanation.lookup),
+        };
+        explanation
+    }
+}
+
+impl Dictionary {
+    /// Find how a prepared word is accepted or forbidden, in the same order as
+    /// `word_ctx`
+    fn lookup_reason(&self, word: &str) -> Reason<'_> {
+        let data = &self.data;
+        let mut buf = self.data.lower_buf();
+        let lower = buf.lower(word);
+
+        if let Some((matched, _)) = data.wordlist_forbidden.get_either(word, lower) {
+            return Reason::Forbidden { matched };
+        }
+
+        let listed = data
+            .wordlist
+            .get_either(word, lower)
+            .map(|(matched, _)| (matched, ListKind::Words))
+            .or_else(|| {
+                data.wordlist_nosuggest
+                    .get_either(word, lower)
+                    .map(|(matched, _)| (matched, ListKind::NoSuggest))
+            });
+        if let Some((matched, list)) = listed {
+            let case = if matched == word {
+                CaseMatch::Exact
+            } else {
+                CaseMatch::Lowercase
+            };
+            return Reason::Listed {
+                matched,
+                list,
+                case,
+            };
+        }
+
+        let (derived_word, derived) = data.derive_either(word, lower);
+        if let Some(forbidden) = derived.iter().find(|d| d.list() == EntryList::Forbidden) {
+            return Reason::Forbidden {
+                matched: forbidden.root(),
+            };
+        }
+        let derived = rules_reverse::accepted(derived);
+        if let Some(first) = derived.first() {
+            let list = if first.list() == EntryList::Words {
+                ListKind::Words
+            } else {
+                ListKind::NoSuggest
+            };
+            let case = if derived_word == word {
+                CaseMatch::Exact
+            } else {
+                CaseMatch::Lowercase
+            };
+            let mut roots: Vec<_> = derived.iter().map(Derivation::root).collect();
+            roots.dedup();
+            return Reason::Derived { roots, list, case };
+        }
+
+        if self.is_accepted_number(word) {
+            Reason::Number
+        } else if let Some(parts) = data.compound_parts(word) {
+            Reason::Compound { parts }
+        } else if let Some(parts) = data.elision_parts(word) {
+            Reason::Elision { parts }
+        } else {
+            Reason::NotFound { hints: Vec::new() }
+        }
+    }
+}
+
+impl DictData {
+    /// Near misses for a prepared word that was not found
+    fn hints(&self, word: &str) -> Vec<Hint<'_>> {
+        let mut hints = Vec::new();
+        let mut buf = self.lower_buf();
+        let lower = buf.lower(word);
+
+        if let Some((matched, _)) = self.wordlist_compound_only.get_either(word, lower) {
+            hints.push(Hint::OnlyInCompound { matched });
+        }
+
+        let casing = self.casing;
+        let capitalized = casing.capitalize(&casing.to_lowercase(word));
+        let other_case = [capitalized, casing.to_uppercase(word)]
+            .into_iter()
+            .filter(|form| form != word)
+            .find_map(|form| {
+                self.wordlist
+                    .0
+                    .get_key_value(form.as_str())
+                    .or_else(|| self.wordlist_nosuggest.0.get_key_value(form.as_str()))
+            });
+        if let Some((matched, _)) = other_case {
+            hints.push(Hint::Capitalization { matched });
+        }
+
+        let split = word.char_indices().skip(1).find_map(|(idx, _)| {
+            let (left, right) = word.split_at(idx);
+            Some(vec![self.lookup_any(left)?, self.lookup_any(right)?])
+        });
+        if let Some(parts) = split {
+            hints.push(Hint::CompoundRejected { parts });
+        }
+
+        hints
+    }
+
+    /// Find a word that may appear in a compound, ignoring compound rules
+    fn lookup_any(&self, word: &str) -> Option<&str> {
+        let mut buf = self.lower_buf();
+        let lower = buf.lower(word);
+        self.wordlist
+            .get_either(word, lower)
+            .or_else(|| self.wordlist_nosuggest.get_either(word, lower))
+            .or_else(|| self.wordlist_compound_only.get_either(word, lower))
+            .map(|(matched, _)| matched)
+    }
+}
@@ -1,5 +1,5 @@
-use std::fmt::Display;
-use std::sync::Arc;
+use alloc::sync::Arc;
+use core::fmt::Display;
 
 use super::rule::AfxRule;
 
@@ -35,7 +35,7 @@ pub enum FlagValue {
 }
 
 impl Display for FlagValue {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             FlagValue::AfxCircumfix => write!(f, "AfxCircumfix"),
             FlagValue::AfxKeepCase => write!(f, "AfxKeepCase"),
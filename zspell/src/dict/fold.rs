@@ -0,0 +1,101 @@
+//! Case-insensitive lookups, used when a dictionary is built with
+//! [`DictBuilder::case_insensitive`](crate::DictBuilder::case_insensitive)
+//!
+//! A lowercased word already finds dictionary words that are lowercase, so only
+//! words that case folding changes need to be indexed. These are words with
+//! uppercase letters such as "Paris" or "iPhone", and with the `icu` feature
+//! also words such as "straße" that fold to a different spelling.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::mem::size_of;
+
+use super::DictData;
+use crate::casing::Casing;
+use crate::helpers::FxHashMap;
+
+/// The dictionary spellings of words that case folding changes, by their folded
+/// form
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct FoldedIndex {
+    words: FxHashMap<Box<str>, Vec<Box<str>>>,
+    casing: Casing,
+}
+
+impl FoldedIndex {
+    fn new(casing: Casing) -> Self {
+        Self {
+            words: FxHashMap::default(),
+            casing,
+        }
+    }
+
+    /// Add a dictionary spelling, if case folding changes it
+    pub(super) fn insert(&mut self, word: &str) {
+        let folded = self.casing.fold(word);
+        if folded == word {
+            return;
+        }
+        let spellings = self.words.entry(folded.into()).or_default();
+        if !spellings.iter().any(|spelling| &**spelling == word) {
+            spellings.push(word.into());
+        }
+    }
+
+    /// Indexed dictionary spellings that are the same as `word` apart from case, other than `word` itself. Words that have since been
+    /// removed may be included, so each one must still be looked up.
+    pub(super) fn spellings<'a>(&'a self, word: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.words
+            .get(self.casing.fold(word).as_str())
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(move |spelling| *spelling != word)
+    }
+
+    pub(super) fn shrink_to_fit(&mut self) {
+        for spellings in self.words.values_mut() {
+            spellings.shrink_to_fit();
+        }
+        self.words.shrink_to_fit();
+    }
+
+    /// Estimate the heap memory used by the index
+    pub(super) fn heap_size(&self) -> usize {
+        // Each bucket has one control byte
+        let table = self.words.capacity() * (size_of::<(Box<str>, Vec<Box<str>>)>() + 1);
+        let entries: usize = self
+            .words
+            .iter()
+            .map(|(lower, spellings)| {
+                lower.len()
+                    + spellings.capacity() * size_of::<Box<str>>()
+                    + spellings.iter().map(|s| s.len()).sum::<usize>()
+            })
+            .sum();
+        table + entries
+    }
+}
+
+impl DictData {
+    /// Index the spellings of every accepted word and every root of lazily
+    /// derived words
+    pub(super) fn build_folded(&mut self) {
+        let mut folded = FoldedIndex::new(self.casing);
+        let words = self
+            .wordlist
+            .0
+            .keys()
+            .chain(self.wordlist_nosuggest.0.keys());
+        for word in words {
+            folded.insert(word);
+        }
+        if let Some(lazy) = &self.lazy {
+            for stem in lazy.stems() {
+                folded.insert(stem);
+            }
+        }
+        folded.shrink_to_fit();
+        self.folded = Some(folded);
+    }
+}
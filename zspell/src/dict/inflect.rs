@@ -0,0 +1,128 @@
+//! Suggestions that are other forms of the same stem as a misspelling, such as
+//! "ran" and "running" for "runned". Inflection errors like this are often too
+//! far from the right word for edit distance to find it.
+//!
+//! The affix of the misspelling is removed with every rule whose text it ends
+//! (or starts) with, ignoring flags and conditions since the misspelling may
+//! use a rule that its stem does not allow. If no word is left, one more letter
+//! is dropped to undo a doubled consonant ("runn" to "run"). Every word with
+//! the same stem is then a candidate, including irregular forms whose entry
+//! names the stem with `st:`.
+
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{DictData, FlagValue, Meta};
+use crate::affix::RuleType;
+use crate::suggestions::{Deadline, EditDistance};
+
+impl DictData {
+    /// Suggest at most `max` other forms of the stem that `word` seems to be
+    /// an inflection of, closest to `word` first
+    pub(super) fn stem_suggestions(
+        &self,
+        word: &str,
+        max: usize,
+        deadline: &Deadline<'_>,
+    ) -> Vec<&str> {
+        if max == 0 || deadline.expired() {
+            return Vec::new();
+        }
+        let stems = self.misspelled_stems(word);
+        if stems.is_empty() {
+            return Vec::new();
+        }
+
+        let distance = EditDistance::new(word);
+        let mut forms: Vec<(u32, &str)> = self
+            .wordlist
+            .0
+            .iter()
+            .take_while(|_| !deadline.expired())
+            .filter(|(_, metas)| metas.iter().any(|meta| stems.contains(meta.stem())))
+            .map(|(key, _)| (distance.distance(key), key.as_ref()))
+            .collect();
+
+        let collation = self.collation();
+        forms.sort_unstable_by(|(dist_a, a), (dist_b, b)| {
+            dist_a
+                .cmp(dist_b)
+                .then_with(|| self.frequency(b).cmp(&self.frequency(a)))
+                .then_with(|| collation.compare(a, b))
+        });
+        forms
+            .into_iter()
+            .take(max)
+            .map(|(_dist, form)| form)
+            .collect()
+    }
+
+    /// Stems of the words that are left after removing an affix from `word`.
+    /// Only the longest affixes that leave a known word are used, so that
+    /// "runned" is taken as "run" with "-ed" rather than "rune" with "-d".
+    fn misspelled_stems(&self, word: &str) -> BTreeSet<&str> {
+        let mut bases: Vec<(usize, String)> = Vec::new();
+        for value in self.affix_flags.values() {
+            let FlagValue::Rule(rule) = value else {
+                continue;
+            };
+            for pat in rule.patterns() {
+                let afx = pat.affix();
+                let rest = match rule.kind() {
+                    RuleType::Prefix => word.strip_prefix(afx),
+                    RuleType::Suffix => word.strip_suffix(afx),
+                };
+                let Some(rest) = rest.filter(|rest| !afx.is_empty() && !rest.is_empty()) else {
+                    continue;
+                };
+                bases.push((afx.len(), rest.to_owned()));
+                if let Some(strip) = pat.strip() {
+                    let restored = match rule.kind() {
+                        RuleType::Prefix => [strip, rest].concat(),
+                        RuleType::Suffix => [rest, strip].concat(),
+                    };
+                    bases.push((afx.len(), restored));
+                }
+            }
+        }
+        bases.sort_unstable_by(|(len_a, a), (len_b, b)| len_b.cmp(len_a).then_with(|| a.cmp(b)));
+        bases.dedup();
+
+        let mut stems = BTreeSet::new();
+        let mut start = 0;
+        while start < bases.len() {
+            let len = bases[start].0;
+            let end = start + bases[start..].iter().take_while(|(l, _)| *l == len).count();
+            let tier = &bases[start..end];
+            start = end;
+
+            for (_, base) in tier {
+                self.add_stems(base, &mut stems);
+            }
+            if stems.is_empty() {
+                for (_, base) in tier {
+                    for (idx, ch) in base.char_indices() {
+                        let shorter = [&base[..idx], &base[idx + ch.len_utf8()..]].concat();
+                        if !shorter.is_empty() {
+                            self.add_stems(&shorter, &mut stems);
+                        }
+                    }
+                }
+            }
+            if !stems.is_empty() {
+                break;
+            }
+        }
+
+        stems
+    }
+
+    /// Add the stems of `word` to `stems` if it is in the wordlist
+    fn add_stems<'a>(&'a self, word: &str, stems: &mut BTreeSet<&'a str>) {
+        if let Some(metas) = self.wordlist.0.get(word) {
+            stems.extend(metas.iter().map(Meta::stem));
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Deduplicated storage for data that is shared among many wordlist entries
+
+use alloc::sync::Arc;
+use core::hash::Hash;
+use core::mem::{size_of, size_of_val};
+
+use crate::helpers::FxHashSet;
+
+/// An interning arena. Each distinct value is allocated once and handed out as an
+/// `Arc`, so entries created from the same stem, morph info, or flag set share a
+/// single allocation.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Interner<T: ?Sized + Eq + Hash>(FxHashSet<Arc<T>>);
+
+impl<T: ?Sized + Eq + Hash> Interner<T> {
+    pub fn new() -> Self {
+        Self(FxHashSet::default())
+    }
+
+    /// Return the stored copy of `value`, creating it with `f` if it does not yet
+    /// exist
+    pub fn intern_with(&mut self, value: &T, f: impl FnOnce(&T) -> Arc<T>) -> Arc<T> {
+        Arc::clone(self.0.get_or_insert_with(value, f))
+    }
+
+    /// Return the stored copy of `value`, copying it into the arena if needed
+    pub fn intern(&mut self, value: &T) -> Arc<T>
+    where
+        for<'a> Arc<T>: From<&'a T>,
+    {
+        self.intern_with(value, |v| v.into())
+    }
+
+    /// Make room for `additional` more values
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit();
+    }
+
+    /// Iterate over the distinct values stored
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.iter().map(AsRef::as_ref)
+    }
+
+    /// Number of distinct values stored
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Estimate the heap memory used by the arena and its values, not counting
+    /// memory that the values themselves point to
+    pub fn heap_size(&self) -> usize {
+        // Each bucket has one control byte, and each `Arc` has its strong and
+        // weak counts in front of the value
+        let table = self.0.capacity() * (size_of::<Arc<T>>() + 1);
+        let values: usize = self
+            .0
+            .iter()
+            .map(|v| 2 * size_of::<usize>() + size_of_val(v.as_ref()))
+            .sum();
+        table + values
+    }
+}
+
+// Derived `Clone` would require `T: Clone`, which unsized types cannot satisfy
+impl<T: ?Sized + Eq + Hash> Clone for Interner<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized + Eq + Hash> Default for Interner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares() {
+        let mut interner: Interner<str> = Interner::new();
+        let a = interner.intern("foo");
+        let b = interner.intern("foo");
+        let c = interner.intern("bar");
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+
+        let mut sets: Interner<[u32]> = Interner::new();
+        let a = sets.intern(&[1, 2, 3]);
+        let b = sets.intern(&[1, 2, 3]);
+        assert!(Arc::ptr_eq(&a, &b));
+    }
+}
@@ -1,7 +1,10 @@
-use std::borrow::Borrow;
-use std::sync::Arc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
 
 use super::rule::AfxRule;
+use super::Flag;
+use crate::affix::RuleType;
 use crate::morph::MorphInfo;
 
 /// Additional information attached to an entry in a dictionary
@@ -10,13 +13,16 @@ use crate::morph::MorphInfo;
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Meta {
     stem: Arc<str>,
+    /// Flags of the dictionary entry this word was created from
+    flags: Arc<[Flag]>,
     source: Source,
 }
 
 impl Meta {
-    pub(crate) fn new(stem_rc: Arc<str>, source: Source) -> Self {
+    pub(crate) fn new(stem_rc: Arc<str>, flags: Arc<[Flag]>, source: Source) -> Self {
         Self {
             stem: stem_rc,
+            flags,
             source,
         }
     }
@@ -43,8 +49,21 @@ impl Meta {
     pub fn source(&self) -> &Source {
         &self.source
     }
+
+    /// The stem that this entry was created from, ignoring any morph info
+    pub(crate) fn base_stem(&self) -> &str {
+        &self.stem
+    }
+
+    /// Flags of the dictionary entry this word was created from
+    pub(crate) fn flags(&self) -> &[Flag] {
+        &self.flags
+    }
 }
 
+/// An affix rule along with the index of the pattern that was applied
+pub type AppliedRule = (Arc<AfxRule>, usize);
+
 /// Source information
 #[allow(clippy::box_collection)]
 #[non_exhaustive]
@@ -58,6 +77,10 @@ pub enum Source {
         /// but that might require a RefCell, and I don't want to risk reference
         pat_idx: usize,
     },
+    /// This meta came from applying more than one affix rule, e.g. a prefix and a
+    /// suffix. Rules and their pattern indices are listed in the order they were
+    /// applied.
+    AffixCombined(Arc<[AppliedRule]>),
     /// This meta came from a .dic file, only contains morphinfo
     Dict(Arc<[Arc<MorphInfo>]>),
     /// This meta came from the personal dictionary
@@ -69,14 +92,35 @@ pub enum Source {
 impl Source {
     /// Iterate through all morph info available
     pub fn morphs(&self) -> impl Iterator<Item = &MorphInfo> {
-        match self {
-            Source::Affix { rule, pat_idx } => rule.patterns()[*pat_idx].morph_info(),
-            Source::Dict(v) => v.as_ref(),
-            Source::Personal(v) => v.morph.as_ref(),
-            Source::Raw => &[],
-        }
-        .iter()
-        .map(AsRef::as_ref)
+        let (first, rest): (&[Arc<MorphInfo>], &[AppliedRule]) = match self {
+            Source::Affix { rule, pat_idx } => (rule.patterns()[*pat_idx].morph_info(), &[]),
+            Source::AffixCombined(v) => (&[], v.as_ref()),
+            Source::Dict(v) => (v.as_ref(), &[]),
+            Source::Personal(v) => (v.morph.as_ref(), &[]),
+            Source::Raw => (&[], &[]),
+        };
+
+        first
+            .iter()
+            .chain(
+                rest.iter()
+                    .flat_map(|(rule, pat_idx)| rule.patterns()[*pat_idx].morph_info()),
+            )
+            .map(AsRef::as_ref)
+    }
+
+    /// Iterate through the affix rules and pattern indices that were applied to
+    /// create this entry, if any
+    pub fn affixes(&self) -> impl Iterator<Item = (&AfxRule, usize)> {
+        let (single, combined) = match self {
+            Source::Affix { rule, pat_idx } => (Some((rule.as_ref(), *pat_idx)), &[][..]),
+            Source::AffixCombined(v) => (None, v.as_ref()),
+            _ => (None, &[][..]),
+        };
+
+        single
+            .into_iter()
+            .chain(combined.iter().map(|(rule, idx)| (rule.as_ref(), *idx)))
     }
 
     /// Helper to create an `Affix` source when the `Arc` already exists
@@ -88,6 +132,92 @@ impl Source {
     }
 }
 
+/// A stem of a word along with the affixes that were stripped to reach it. This is
+/// created by [`WordEntry::stems_with_affixes`](crate::WordEntry::stems_with_affixes).
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StemInfo<'a> {
+    /// The stem word
+    pub stem: &'a str,
+    /// Affixes that were removed from the word, in the order they were applied to
+    /// the stem. This is empty if the word is itself a stem.
+    pub affixes: Vec<AffixInfo<'a>>,
+}
+
+/// A single affix rule application, see [`StemInfo`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AffixInfo<'a> {
+    /// Whether this is a prefix or a suffix
+    pub kind: RuleType,
+    /// The flag identifying the rule group, as written in the affix file (e.g.
+    /// `X` for `SFX X Y 1`)
+    pub flag: &'a str,
+    /// Index of the matching pattern line within the rule group
+    pub pattern: usize,
+    /// The text that the pattern adds to the stem
+    pub affix: &'a str,
+}
+
+impl<'a> AffixInfo<'a> {
+    pub(crate) fn new(rule: &'a AfxRule, pat_idx: usize) -> Self {
+        Self {
+            kind: rule.kind(),
+            flag: rule.ident(),
+            pattern: pat_idx,
+            affix: rule.patterns()[pat_idx].affix(),
+        }
+    }
+}
+
+/// A word in the expanded wordlist along with how it was created, see
+/// [`Dictionary::word_forms`](crate::Dictionary::word_forms).
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WordForm<'a> {
+    /// The word as it is accepted
+    pub word: &'a str,
+    /// The dictionary entry that the word was created from
+    pub root: &'a str,
+    /// Affixes that were applied to the root to create the word, in the order
+    /// they were applied. This is empty if the word is itself a root.
+    pub affixes: Vec<AffixInfo<'a>>,
+}
+
+impl<'a> WordForm<'a> {
+    /// Describe the distinct ways `word` was created from its wordlist entry's
+    /// `metas`
+    pub(crate) fn from_metas(word: &'a str, metas: &'a [Meta]) -> Vec<Self> {
+        let mut ret: Vec<Self> = Vec::new();
+        for meta in metas {
+            let affixes: Vec<_> = meta
+                .source()
+                .affixes()
+                .map(|(rule, pat_idx)| AffixInfo::new(rule, pat_idx))
+                .collect();
+
+            // Affixed words also carry the meta of their root's dictionary entry,
+            // which does not describe this word
+            if affixes.is_empty() && meta.base_stem() != word {
+                continue;
+            }
+
+            let form = Self {
+                word,
+                root: meta.base_stem(),
+                affixes,
+            };
+            if !ret.contains(&form) {
+                ret.push(form);
+            }
+        }
+        ret
+    }
+}
+
 /// Representation of meta info for a personal dictionary
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct PersonalMeta {
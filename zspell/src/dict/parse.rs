@@ -1,10 +1,15 @@
 //! Parse a dict file
 
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 
+use super::intern::Interner;
 use super::Flag;
 use crate::affix::FlagType;
 use crate::error::ParseError;
+#[cfg(feature = "unstable-suggestions")]
+use crate::error::ParseErrorKind;
 use crate::helpers::convertu32;
 use crate::morph::MorphInfo;
 
@@ -22,7 +27,8 @@ use crate::morph::MorphInfo;
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DictEntry {
     pub(super) stem: Arc<str>,
-    pub(super) flags: Vec<Flag>,
+    /// Flag sets are shared among all entries with the same flags
+    pub(super) flags: Arc<[Flag]>,
     pub(super) morph: Vec<Arc<MorphInfo>>,
 }
 
@@ -32,13 +38,17 @@ impl DictEntry {
     pub(crate) fn new(stem: &str, flags: &[Flag], morph: &[MorphInfo]) -> Self {
         Self {
             stem: stem.into(),
-            flags: flags.to_owned(),
+            flags: flags.into(),
             morph: morph.iter().map(|v| Arc::new(v.clone())).collect(),
         }
     }
 
     /// Create a `DictEntry` from a single line in a `.dic` file. Does not strip comments.
-    fn parse_single(value: &str, flag_type: FlagType, line_num: u32) -> Result<Self, ParseError> {
+    pub(super) fn parse_single(
+        value: &str,
+        flag_type: FlagType,
+        line_num: u32,
+    ) -> Result<Self, ParseError> {
         let (stem, flagstr, morphstr) = separate_into_parts(value);
 
         let flags: Vec<Flag> = match flagstr {
@@ -51,44 +61,37 @@ impl DictEntry {
             .map(Arc::new)
             .collect();
         let ret = Self {
-            stem: stem.trim().into(),
-            flags,
+            stem: unescape_slashes(stem.trim()),
+            flags: flags.into(),
             morph,
         };
         Ok(ret)
     }
 
-    /// Parse a complete dictionary file (usually `.dic`)
+    /// Parse a complete dictionary file (usually `.dic`), returning the entries
+    /// and the line number of each
     ///
     /// # Errors
     ///
     /// Returns an error if any entry is incorrect.
     #[inline]
-    #[allow(clippy::option_if_let_else)]
-    pub fn parse_all(input: &str, flag_type: FlagType) -> Result<Vec<DictEntry>, ParseError> {
-        // Ignore empty lines and
-        let mut lines_iter = extract_content(input);
-        let lines_backup = lines_iter.clone();
-
-        let Some(first) = lines_iter.next() else {
-            return Ok(Vec::new());
-        };
-
-        // Try to parse the first line as an integer; if not, ignore it
-        let (mut ret, start) = if let Ok(cap) = first.parse::<usize>() {
-            (Vec::with_capacity(cap), 2)
-        } else {
-            lines_iter = lines_backup;
-            (Vec::new(), 1)
-        };
+    pub fn parse_numbered(
+        input: &str,
+        flag_type: FlagType,
+    ) -> Result<(Vec<DictEntry>, Vec<u32>), ParseError> {
+        let (count, lines) = split_count(input);
+        let cap = count.map_or(0, |(_, cap)| cap);
+        let mut entries = Vec::with_capacity(cap);
+        let mut line_nums = Vec::with_capacity(cap);
 
-        for (i, line) in lines_iter.enumerate() {
-            ret.push(
-                DictEntry::parse_single(line, flag_type, convertu32(i + start))
-                    .map_err(|e| e.add_offset_ret(i + start, 0))?,
-            );
+        let mut flag_sets: Interner<[Flag]> = Interner::new();
+        for (line_num, line) in lines {
+            let mut entry = DictEntry::parse_single(line, flag_type, line_num)?;
+            entry.flags = flag_sets.intern_with(&entry.flags, |_| Arc::clone(&entry.flags));
+            entries.push(entry);
+            line_nums.push(line_num);
         }
-        Ok(ret)
+        Ok((entries, line_nums))
     }
 }
 
@@ -138,16 +141,72 @@ impl PersonalEntry {
         let morph = MorphInfo::many_from_str(morphstr).collect();
 
         Self {
-            stem: stem.trim().into(),
+            stem: unescape_slashes(stem.trim()),
             friend: friend.map(|f| f.trim().into()),
             morph,
             forbid,
         }
     }
-    /// Parse a personal dictionary file
+    /// Parse a personal dictionary file. A header line naming the dictionary the
+    /// file extends (as Hunspell writes it, e.g. `en_US`) or giving the entry count
+    /// is skipped.
     pub fn parse_all(s: &str) -> Vec<PersonalEntry> {
-        extract_content(s).map(Self::parse_single).collect()
+        let mut lines = extract_content(s).peekable();
+        if lines.peek().map_or(false, |line| is_personal_header(line)) {
+            lines.next();
+        }
+        lines.map(Self::parse_single).collect()
+    }
+}
+
+/// Parse a word frequency file, returning each word with its count.
+///
+/// Each line holds a word and the number of times it occurs, separated by
+/// whitespace (e.g. `the 23135851162`). This is the format of most published
+/// frequency lists. Empty lines and `#` comments are skipped.
+#[cfg(feature = "unstable-suggestions")]
+pub fn parse_frequencies(input: &str) -> Result<Vec<(&str, u64)>, ParseError> {
+    let mut ret = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        let content = line
+            .split_once('#')
+            .map_or(line, |(before, _)| before)
+            .trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        let (word, count) = content
+            .rsplit_once(char::is_whitespace)
+            .unwrap_or((content, ""));
+        let count = count
+            .parse()
+            .map_err(|e| ParseError::new_nocol(ParseErrorKind::Int(e), line, idx + 1))?;
+        ret.push((word.trim_end(), count));
+    }
+
+    Ok(ret)
+}
+
+/// Check whether the first line of a personal dictionary is a header rather than
+/// a word: either an entry count or a dictionary name such as `en_US` or
+/// `de_DE_frami`.
+fn is_personal_header(line: &str) -> bool {
+    if line.bytes().all(|b| b.is_ascii_digit()) {
+        return true;
     }
+
+    let mut parts = line.split(['_', '-']);
+    let lang = parts.next().unwrap_or_default();
+    let Some(region) = parts.next() else {
+        return false;
+    };
+
+    (2..=3).contains(&lang.len())
+        && lang.bytes().all(|b| b.is_ascii_lowercase())
+        && region.len() == 2
+        && region.bytes().all(|b| b.is_ascii_uppercase())
 }
 
 /// Separate `(stem, flagstr, morphstr)` into parts
@@ -159,7 +218,7 @@ fn separate_into_parts(value: &str) -> (&str, Option<&str>, &str) {
     let value = value.split_once('#').unwrap_or((value, "")).0;
 
     // Split out the sections
-    if let Some((word, rest)) = value.split_once('/') {
+    if let Some((word, rest)) = flag_slash(value).map(|idx| (&value[..idx], &value[idx + 1..])) {
         // Easy case, we have an affix and can split on `/`. Then just split the first
         // whitespace to separate morph from the flags.
         stem = word;
@@ -181,16 +240,59 @@ fn separate_into_parts(value: &str) -> (&str, Option<&str>, &str) {
     (stem, flagstr, morphstr)
 }
 
+/// Find the slash that starts the flags of an entry: the first that is not at
+/// the start of the word or escaped as `\/`
+fn flag_slash(value: &str) -> Option<usize> {
+    value
+        .match_indices('/')
+        .map(|(idx, _)| idx)
+        .find(|&idx| idx > 0 && !value[..idx].ends_with('\\'))
+}
+
+/// Replace escaped slashes in a word, `\/`, with plain ones
+fn unescape_slashes(word: &str) -> Arc<str> {
+    if word.contains("\\/") {
+        word.replace("\\/", "/").into()
+    } else {
+        word.into()
+    }
+}
+
+/// Split the entry count, with its line number, from the lines of a `.dic`
+/// file. The count is optional.
+pub(super) fn split_count(
+    input: &str,
+) -> (
+    Option<(u32, usize)>,
+    impl Iterator<Item = (u32, &str)> + Clone,
+) {
+    let mut lines = numbered_content(input);
+    let count = lines
+        .clone()
+        .next()
+        .and_then(|(line_num, first)| Some((line_num, first.parse().ok()?)));
+    if count.is_some() {
+        lines.next();
+    }
+    (count, lines)
+}
+
 /// Extract nonempty lines that do not contain a comment
 fn extract_content(input: &str) -> impl Iterator<Item = &str> + Clone {
+    numbered_content(input).map(|(_, line)| line)
+}
+
+/// Like `extract_content`, but with the number of each line, starting at 1
+fn numbered_content(input: &str) -> impl Iterator<Item = (u32, &str)> + Clone {
     input
         .lines()
+        .enumerate()
+        .map(|(idx, line)| (convertu32(idx + 1), line))
         // Dictionary files sometimes use tabs for comments, need to check before trim
-        .filter(|line| !line.starts_with('\t'))
+        .filter(|(_, line)| !line.starts_with('\t'))
         // Trim hash comments
-        .map(|line| line.split_once('#').unwrap_or((line, "")).0)
-        .map(str::trim)
-        .filter(|line| !line.is_empty())
+        .map(|(num, line)| (num, line.split_once('#').unwrap_or((line, "")).0.trim()))
+        .filter(|(_, line)| !line.is_empty())
 }
 
 #[cfg(test)]
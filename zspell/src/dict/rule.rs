@@ -1,15 +1,23 @@
 //! Implementation for a stored rule
 
-use std::hash::Hash;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::hash::Hash;
 
-use crate::affix::{ParsedCfg, ParsedRuleGroup, RuleType};
-use crate::helpers::ReWrapper;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::Flag;
+use crate::affix::{Condition, ParsedCfg, ParsedRuleGroup, RuleType};
+use crate::error::ParseError;
 use crate::morph::MorphInfo;
 
 /// A single rule group
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct AfxRule {
+    /// The rule group's flag as written in the affix file
+    ident: Box<str>,
     kind: RuleType,
     can_combine: bool,
     patterns: Vec<AfxRulePattern>,
@@ -27,6 +35,7 @@ impl AfxRule {
         _condition: Option<&str>,
     ) -> Self {
         let mut ret = Self {
+            ident: "".into(),
             kind,
             can_combine,
             patterns: affixes
@@ -45,8 +54,9 @@ impl AfxRule {
     /// NOTE: returns a vec reference and `Self`'s morph vec will be empty!
     /// Needs construction wherever the Arc target is
     // PERF: bench with & without vec reference instead of output
-    pub fn from_parsed_group(_cfg: &ParsedCfg, group: &ParsedRuleGroup) -> Self {
+    pub fn from_parsed_group(cfg: &ParsedCfg, group: &ParsedRuleGroup) -> Result<Self, ParseError> {
         let mut ret = Self {
+            ident: group.flag.as_str().into(),
             kind: group.kind,
             can_combine: group.can_combine,
             patterns: Vec::with_capacity(group.rules.len()),
@@ -54,16 +64,44 @@ impl AfxRule {
 
         for rule in &group.rules {
             let morph_info: Vec<Arc<MorphInfo>> = rule.morph_info.clone();
+            let cont_flags = match &rule.cont_flags {
+                Some(flags) => cfg
+                    .flag_type()
+                    .parse_str(flags)
+                    .map_err(|e| ParseError::new_nospan(e, flags))?,
+                None => Vec::new(),
+            };
+            let compound_only = cfg
+                .compound_config()
+                .only_flag()
+                .map_or(false, |flag| cont_flags.contains(&flag));
+            let needs_affix = cfg
+                .incomplete_affix_flags()
+                .any(|flag| cont_flags.contains(&flag));
 
             ret.patterns.push(AfxRulePattern {
                 affix: rule.affix.as_str().into(),
                 condition: rule.condition.clone(),
                 strip: rule.strip.as_ref().map(Arc::clone),
                 morph_info,
+                cont_flags: cont_flags.into(),
+                needs_affix,
+                full_strip: cfg.full_strip(),
+                graphemes: cfg.graphemes(),
+                compound_only,
             });
         }
 
-        ret
+        Ok(ret)
+    }
+
+    /// The flag that identifies this rule group, as written in the affix file
+    pub fn ident(&self) -> &str {
+        &self.ident
+    }
+
+    pub fn kind(&self) -> RuleType {
+        self.kind
     }
 
     pub fn is_pfx(&self) -> bool {
@@ -79,7 +117,8 @@ impl AfxRule {
     }
 
     /// Apply this rules patterns. Returns an iterator over the index of the
-    /// pattern and the resulting string
+    /// pattern and the resulting string. Results may need another affix to be
+    /// a word, see [`AfxRulePattern::needs_affix`].
     pub fn apply_patterns<'a>(
         &'a self,
         stem: &'a str,
@@ -93,6 +132,25 @@ impl AfxRule {
     pub(crate) fn patterns(&self) -> &[AfxRulePattern] {
         &self.patterns
     }
+
+    /// Estimate the heap memory used by this rule, not counting shared morph
+    /// info
+    pub(crate) fn heap_size(&self) -> usize {
+        let patterns: usize = self
+            .patterns
+            .iter()
+            .map(|pat| {
+                pat.affix.len()
+                    + pat.strip.as_ref().map_or(0, |s| s.len())
+                    + pat.morph_info.capacity() * core::mem::size_of::<Arc<MorphInfo>>()
+                    + core::mem::size_of_val(pat.cont_flags.as_ref())
+                    + pat.condition.as_ref().map_or(0, Condition::heap_size)
+            })
+            .sum();
+        self.ident.len()
+            + self.patterns.capacity() * core::mem::size_of::<AfxRulePattern>()
+            + patterns
+    }
 }
 
 /// A single affix rule application
@@ -100,11 +158,23 @@ impl AfxRule {
 pub struct AfxRulePattern {
     affix: Box<str>,
     /// Condition to be met to apply this rule.
-    condition: Option<ReWrapper>,
+    condition: Option<Condition>,
     /// Characters to strip
     strip: Option<Arc<str>>,
     /// Associated morph info
     morph_info: Vec<Arc<MorphInfo>>,
+    /// Flags of the affixes that may be applied after this one
+    cont_flags: Box<[Flag]>,
+    /// Set if the result needs another affix to be a word, i.e. the
+    /// continuation flags include `NEEDAFFIX` or `CIRCUMFIX`
+    needs_affix: bool,
+    /// Whether the whole stem may be stripped, `FULLSTRIP`
+    full_strip: bool,
+    /// Whether stripping may only remove whole grapheme clusters
+    graphemes: bool,
+    /// Set if the result may only be used inside a compound, i.e. the
+    /// continuation flags include `ONLYINCOMPOUND`
+    compound_only: bool,
 }
 
 impl AfxRulePattern {
@@ -116,13 +186,22 @@ impl AfxRulePattern {
             condition: None,
             strip: strip.map(Into::into),
             morph_info: Vec::new(),
+            cont_flags: Box::new([]),
+            needs_affix: false,
+            full_strip: false,
+            graphemes: false,
+            compound_only: false,
         }
     }
 
     /// Helper for testing, sets the condition based on a kind
     #[cfg(test)]
-    pub fn set_pattern(&mut self, condition: &str, kind: RuleType) -> Result<(), regex::Error> {
-        self.condition = crate::helpers::compile_re_pattern(condition, kind)?;
+    pub fn set_pattern(
+        &mut self,
+        condition: &str,
+        kind: RuleType,
+    ) -> Result<(), crate::error::ParseErrorKind> {
+        self.condition = Condition::new(condition, kind)?;
         Ok(())
     }
 
@@ -130,7 +209,7 @@ impl AfxRulePattern {
     #[allow(clippy::option_if_let_else)]
     pub fn check_condition(&self, s: &str) -> bool {
         match &self.condition {
-            Some(re) => re.is_match(s),
+            Some(cond) => cond.matches(s),
             None => true,
         }
     }
@@ -139,39 +218,106 @@ impl AfxRulePattern {
         &self.morph_info
     }
 
+    /// The text that this pattern adds to a word
+    pub(crate) fn affix(&self) -> &str {
+        &self.affix
+    }
+
+    /// The text that this pattern removes from a word before adding its affix
+    pub(crate) fn strip(&self) -> Option<&str> {
+        self.strip.as_deref().filter(|strip| !strip.is_empty())
+    }
+
+    /// Flags of the affixes that may be applied after this one
+    pub(crate) fn cont_flags(&self) -> &[Flag] {
+        &self.cont_flags
+    }
+
+    /// Whether the result of this pattern may only be used inside a compound
+    pub(crate) fn compound_only(&self) -> bool {
+        self.compound_only
+    }
+
+    /// Whether the result of this pattern needs another affix to be a word
+    pub(crate) fn needs_affix(&self) -> bool {
+        self.needs_affix
+    }
+
+    /// Whether this is a zero affix for `s`, which strips and adds nothing
+    /// (written as `0 0`), so the word it forms is `s` itself
+    pub(crate) fn is_zero_affix(&self, s: &str) -> bool {
+        self.strip().is_none()
+            && matches!(self.affix.as_ref(), "" | "0")
+            && !self.needs_affix
+            && self.check_condition(s)
+    }
+
     // Verify the match condition and apply this rule
-    #[allow(clippy::option_if_let_else)]
     fn apply_pattern(&self, s: &str, kind: RuleType) -> Option<String> {
         // No return if condition doesn't match
         if !self.check_condition(s) {
             return None;
         }
 
+        // Remove stripping characters if they exist
+        let rest = match (&self.strip, kind) {
+            (Some(sc), RuleType::Prefix) => s.strip_prefix(sc.as_ref()).unwrap_or(s),
+            (Some(sc), RuleType::Suffix) => s.strip_suffix(sc.as_ref()).unwrap_or(s),
+            (None, _) => s,
+        };
+
+        // Don't separate a letter from its combining marks
+        if self.graphemes && rest.len() < s.len() {
+            let cut = match kind {
+                RuleType::Prefix => s.len() - rest.len(),
+                RuleType::Suffix => rest.len(),
+            };
+            if cut < s.len() && !s.grapheme_indices(true).any(|(idx, _)| idx == cut) {
+                return None;
+            }
+        }
+
+        // Stripping the entire stem is only allowed with `FULLSTRIP`, and the
+        // result may never be empty
+        if rest.is_empty() && (!self.full_strip || self.affix.is_empty()) {
+            return None;
+        }
+
+        let mut working = String::with_capacity(rest.len() + self.affix.len());
         match kind {
             RuleType::Prefix => {
-                // If stripping chars exist, strip them from the prefix
-                let mut working: String = self.affix.as_ref().into();
-
-                if let Some(sc) = &self.strip {
-                    working.push_str(s.strip_prefix(sc.as_ref()).unwrap_or(s));
-                } else {
-                    working.push_str(s);
-                }
-                working.shrink_to_fit();
-                Some(working)
+                working.push_str(&self.affix);
+                working.push_str(rest);
             }
             RuleType::Suffix => {
-                // Same logic as above
-                let mut working = if let Some(sc) = &self.strip {
-                    s.strip_suffix(sc.as_ref()).unwrap_or(s).to_owned()
-                } else {
-                    s.to_owned()
-                };
+                working.push_str(rest);
                 working.push_str(&self.affix);
-                working.shrink_to_fit();
-                Some(working)
             }
         }
+        Some(working)
+    }
+
+    /// Find the words that this pattern turns into `word`, the reverse of
+    /// `apply_pattern`. There are at most two: the stripped characters are only
+    /// removed if the word ends (or starts) with them.
+    pub(crate) fn unapply<'a>(
+        &'a self,
+        word: &'a str,
+        kind: RuleType,
+    ) -> impl Iterator<Item = String> + 'a {
+        let rest = match kind {
+            RuleType::Prefix => word.strip_prefix(self.affix.as_ref()),
+            RuleType::Suffix => word.strip_suffix(self.affix.as_ref()),
+        };
+        let restored = rest.zip(self.strip()).map(|(rest, strip)| match kind {
+            RuleType::Prefix => [strip, rest].concat(),
+            RuleType::Suffix => [rest, strip].concat(),
+        });
+
+        [restored, rest.map(String::from)]
+            .into_iter()
+            .flatten()
+            .filter(move |base| self.apply_pattern(base, kind).as_deref() == Some(word))
     }
 }
 
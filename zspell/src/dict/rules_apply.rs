@@ -1,33 +1,141 @@
 //! Tools to forward generate a wordlist. That is, take an initial wordlist and a
 //! group of rules, and expand the wordlist by applying those rules.
 
-use std::sync::Arc;
-
-use unicode_segmentation::UnicodeSegmentation;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 
 use super::rule::AfxRule;
-use super::WordList;
+use super::{Flag, FlagValue, WordList};
+use crate::affix::RuleType;
 use crate::dict::meta::{Meta, Source};
 
-/// A rule that may be combined with another
-type PossibleCombination<'a> = (String, &'a Arc<AfxRule>, usize);
+/// A rule and the index of the pattern that was applied
+pub(super) type RulePattern<'a> = (&'a Arc<AfxRule>, usize);
+
+/// A prefixed word that may be combined with a suffix, along with the prefix that
+/// created it and the outer prefix added with `COMPLEXPREFIXES`, if any
+type PossibleCombination<'a> = (String, RulePattern<'a>, Option<RulePattern<'a>>);
+
+/// Affixes that may be applied on top of an affix of the same kind, as named by
+/// its continuation flags. This is prefixes with `COMPLEXPREFIXES` and suffixes
+/// otherwise.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Twofold<'a> {
+    /// All affix flags, used to look up continuation flags
+    pub(super) affix_flags: &'a BTreeMap<Flag, FlagValue>,
+    /// The kind of affix that may be applied twice
+    pub(super) kind: RuleType,
+}
+
+impl<'a> Twofold<'a> {
+    /// Rules that may be applied on top of a pattern
+    fn outer_rules(
+        self,
+        (rule, pat_idx): RulePattern<'a>,
+    ) -> impl Iterator<Item = &'a Arc<AfxRule>> {
+        let cont_flags = if rule.kind() == self.kind {
+            rule.patterns()[pat_idx].cont_flags()
+        } else {
+            &[]
+        };
+        cont_flags
+            .iter()
+            .filter_map(move |flag| match self.affix_flags.get(flag) {
+                Some(FlagValue::Rule(outer)) if outer.kind() == self.kind => Some(outer),
+                _ => None,
+            })
+    }
+}
+
+/// The dictionary entry being expanded, and where to store the results
+pub(super) struct Target<'a> {
+    pub(super) stem: &'a Arc<str>,
+    /// Flags of the dictionary entry
+    pub(super) flags: &'a Arc<[Flag]>,
+    /// Metadata from the dictionary entry
+    pub(super) dict_meta: Option<&'a Meta>,
+    pub(super) dest: &'a mut WordList,
+    /// Where to store words with an `ONLYINCOMPOUND` affix. If `None`, they go to
+    /// `dest`.
+    pub(super) compound_only_dest: Option<&'a mut WordList>,
+}
+
+impl Target<'_> {
+    /// Store a word created by applying the rules in `applied` in order, unless it
+    /// still needs another affix. Returns whether the word was stored.
+    fn store(&mut self, applied: &[RulePattern], affixed: &str) -> bool {
+        if !is_complete(applied) {
+            return false;
+        }
+
+        let source = match applied {
+            [(rule, pat_idx)] => Source::new_affix(rule, *pat_idx),
+            _ => Source::AffixCombined(
+                applied
+                    .iter()
+                    .map(|&(rule, pat_idx)| (Arc::clone(rule), pat_idx))
+                    .collect(),
+            ),
+        };
+        let meta = Meta::new(Arc::clone(self.stem), Arc::clone(self.flags), source);
+
+        let compound_only = applied
+            .iter()
+            .any(|(rule, pat_idx)| rule.patterns()[*pat_idx].compound_only());
+        let dest = match &mut self.compound_only_dest {
+            Some(compound_only_dest) if compound_only => compound_only_dest,
+            _ => &mut self.dest,
+        };
+
+        // Add this entry to the wordlist or update an existing one
+        let dict_meta = self.dict_meta;
+        let meta_vec = dest
+            .0
+            .entry_ref(affixed)
+            .or_insert_with(|| Vec::with_capacity(1 + usize::from(dict_meta.is_some())));
+        meta_vec.push(meta);
+
+        if let Some(meta) = dict_meta {
+            meta_vec.push(Meta::clone(meta));
+        }
+        true
+    }
+}
+
+/// An affixed word is a complete word unless its outermost prefix and suffix
+/// both need another affix, i.e. have `NEEDAFFIX` as a continuation flag
+pub(super) fn is_complete(applied: &[RulePattern]) -> bool {
+    let outermost = |kind| applied.iter().rev().find(|(rule, _)| rule.kind() == kind);
+    [RuleType::Prefix, RuleType::Suffix]
+        .into_iter()
+        .filter_map(outermost)
+        .any(|(rule, pat_idx)| !rule.patterns()[*pat_idx].needs_affix())
+}
 
 /// For a given stem, find all prefix and suffix rules that can apply, and store them
-/// to a wordlist.
+/// to the target's wordlist.
+///
+/// If `twofold` is given, a prefix or suffix's continuation flags may add a second
+/// affix of the same kind on top of it.
 ///
 /// Also finds words
 #[allow(clippy::similar_names)] // thinks pfx and sfx are too similar
-pub(super) fn create_affixed_word_map(
-    stem: &Arc<str>,
-    pfx_rules: &[&Arc<AfxRule>],
-    sfx_rules: &[&Arc<AfxRule>],
-    dict_meta: Option<&Meta>,
-    dest: &mut WordList,
+pub(super) fn create_affixed_word_map<'a>(
+    mut target: Target,
+    pfx_rules: &[&'a Arc<AfxRule>],
+    sfx_rules: &[&'a Arc<AfxRule>],
+    twofold: Option<Twofold<'a>>,
 ) -> bool {
     if pfx_rules.is_empty() && sfx_rules.is_empty() {
         return false;
     }
 
+    let stem = target.stem;
+    let twofold_pfx = twofold.filter(|t| t.kind == RuleType::Prefix);
+    let twofold_sfx = twofold.filter(|t| t.kind == RuleType::Suffix);
+
     // Store words with prefixes that can also have suffixes
     let mut pfxd_maybe_sfx: Vec<PossibleCombination> = Vec::new();
     let mut rule_found = false;
@@ -35,25 +143,50 @@ pub(super) fn create_affixed_word_map(
     for &pfx_rule in pfx_rules {
         // Locate matching prefix rules
         for (pat_idx, prefixed) in pfx_rule.apply_patterns(stem) {
-            store_applied_pattern(stem, pfx_rule, pat_idx, &prefixed, dict_meta, dest);
+            let inner = (pfx_rule, pat_idx);
+            for outer_rule in twofold_pfx.into_iter().flat_map(|t| t.outer_rules(inner)) {
+                for (outer_idx, twice) in outer_rule.apply_patterns(&prefixed) {
+                    let outer = (outer_rule, outer_idx);
+                    rule_found |= target.store(&[inner, outer], &twice);
+
+                    if pfx_rule.can_combine() && outer_rule.can_combine() {
+                        pfxd_maybe_sfx.push((twice, inner, Some(outer)));
+                    }
+                }
+            }
 
-            rule_found = true;
+            rule_found |= target.store(&[inner], &prefixed);
 
             // Save rules that can have a prefix and a suffix
             if pfx_rule.can_combine() {
-                pfxd_maybe_sfx.push((prefixed, pfx_rule, pat_idx));
+                pfxd_maybe_sfx.push((prefixed, inner, None));
             }
         }
     }
 
+    // Rules applied to the current word, reused to avoid allocations
+    let mut applied = Vec::with_capacity(3);
+
     for &sfx_rule in sfx_rules {
-        // Locate matching prefix rules
+        // Locate matching suffix rules
         for (pat_idx, suffixed) in sfx_rule.apply_patterns(stem) {
-            store_applied_pattern(stem, sfx_rule, pat_idx, &suffixed, dict_meta, dest);
-            rule_found = true;
+            applied.clear();
+            applied.push((sfx_rule, pat_idx));
+            rule_found |= apply_suffixed(&mut target, twofold_sfx, &mut applied, &suffixed);
+        }
 
-            if sfx_rule.can_combine() {
-                apply_combo_words(stem, &pfxd_maybe_sfx, sfx_rule, dict_meta, dest);
+        // This checks every pattern against the prefixed words, so it only needs to
+        // run once per rule
+        if !sfx_rule.can_combine() {
+            continue;
+        }
+        for (prefixed, pfx, outer_pfx) in &pfxd_maybe_sfx {
+            for (pat_idx, new_word) in sfx_rule.apply_patterns(prefixed) {
+                applied.clear();
+                applied.push(*pfx);
+                applied.extend(*outer_pfx);
+                applied.push((sfx_rule, pat_idx));
+                rule_found |= apply_suffixed(&mut target, twofold_sfx, &mut applied, &new_word);
             }
         }
     }
@@ -61,57 +194,33 @@ pub(super) fn create_affixed_word_map(
     rule_found
 }
 
-/// Create meta and store an applied pattern to a wordlist
-fn store_applied_pattern(
-    stem_arc: &Arc<str>,      // stem word
-    rule: &Arc<AfxRule>,      // rule that was applied
-    pat_idx: usize,           // index of the relevant pattern within the rule
-    affixed: &str,            // affixed (created) word
-    dict_meta: Option<&Meta>, // metadata from the dictionary entry
-    dest: &mut WordList,      // store the result here
-) {
-    // Create metadata for this application
-    let meta = Meta::new(Arc::clone(stem_arc), Source::new_affix(rule, pat_idx));
-
-    // Add this entry to the wordlist or update an existing one
-    let meta_vec = dest.0.entry_ref(affixed).or_default();
-    meta_vec.push(meta);
-
-    if let Some(meta) = dict_meta {
-        meta_vec.push(Meta::clone(meta));
-    }
-}
-
-/// Given a list of words that are eligible for combinations, check if a rule applies. If
-/// so, save it to the word list
-#[allow(clippy::similar_names)] // pfx sfx...
-fn apply_combo_words(
-    stem_arc: &Arc<str>,
-    pfxd_maybe_sfx: &[PossibleCombination],
-    rule: &Arc<AfxRule>,
-    dict_meta: Option<&Meta>, // metadata from the dictionary entry
-    dest: &mut WordList,
-) {
-    for (prefixed, pfx_rule, pfx_idx) in pfxd_maybe_sfx {
-        for (sfx_idx, new_word) in rule.apply_patterns(prefixed) {
-            let meta_vec = dest.0.entry_ref(new_word.as_str()).or_insert_with(Vec::new);
-
-            let meta_pfx = Meta::new(stem_arc.clone(), Source::new_affix(pfx_rule, *pfx_idx));
-            let meta_sfx = Meta::new(stem_arc.clone(), Source::new_affix(rule, sfx_idx));
-            meta_vec.push(meta_pfx);
-            meta_vec.push(meta_sfx);
-
-            if let Some(meta) = dict_meta {
-                meta_vec.push(Meta::clone(meta));
-            }
+/// Store a word whose last applied rule is a suffix, then add any outer suffixes
+/// that its continuation flags allow. If the word also has a prefix, the outer
+/// suffix must be able to combine with it.
+fn apply_suffixed<'a>(
+    target: &mut Target,
+    twofold: Option<Twofold<'a>>,
+    applied: &mut Vec<RulePattern<'a>>,
+    suffixed: &str,
+) -> bool {
+    let mut found = target.store(applied, suffixed);
+    let has_prefix = applied.first().map_or(false, |(rule, _)| rule.is_pfx());
+    let Some(&inner) = applied.last() else {
+        return found;
+    };
+
+    for outer_rule in twofold.into_iter().flat_map(|t| t.outer_rules(inner)) {
+        if has_prefix && !outer_rule.can_combine() {
+            continue;
+        }
+        for (outer_idx, twice) in outer_rule.apply_patterns(suffixed) {
+            applied.push((outer_rule, outer_idx));
+            found |= target.store(applied, &twice);
+            applied.pop();
         }
     }
-}
 
-/// Segment words by unicode boundaries.
-pub fn word_splitter(s: &str) -> impl Iterator<Item = (usize, &str)> {
-    s.split_word_bound_indices()
-        .filter(|split| split.1.chars().all(|c| c.is_alphanumeric() || c == '-'))
+    found
 }
 
 #[cfg(test)]
@@ -147,6 +256,14 @@ mod tests {
             None,
             None,
         ));
+        let rul4 = Arc::new(AfxRule::new(
+            RuleType::Suffix,
+            &["cc", "dd"],
+            &[".", "."],
+            true,
+            None,
+            None,
+        ));
 
         let conditions = [
             ("xxx", &[&rul1][..], &[][..], &["aaxxx"][..]),
@@ -180,12 +297,26 @@ mod tests {
                 &[&rul3][..],
                 &["aayyy", "bbyyy", "yyydd", "bbyyydd"][..],
             ),
+            (
+                "xxx",
+                &[&rul2][..],
+                &[&rul4][..],
+                &["bbxxx", "xxxcc", "xxxdd", "bbxxxcc", "bbxxxdd"][..],
+            ),
         ];
 
         for (i, (word, pfxs, sfxs, expected_slice)) in conditions.iter().enumerate() {
             let mut dest = WordList::new();
             let stem_rc = Arc::from(*word);
-            create_affixed_word_map(&stem_rc, pfxs, sfxs, None, &mut dest);
+            let flags = Arc::from([]);
+            let target = Target {
+                stem: &stem_rc,
+                flags: &flags,
+                dict_meta: None,
+                dest: &mut dest,
+                compound_only_dest: None,
+            };
+            create_affixed_word_map(target, pfxs, sfxs, None);
 
             let tmp: Vec<(Box<str>, _)> = dest.0.into_iter().collect();
             let mut result: Vec<_> = tmp.iter().map(|(s, _)| s.as_ref()).collect();
@@ -197,24 +328,19 @@ mod tests {
                 result, expected,
                 "testing index {i} with prefixes: {pfxs:#?}\nand suffixes: {sfxs:#?}"
             );
+            // Each word should be created exactly once
+            assert!(
+                tmp.iter().all(|(_, metas)| metas.len() == 1),
+                "duplicate metas at index {i}: {tmp:#?}"
+            );
         }
     }
-
-    #[test]
-    fn test_word_splitter() {
-        let s = "the quick brown.     Fox Jum-ped --\t where? 'over' (the) very--lazy dog";
-        let _: Vec<_> = dbg!(word_splitter(s).collect());
-        let _: Vec<_> = dbg!(s.split_word_bound_indices().collect());
-        // FIXME: do something with these
-    }
 }
 
 // TODO: evaluate this for hyphenation
 // mod peek_map {
 //     use std::iter::Peekable;
-//     use unicode_segmentation::UnicodeSegmentation;
-
-//     pub struct PeekMap<I: Iterator, F>(Peekable<I>, F);
+//     //     pub struct PeekMap<I: Iterator, F>(Peekable<I>, F);
 
 //     pub fn peek_map<R, I: Iterator, F: FnMut(I::Item, Option<&I::Item>) -> R>(
 //         it: Peekable<I>,
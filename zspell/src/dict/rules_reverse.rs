@@ -1,21 +1,432 @@
-//! Take rules and qpply them to a word, trying to find a match in an
-//! existing wordlist.
-#![allow(unused)]
+//! Take rules and apply them in reverse to a word, trying to find its root in
+//! the dictionary. This is used instead of expanding every root up front when a
+//! dictionary is built with [`DictBuilder::lazy_affixes`](crate::DictBuilder::lazy_affixes).
+//!
+//! Each affix that is stripped is checked by applying it forward again, so a word
+//! is derived exactly when the eager expansion would have created it.
 
-use crate::affix::CompoundConfig;
-use crate::Dictionary;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter;
+use core::mem::size_of;
 
-/// Try to create a word
-fn entrypoint(dict: &Dictionary, word: &str) -> bool {
-    todo!()
+use super::fold::FoldedIndex;
+use super::meta::Meta;
+use super::rule::{AfxRule, AfxRulePattern};
+use super::rules_apply::{is_complete, RulePattern};
+use super::{DictData, EntryList, Flag, FlagValue};
+use crate::affix::RuleType;
+use crate::helpers::FxHashMap;
+use crate::morph::MorphInfo;
+
+/// A dictionary entry whose affixed forms are derived when they are looked up
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct LazyRoot {
+    stem: Arc<str>,
+    /// Flags of the dictionary entry
+    flags: Arc<[Flag]>,
+    /// Metadata from the dictionary entry, `None` if it needs an affix
+    dict_meta: Option<Meta>,
+    /// The wordlist that the entry's affixed forms would be stored in
+    list: EntryList,
+}
+
+impl LazyRoot {
+    pub(super) fn new(
+        stem: Arc<str>,
+        flags: Arc<[Flag]>,
+        dict_meta: Option<Meta>,
+        list: EntryList,
+    ) -> Self {
+        Self {
+            stem,
+            flags,
+            dict_meta,
+            list,
+        }
+    }
+}
+
+/// One pattern of an affix rule, along with the flag that names the rule
+#[derive(Clone, Debug, PartialEq)]
+struct AffixPattern {
+    flag: Flag,
+    rule: Arc<AfxRule>,
+    pat_idx: usize,
+}
+
+impl AffixPattern {
+    fn pattern(&self) -> &AfxRulePattern {
+        &self.rule.patterns()[self.pat_idx]
+    }
+}
+
+/// Roots and affix patterns, indexed so that affixes can be stripped from words
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct LazyAffixes {
+    /// Dictionary entries that have affix rules, by stem
+    roots: FxHashMap<Arc<str>, Vec<LazyRoot>>,
+    /// Prefix patterns by the text they add
+    prefixes: FxHashMap<Box<str>, Vec<AffixPattern>>,
+    /// Suffix patterns by the text they add
+    suffixes: FxHashMap<Box<str>, Vec<AffixPattern>>,
+    /// The kind of affix that may be applied twice, see `Twofold`
+    twofold: RuleType,
+}
+
+/// A way that a word is created from a dictionary entry
+#[derive(Clone, Debug)]
+pub(super) struct Derivation<'dict> {
+    root: &'dict LazyRoot,
+    /// The rules in the order they were applied, like `Source::AffixCombined`
+    applied: Vec<RulePattern<'dict>>,
+    /// The wordlist that the word would be stored in
+    list: EntryList,
+}
+
+impl<'dict> Derivation<'dict> {
+    pub(super) fn root(&self) -> &'dict str {
+        &self.root.stem
+    }
+
+    /// Flags of the dictionary entry of the root
+    pub(super) fn flags(&self) -> &'dict [Flag] {
+        &self.root.flags
+    }
+
+    pub(super) fn list(&self) -> EntryList {
+        self.list
+    }
+}
+
+impl LazyAffixes {
+    /// Index the affix rules of `data`, with no roots yet
+    pub(super) fn new(data: &DictData) -> Self {
+        let mut prefixes: FxHashMap<Box<str>, Vec<AffixPattern>> = FxHashMap::default();
+        let mut suffixes: FxHashMap<Box<str>, Vec<AffixPattern>> = FxHashMap::default();
+        for (&flag, value) in &data.affix_flags {
+            let FlagValue::Rule(rule) = value else {
+                continue;
+            };
+            let index = if rule.is_pfx() {
+                &mut prefixes
+            } else {
+                &mut suffixes
+            };
+            for (pat_idx, pat) in rule.patterns().iter().enumerate() {
+                index
+                    .entry_ref(pat.affix())
+                    .or_default()
+                    .push(AffixPattern {
+                        flag,
+                        rule: Arc::clone(rule),
+                        pat_idx,
+                    });
+            }
+        }
+
+        Self {
+            roots: FxHashMap::default(),
+            prefixes,
+            suffixes,
+            twofold: data.twofold_kind(),
+        }
+    }
+
+    /// Make room for `additional` more distinct roots
+    pub(super) fn reserve(&mut self, additional: usize) {
+        self.roots.reserve(additional);
+    }
+
+    pub(super) fn add_root(&mut self, root: LazyRoot) {
+        self.roots
+            .entry(Arc::clone(&root.stem))
+            .or_insert_with(|| Vec::with_capacity(1))
+            .push(root);
+    }
+
+    /// The stems of every root
+    pub(super) fn stems(&self) -> impl Iterator<Item = &str> {
+        self.roots.keys().map(AsRef::as_ref)
+    }
+
+    pub(super) fn shrink_to_fit(&mut self) {
+        for roots in self.roots.values_mut() {
+            roots.shrink_to_fit();
+        }
+        self.roots.shrink_to_fit();
+    }
+
+    /// Estimate the heap memory used by the index, not counting data shared with
+    /// the wordlists and rules
+    pub(super) fn heap_size(&self) -> usize {
+        // Each bucket has one control byte
+        let roots = self.roots.capacity() * (size_of::<(Arc<str>, Vec<LazyRoot>)>() + 1)
+            + self
+                .roots
+                .values()
+                .map(|roots| roots.capacity() * size_of::<LazyRoot>())
+                .sum::<usize>();
+        let patterns: usize = [&self.prefixes, &self.suffixes]
+            .iter()
+            .map(|index| {
+                index.capacity() * (size_of::<(Box<str>, Vec<AffixPattern>)>() + 1)
+                    + index
+                        .iter()
+                        .map(|(affix, pats)| {
+                            affix.len() + pats.capacity() * size_of::<AffixPattern>()
+                        })
+                        .sum::<usize>()
+            })
+            .sum();
+        roots + patterns
+    }
+
+    /// Find every way that `word` is created from a root. Roots are also found
+    /// by their other spellings in `folded`, if given.
+    pub(super) fn derive<'a>(
+        &'a self,
+        word: &str,
+        folded: Option<&'a FoldedIndex>,
+    ) -> Vec<Derivation<'a>> {
+        let mut ret = Vec::new();
+
+        // Suffixes are applied after prefixes, so they are stripped first. Chains
+        // list their suffixes from the inside out.
+        let mut sfx_chains: Vec<(String, Vec<&AffixPattern>)> = vec![(word.into(), Vec::new())];
+        for (outer, base) in self.strip(word, RuleType::Suffix) {
+            if self.twofold == RuleType::Suffix {
+                for (inner, inner_base) in self.strip(&base, RuleType::Suffix) {
+                    if inner.pattern().cont_flags().contains(&outer.flag) {
+                        sfx_chains.push((inner_base, vec![inner, outer]));
+                    }
+                }
+            }
+            sfx_chains.push((base, vec![outer]));
+        }
+
+        for (base, sfx) in &sfx_chains {
+            if !sfx.is_empty() {
+                self.push_derivations(base, &[], sfx, folded, &mut ret);
+            }
+
+            // A word with both kinds of affix needs every rule to allow combining
+            let sfx_combine = sfx.iter().all(|pat| pat.rule.can_combine());
+            let combine =
+                |pat: &AffixPattern| sfx.is_empty() || (sfx_combine && pat.rule.can_combine());
+            for (outer, pfx_base) in self.strip(base, RuleType::Prefix) {
+                if !combine(outer) {
+                    continue;
+                }
+                self.push_derivations(&pfx_base, &[outer], sfx, folded, &mut ret);
+
+                if self.twofold != RuleType::Prefix {
+                    continue;
+                }
+                for (inner, inner_base) in self.strip(&pfx_base, RuleType::Prefix) {
+                    if combine(inner) && inner.pattern().cont_flags().contains(&outer.flag) {
+                        self.push_derivations(&inner_base, &[inner, outer], sfx, folded, &mut ret);
+                    }
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// Patterns of rules of `kind` that create `word`, along with the word they
+    /// were applied to
+    fn strip<'a>(&'a self, word: &str, kind: RuleType) -> Vec<(&'a AffixPattern, String)> {
+        let index = match kind {
+            RuleType::Prefix => &self.prefixes,
+            RuleType::Suffix => &self.suffixes,
+        };
+        let boundaries = word.char_indices().map(|(idx, _)| idx);
+
+        boundaries
+            .chain(iter::once(word.len()))
+            .filter_map(|idx| match kind {
+                RuleType::Prefix => index.get(&word[..idx]),
+                RuleType::Suffix => index.get(&word[idx..]),
+            })
+            .flatten()
+            .flat_map(|pat| {
+                pat.pattern()
+                    .unapply(word, kind)
+                    .map(move |base| (pat, base))
+            })
+            .collect()
+    }
+
+    /// Add a derivation for each entry of `stem` that has the innermost prefix
+    /// and suffix
+    fn push_derivations<'a>(
+        &'a self,
+        stem: &str,
+        pfx: &[&'a AffixPattern],
+        sfx: &[&'a AffixPattern],
+        folded: Option<&'a FoldedIndex>,
+        dest: &mut Vec<Derivation<'a>>,
+    ) {
+        let other_spellings = folded.into_iter().flat_map(|folded| folded.spellings(stem));
+        let mut roots = self
+            .roots
+            .get(stem)
+            .into_iter()
+            .chain(other_spellings.filter_map(|spelling| self.roots.get(spelling)))
+            .flatten()
+            .peekable();
+        if roots.peek().is_none() {
+            return;
+        }
+        let applied: Vec<RulePattern> = pfx
+            .iter()
+            .chain(sfx)
+            .map(|pat| (&pat.rule, pat.pat_idx))
+            .collect();
+        if !is_complete(&applied) {
+            return;
+        }
+        let compound_only = applied
+            .iter()
+            .any(|(rule, pat_idx)| rule.patterns()[*pat_idx].compound_only());
+
+        let has_rule = |root: &LazyRoot, pat: Option<&&AffixPattern>| {
+            pat.map_or(true, |pat| root.flags.contains(&pat.flag))
+        };
+        for root in roots {
+            if !has_rule(root, pfx.first()) || !has_rule(root, sfx.first()) {
+                continue;
+            }
+            // Forms with an `ONLYINCOMPOUND` affix have their own list unless
+            // forbidden, as in `create_affixed_words`
+            let list = match root.list {
+                EntryList::Words | EntryList::NoSuggest if compound_only => EntryList::CompoundOnly,
+                list => list,
+            };
+            dest.push(Derivation {
+                root,
+                applied: applied.clone(),
+                list,
+            });
+        }
+    }
 }
 
-fn try_strip_pfx() {
-    todo!()
+/// Metadata of a correct word, either stored in a wordlist or describing the
+/// affixes of a derivation. The stored metas of derived words are those of their
+/// dictionary entries.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum MetaRef<'a, 'dict> {
+    Stored(&'dict Meta),
+    Derived(&'a Derivation<'dict>),
 }
 
-fn try_strip_sfx() {}
+impl<'a, 'dict: 'a> MetaRef<'a, 'dict> {
+    /// The metas of a word's derivations, in the same order as they would be
+    /// stored in the wordlist
+    pub(super) fn from_derivations(
+        derivations: &'a [Derivation<'dict>],
+    ) -> impl Iterator<Item = Self> + 'a {
+        derivations.iter().flat_map(|derivation| {
+            let root: &'dict LazyRoot = derivation.root;
+            iter::once(Self::Derived(derivation)).chain(root.dict_meta.iter().map(Self::Stored))
+        })
+    }
 
-/// Try splitting the word at each position and testing the parts according to
-/// compound rules
-fn compound_thing(cfg: &CompoundConfig) {}
+    /// See [`Meta::stem`]
+    pub(super) fn stem(self) -> &'dict str {
+        match self {
+            Self::Stored(meta) => meta.stem(),
+            Self::Derived(derivation) => derivation.root(),
+        }
+    }
+
+    /// See [`Meta::base_stem`]
+    pub(super) fn base_stem(self) -> &'dict str {
+        match self {
+            Self::Stored(meta) => meta.base_stem(),
+            Self::Derived(derivation) => derivation.root(),
+        }
+    }
+
+    /// See [`Source::affixes`](super::meta::Source::affixes)
+    pub(super) fn affixes(self) -> impl Iterator<Item = (&'dict AfxRule, usize)> + 'a {
+        let (stored, derived) = match self {
+            Self::Stored(meta) => (Some(meta.source().affixes()), &[][..]),
+            Self::Derived(derivation) => (None, derivation.applied.as_slice()),
+        };
+        stored.into_iter().flatten().chain(
+            derived
+                .iter()
+                .map(|&(rule, pat_idx)| (rule.as_ref(), pat_idx)),
+        )
+    }
+
+    /// See [`Source::morphs`](super::meta::Source::morphs)
+    pub(super) fn morphs(self) -> impl Iterator<Item = &'dict MorphInfo> + 'a {
+        let (stored, derived) = match self {
+            Self::Stored(meta) => (Some(meta.source().morphs()), &[][..]),
+            Self::Derived(derivation) => (None, derivation.applied.as_slice()),
+        };
+        let derived = derived
+            .iter()
+            .flat_map(|&(rule, pat_idx)| rule.patterns()[pat_idx].morph_info())
+            .map(AsRef::as_ref);
+        stored.into_iter().flatten().chain(derived)
+    }
+}
+
+impl DictData {
+    /// The kind of affix that may be applied twice. Continuation flags allow two
+    /// prefixes with `COMPLEXPREFIXES`, otherwise two suffixes.
+    pub(super) fn twofold_kind(&self) -> RuleType {
+        if self.parsed_config.complex_prefixes() {
+            RuleType::Prefix
+        } else {
+            RuleType::Suffix
+        }
+    }
+
+    /// Find the ways `word`, or `lower` if `word` has none, is created from a
+    /// root. This is always empty unless affixes are expanded lazily.
+    pub(super) fn derive_either<'w>(
+        &self,
+        word: &'w str,
+        lower: Option<&'w str>,
+    ) -> (&'w str, Vec<Derivation<'_>>) {
+        let Some(lazy) = &self.lazy else {
+            return (word, Vec::new());
+        };
+        let folded = self.folded.as_ref();
+        let derivations = lazy.derive(word, folded);
+        match lower {
+            Some(lower) if derivations.is_empty() => (lower, lazy.derive(lower, folded)),
+            _ => (word, derivations),
+        }
+    }
+}
+
+/// Whether any derivation creates a forbidden word
+pub(super) fn any_forbidden(derivations: &[Derivation]) -> bool {
+    derivations
+        .iter()
+        .any(|derivation| derivation.list == EntryList::Forbidden)
+}
+
+/// The derivations of a word that is accepted, i.e. not forbidden. Like the
+/// wordlist lookups, this is those in the main list if there are any, otherwise
+/// those in the nosuggest list.
+pub(super) fn accepted(mut derivations: Vec<Derivation>) -> Vec<Derivation> {
+    if any_forbidden(&derivations) {
+        derivations.clear();
+    } else if derivations.iter().any(|d| d.list == EntryList::Words) {
+        derivations.retain(|d| d.list == EntryList::Words);
+    } else {
+        derivations.retain(|d| d.list == EntryList::NoSuggest);
+    }
+    derivations
+}
@@ -0,0 +1,151 @@
+//! Counts and memory estimates for a dictionary, see [`Dictionary::stats`]
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::mem::{size_of, size_of_val};
+
+use super::fold::FoldedIndex;
+use super::meta::{Meta, Source};
+use super::rules_reverse::LazyAffixes;
+use super::{DictData, Dictionary, Flag, FlagValue, WordList};
+use crate::affix::RuleType;
+use crate::ParsedCfg;
+
+/// Size of the strong and weak counts stored in front of the data of an `Arc`
+const ARC_HEADER: usize = 2 * size_of::<usize>();
+
+/// Counts of the words and rules in a [`Dictionary`], along with an estimate of
+/// its memory use. See [`Dictionary::stats`].
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DictStats {
+    /// Number of distinct stems that words were created from, including those
+    /// from the personal dictionary
+    pub roots: usize,
+    /// Number of accepted words that may be suggested, including all affixed
+    /// forms
+    pub words: usize,
+    /// Number of accepted words that are never suggested, `NOSUGGEST`
+    pub nosuggest: usize,
+    /// Number of forbidden words
+    pub forbidden: usize,
+    /// Number of words that are only accepted inside compounds,
+    /// `ONLYINCOMPOUND`
+    pub compound_only: usize,
+    /// Number of prefix rule groups, `PFX`
+    pub prefix_groups: usize,
+    /// Number of suffix rule groups, `SFX`
+    pub suffix_groups: usize,
+    /// Estimated heap memory used by the dictionary, in bytes. Data shared
+    /// among clones of a `Dictionary` is counted once. This is a rough figure
+    /// meant for comparing dictionaries, not an exact measurement.
+    pub heap_bytes: usize,
+}
+
+impl Dictionary {
+    /// Count the words and rules in this dictionary and estimate its memory use,
+    /// which can be used to monitor or compare dictionaries
+    ///
+    /// ```
+    /// let aff = "SFX S Y 1\nSFX S 0 s .\n";
+    /// let dict = zspell::builder()
+    ///     .config_str(aff)
+    ///     .dict_str("2\napple/S\npear")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let stats = dict.stats();
+    /// assert_eq!(stats.roots, 2);
+    /// assert_eq!(stats.words, 3);
+    /// assert_eq!(stats.suffix_groups, 1);
+    /// assert!(stats.heap_bytes > 0);
+    /// ```
+    #[inline]
+    pub fn stats(&self) -> DictStats {
+        let data = &self.data;
+        let count_groups = |kind: RuleType| {
+            data.affix_flags
+                .values()
+                .filter(|value| matches!(value, FlagValue::Rule(rule) if rule.kind() == kind))
+                .count()
+        };
+
+        DictStats {
+            roots: data.stems.len(),
+            words: data.wordlist.0.len(),
+            nosuggest: data.wordlist_nosuggest.0.len(),
+            forbidden: data.wordlist_forbidden.0.len(),
+            compound_only: data.wordlist_compound_only.0.len(),
+            prefix_groups: count_groups(RuleType::Prefix),
+            suffix_groups: count_groups(RuleType::Suffix),
+            heap_bytes: data.heap_size(),
+        }
+    }
+}
+
+impl DictData {
+    /// Estimate the heap memory used by the dictionary data
+    pub(super) fn heap_size(&self) -> usize {
+        let wordlists = [
+            &self.wordlist,
+            &self.wordlist_nosuggest,
+            &self.wordlist_forbidden,
+            &self.wordlist_compound_only,
+        ]
+        .iter()
+        .map(|wordlist| wordlist.heap_size())
+        .sum::<usize>();
+
+        let interned = self.stems.heap_size()
+            + self.morphs.heap_size()
+            + self.morph_sets.heap_size()
+            + self.flag_sets.heap_size();
+
+        let rules: usize = self
+            .affix_flags
+            .values()
+            .map(|value| match value {
+                FlagValue::Rule(rule) => ARC_HEADER + size_of_val(rule.as_ref()) + rule.heap_size(),
+                _ => 0,
+            })
+            .sum();
+        // B-tree nodes are often not full, so allow for some unused space
+        let flags = self.affix_flags.len() * size_of::<(Flag, FlagValue)>() * 3 / 2;
+
+        #[cfg(feature = "unstable-suggestions")]
+        let frequencies = self.frequencies.capacity() * (size_of::<(Box<str>, u64)>() + 1)
+            + self.frequencies.keys().map(|k| k.len()).sum::<usize>();
+        #[cfg(not(feature = "unstable-suggestions"))]
+        let frequencies = 0;
+
+        let lazy = self.lazy.as_ref().map_or(0, LazyAffixes::heap_size);
+        let folded = self.folded.as_ref().map_or(0, FoldedIndex::heap_size);
+
+        wordlists + interned + rules + flags + frequencies + lazy + folded + size_of::<ParsedCfg>()
+    }
+}
+
+impl WordList {
+    /// Estimate the heap memory used by the list, not counting data shared
+    /// with other entries
+    fn heap_size(&self) -> usize {
+        // Each bucket has one control byte
+        let table = self.0.capacity() * (size_of::<(Box<str>, Vec<Meta>)>() + 1);
+        let entries: usize = self
+            .0
+            .iter()
+            .map(|(word, metas)| {
+                let unshared: usize = metas
+                    .iter()
+                    .map(|meta| match meta.source() {
+                        Source::AffixCombined(rules) => ARC_HEADER + size_of_val(rules.as_ref()),
+                        _ => 0,
+                    })
+                    .sum();
+                word.len() + metas.capacity() * size_of::<Meta>() + unshared
+            })
+            .sum();
+        table + entries
+    }
+}
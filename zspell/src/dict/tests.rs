@@ -7,6 +7,7 @@ use pretty_assertions::assert_eq;
 use test_util::workspace_root;
 
 use super::*;
+use crate::affix::RuleType;
 
 #[test]
 fn test_update_personal() {
@@ -22,11 +23,13 @@ fn test_update_personal() {
     ";
 
     let mut d = Dictionary::new(ParsedCfg::default()).unwrap();
-    d.parse_update_personal(personal_str, &[]).unwrap();
-    assert!(d.wordlist.0.contains_key("abcd"));
-    assert!(d.wordlist.0.contains_key("efgh"));
-    assert!(!d.wordlist.0.contains_key("ijkl"));
-    assert!(d.wordlist_forbidden.0.contains_key("ijkl"));
+    d.data_mut()
+        .parse_update_personal(personal_str, &[])
+        .unwrap();
+    assert!(d.data.wordlist.0.contains_key("abcd"));
+    assert!(d.data.wordlist.0.contains_key("efgh"));
+    assert!(!d.data.wordlist.0.contains_key("ijkl"));
+    assert!(d.data.wordlist_forbidden.0.contains_key("ijkl"));
     assert!(d.check("abcd"));
     assert!(d.check("uvwx"));
     assert!(!d.check("ijkl"));
@@ -92,7 +95,7 @@ fn test_morph() {
         .build()
         .unwrap();
 
-    let meta = d.wordlist.0.get("drinkable").unwrap();
+    let meta = d.data.wordlist.0.get("drinkable").unwrap();
     assert_eq!(meta[0].stem(), "drink");
     assert_eq!(meta[1].stem(), "drink");
     assert!(matches!(
@@ -111,10 +114,1460 @@ fn test_morph() {
     assert_eq!(mvec.as_ref(), [po.clone().into()]);
 
     let entry = d.entry("drinkable");
-    let morph = entry.analyze().unwrap().collect::<Vec<_>>();
+    let morph = entry.analyze().unwrap().morphs().collect::<Vec<_>>();
     assert_eq!(morph, [&MorphInfo::DerivSfx("able".into()), &po]);
 
     let stems = entry.stems().unwrap().collect::<Vec<_>>();
     assert_eq!(stems, ["drink"]);
     // assert_eq!(stems, ["drinkable", "drink"]);
+
+    let stem_info = entry.stems_with_affixes().unwrap();
+    assert_eq!(stem_info.len(), 1);
+    assert_eq!(stem_info[0].stem, "drink");
+    assert_eq!(
+        stem_info[0].affixes,
+        [AffixInfo {
+            kind: RuleType::Suffix,
+            flag: "X",
+            pattern: 0,
+            affix: "able",
+        }]
+    );
+}
+
+#[test]
+fn test_normalization() {
+    use crate::{DictBuilder, Normalization};
+
+    // Dictionary stored decomposed, input composed
+    let dict = DictBuilder::new()
+        .config_str("SET UTF-8")
+        .dict_str("2\ncafe\u{301}\n\u{fb01}ne")
+        .normalization(Normalization::Nfkc)
+        .build()
+        .unwrap();
+
+    assert_eq!(dict.normalization(), Normalization::Nfkc);
+    assert!(dict.check_word("caf\u{e9}"));
+    assert!(dict.check_word("fine"));
+    assert!(dict.check("caf\u{e9} cafe\u{301} \u{fb01}ne"));
+
+    // The entry keeps the original word but matches the normalized entry
+    let entry = dict.entry("cafe\u{301}");
+    assert_eq!(entry.word(), "cafe\u{301}");
+    assert!(entry.correct());
+    assert_eq!(entry.stems().unwrap().collect::<Vec<_>>(), ["caf\u{e9}"]);
+
+    let dict = DictBuilder::new()
+        .config_str("SET UTF-8")
+        .dict_str("1\ncaf\u{e9}")
+        .build()
+        .unwrap();
+    assert!(!dict.check_word("cafe\u{301}"));
+}
+
+#[test]
+fn test_clone_shares_data() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Dictionary>();
+
+    let dict = DictBuilder::new()
+        .config_str("")
+        .dict_str("1\nfoo")
+        .build()
+        .unwrap();
+    let cloned = dict.clone();
+    assert!(Arc::ptr_eq(&dict.data, &cloned.data));
+    assert_eq!(dict, cloned);
+    assert!(cloned.check_word("foo"));
+}
+
+#[test]
+fn test_add_remove_word() {
+    let mut dict = DictBuilder::new()
+        .config_str("FORBIDDENWORD !")
+        .dict_str("2\nfoo\nbar/!")
+        .build()
+        .unwrap();
+    let original = dict.clone();
+
+    assert!(!dict.check_word("bar"));
+    assert!(dict.add_word("bar"));
+    assert!(dict.check_word("bar"));
+    assert!(!dict.add_word("bar"));
+
+    assert!(dict.remove_word("foo"));
+    assert!(!dict.check_word("foo"));
+    assert!(!dict.remove_word("foo"));
+
+    // Updates copy the shared data rather than modifying other clones
+    assert!(!Arc::ptr_eq(&dict.data, &original.data));
+    assert!(original.check_word("foo"));
+    assert!(!original.check_word("bar"));
+}
+
+#[test]
+fn test_personal_hunspell_compat() {
+    let aff_str = indoc! {"
+        SFX X Y 1
+        SFX X 0 able .
+    "};
+    let personal_str = indoc! {"
+        en_US
+        sip/drink
+        gulp/notaword
+        *drinkable
+    "};
+
+    let d = DictBuilder::new()
+        .config_str(aff_str)
+        .dict_str("1\ndrink/X")
+        .personal_str(personal_str)
+        .build()
+        .unwrap();
+
+    // The header is not a word
+    assert!(!d.check_word("en_US"));
+    // `sip` gets the affixes of `drink`
+    assert!(d.check("sip sipable drink"));
+    // Unknown roots add the word without affixes
+    assert!(d.check_word("gulp"));
+    assert!(!d.check_word("gulpable"));
+    assert!(!d.check_word("notaword"));
+    // Forbidden words are rejected even if they exist in the dictionary
+    assert!(!d.check_word("drinkable"));
+}
+
+#[test]
+fn test_complex_prefixes() {
+    let aff = indoc! {"
+        PFX A Y 1
+        PFX A 0 al .
+
+        PFX B Y 1
+        PFX B 0 wa/A .
+    "};
+    let dic = "1\nkitab/B";
+
+    // Continuation flags on prefixes are only used with `COMPLEXPREFIXES`
+    let d = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .build()
+        .unwrap();
+    assert!(d.check_word("wakitab"));
+    assert!(!d.check_word("alwakitab"));
+
+    let aff = format!("COMPLEXPREFIXES\n{aff}");
+    let d = DictBuilder::new()
+        .config_str(&aff)
+        .dict_str(dic)
+        .build()
+        .unwrap();
+    assert!(d.check_word("wakitab"));
+    assert!(d.check_word("alwakitab"));
+    assert!(!d.check_word("alkitab"));
+
+    // Prefixes are listed in the order they were applied
+    let stems = d.entry("alwakitab").stems_with_affixes().unwrap();
+    assert_eq!(stems.len(), 1);
+    assert_eq!(stems[0].stem, "kitab");
+    let flags: Vec<_> = stems[0].affixes.iter().map(|afx| afx.flag).collect();
+    assert_eq!(flags, ["B", "A"]);
+}
+
+#[test]
+#[cfg(feature = "unstable-suggestions")]
+fn test_suggest_limit() {
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str("5\ncat\ncar\ncap\ncan\ndog")
+        .suggestion_cache(10)
+        .build()
+        .unwrap();
+
+    let mut full = d.entry("caz").suggest().unwrap();
+    full.sort_unstable();
+    assert_eq!(full, ["can", "cap", "car", "cat"]);
+
+    // Limited results come from the cache if available
+    let limited = d.entry("caz").suggest_limit(2).unwrap();
+    assert_eq!(limited.len(), 2);
+    assert!(limited.iter().all(|s| full.contains(s)));
+
+    // Early-exit searches are not cached
+    assert_eq!(d.entry("cax").suggest_limit(1).unwrap().len(), 1);
+    assert_eq!(d.entry("cax").suggest_limit(0).unwrap(), Vec::<&str>::new());
+    let stats = d.suggestion_cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses), (1, 3));
+}
+
+#[test]
+fn test_cancel() {
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str("4\nthe\ncat\nsat\nmat")
+        .build()
+        .unwrap();
+    let cancel = CancelToken::new();
+
+    let mut entries = d.entries_cancellable("the cat sta on teh mat", &cancel);
+    assert_eq!(entries.next().unwrap().word(), "the");
+    assert_eq!(entries.next().unwrap().word(), "cat");
+    cancel.clone().cancel();
+    assert!(entries.next().is_none());
+
+    assert_eq!(d.check_indices_cancellable("teh", &cancel).count(), 0);
+    assert_eq!(d.check_cancellable("teh", &cancel), None);
+    assert_eq!(d.check_cancellable("teh", &CancelToken::new()), Some(false));
+}
+
+#[test]
+fn test_check_all() {
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str("3\nthe\ncat\nsat")
+        .check_break_parts(true)
+        .build()
+        .unwrap();
+
+    let words = ["the", "cat", "teh", "cat-sat", "", "the"];
+    let expected: Vec<bool> = words.iter().map(|w| d.check_word(w)).collect();
+    assert_eq!(expected, [true, true, false, true, false, true]);
+    assert_eq!(d.check_all(words), expected);
+    assert_eq!(d.check_all_errors(words), [(2, "teh"), (4, "")]);
+    assert!(d.check_all(Vec::new()).is_empty());
+
+    // Large enough to be split across threads where there are several cores
+    let words: Vec<&str> = ["the", "sta", "cat"].repeat(10_000);
+    let correct = d.check_all(words.iter().copied());
+    assert_eq!(correct.len(), words.len());
+    assert!(correct.chunks(3).all(|c| c == [true, false, true]));
+    let errors = d.check_all_errors(words.iter().copied());
+    assert_eq!(errors.len(), 10_000);
+    assert_eq!(errors[9_999], (29_998, "sta"));
+}
+
+#[test]
+fn test_spellcheck_ext() {
+    use crate::SpellcheckExt;
+
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str("3\nthe\ncat\nsat")
+        .build()
+        .unwrap();
+
+    let text = "the cat\nsta on\nteh mat";
+    let checked: Vec<_> = text
+        .lines()
+        .flat_map(str::split_whitespace)
+        .spellcheck(&d)
+        .collect();
+    assert_eq!(
+        checked,
+        [
+            ("the", true),
+            ("cat", true),
+            ("sta", false),
+            ("on", false),
+            ("teh", false),
+            ("mat", false)
+        ]
+    );
+
+    let mut errors = text.split_whitespace().misspellings(&d);
+    assert_eq!(errors.size_hint(), (0, None));
+    assert_eq!(errors.next(), Some("sta"));
+    assert_eq!(errors.collect::<Vec<_>>(), ["on", "teh", "mat"]);
+
+    let words = ["the", "cat"];
+    assert_eq!(words.iter().spellcheck(&d).size_hint(), (2, Some(2)));
+    assert_eq!(words.iter().misspellings(&d).count(), 0);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde() {
+    use serde_json::json;
+
+    use crate::{DictStats, MorphInfo, PartOfSpeech};
+
+    let aff = "SFX S Y 1\nSFX S 0 s .\n";
+    let d = DictBuilder::new()
+        .config_str(aff)
+        .dict_str("1\ncat/S po:noun")
+        .build()
+        .unwrap();
+
+    // Owned types round trip
+    let info = vec![
+        MorphInfo::Part(PartOfSpeech::Noun),
+        MorphInfo::Stem("cat".into()),
+        MorphInfo::Part(PartOfSpeech::Other("x".into())),
+    ];
+    let value = serde_json::to_value(&info).unwrap();
+    assert_eq!(
+        value,
+        json!([{"Part": "Noun"}, {"Stem": "cat"}, {"Part": {"Other": "x"}}])
+    );
+    assert_eq!(
+        serde_json::from_value::<Vec<MorphInfo>>(value).unwrap(),
+        info
+    );
+
+    let stats = d.stats();
+    let value = serde_json::to_value(stats).unwrap();
+    assert_eq!(value["words"], 2);
+    assert_eq!(serde_json::from_value::<DictStats>(value).unwrap(), stats);
+
+    // Results that borrow from the dictionary can be serialized
+    let entry = d.entry("cats");
+    let value = serde_json::to_value(entry.stems_with_affixes().unwrap()).unwrap();
+    assert_eq!(
+        value,
+        json!([{
+            "stem": "cat",
+            "affixes": [{"kind": "Suffix", "flag": "S", "pattern": 0, "affix": "s"}]
+        }])
+    );
+    let value = serde_json::to_value(entry.explain()).unwrap();
+    assert_eq!(value["correct"], true);
+
+    // As can parse errors
+    let err = DictBuilder::new()
+        .config_str("SFX S Y 1\nSFX S 0\n")
+        .dict_str("0")
+        .build()
+        .unwrap_err();
+    let crate::Error::Parse(err) = err else {
+        panic!("expected a parse error, got {err:?}");
+    };
+    let value = serde_json::to_value(&err).unwrap();
+    assert!(value["span"].is_object(), "{value}");
+}
+
+#[test]
+fn test_wordlist_set_ops() {
+    let aff = "SFX S Y 1\nSFX S 0 s .\nNOSUGGEST !\n";
+    let d = DictBuilder::new()
+        .config_str(aff)
+        .dict_str("3\ncat/S\ndog/S\nheck/!")
+        .build()
+        .unwrap();
+    let sorted = |list: &WordList| {
+        let mut words: Vec<&str> = list.iter().collect();
+        words.sort_unstable();
+        words.join(" ")
+    };
+
+    let words = d.wordlist();
+    assert_eq!(sorted(words), "cat cats dog dogs");
+    assert_eq!(sorted(d.wordlist_nosuggest()), "heck");
+    assert!(d.wordlist_forbidden().is_empty());
+    assert!(d.wordlist_compound_only().is_empty());
+    assert!(words.contains("cats"));
+    assert!(!words.contains("Cats"));
+
+    let other: WordList = ["cats", "dogs", "heck", "mice", "mice"]
+        .into_iter()
+        .collect();
+    assert_eq!(other.len(), 4);
+    assert_eq!(sorted(&words.union(&other)), "cat cats dog dogs heck mice");
+    assert_eq!(sorted(&words.intersection(&other)), "cats dogs");
+    assert_eq!(sorted(&words.difference(&other)), "cat dog");
+    assert_eq!(sorted(&other.difference(words)), "heck mice");
+    assert_eq!(
+        words.union(d.wordlist_nosuggest()).to_dic(),
+        "5\ncat\ncats\ndog\ndogs\nheck\n"
+    );
+
+    // Words keep how they were created in the first list
+    let union = words.union(&other);
+    let forms = WordForm::from_metas("cats", &union.inner()["cats"]);
+    assert_eq!(forms[0].root, "cat");
+    assert_eq!(forms[0].affixes[0].flag, "S");
+
+    // An exported list loads back as the same words
+    let exported = DictBuilder::new()
+        .config_str("")
+        .dict_str(&words.to_dic())
+        .build()
+        .unwrap();
+    assert_eq!(sorted(exported.wordlist()), sorted(words));
+    assert_eq!(WordList::from_iter([]).to_dic(), "0\n");
+}
+
+#[test]
+fn test_case_insensitive() {
+    let aff = indoc! {"
+        SFX S Y 1
+        SFX S 0 's .
+    "};
+    let dic = "4\nParis/S\niPhone\nNASA\ncat";
+
+    let default = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .build()
+        .unwrap();
+    assert!(!default.check_word("paris"));
+    assert!(!default.check_word("IPHONE"));
+
+    for lazy in [false, true] {
+        let mut dict = DictBuilder::new()
+            .config_str(aff)
+            .dict_str(dic)
+            .lazy_affixes(lazy)
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        for word in [
+            "paris", "PARIS", "pArIs", "paris's", "PARIS'S", "iphone", "IPHONE",
+        ] {
+            assert!(dict.check_word(word), "{word}, lazy: {lazy}");
+            assert!(dict.entry(word).correct(), "{word}, lazy: {lazy}");
+        }
+        assert!(dict.check("nasa Nasa CAT cAt"));
+        assert!(!dict.check_word("pariss"));
+        assert!(!dict.entry("iphones").correct());
+
+        assert!(dict.add_word("McDonald"));
+        assert!(dict.check_word("MCDONALD"));
+        assert!(dict.remove_word("McDonald"));
+        assert!(!dict.check_word("mcdonald"));
+    }
+}
+
+#[test]
+fn test_unsupported_options() {
+    let aff = indoc! {"
+        TRY esianrtolcdugmphbyfvkwz
+        KEY qwertyuiop|asdfghjkl|zxcvbnm
+        COMPOUNDFLAG C
+        NEEDAFFIX N
+        KEEPCASE K
+        KEEPCASE L
+        OCONV 1
+        OCONV ' ’
+    "};
+    let mut reported = Vec::new();
+    let dict = DictBuilder::new()
+        .config_str(aff)
+        .dict_str("1\nword")
+        .build_with_unsupported(|directive| reported.push(directive))
+        .unwrap();
+    assert_eq!(
+        dict.unsupported_options(),
+        ["TRY", "KEY", "KEEPCASE", "OCONV"]
+    );
+    assert_eq!(reported, dict.unsupported_options());
+
+    let dict = DictBuilder::new()
+        .config_str("COMPOUNDFLAG C\nNEEDAFFIX N")
+        .dict_str("1\nword")
+        .build()
+        .unwrap();
+    assert!(dict.unsupported_options().is_empty());
+}
+
+#[test]
+fn test_validate_dict() {
+    let aff = indoc! {"
+        SFX S Y 1
+        SFX S 0 s .
+    "};
+    let dic = indoc! {"
+        4
+        cat/S
+        # a comment
+
+        dog/S
+        cat/S
+        cat/S po:noun
+        cat
+    "};
+    let builder = DictBuilder::new().config_str(aff).dict_str(dic);
+    assert!(builder.clone().build().unwrap().dict_warnings().is_empty());
+
+    let dict = builder.validate_dict(true).build().unwrap();
+    assert_eq!(
+        dict.dict_warnings(),
+        [
+            DictWarning::CountMismatch {
+                expected: 4,
+                actual: 5,
+                line: 1
+            },
+            DictWarning::DuplicateEntry {
+                word: "cat".to_owned(),
+                line: 6,
+                first_line: 2
+            },
+        ]
+    );
+    assert_eq!(dict.dict_warnings()[0].line(), Some(1));
+
+    let dict = DictBuilder::new()
+        .config_str(aff)
+        .dict_str("cat/S\ndog/S")
+        .validate_dict(true)
+        .build()
+        .unwrap();
+    assert_eq!(dict.dict_warnings(), [DictWarning::MissingCount]);
+}
+
+#[test]
+fn test_build_with_warnings() {
+    let aff = indoc! {"
+        LEMMA_PRESENT L
+        CHECKSHARPS
+        PSEUDOROOT X
+        PSEUDOROOT X
+        SFX S Y 1
+        SFX S 0 s .
+        SFX S 0 es .
+    "};
+    let dic = indoc! {"
+        2
+        cat/S
+        dog/Z
+    "};
+    let builder = DictBuilder::new().config_str(aff).dict_str(dic);
+    assert!(builder.clone().build_with_warnings().is_err());
+
+    let (dict, warnings) = builder
+        .parse_mode(ParseMode::Permissive)
+        .build_with_warnings()
+        .unwrap();
+    assert!(dict.check_word("cats"));
+
+    let BuildWarning::Parse(parse_err) = &warnings[3] else {
+        panic!("expected a parse warning, got {warnings:?}");
+    };
+    assert_eq!(
+        warnings,
+        [
+            BuildWarning::Unsupported("CHECKSHARPS"),
+            BuildWarning::Deprecated {
+                directive: "LEMMA_PRESENT",
+                replacement: None
+            },
+            BuildWarning::Deprecated {
+                directive: "PSEUDOROOT",
+                replacement: Some("NEEDAFFIX")
+            },
+            BuildWarning::Parse(parse_err.clone()),
+            BuildWarning::Dict(DictWarning::UndefinedFlag {
+                word: "dog".to_owned(),
+                flag: "Z".to_owned(),
+                line: 3
+            }),
+        ]
+    );
+    assert_eq!(warnings[3].line(), Some(7));
+    assert_eq!(warnings[4].line(), Some(3));
+    assert_eq!(
+        warnings[2].to_string(),
+        "directive 'PSEUDOROOT' is deprecated, use NEEDAFFIX"
+    );
+}
+
+#[test]
+fn test_undefined_flags() {
+    let aff = indoc! {"
+        SFX S Y 1
+        SFX S 0 s .
+
+        COMPOUNDRULE 1
+        COMPOUNDRULE nm*
+        KEEPCASE K
+    "};
+    let dic = "4\n1/n\nth/m\nParis/KX\ncat/SY";
+
+    // Flags used by compound rules and directives are defined
+    let err = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Build(BuildError::NonmatchingFlag {
+            stem: "Paris".to_owned(),
+            flag: "X".to_owned()
+        })
+    );
+
+    let dict = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .parse_mode(ParseMode::Permissive)
+        .validate_dict(true)
+        .build()
+        .unwrap();
+    assert_eq!(
+        dict.dict_warnings(),
+        [
+            DictWarning::UndefinedFlag {
+                word: "Paris".to_owned(),
+                flag: "X".to_owned(),
+                line: 4
+            },
+            DictWarning::UndefinedFlag {
+                word: "cat".to_owned(),
+                flag: "Y".to_owned(),
+                line: 5
+            },
+        ]
+    );
+    assert!(dict.check("cats"));
+
+    // Flags that are `AF` aliases are not checked
+    let dict = DictBuilder::new()
+        .config_str("AF 1\nAF S\nSFX S Y 1\nSFX S 0 s .\n")
+        .dict_str("1\ncat/1")
+        .build()
+        .unwrap();
+    assert!(dict.dict_warnings().is_empty());
+}
+
+#[test]
+fn test_dict_parse_error_line() {
+    let err = DictBuilder::new()
+        .config_str("FLAG num")
+        .dict_str("3\none/1\n\n# comment\ntwo/x\nthree")
+        .build()
+        .unwrap_err();
+    let Error::Parse(err) = err else {
+        panic!("expected a parse error, got {err:?}");
+    };
+    assert_eq!(err.span().unwrap().start().line(), 5);
+}
+
+#[test]
+fn test_unused_affix_flags() {
+    let aff = indoc! {"
+        PFX U Y 1
+        PFX U 0 un .
+
+        SFX S Y 1
+        SFX S 0 s .
+
+        SFX D Y 1
+        SFX D 0 ed/S .
+
+        SFX G Y 1
+        SFX G 0 ing .
+
+        FORBIDDENWORD F
+    "};
+    let dict = DictBuilder::new()
+        .config_str(aff)
+        .dict_str("2\nlock/UD\nthe/F")
+        .build()
+        .unwrap();
+    // `S` is only used by `D`, and flags that are not rules are never listed
+    assert_eq!(dict.unused_affix_flags(), ["G"]);
+
+    let dict = DictBuilder::new()
+        .config_str(aff)
+        .dict_str("1\nlock/UDG")
+        .build()
+        .unwrap();
+    assert!(dict.unused_affix_flags().is_empty());
+}
+
+#[test]
+#[cfg(feature = "icu")]
+fn test_case_insensitive_folding() {
+    let dict = DictBuilder::new()
+        .config_str("")
+        .dict_str("1\nStraße")
+        .case_insensitive(true)
+        .build()
+        .unwrap();
+    assert!(dict.check("STRASSE strasse STRAẞE"));
+    assert!(!dict.check_word("strase"));
+}
+
+#[test]
+fn test_turkic_casing() {
+    let dic = "3\nılık\nistanbul\nİzmir";
+    let build = |lang: &str| {
+        DictBuilder::new()
+            .config_str(&format!("LANG {lang}"))
+            .dict_str(dic)
+            .case_insensitive(true)
+            .build()
+            .unwrap()
+    };
+
+    // Dotted and dotless `i` are separate letters in both cases
+    let dict = build("tr_TR");
+    assert!(dict.check("ILIK İSTANBUL İstanbul İZMİR izmir"));
+    assert!(!dict.check_word("ISTANBUL"));
+    assert!(!dict.check_word("Istanbul"));
+
+    // Elsewhere `I` is the capital of `i`
+    let dict = build("en_US");
+    assert!(dict.check("ISTANBUL Istanbul"));
+    assert!(!dict.check_word("ILIK"));
+    assert!(!dict.check_word("İSTANBUL"));
+    assert!(!dict.check_word("izmir"));
+}
+
+#[test]
+fn test_grapheme_affixes() {
+    // "á" is written as `a` and a combining accent throughout
+    let aff = indoc! {"
+        SFX S Y 1
+        SFX S 0 s [^a\u{301}]
+
+        PFX P Y 1
+        PFX P a x a
+    "};
+    let dic = "3\nba/S\nba\u{301}/S\na\u{301}b/P";
+
+    for lazy in [false, true] {
+        let by_char = DictBuilder::new()
+            .config_str(aff)
+            .dict_str(dic)
+            .lazy_affixes(lazy)
+            .build()
+            .unwrap();
+        assert!(!by_char.check_word("bas"));
+        assert!(!by_char.check_word("ba\u{301}s"));
+        assert!(by_char.check_word("x\u{301}b"));
+
+        let by_grapheme = DictBuilder::new()
+            .config_str(aff)
+            .dict_str(dic)
+            .lazy_affixes(lazy)
+            .grapheme_affixes(true)
+            .build()
+            .unwrap();
+        assert!(by_grapheme.check_word("bas"), "lazy: {lazy}");
+        assert!(!by_grapheme.check_word("ba\u{301}s"));
+        assert!(!by_grapheme.check_word("x\u{301}b"));
+        assert!(by_grapheme.check_word("a\u{301}b"));
+    }
+}
+
+#[test]
+fn test_check_progress() {
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str("2\nthe\ncat")
+        .build()
+        .unwrap();
+
+    // 100k words of 4 bytes each, with one error at the end
+    let input = "the ".repeat(100_000) + "teh";
+    let mut reports = Vec::new();
+    let errors: Vec<_> = d
+        .check_indices_with_progress(&input, |p| reports.push(p))
+        .collect();
+    assert_eq!(errors, [(400_000, "teh")]);
+
+    // One report per 64 KiB, then the final one
+    assert_eq!(reports.len(), 7);
+    assert!(reports.windows(2).all(|w| w[0].bytes < w[1].bytes));
+    assert!(reports[..6].iter().all(|p| !p.is_done()));
+    assert!(reports[0].bytes >= 64 * 1024);
+    let last = reports.last().unwrap();
+    assert!(last.is_done());
+    assert_eq!(last.bytes, input.len());
+    assert_eq!(last.words, 100_001);
+    assert!((last.fraction() - 1.0).abs() < f64::EPSILON);
+
+    // Empty input still reports completion
+    let mut reports = Vec::new();
+    assert_eq!(
+        d.check_indices_with_progress("", |p| reports.push(p))
+            .count(),
+        0
+    );
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].is_done());
+}
+
+#[test]
+fn test_repeated_words() {
+    let d = DictBuilder::new()
+        .config_str("LANG tr_TR")
+        .dict_str("3\nthe\ncat\nışık")
+        .ignore_literal("XYZ")
+        .build()
+        .unwrap();
+    let repeated = |input| d.repeated_words(input).collect::<Vec<_>>();
+
+    assert_eq!(repeated("the cat the cat"), []);
+    // Misspelled words and line breaks make no difference
+    assert_eq!(repeated("teh  teh\n\tteh"), [(5, "teh"), (10, "teh")]);
+    // Punctuation, ignored text, and numbers are not repetitions
+    assert_eq!(repeated("the. the"), []);
+    assert_eq!(repeated("the XYZ the"), []);
+    assert_eq!(repeated("cat 10 10"), []);
+    // Case is compared with the rules of the language
+    assert_eq!(repeated("IŞIK ışık"), [(6, "ışık")]);
+}
+
+#[test]
+#[cfg(feature = "unstable-suggestions")]
+fn test_suggest_cancelled() {
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str("4\nthe\ncat\nsat\nmat")
+        .suggestion_cache(10)
+        .build()
+        .unwrap();
+    let cancel = CancelToken::new();
+
+    let entry = d.entries_cancellable("cag", &cancel).next().unwrap();
+    assert_eq!(entry.suggest().unwrap(), ["cat"]);
+
+    // Entries that were already produced stop suggesting once cancelled, and
+    // the partial results are not cached
+    let entry = d.entries_cancellable("mta", &cancel).next().unwrap();
+    cancel.cancel();
+    assert_eq!(entry.suggest().unwrap(), Vec::<&str>::new());
+    assert_eq!(d.suggestion_cache_stats().unwrap().len, 1);
+}
+
+#[test]
+#[cfg(feature = "unstable-suggestions")]
+fn test_suggest_within() {
+    use std::time::Duration;
+
+    let d = DictBuilder::new()
+        .config_str("MAP 1\nMAP zt")
+        .dict_str("5\ncat\ncar\ncap\ncan\ndog")
+        .suggestion_cache(10)
+        .build()
+        .unwrap();
+
+    // Without time for the wordlist scan, only the cheap `MAP` pass runs, and
+    // the partial results are not cached
+    let rushed = d.entry("caz").suggest_within(Duration::ZERO).unwrap();
+    assert_eq!(rushed, ["cat"]);
+    assert_eq!(d.suggestion_cache_stats().unwrap().len, 0);
+
+    let mut full = d
+        .entry("caz")
+        .suggest_within(Duration::from_secs(60))
+        .unwrap();
+    full.sort_unstable();
+    assert_eq!(full, ["can", "cap", "car", "cat"]);
+    assert_eq!(d.suggestion_cache_stats().unwrap().len, 1);
+}
+
+#[test]
+fn test_compound_check() {
+    let aff = indoc! {"
+        COMPOUNDBEGIN B
+        COMPOUNDMIDDLE M
+        COMPOUNDEND E
+        COMPOUNDFLAG F
+
+        SFX S Y 1
+        SFX S 0 s .
+    "};
+    let dic = indoc! {"
+        5
+        sun/BS
+        flower/ES
+        pot/M
+        foo/F
+        ab/F
+    "};
+    let d = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .build()
+        .unwrap();
+
+    assert!(d.check_word("sunflower"));
+    assert!(d.check_word("sunpotflower"));
+    assert!(d.check_word("foofoo"));
+    assert!(d.check_word("Sunflower"));
+    // Suffixes are only allowed at the end
+    assert!(d.check_word("sunflowers"));
+    assert!(!d.check_word("sunsflower"));
+    // Parts must be at their allowed position
+    assert!(!d.check_word("flowersun"));
+    assert!(!d.check_word("sunpot"));
+    // `COMPOUNDMIN` defaults to 3
+    assert!(!d.check_word("abfoo"));
+
+    let entry = d.entry("sunpotflower");
+    assert_eq!(entry.compound_parts().unwrap(), ["sun", "pot", "flower"]);
+    assert!(entry.stems().is_none());
+}
+
+#[test]
+#[cfg(feature = "unstable-suggestions")]
+fn test_compound_suggest() {
+    let aff = indoc! {"
+        COMPOUNDFLAG F
+        FORCEUCASE U
+        MAXCPDSUGS 1
+    "};
+    let dic = indoc! {"
+        4
+        sun/F
+        flower/F
+        flowed/F
+        berlin/FU
+    "};
+    let d = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .build()
+        .unwrap();
+
+    // Either part may be fixed
+    assert_eq!(d.entry("sunflowr").suggest().unwrap(), ["sunflower"]);
+    assert_eq!(d.entry("snflower").suggest().unwrap(), ["sunflower"]);
+    // `FORCEUCASE` on the last part capitalizes the suggestion
+    assert_eq!(d.entry("sunberln").suggest().unwrap(), ["Sunberlin"]);
+    // `MAXCPDSUGS` bounds the compound suggestions
+    assert_eq!(d.entry("sunflowe").suggest().unwrap().len(), 1);
+}
+
+#[test]
+#[cfg(feature = "unstable-suggestions")]
+fn test_stem_suggest() {
+    let aff = indoc! {"
+        SFX S Y 1
+        SFX S 0 s .
+
+        SFX D Y 1
+        SFX D 0 ed .
+
+        SFX R Y 1
+        SFX R 0 r e
+    "};
+    let dic = indoc! {"
+        6
+        run/S
+        ran st:run
+        running st:run
+        rune/SR
+        walk/SD
+        stone/S
+    "};
+    let d = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .build()
+        .unwrap();
+
+    // A doubled consonant is undone, and "-ed" is preferred over "-d" from
+    // "rune". Irregular forms are found by their `st:` stem.
+    assert_eq!(
+        d.entry("runned").suggest().unwrap(),
+        ["run", "running", "runs", "ran"]
+    );
+    // Rules that the stem does not have are undone too
+    assert_eq!(d.entry("walkr").suggest().unwrap()[..2], ["walk", "walks"]);
+}
+
+#[test]
+#[cfg(feature = "unstable-suggestions")]
+fn test_phonetic_suggest() {
+    use crate::PhoneticSuggestions;
+
+    let dic = indoc! {"
+        3
+        knowledge
+        night
+        fonetic
+    "};
+    let builder = DictBuilder::new().config_str("").dict_str(dic);
+
+    let d = builder.clone().build().unwrap();
+    assert!(d.entry("nolij").suggest().unwrap().is_empty());
+
+    let d = builder
+        .clone()
+        .phonetic_suggestions(PhoneticSuggestions::DoubleMetaphone)
+        .build()
+        .unwrap();
+    assert_eq!(d.entry("nolij").suggest().unwrap(), ["knowledge"]);
+    assert_eq!(d.entry("nite").suggest().unwrap(), ["night"]);
+
+    // A `PHONE` table takes precedence over Double Metaphone
+    let aff = indoc! {"
+        PHONE 5
+        PHONE PH F
+        PHONE F F
+        PHONE G G
+        PHONE N N
+        PHONE T T
+    "};
+    let d = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .phonetic_suggestions(PhoneticSuggestions::DoubleMetaphone)
+        .build()
+        .unwrap();
+    assert_eq!(d.entry("phonnnetik").suggest().unwrap(), ["fonetic"]);
+    assert!(d.entry("nite").suggest().unwrap().is_empty());
+
+    let d = builder
+        .phonetic_suggestions(PhoneticSuggestions::Disabled)
+        .build()
+        .unwrap();
+    assert!(d.entry("nite").suggest().unwrap().is_empty());
+}
+
+#[test]
+fn test_trailing_dots() {
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str("2\nword\netc.")
+        .build()
+        .unwrap();
+
+    assert!(d.check_word("word.."));
+    assert!(d.check_word("etc.."));
+    assert!(!d.check_word("etc"));
+    assert!(!d.check_word("."));
+
+    // The dot of an abbreviation is kept as part of the word
+    let words: Vec<_> = d
+        .entries("word etc.")
+        .map(|e| e.word().to_owned())
+        .collect();
+    assert_eq!(words, ["word", "etc."]);
+    assert!(d.check("word, etc. word."));
+}
+
+#[test]
+fn test_trailing_dots_keepcase() {
+    for lazy in [false, true] {
+        let d = DictBuilder::new()
+            .config_str("KEEPCASE A\nWORDCHARS .\nSFX S Y 1\nSFX S 0 s .")
+            .dict_str("3\nbaz./A\nqux\nbar/AS")
+            .lazy_affixes(lazy)
+            .build()
+            .unwrap();
+
+        assert!(d.check("baz."));
+        assert!(!d.check("Baz."));
+        assert!(!d.check("BAZ.."));
+        assert!(!d.check_word("Baz.."));
+        assert!(d.check_word("Qux.."));
+        assert!(d.check_word("bars.."));
+        assert!(!d.check_word("Bars.."), "lazy: {lazy}");
+    }
+}
+
+#[test]
+#[cfg(feature = "unstable-suggestions")]
+fn test_trailing_dots_suggest() {
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str("1\nword")
+        .build()
+        .unwrap();
+
+    // Without `SUGSWITHDOTS`, the dots are dropped
+    assert_eq!(d.entry("wrd.").suggest().unwrap(), ["word"]);
+}
+
+#[test]
+fn test_elision() {
+    let d = DictBuilder::new()
+        .config_str("ICONV 1\nICONV ’ '")
+        .dict_str("2\nl'\narbre")
+        .build()
+        .unwrap();
+
+    let entry = d.entry("l’arbre");
+    assert!(entry.correct());
+    assert_eq!(entry.compound_parts().unwrap(), ["l'", "arbre"]);
+    assert!(!d.check_word("l'eau"));
+    assert!(d.check("l'arbre, l’arbre"));
+}
+
+#[test]
+fn test_check_break_parts() {
+    let dic = "5\nwell\nknown\ne-mail\naddress\nfoo";
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str(dic)
+        .check_break_parts(true)
+        .build()
+        .unwrap();
+
+    assert!(d.check_word("well-known"));
+    assert!(d.check_word("e-mail-address"));
+    assert!(d.check_word("-foo"));
+    assert!(d.check_word("foo-"));
+    assert!(!d.check_word("wel-known"));
+    assert!(d.check("well-known e-mail, foo-foo"));
+
+    let errors: Vec<_> = d.check_indices("a well-known wel-knwn").collect();
+    assert_eq!(errors, [(0, "a"), (13, "wel"), (17, "knwn")]);
+
+    let entries: Vec<_> = d
+        .entries("e-mail wel-known")
+        .map(|e| (e.index(), e.word().to_owned(), e.correct()))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            (0, "e-mail".to_owned(), true),
+            (7, "wel".to_owned(), false),
+            (11, "known".to_owned(), true)
+        ]
+    );
+
+    // `BREAK 0` disables splitting and joining
+    let d = DictBuilder::new()
+        .config_str("BREAK 0\n")
+        .dict_str(dic)
+        .check_break_parts(true)
+        .build()
+        .unwrap();
+    assert!(!d.check_word("well-known"));
+    assert!(!d.check("e-mail"));
+
+    // Other separators can be used
+    let d = DictBuilder::new()
+        .config_str("BREAK 1\nBREAK _\n")
+        .dict_str(dic)
+        .check_break_parts(true)
+        .build()
+        .unwrap();
+    assert!(d.check_word("well_known"));
+    assert!(!d.check_word("well-known"));
+
+    // Without the option, words are not split but text is checked word by word
+    let d = DictBuilder::new()
+        .config_str("")
+        .dict_str(dic)
+        .build()
+        .unwrap();
+    assert!(!d.check_word("well-known"));
+    assert!(d.check("well-known"));
+    assert!(!d.check("e-mail"));
+}
+
+#[test]
+fn test_stats() {
+    let aff = indoc! {"
+        NOSUGGEST ?
+        FORBIDDENWORD !
+        ONLYINCOMPOUND _
+        COMPOUNDFLAG C
+
+        PFX U Y 1
+        PFX U 0 un .
+
+        SFX S Y 1
+        SFX S 0 s .
+
+        SFX D Y 1
+        SFX D 0 ed .
+    "};
+    let dic = "5\nhappy/U\nlock/USD\nheck/?\nfoo/!\nbar/_C";
+    let dict = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .personal_str("zspell\n")
+        .build()
+        .unwrap();
+
+    let stats = dict.stats();
+    assert_eq!(
+        (
+            stats.roots,
+            stats.words,
+            stats.nosuggest,
+            stats.forbidden,
+            stats.compound_only
+        ),
+        (6, 9, 1, 1, 1)
+    );
+    assert_eq!((stats.prefix_groups, stats.suffix_groups), (1, 2));
+
+    let larger = DictBuilder::new()
+        .config_str(&fs::read_to_string("tests/files/w1_eng_short.aff").unwrap())
+        .dict_str(&fs::read_to_string("tests/files/w1_eng_short.dic").unwrap())
+        .build()
+        .unwrap();
+    assert!(larger.stats().heap_bytes > stats.heap_bytes);
+}
+
+#[test]
+fn test_word_forms() {
+    let aff = indoc! {"
+        NOSUGGEST ?
+        FORBIDDENWORD !
+        ONLYINCOMPOUND _
+
+        SFX S Y 1
+        SFX S 0 s .
+    "};
+    let dict = DictBuilder::new()
+        .config_str(aff)
+        .dict_str("4\nheck/?S\nfoo/!\nbar/_\nbaz")
+        .personal_str("qux\n")
+        .build()
+        .unwrap();
+
+    let mut forms: Vec<_> = dict
+        .word_forms()
+        .map(|f| {
+            let flags: Vec<_> = f.affixes.iter().map(|a| (a.kind, a.flag)).collect();
+            (f.word, f.root, flags)
+        })
+        .collect();
+    forms.sort_unstable_by_key(|(word, _, _)| *word);
+
+    assert_eq!(
+        forms,
+        [
+            ("bar", "bar", vec![]),
+            ("baz", "baz", vec![]),
+            ("heck", "heck", vec![]),
+            ("hecks", "heck", vec![(RuleType::Suffix, "S")]),
+            ("qux", "qux", vec![]),
+        ]
+    );
+}
+
+#[test]
+fn test_derivations() {
+    let aff = indoc! {"
+        NOSUGGEST ?
+
+        PFX U Y 1
+        PFX U 0 un .
+
+        SFX S Y 1
+        SFX S 0 s .
+    "};
+    let dict = DictBuilder::new()
+        .config_str(aff)
+        .dict_str("2\nlock/US\nunlock/?")
+        .build()
+        .unwrap();
+
+    let derivations: Vec<_> = dict
+        .derivations("unlocks")
+        .into_iter()
+        .map(|d| {
+            let flags: Vec<_> = d.affixes.iter().map(|a| (a.kind, a.flag)).collect();
+            (d.word, d.root, flags)
+        })
+        .collect();
+    assert_eq!(
+        derivations,
+        [(
+            "unlocks",
+            "lock",
+            vec![(RuleType::Prefix, "U"), (RuleType::Suffix, "S")]
+        )]
+    );
+
+    // Both the affixed form and the nosuggest entry are found
+    let roots: Vec<_> = dict.derivations("unlock").iter().map(|d| d.root).collect();
+    assert_eq!(roots, ["lock", "unlock"]);
+}
+
+#[test]
+fn test_explain() {
+    let aff = indoc! {"
+        NOSUGGEST ?
+        FORBIDDENWORD !
+        ONLYINCOMPOUND _
+        COMPOUNDFLAG C
+    "};
+    let dic = "7\nfoo/C\nbar/C\nbaz\nqux/?\nbad/!\ninner/_C\nwell";
+    let dict = DictBuilder::new()
+        .config_str(aff)
+        .dict_str(dic)
+        .check_break_parts(true)
+        .build()
+        .unwrap();
+    let explain = |word| dict.entry(word).explain();
+
+    let exp = explain("qux");
+    assert!(exp.correct);
+    assert_eq!(
+        exp.reason,
+        Reason::Listed {
+            matched: "qux",
+            list: ListKind::NoSuggest,
+            case: CaseMatch::Exact
+        }
+    );
+
+    let exp = explain("baz...");
+    assert!(exp.correct);
+    assert_eq!(exp.lookup, "baz");
+
+    assert_eq!(explain("42").reason, Reason::Number);
+    assert_eq!(
+        explain("foobar").reason,
+        Reason::Compound {
+            parts: vec!["foo", "bar"]
+        }
+    );
+
+    let exp = explain("bad");
+    assert!(!exp.correct);
+    assert_eq!(exp.reason, Reason::Forbidden { matched: "bad" });
+
+    let exp = explain("well-baz");
+    assert!(exp.correct);
+    assert_eq!(
+        exp.reason,
+        Reason::Break {
+            parts: vec!["well".to_owned(), "baz".to_owned()]
+        }
+    );
+    assert_eq!(
+        explain("well-bax").reason,
+        Reason::NotFound {
+            hints: vec![Hint::MisspelledParts {
+                parts: vec!["bax".to_owned()]
+            }]
+        }
+    );
+
+    assert_eq!(
+        explain("inner").reason,
+        Reason::NotFound {
+            hints: vec![Hint::OnlyInCompound { matched: "inner" }]
+        }
+    );
+    assert_eq!(
+        explain("bazwell").reason,
+        Reason::NotFound {
+            hints: vec![Hint::CompoundRejected {
+                parts: vec!["baz", "well"]
+            }]
+        }
+    );
+    assert_eq!(explain("zzz").reason, Reason::NotFound { hints: vec![] });
+}
+
+#[test]
+fn test_lazy_affixes() {
+    fn stems<'d>(entry: &WordEntry<'d, '_>) -> Vec<StemInfo<'d>> {
+        let mut stems = entry.stems_with_affixes().unwrap_or_default();
+        stems.sort_by_key(|info| (info.stem, info.affixes.len()));
+        stems
+    }
+
+    fn morphs<'d>(entry: &WordEntry<'d, '_>) -> Vec<&'d MorphInfo> {
+        let mut morphs: Vec<_> = entry
+            .analyze()
+            .map(|analysis| analysis.morphs().collect())
+            .unwrap_or_default();
+        morphs.sort_by_key(|morph| format!("{morph:?}"));
+        morphs
+    }
+
+    let aff = indoc! {"
+        NOSUGGEST ?
+        FORBIDDENWORD !
+        NEEDAFFIX _
+        ONLYINCOMPOUND O
+
+        PFX U Y 1
+        PFX U 0 un .
+
+        PFX R N 1
+        PFX R 0 re .
+
+        SFX S Y 2
+        SFX S y ies [^aeiou]y
+        SFX S 0 s [^y]
+
+        SFX D Y 1
+        SFX D 0 ed/S . ds:ed
+
+        SFX N Y 1
+        SFX N 0 ness/_ .
+
+        SFX I Y 1
+        SFX I 0 in/O .
+    "};
+    let dic = indoc! {"
+        7
+        lock/UDS po:verb
+        fly/SR
+        heck/?S
+        bad/!S
+        dark/N
+        stand/_UD
+        play/I
+    "};
+    let build = |lazy| {
+        DictBuilder::new()
+            .config_str(aff)
+            .dict_str(dic)
+            .lazy_affixes(lazy)
+            .build()
+            .unwrap()
+    };
+    let eager = build(false);
+    let lazy = build(true);
+
+    let mut words: Vec<_> = eager.word_forms().map(|form| form.word).collect();
+    words.extend(eager.data.wordlist_forbidden.0.keys().map(AsRef::as_ref));
+    words.extend([
+        "Unlocks",
+        "flys",
+        "flyies",
+        "refly",
+        "reflies",
+        "unfly",
+        "unstand",
+        "darkness",
+        "standeds",
+        "unlockeds",
+        "stand",
+        "bads",
+        "locksed",
+    ]);
+    for word in words {
+        let (expected, actual) = (eager.entry(word), lazy.entry(word));
+        assert_eq!(actual.correct(), expected.correct(), "checking {word}");
+        assert_eq!(actual.forbidden(), expected.forbidden(), "checking {word}");
+        assert_eq!(stems(&actual), stems(&expected), "stems of {word}");
+        assert_eq!(morphs(&actual), morphs(&expected), "morphs of {word}");
+    }
+
+    // Only the roots are stored
+    assert!(lazy.stats().words < eager.stats().words);
+    assert!(!lazy.data.wordlist.0.contains_key("locks"));
+    assert_eq!(lazy.entry("Locked").matched_entry(), Some("locked"));
+
+    assert_eq!(
+        lazy.entry("unlocked").explain().reason,
+        Reason::Derived {
+            roots: vec!["lock"],
+            list: ListKind::Words,
+            case: CaseMatch::Exact,
+        }
+    );
+    assert_eq!(
+        lazy.entry("hecks").explain().reason,
+        Reason::Derived {
+            roots: vec!["heck"],
+            list: ListKind::NoSuggest,
+            case: CaseMatch::Exact,
+        }
+    );
+    assert_eq!(
+        lazy.entry("bads").explain().reason,
+        Reason::Forbidden { matched: "bad" }
+    );
 }
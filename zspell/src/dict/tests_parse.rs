@@ -58,6 +58,25 @@ fn test_dict_entry_ok() {
     assert_eq!(DictEntry::parse_single(s8, f2, 0), Ok(r4));
 }
 
+#[test]
+fn test_dict_entry_slashes() {
+    let f = FlagType::Ascii;
+    let a = Flag('A'.into());
+
+    assert_eq!(
+        DictEntry::parse_single("/", f, 0),
+        Ok(DictEntry::new("/", &[], &[]))
+    );
+    assert_eq!(
+        DictEntry::parse_single("1\\/2", f, 0),
+        Ok(DictEntry::new("1/2", &[], &[]))
+    );
+    assert_eq!(
+        DictEntry::parse_single("\\/usr\\/share/A", f, 0),
+        Ok(DictEntry::new("/usr/share", &[a], &[]))
+    );
+}
+
 #[test]
 fn test_personal_entry_ok() {
     let s1 = "abcd # comment";
@@ -83,3 +102,38 @@ fn test_personal_entry_ok() {
     assert_eq!(PersonalEntry::parse_single(s3), r3);
     assert_eq!(PersonalEntry::parse_single(s4), r4);
 }
+
+#[test]
+fn test_personal_header() {
+    let words = |s| {
+        PersonalEntry::parse_all(s)
+            .into_iter()
+            .map(|e| e.stem)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(words("en_US\nfoo\nbar"), [Arc::from("foo"), "bar".into()]);
+    assert_eq!(words("de_DE_frami\nfoo"), [Arc::from("foo")]);
+    assert_eq!(words("2\nfoo\nbar"), [Arc::from("foo"), "bar".into()]);
+    // Only the first line can be a header
+    assert_eq!(words("foo\nen_US"), [Arc::from("foo"), "en_US".into()]);
+    assert_eq!(words("an\nfoo"), [Arc::from("an"), "foo".into()]);
+}
+
+#[test]
+#[cfg(feature = "unstable-suggestions")]
+fn test_parse_frequencies() {
+    let s = "the 23135851162\n# comment\n\nof\t13151942776\nnew york 1234 # trailing\n";
+    assert_eq!(
+        parse_frequencies(s).unwrap(),
+        [
+            ("the", 23_135_851_162),
+            ("of", 13_151_942_776),
+            ("new york", 1234)
+        ]
+    );
+
+    let err = parse_frequencies("the 1\nof\n").unwrap_err();
+    assert!(matches!(err.err(), ParseErrorKind::Int(_)));
+    assert_eq!(err.span(), Some(&crate::error::Span::new(2, 0)));
+    assert!(parse_frequencies("the x").is_err());
+}
@@ -49,6 +49,73 @@ fn test_apply_pattern() {
     assert_eq!(rule.apply_pattern("xxx", kind), Some("xxxzzz".to_owned()));
 }
 
+#[test]
+fn test_apply_pattern_full_strip() {
+    let kind = RuleType::Suffix;
+    let mut rule = AfxRulePattern::new("zzz", Some("xxx"));
+
+    // The whole stem may only be stripped with `FULLSTRIP`
+    assert_eq!(rule.apply_pattern("xxx", kind), None);
+    assert_eq!(rule.apply_pattern("axxx", kind), Some("azzz".to_owned()));
+    rule.full_strip = true;
+    assert_eq!(rule.apply_pattern("xxx", kind), Some("zzz".to_owned()));
+
+    // But the result may not be empty
+    let mut rule = AfxRulePattern::new("", Some("xxx"));
+    rule.full_strip = true;
+    assert_eq!(rule.apply_pattern("xxx", kind), None);
+}
+
+#[test]
+fn test_apply_pattern_graphemes() {
+    // Strip `a` from the start of "ábc", written with a combining accent
+    let kind = RuleType::Prefix;
+    let mut rule = AfxRulePattern::new("x", Some("a"));
+    assert_eq!(
+        rule.apply_pattern("a\u{301}bc", kind),
+        Some("x\u{301}bc".to_owned())
+    );
+    rule.graphemes = true;
+    assert_eq!(rule.apply_pattern("a\u{301}bc", kind), None);
+    assert_eq!(rule.apply_pattern("abc", kind), Some("xbc".to_owned()));
+
+    let kind = RuleType::Suffix;
+    let mut rule = AfxRulePattern::new("o", Some("a\u{301}"));
+    rule.graphemes = true;
+    assert_eq!(rule.apply_pattern("ba\u{301}", kind), Some("bo".to_owned()));
+    // Stripping everything is still allowed with `FULLSTRIP`
+    let mut rule = AfxRulePattern::new("x", Some("a\u{301}"));
+    rule.graphemes = true;
+    rule.full_strip = true;
+    assert_eq!(
+        rule.apply_pattern("a\u{301}", RuleType::Prefix),
+        Some("x".to_owned())
+    );
+}
+
+#[test]
+fn test_unapply() {
+    let kind = RuleType::Suffix;
+    let mut rule = AfxRulePattern::new("ies", Some("y"));
+    rule.set_pattern("[^aeiou]y", kind).unwrap();
+    assert_eq!(rule.unapply("flies", kind).collect::<Vec<_>>(), ["fly"]);
+    assert_eq!(rule.unapply("boies", kind).count(), 0);
+    assert_eq!(rule.unapply("fly", kind).count(), 0);
+
+    // Without a condition, the stripped characters are optional
+    let rule = AfxRulePattern::new("ies", Some("y"));
+    assert_eq!(
+        rule.unapply("flies", kind).collect::<Vec<_>>(),
+        ["fly", "fl"]
+    );
+
+    let kind = RuleType::Prefix;
+    let mut rule = AfxRulePattern::new("un", None);
+    rule.set_pattern("l", kind).unwrap();
+    assert_eq!(rule.unapply("unlock", kind).collect::<Vec<_>>(), ["lock"]);
+    assert_eq!(rule.unapply("undo", kind).count(), 0);
+}
+
 // #[test]
 // fn test_rule_group_apply_pattern() {
 //     let kind = RuleType::Suffix;
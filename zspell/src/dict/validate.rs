@@ -0,0 +1,95 @@
+//! Optional checks of a `.dic` file for mistakes that do not stop it from
+//! being loaded, see `DictBuilder::validate_dict`
+
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+
+use super::parse::{split_count, DictEntry};
+use super::{DictData, Flag};
+use crate::affix::ParseMode;
+use crate::error::{BuildError, DictWarning};
+use crate::helpers::FxHashMap;
+
+impl DictData {
+    /// Make sure that the affix file defines the flags of each entry. In
+    /// strict mode, fail on the first flag that it does not; otherwise add a
+    /// warning for each.
+    pub(super) fn check_flags(
+        &mut self,
+        entries: &[DictEntry],
+        line_nums: &[u32],
+        mode: ParseMode,
+    ) -> Result<(), BuildError> {
+        // Flags are numbers of `AF` aliases, which are not supported yet, so
+        // they cannot be checked
+        if self.parsed_config.uses_affix_aliases() {
+            return Ok(());
+        }
+
+        let other_flags: Vec<Flag> = self.parsed_config.other_flags().collect();
+        for (entry, &line) in entries.iter().zip(line_nums) {
+            for flag in entry.flags.iter() {
+                if self.affix_flags.contains_key(flag) || other_flags.contains(flag) {
+                    continue;
+                }
+                let word = entry.stem.as_ref().to_owned();
+                let flag = self.flag_type.flag_to_str(*flag);
+                match mode {
+                    ParseMode::Strict => {
+                        return Err(BuildError::NonmatchingFlag { stem: word, flag });
+                    }
+                    ParseMode::Permissive => {
+                        self.dict_warnings
+                            .push(DictWarning::UndefinedFlag { word, flag, line });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the entries of a `.dic` file that was already loaded for mistakes
+    /// other than undefined flags
+    pub(super) fn validate_wordlist(&self, source: &str) -> Vec<DictWarning> {
+        let mut ret = Vec::new();
+        let (count, lines) = split_count(source);
+
+        let mut seen: FxHashMap<DictEntry, u32> = FxHashMap::default();
+        let mut actual = 0;
+        for (line_num, line) in lines {
+            // Lines that cannot be parsed already failed to load
+            let Ok(entry) = DictEntry::parse_single(line, self.flag_type, line_num) else {
+                continue;
+            };
+            actual += 1;
+
+            if let Some(&first_line) = seen.get(&entry) {
+                ret.push(DictWarning::DuplicateEntry {
+                    word: entry.stem.as_ref().to_owned(),
+                    line: line_num,
+                    first_line,
+                });
+            } else {
+                seen.insert(entry, line_num);
+            }
+        }
+
+        // The count comes first in the file, so put its problems first too
+        match count {
+            Some((line, expected)) if expected != actual => {
+                ret.insert(
+                    0,
+                    DictWarning::CountMismatch {
+                        expected,
+                        actual,
+                        line,
+                    },
+                );
+            }
+            Some(_) => (),
+            None => ret.insert(0, DictWarning::MissingCount),
+        }
+
+        ret
+    }
+}
@@ -3,8 +3,11 @@
 //! [`Error`] is the main error type for this crate, all other types of errors
 //! will fall under it.
 
-use std::fmt::Display;
-use std::num::ParseIntError;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt::Display;
+use core::num::ParseIntError;
 
 use crate::affix::FlagType;
 use crate::dict::FlagValue;
@@ -19,15 +22,21 @@ pub enum Error {
     /// Error during building
     Build(BuildError),
     /// Regex error from user-provided input
+    #[cfg(feature = "regex")]
     Regex(regex::Error),
-
+    #[cfg(feature = "std")]
     Io(IoError),
+    /// Error reading a dictionary bundle such as an `.oxt` extension
+    Archive(ArchiveError),
+    /// Error downloading a dictionary
+    Fetch(FetchError),
 }
 
 /// An error that occured while parsing, consisting of an error variant and a
 /// location
 #[non_exhaustive]
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ParseError {
     /// The error that occured
     err: Box<ParseErrorKind>,
@@ -40,6 +49,7 @@ pub struct ParseError {
 /// A representation of where a [`ParseError`] occured
 #[non_exhaustive]
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
     start: LineCol,
     end: LineCol,
@@ -47,6 +57,7 @@ pub struct Span {
 
 /// A location within a file
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LineCol {
     line: u32,
     col: u32,
@@ -73,26 +84,117 @@ pub enum BuildError {
         /// Second duplicate type if Some; affix rule if None
         t2: Option<FlagValue>,
     },
-    /// A flag in a dictionary file does not match any known flags
+    /// A flag in a dictionary file is not defined by the affix file, which
+    /// usually means that the `FLAG` type is wrong
     NonmatchingFlag { stem: String, flag: String },
 }
 
+/// A problem in a dictionary word list that does not stop the dictionary from
+/// being built, see [`Dictionary::dict_warnings`]. Line numbers start at 1.
+///
+/// [`Dictionary::dict_warnings`]: crate::Dictionary::dict_warnings
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DictWarning {
+    /// An entry repeats an earlier one, with the same flags and morphological
+    /// info
+    DuplicateEntry {
+        word: String,
+        line: u32,
+        /// Line of the earlier entry
+        first_line: u32,
+    },
+    /// An entry has a flag that the affix file does not define, with
+    /// [`ParseMode::Permissive`](crate::ParseMode::Permissive)
+    UndefinedFlag {
+        word: String,
+        flag: String,
+        line: u32,
+    },
+    /// The entry count on the first line does not match the number of entries
+    CountMismatch {
+        expected: usize,
+        actual: usize,
+        line: u32,
+    },
+    /// The file does not start with an entry count
+    MissingCount,
+}
+
+/// A non-fatal issue found while building a dictionary, see
+/// [`DictBuilder::build_with_warnings`](crate::DictBuilder::build_with_warnings)
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BuildWarning {
+    /// A directive in the affix file is parsed but not honored yet
+    Unsupported(&'static str),
+    /// A directive in the affix file is deprecated. It still works, but
+    /// `replacement` should be used instead if there is one
+    Deprecated {
+        directive: &'static str,
+        replacement: Option<&'static str>,
+    },
+    /// A line of the affix file could not be parsed and was skipped, with
+    /// [`ParseMode::Permissive`](crate::ParseMode::Permissive)
+    Parse(ParseError),
+    /// A problem in the word list
+    Dict(DictWarning),
+}
+
 /// An I/O error. This is a wrapper around [`std::io::ErrorKind`]
+#[cfg(feature = "std")]
 #[derive(Clone, Debug, PartialEq)]
 pub struct IoError {
     fname: String,
     err: std::io::ErrorKind,
 }
 
+/// Errors that can occur when reading a dictionary from an archive
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArchiveError {
+    /// The file is not a valid zip archive
+    Zip(String),
+    /// A file was expected in the archive but does not exist
+    MissingFile(String),
+    /// The archive does not list any spellcheck dictionaries
+    NoDictionary,
+    /// No dictionary in the archive is registered for this locale
+    LocaleNotFound(String),
+}
+
+/// Errors that can occur when downloading a dictionary
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FetchError {
+    /// A request failed
+    Http { url: String, msg: String },
+    /// The index could not be parsed
+    Index(String),
+    /// The index does not have a dictionary for this language
+    LanguageNotFound(String),
+    /// The dictionary for this language is not in a format that can be loaded
+    UnsupportedFormat(String),
+    /// A file in the index has no URLs to download it from
+    NoUrls,
+    /// The index uses a hash algorithm that is not supported
+    UnsupportedHash(String),
+    /// The downloaded file does not match the hash in the index
+    ChecksumMismatch { url: String },
+}
+
 /// A kind of error that would occur during parsing, with additional information
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ParseErrorKind {
     /// A boolean flag
     Boolean,
     /// Expected `a` chars but got `b`
     Char(usize, usize),
     /// Error parsing any integer
-    Int(ParseIntError),
+    Int(#[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))] ParseIntError),
     /// Wrong number of items in a table
     TableCount {
         expected: u32,
@@ -120,15 +222,38 @@ pub enum ParseErrorKind {
     InvalidFlag,
 
     CompoundSyllableCount(usize),
-    CompoundSyllableParse(ParseIntError),
+    CompoundSyllableParse(
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))] ParseIntError,
+    ),
     // An error parsing the personal dictionary
     Personal,
     CompoundPattern,
+    /// A `COMPOUNDRULE` with an unclosed `(` or a dangling `*` or `?`
+    CompoundRule,
     Phonetic(usize),
+    /// A parenthesized group in a `MAP` entry was not closed
+    MapGroupUnclosed,
     PartOfSpeech(String),
     DictEntry,
-    /// Regex error while parsing
-    Regex(regex::Error),
+    /// An affix rule condition with an unclosed or empty `[` group
+    AffixCondition(String),
+    /// A line of an affix file that does not start with a known directive
+    UnknownDirective(String),
+    /// A prefix or suffix rule group uses a flag that an earlier group already
+    /// uses
+    DuplicateAffixFlag(String),
+    /// A line of a thesaurus that should start an entry is not `word|count`
+    ThesaurusEntry,
+}
+
+/// Serialize a field that only implements `Display`, such as [`ParseIntError`]
+#[cfg(feature = "serde")]
+fn serialize_display<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: serde::Serializer,
+{
+    serializer.collect_str(value)
 }
 
 impl Span {
@@ -143,6 +268,32 @@ impl Span {
             },
         }
     }
+
+    /// Where the error starts
+    #[inline]
+    pub fn start(&self) -> LineCol {
+        self.start
+    }
+
+    /// Where the error ends
+    #[inline]
+    pub fn end(&self) -> LineCol {
+        self.end
+    }
+}
+
+impl LineCol {
+    /// The 1-based line number
+    #[inline]
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The column on the line
+    #[inline]
+    pub fn col(&self) -> u32 {
+        self.col
+    }
 }
 
 impl ParseError {
@@ -205,6 +356,33 @@ impl ParseError {
     }
 }
 
+impl DictWarning {
+    /// The line that the problem is on, if it is on one line
+    #[inline]
+    pub fn line(&self) -> Option<u32> {
+        match self {
+            DictWarning::DuplicateEntry { line, .. }
+            | DictWarning::UndefinedFlag { line, .. }
+            | DictWarning::CountMismatch { line, .. } => Some(*line),
+            DictWarning::MissingCount => None,
+        }
+    }
+}
+
+impl BuildWarning {
+    /// The line that the problem is on, if it is on one line. Lines of
+    /// [`BuildWarning::Parse`] are in the affix file and those of
+    /// [`BuildWarning::Dict`] are in the word list.
+    #[inline]
+    pub fn line(&self) -> Option<u32> {
+        match self {
+            BuildWarning::Unsupported(_) | BuildWarning::Deprecated { .. } => None,
+            BuildWarning::Parse(e) => e.span().map(|span| span.start().line()),
+            BuildWarning::Dict(w) => w.line(),
+        }
+    }
+}
+
 impl ParseErrorKind {
     #[allow(unused)]
     fn help_msg(&self) -> Option<&'static str> {
@@ -219,6 +397,7 @@ impl ParseErrorKind {
     }
 }
 
+#[cfg(feature = "std")]
 impl IoError {
     pub(crate) fn new(fname: &str, err: std::io::ErrorKind) -> Self {
         Self {
@@ -230,26 +409,38 @@ impl IoError {
 
 /* trait impls */
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
+#[cfg(feature = "std")]
 impl std::error::Error for ParseError {}
+#[cfg(feature = "std")]
 impl std::error::Error for ParseErrorKind {}
+#[cfg(feature = "std")]
 impl std::error::Error for BuildError {}
+#[cfg(feature = "std")]
+impl std::error::Error for ArchiveError {}
+#[cfg(feature = "std")]
+impl std::error::Error for FetchError {}
 
 impl Display for Error {
     #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::Parse(e) => write!(f, "parse error: {e}"),
             Error::Build(e) => write!(f, "build error: {e}"),
+            #[cfg(feature = "regex")]
             Error::Regex(e) => write!(f, "regex error: {e}"),
+            #[cfg(feature = "std")]
             Error::Io(e) => write!(f, "io error: {e}"),
+            Error::Archive(e) => write!(f, "archive error: {e}"),
+            Error::Fetch(e) => write!(f, "fetch error: {e}"),
         }
     }
 }
 
 impl Display for ParseError {
     #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         const MAX_CTX_LEN: usize = 100;
         let mut ctx_to_print = String::with_capacity(MAX_CTX_LEN + 12);
         if self.ctx.len() > MAX_CTX_LEN {
@@ -273,7 +464,7 @@ impl Display for ParseError {
 
 impl Display for ParseErrorKind {
     #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             ParseErrorKind::Boolean => write!(f, "expected a boolean flag with no content"),
             ParseErrorKind::Char(a, b) => write!(f, "expected {a} flags but got {b}"),
@@ -305,7 +496,9 @@ impl Display for ParseErrorKind {
             ParseErrorKind::Encoding => write!(f, "unrecognized encoding"),
             ParseErrorKind::FlagType => write!(f, "unrecognized flag"),
             ParseErrorKind::CompoundPattern => write!(f, "invalid compound pattern"),
+            ParseErrorKind::CompoundRule => write!(f, "invalid compound rule"),
             ParseErrorKind::Phonetic(n) => write!(f, "expected 2 items but got {n}"),
+            ParseErrorKind::MapGroupUnclosed => write!(f, "unclosed '(' in map entry"),
             ParseErrorKind::DictEntry => write!(f, "invalid dictionary entry"),
             ParseErrorKind::PartOfSpeech(s) => {
                 write!(f, "value '{s}' is not a known part of speech")
@@ -315,7 +508,16 @@ impl Display for ParseErrorKind {
             }
             ParseErrorKind::CompoundSyllableCount(n) => write!(f, "expected 2 items but got {n}"),
             ParseErrorKind::CompoundSyllableParse(e) => write!(f, "unable to parse integer: {e}"),
-            ParseErrorKind::Regex(e) => e.fmt(f),
+            ParseErrorKind::AffixCondition(cond) => write!(f, "invalid affix condition '{cond}'"),
+            ParseErrorKind::UnknownDirective(directive) => {
+                write!(f, "unknown directive '{directive}'")
+            }
+            ParseErrorKind::DuplicateAffixFlag(flag) => {
+                write!(f, "affix flag '{flag}' is already defined")
+            }
+            ParseErrorKind::ThesaurusEntry => {
+                write!(f, "expected a thesaurus entry such as 'word|2'")
+            }
             ParseErrorKind::Personal => write!(f, "error parsing entry in personal dictionary"),
             ParseErrorKind::InvalidFlag => {
                 write!(f, "expected a single alphanumeric flag (4 bytes maximum)")
@@ -327,7 +529,7 @@ impl Display for ParseErrorKind {
 
 impl Display for BuildError {
     #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             BuildError::BuilderCfgSpecTwice => {
                 write!(f, "configuration specified twice in builder")
@@ -356,15 +558,103 @@ impl Display for BuildError {
             ),
             BuildError::NonmatchingFlag { stem, flag } => write!(
                 f,
-                "stem '{stem}' is marked with flag '{flag}' but it does not match any patterns"
+                "stem '{stem}' has flag '{flag}', which the affix file does not define"
+            ),
+        }
+    }
+}
+
+impl Display for DictWarning {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DictWarning::DuplicateEntry {
+                word, first_line, ..
+            } => write!(f, "entry '{word}' repeats the entry on line {first_line}"),
+            DictWarning::UndefinedFlag { word, flag, .. } => write!(
+                f,
+                "entry '{word}' has flag '{flag}', which the affix file does not define"
             ),
+            DictWarning::CountMismatch {
+                expected, actual, ..
+            } => write!(f, "expected {expected} entries but got {actual}"),
+            DictWarning::MissingCount => write!(f, "missing entry count on the first line"),
+        }
+    }
+}
+
+impl Display for BuildWarning {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BuildWarning::Unsupported(directive) => {
+                write!(
+                    f,
+                    "directive '{directive}' is not supported yet and has no effect"
+                )
+            }
+            BuildWarning::Deprecated {
+                directive,
+                replacement: Some(replacement),
+            } => write!(
+                f,
+                "directive '{directive}' is deprecated, use {replacement}"
+            ),
+            BuildWarning::Deprecated {
+                directive,
+                replacement: None,
+            } => write!(f, "directive '{directive}' is deprecated"),
+            BuildWarning::Parse(e) => e.fmt(f),
+            BuildWarning::Dict(w) => match w.line() {
+                Some(line) => write!(f, "word list line {line}: {w}"),
+                None => write!(f, "word list: {w}"),
+            },
+        }
+    }
+}
+
+impl Display for ArchiveError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ArchiveError::Zip(e) => write!(f, "invalid zip file: {e}"),
+            ArchiveError::MissingFile(path) => write!(f, "file '{path}' not found in archive"),
+            ArchiveError::NoDictionary => write!(f, "no spellcheck dictionaries in archive"),
+            ArchiveError::LocaleNotFound(loc) => {
+                write!(f, "no dictionary in archive for locale '{loc}'")
+            }
+        }
+    }
+}
+
+impl Display for FetchError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FetchError::Http { url, msg } => write!(f, "request to '{url}' failed: {msg}"),
+            FetchError::Index(e) => write!(f, "invalid index: {e}"),
+            FetchError::LanguageNotFound(lang) => {
+                write!(f, "no dictionary found for language '{lang}'")
+            }
+            FetchError::UnsupportedFormat(lang) => {
+                write!(
+                    f,
+                    "dictionary for language '{lang}' is not in Hunspell format"
+                )
+            }
+            FetchError::NoUrls => write!(f, "no URLs available for file"),
+            FetchError::UnsupportedHash(h) => write!(f, "unsupported hash '{h}'"),
+            FetchError::ChecksumMismatch { url } => {
+                write!(f, "checksum mismatch for file downloaded from '{url}'")
+            }
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl Display for IoError {
     #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "in file '{}' {}", self.fname, self.err)
     }
 }
@@ -383,6 +673,7 @@ impl From<BuildError> for Error {
     }
 }
 
+#[cfg(feature = "regex")]
 impl From<regex::Error> for Error {
     #[inline]
     fn from(value: regex::Error) -> Self {
@@ -390,20 +681,28 @@ impl From<regex::Error> for Error {
     }
 }
 
-impl From<regex::Error> for ParseErrorKind {
+impl From<ParseIntError> for ParseErrorKind {
     #[inline]
-    fn from(value: regex::Error) -> Self {
-        Self::Regex(value)
+    fn from(value: ParseIntError) -> Self {
+        Self::Int(value)
     }
 }
 
-impl From<ParseIntError> for ParseErrorKind {
+impl From<ArchiveError> for Error {
     #[inline]
-    fn from(value: ParseIntError) -> Self {
-        Self::Int(value)
+    fn from(value: ArchiveError) -> Self {
+        Self::Archive(value)
+    }
+}
+
+impl From<FetchError> for Error {
+    #[inline]
+    fn from(value: FetchError) -> Self {
+        Self::Fetch(value)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<IoError> for Error {
     #[inline]
     fn from(value: IoError) -> Self {
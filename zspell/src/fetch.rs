@@ -0,0 +1,294 @@
+//! Download dictionaries from an online index (requires feature `fetch`)
+//!
+//! An index is a JSON file listing dictionaries by language tag, along with the
+//! URLs and checksums of their files. By default the [ZSpell index] is used,
+//! which mirrors dictionaries from the LibreOffice and wooorm repositories, but
+//! any index in the same format can be configured with
+//! [`Fetcher::index_url`].
+//!
+//! Downloaded files are verified against the index and stored in a per-user
+//! cache directory, so a dictionary is only downloaded once.
+//!
+//! ```no_run
+//! use zspell::fetch::Fetcher;
+//!
+//! let fetched = Fetcher::new().fetch("de-AT").unwrap();
+//! println!("dictionary saved to {}", fetched.dic_path().display());
+//!
+//! let dict = fetched.build().unwrap();
+//! assert!(dict.check("Wörterbuch"));
+//! ```
+//!
+//! [ZSpell index]: https://github.com/pluots/zspell-index
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{env, io};
+
+use sha1::{Digest, Sha1};
+use zspell_index::{DictionaryFormat, Downloadable, Index};
+
+use crate::error::{Error, FetchError, IoError};
+use crate::{DictBuilder, Dictionary};
+
+/// Index used if none is specified
+pub const DEFAULT_INDEX_URL: &str =
+    "https://raw.githubusercontent.com/pluots/zspell-index/main/zspell-index.json";
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// Refuse to download anything larger than this
+const MAX_DOWNLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Downloads and caches dictionaries. See the [module-level
+/// documentation](crate::fetch) for an example.
+#[derive(Debug)]
+pub struct Fetcher {
+    index_url: String,
+    cache_dir: PathBuf,
+    force: bool,
+    agent: ureq::Agent,
+}
+
+/// Paths to the files of a downloaded dictionary
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FetchedDict {
+    aff: PathBuf,
+    dic: PathBuf,
+    license: PathBuf,
+}
+
+impl Fetcher {
+    /// Create a fetcher that uses the default index and cache directory
+    #[inline]
+    pub fn new() -> Self {
+        let cache_dir = dirs::cache_dir()
+            .unwrap_or_else(env::temp_dir)
+            .join("zspell")
+            .join("dictionaries");
+
+        Self {
+            index_url: DEFAULT_INDEX_URL.to_owned(),
+            cache_dir,
+            force: false,
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(Duration::from_secs(10))
+                .timeout_read(Duration::from_secs(30))
+                .user_agent(USER_AGENT)
+                .build(),
+        }
+    }
+
+    /// Use a different index, e.g. a local mirror
+    #[inline]
+    #[must_use]
+    pub fn index_url(mut self, url: impl Into<String>) -> Self {
+        self.index_url = url.into();
+        self
+    }
+
+    /// Store dictionaries in `dir` rather than the user's cache directory
+    #[inline]
+    #[must_use]
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = dir.into();
+        self
+    }
+
+    /// Download dictionaries even if they already exist in the cache
+    #[inline]
+    #[must_use]
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// The directory that dictionaries are stored in
+    #[inline]
+    pub fn cache_path(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Get the dictionary for the language tag `lang` (e.g. `en-US`),
+    /// downloading it if it is not already cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index cannot be retrieved, does not contain
+    /// `lang`, or if a file fails to download or does not match its checksum.
+    #[inline]
+    pub fn fetch(&self, lang: &str) -> Result<FetchedDict, Error> {
+        let fetched = FetchedDict {
+            aff: self.cache_dir.join(format!("{lang}.aff")),
+            dic: self.cache_dir.join(format!("{lang}.dic")),
+            license: self.cache_dir.join(format!("{lang}.lic")),
+        };
+
+        if !self.force
+            && [&fetched.aff, &fetched.dic, &fetched.license]
+                .iter()
+                .all(|p| p.is_file())
+        {
+            return Ok(fetched);
+        }
+
+        let index = self.get_index()?;
+        let entry = index
+            .items
+            .iter()
+            .find(|item| item.lang.eq_ignore_ascii_case(lang))
+            .ok_or_else(|| FetchError::LanguageNotFound(lang.to_owned()))?;
+        let DictionaryFormat::Hunspell { aff, dic } = &entry.format else {
+            return Err(FetchError::UnsupportedFormat(lang.to_owned()).into());
+        };
+
+        fs::create_dir_all(&self.cache_dir)
+            .map_err(|e| IoError::new(&self.cache_dir.to_string_lossy(), e.kind()))?;
+        self.download(&entry.lic, &fetched.license)?;
+        self.download(aff, &fetched.aff)?;
+        self.download(dic, &fetched.dic)?;
+
+        Ok(fetched)
+    }
+
+    fn get_index(&self) -> Result<Index, Error> {
+        let resp = self
+            .agent
+            .get(&self.index_url)
+            .call()
+            .map_err(|e| http_error(&self.index_url, &e))?;
+        resp.into_json()
+            .map_err(|e| FetchError::Index(e.to_string()).into())
+    }
+
+    /// Download a file, trying each of its URLs in turn, and save it to `dest`
+    /// once its checksum is verified
+    fn download(&self, dl: &Downloadable, dest: &Path) -> Result<(), Error> {
+        let mut last_err = None;
+        let mut found = None;
+        for url in dl.urls.iter() {
+            match self.agent.get(url).call() {
+                Ok(resp) => {
+                    found = Some((url, resp));
+                    break;
+                }
+                Err(e) => last_err = Some(http_error(url, &e)),
+            }
+        }
+        let Some((url, resp)) = found else {
+            return Err(last_err.unwrap_or_else(|| FetchError::NoUrls.into()));
+        };
+
+        let mut buf = Vec::with_capacity(dl.size.try_into().unwrap_or(0));
+        resp.into_reader()
+            .take(MAX_DOWNLOAD_SIZE)
+            .read_to_end(&mut buf)
+            .map_err(|e| IoError::new(url, e.kind()))?;
+
+        verify_hash(&buf, &dl.hash, url)?;
+
+        // Write to a temporary file first so an interrupted download does not
+        // leave a partial file in the cache
+        let tmp = dest.with_extension("part");
+        let io_err = |e: io::Error| IoError::new(&dest.to_string_lossy(), e.kind());
+        File::create(&tmp)
+            .and_then(|mut f| f.write_all(&buf))
+            .map_err(io_err)?;
+        fs::rename(&tmp, dest).map_err(io_err)?;
+
+        Ok(())
+    }
+}
+
+impl Default for Fetcher {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FetchedDict {
+    /// Path to the downloaded `.aff` file
+    #[inline]
+    pub fn aff_path(&self) -> &Path {
+        &self.aff
+    }
+
+    /// Path to the downloaded `.dic` file
+    #[inline]
+    pub fn dic_path(&self) -> &Path {
+        &self.dic
+    }
+
+    /// Path to the dictionary's license
+    #[inline]
+    pub fn license_path(&self) -> &Path {
+        &self.license
+    }
+
+    /// Load the downloaded files into a [`Dictionary`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the files cannot be read or the dictionary fails to
+    /// build.
+    #[inline]
+    pub fn build(&self) -> Result<Dictionary, Error> {
+        let read = |path: &Path| {
+            fs::read_to_string(path).map_err(|e| IoError::new(&path.to_string_lossy(), e.kind()))
+        };
+        let aff = read(&self.aff)?;
+        let dic = read(&self.dic)?;
+        DictBuilder::new().config_str(&aff).dict_str(&dic).build()
+    }
+}
+
+fn http_error(url: &str, e: &ureq::Error) -> Error {
+    FetchError::Http {
+        url: url.to_owned(),
+        msg: e.to_string(),
+    }
+    .into()
+}
+
+/// Check `bytes` against a hash in the index, formatted like `sha1:abcd...`
+fn verify_hash(bytes: &[u8], hash: &str, url: &str) -> Result<(), Error> {
+    let Some(("sha1", digest)) = hash.split_once(':') else {
+        return Err(FetchError::UnsupportedHash(hash.to_owned()).into());
+    };
+
+    if hex_encode(&calculate_git_hash(bytes)).eq_ignore_ascii_case(digest) {
+        Ok(())
+    } else {
+        Err(FetchError::ChecksumMismatch {
+            url: url.to_owned(),
+        }
+        .into())
+    }
+}
+
+/// Perform the function that Git does to calculate its hash, which is what the
+/// index uses for `sha1` hashes
+///
+/// Implementation taken from the git help page, located here
+/// <https://git-scm.com/book/en/v2/Git-Internals-Git-Objects>
+fn calculate_git_hash(bytes: &[u8]) -> [u8; 20] {
+    let mut hasher = Sha1::new();
+    let prefix = format!("blob {}\0", bytes.len());
+    hasher.update(prefix);
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,132 @@
+//! Tests for the `fetch` module
+
+use httpmock::prelude::*;
+use httpmock::Mock;
+use tempfile::tempdir;
+
+use super::*;
+
+const INDEX_PATH: &str = "/zspell-index.json";
+
+// Content for our mock server
+const CONTENT_AFF: &str = "Affix Content\n";
+const CONTENT_DIC: &str = "Dictionary Content\n";
+const CONTENT_LIC: &str = "License Content\n";
+
+macro_rules! make_resp {
+    ($server:expr, $path:expr, $ctype:expr, $body:expr) => {
+        $server.mock(|when, then| {
+            when.method(GET).path($path);
+            then.status(200)
+                .header("content-type", format!("{}; charset=utf-8", $ctype))
+                .body($body);
+        })
+    };
+}
+
+/// Create mocks for an index containing `de-AT`, with the given `.dic` content
+fn mock_de_at_index<'a>(server: &'a MockServer, dic_content: &str) -> Vec<Mock<'a>> {
+    vec![
+        make_resp!(
+            server,
+            INDEX_PATH,
+            "application/json",
+            include_str!("../../tests/files/sample-index.json")
+                .replace(r"{{ROOT_URL}}", &server.base_url())
+        ),
+        make_resp!(
+            server,
+            "/dictionaries/de-AT/index.aff",
+            "text/plain",
+            CONTENT_AFF
+        ),
+        make_resp!(
+            server,
+            "/dictionaries/de-AT/index.dic",
+            "text/plain",
+            dic_content
+        ),
+        make_resp!(
+            server,
+            "/dictionaries/de-AT/license",
+            "text/plain",
+            CONTENT_LIC
+        ),
+    ]
+}
+
+#[test]
+fn calculate_git_hash_ok() {
+    // Use example from git help page
+    assert_eq!(
+        hex_encode(&calculate_git_hash(b"what is up, doc?")),
+        "bd9dbf5aae1a3862dd1526723246b20206e5fc37"
+    );
+}
+
+#[test]
+fn fetch_dict_ok() {
+    let server = MockServer::start();
+    let mocks = mock_de_at_index(&server, CONTENT_DIC);
+    let dir = tempdir().unwrap();
+    let fetcher = Fetcher::new()
+        .index_url(server.url(INDEX_PATH))
+        .cache_dir(dir.path());
+
+    let files = fetcher.fetch("de-AT").unwrap();
+    assert_eq!(files.aff_path(), dir.path().join("de-AT.aff"));
+
+    // Verify we created the expected paths
+    let mut created_files = fs::read_dir(dir.path())
+        .unwrap()
+        .filter_map(Result::ok)
+        .map(|x| x.file_name())
+        .collect::<Vec<_>>();
+    created_files.sort_unstable();
+
+    assert_eq!(created_files, ["de-AT.aff", "de-AT.dic", "de-AT.lic"]);
+    assert_eq!(fs::read_to_string(files.dic_path()).unwrap(), CONTENT_DIC);
+
+    // A second fetch should come from the cache, unless forced
+    assert_eq!(fetcher.fetch("de-AT").unwrap(), files);
+    for mock in &mocks {
+        mock.assert_hits(1);
+    }
+
+    fetcher.force(true).fetch("de-AT").unwrap();
+    for mock in &mocks {
+        mock.assert_hits(2);
+    }
+}
+
+#[test]
+fn fetch_dict_errors() {
+    let server = MockServer::start();
+    let _mocks = mock_de_at_index(&server, "Tampered Content\n");
+    let dir = tempdir().unwrap();
+    let fetcher = Fetcher::new()
+        .index_url(server.url(INDEX_PATH))
+        .cache_dir(dir.path());
+
+    assert_eq!(
+        fetcher.fetch("en-US").unwrap_err(),
+        FetchError::LanguageNotFound("en-US".to_owned()).into()
+    );
+    assert_eq!(
+        fetcher.fetch("de-AT").unwrap_err(),
+        FetchError::ChecksumMismatch {
+            url: server.url("/dictionaries/de-AT/index.dic")
+        }
+        .into()
+    );
+    // The bad file should not be left in the cache
+    assert!(!dir.path().join("de-AT.dic").exists());
+
+    let missing = Fetcher::new()
+        .index_url(server.url("/missing.json"))
+        .cache_dir(dir.path());
+    assert!(matches!(
+        missing.fetch("de-AT"),
+        Err(Error::Fetch(FetchError::Http { .. }))
+    ));
+}
@@ -0,0 +1,320 @@
+//! Filters that select the parts of a document that should be spellchecked,
+//! such as the prose outside of code blocks in Markdown
+//!
+//! A [`DocumentFilter`] returns byte ranges into the document, so the index of
+//! a word found in one of them maps straight back to the document. Formats
+//! that contain other formats are handled by chaining filters with
+//! [`then`](DocumentFilter::then), where each filter only sees the text that
+//! the one before it selected.
+//!
+//! ```
+//! use zspell::filter::{DocumentFilter, Html, Markdown};
+//!
+//! let text = "See `code` and <b>bold</b> text.\n\n```\nnot checked\n```\n";
+//! let filter = Markdown.then(Html);
+//!
+//! let selected: Vec<&str> = filter
+//!     .ranges(text)
+//!     .into_iter()
+//!     .map(|range| &text[range])
+//!     .collect();
+//! assert_eq!(selected, ["See ", " and ", "bold", " text."]);
+//! ```
+//!
+//! [`for_extension`] picks a filter from a file extension.
+
+mod email;
+mod gettext;
+mod html;
+mod json;
+mod keys;
+mod latex;
+mod markdown;
+mod yaml;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+pub use email::Email;
+pub use gettext::Gettext;
+pub use html::Html;
+pub use json::Json;
+pub use latex::Latex;
+pub use markdown::Markdown;
+pub use yaml::Yaml;
+
+/// Selects the text of a document that should be checked.
+///
+/// Implementations only need [`ranges`](Self::ranges). See the
+/// [module-level documentation](crate::filter) for an example.
+pub trait DocumentFilter: fmt::Debug + Send + Sync {
+    /// Return the byte ranges of `text` that should be checked. Ranges are in
+    /// order, do not overlap, and are not empty.
+    fn ranges(&self, text: &str) -> Vec<Range<usize>>;
+
+    /// Like [`ranges`](Self::ranges), but only looks at the given ranges of
+    /// `text`, which must be in order and not overlap.
+    ///
+    /// The ranges are treated as consecutive lines of one document, so that
+    /// state such as an open code block carries from one to the next. This is
+    /// how filters see the output of another filter with
+    /// [`then`](Self::then).
+    #[inline]
+    fn ranges_within(&self, text: &str, within: &[Range<usize>]) -> Vec<Range<usize>> {
+        let mut joined = String::new();
+        // Where each of `within` starts in `joined`
+        let mut starts = Vec::with_capacity(within.len());
+        for range in within {
+            if !starts.is_empty() {
+                joined.push('\n');
+            }
+            starts.push(joined.len());
+            joined.push_str(&text[range.clone()]);
+        }
+
+        let mut ret = Vec::new();
+        for found in self.ranges(&joined) {
+            let first = starts
+                .partition_point(|&start| start <= found.start)
+                .saturating_sub(1);
+            for (&start, range) in starts[first..].iter().zip(&within[first..]) {
+                if start >= found.end {
+                    break;
+                }
+                let from = found.start.max(start) - start + range.start;
+                let to = found.end.min(start + range.len()) - start + range.start;
+                push_range(&mut ret, from..to);
+            }
+        }
+        ret
+    }
+
+    /// Check only the text that `inner` selects from the output of this
+    /// filter, as for Markdown that contains HTML.
+    #[inline]
+    fn then<F: DocumentFilter>(self, inner: F) -> Then<Self, F>
+    where
+        Self: Sized,
+    {
+        Then { outer: self, inner }
+    }
+}
+
+impl<F: DocumentFilter + ?Sized> DocumentFilter for Box<F> {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        (**self).ranges(text)
+    }
+
+    #[inline]
+    fn ranges_within(&self, text: &str, within: &[Range<usize>]) -> Vec<Range<usize>> {
+        (**self).ranges_within(text, within)
+    }
+}
+
+/// Two filters applied one after the other, see [`DocumentFilter::then`]
+#[derive(Clone, Debug)]
+pub struct Then<A, B> {
+    outer: A,
+    inner: B,
+}
+
+impl<A: DocumentFilter, B: DocumentFilter> DocumentFilter for Then<A, B> {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let outer = self.outer.ranges(text);
+        self.inner.ranges_within(text, &outer)
+    }
+}
+
+/// A filter that checks all text
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Plain;
+
+impl DocumentFilter for Plain {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut ret = Vec::new();
+        push_range(&mut ret, 0..text.len());
+        ret
+    }
+}
+
+/// Pick a filter for files with the extension `ext`, ignoring case. Returns
+/// `None` if the extension is not known.
+///
+/// | Extensions                    | Filter                     |
+/// | ----------------------------- | -------------------------- |
+/// | `md`, `markdown`, `mkd`       | [`Markdown`] then [`Html`] |
+/// | `html`, `htm`, `xhtml`, `xml` | [`Html`]                   |
+/// | `tex`, `latex`, `ltx`         | [`Latex`]                  |
+/// | `eml`                         | [`Email`]                  |
+/// | `po`, `pot`                   | [`Gettext`]                |
+/// | `json`                        | [`Json`]                   |
+/// | `yaml`, `yml`                 | [`Yaml`]                   |
+/// | `txt`, `text`                 | [`Plain`]                  |
+///
+/// ```
+/// use zspell::filter::{self, DocumentFilter};
+///
+/// let filter = filter::for_extension("TEX").unwrap();
+/// let text = r"An \emph{example} with $x^2$";
+/// let selected: Vec<&str> = filter.ranges(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(selected, ["An ", "{example} with "]);
+///
+/// assert!(filter::for_extension("rs").is_none());
+/// ```
+#[inline]
+pub fn for_extension(ext: &str) -> Option<Box<dyn DocumentFilter>> {
+    let ext = ext.to_ascii_lowercase();
+    let filter: Box<dyn DocumentFilter> = match ext.as_str() {
+        "md" | "markdown" | "mkd" => Box::new(Markdown.then(Html)),
+        "html" | "htm" | "xhtml" | "xml" => Box::new(Html),
+        "tex" | "latex" | "ltx" => Box::new(Latex),
+        "eml" => Box::new(Email),
+        "po" | "pot" => Box::new(Gettext),
+        "json" => Box::new(Json::new()),
+        "yaml" | "yml" => Box::new(Yaml::new()),
+        "txt" | "text" => Box::new(Plain),
+        _ => return None,
+    };
+    Some(filter)
+}
+
+/// Add `range` to `out` if it is not empty
+fn push_range(out: &mut Vec<Range<usize>>, range: Range<usize>) {
+    if range.start < range.end {
+        out.push(range);
+    }
+}
+
+/// Add `range` of `text` to `out`, leaving out backslash escapes. `hex_digits`
+/// gives the number of hex digits expected after an escape character, such as
+/// 4 for `\u`. Malformed escapes end at the first character that is not a hex
+/// digit. Hex escapes usually stand for a letter or an apostrophe, so the whole
+/// word around them is left out rather than checking its pieces.
+fn push_unescaped(
+    out: &mut Vec<Range<usize>>,
+    text: &str,
+    range: Range<usize>,
+    hex_digits: fn(char) -> usize,
+) {
+    let mut last = range.start;
+    let mut idx = range.start;
+    while let Some(pos) = text[idx..range.end].find('\\') {
+        let esc = idx + pos;
+        let mut chars = text[esc + 1..range.end].chars();
+        let mut skip = esc..esc + 1;
+        if let Some(c) = chars.next() {
+            let digits = hex_digits(c);
+            // Hex digits are ASCII, so each is one byte
+            skip.end += c.len_utf8()
+                + chars
+                    .take(digits)
+                    .take_while(char::is_ascii_hexdigit)
+                    .count();
+            if digits > 0 {
+                skip = word_around(text, last..range.end, skip);
+            }
+        }
+        push_range(out, last..skip.start);
+        idx = skip.end;
+        last = idx;
+    }
+    push_range(out, last..range.end);
+}
+
+/// Widen `span` of `text` to cover the alphanumeric characters on either side
+/// of it, staying within `bounds`
+fn word_around(text: &str, bounds: Range<usize>, span: Range<usize>) -> Range<usize> {
+    let start = text[bounds.start..span.start]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric())
+        .last()
+        .map_or(span.start, |(i, _)| bounds.start + i);
+    let end = text[span.end..bounds.end]
+        .find(|c: char| !c.is_alphanumeric())
+        .map_or(bounds.end, |i| span.end + i);
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Return the text that `filter` selects from `text`
+    pub(super) fn selected<'a>(filter: &dyn DocumentFilter, text: &'a str) -> Vec<&'a str> {
+        filter.ranges(text).into_iter().map(|r| &text[r]).collect()
+    }
+
+    /// Selects words that start with an uppercase letter
+    #[derive(Debug)]
+    struct Capitalized;
+
+    impl DocumentFilter for Capitalized {
+        fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+            let mut ret = Vec::new();
+            let mut idx = 0;
+            for word in text.split(|c: char| !c.is_alphabetic()) {
+                if word.starts_with(char::is_uppercase) {
+                    ret.push(idx..idx + word.len());
+                }
+                idx += word.len() + 1;
+            }
+            ret
+        }
+    }
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(selected(&Plain, "some text"), ["some text"]);
+        assert!(Plain.ranges("").is_empty());
+    }
+
+    #[test]
+    fn test_ranges_within() {
+        let text = "One two Three four";
+        assert_eq!(
+            Capitalized.ranges_within(text, &[0..7, 8..18]),
+            [0..3, 8..13]
+        );
+        // Pieces are joined with newlines, so words do not run together
+        assert_eq!(
+            Capitalized.ranges_within(text, &[0..2, 2..13]),
+            [0..2, 8..13]
+        );
+        assert_eq!(Plain.ranges_within(text, &[4..7, 14..18]), [4..7, 14..18]);
+        assert!(Plain.ranges_within(text, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_then() {
+        let text = "Some `Code` and More\n```\nIn Block\n```\n";
+        let filter = Markdown.then(Capitalized);
+        assert_eq!(selected(&filter, text), ["Some", "More"]);
+
+        // A filter that spans lines sees the joined ranges
+        let filter = Plain.then(Markdown);
+        assert_eq!(selected(&filter, text), ["Some ", " and More"]);
+    }
+
+    #[test]
+    fn test_for_extension() {
+        let text = "<i>text</i> `code` $x$";
+        let md = for_extension("md").unwrap();
+        assert_eq!(selected(&md, text), ["text", " ", " $x$"]);
+        let html = for_extension("HTML").unwrap();
+        assert_eq!(selected(&html, text), ["text", " `code` $x$"]);
+        let tex = for_extension("tex").unwrap();
+        assert_eq!(selected(&tex, text), ["<i>text</i> `code` "]);
+        let plain = for_extension("txt").unwrap();
+        assert_eq!(selected(&plain, text), [text]);
+        assert!(for_extension("").is_none());
+    }
+}
@@ -0,0 +1,174 @@
+//! Email filter, see [`Email`]
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::{push_range, DocumentFilter};
+
+/// A filter for plain text email that only checks the writer's own text.
+///
+/// Headers at the start of the message (lines such as `Subject: text`,
+/// followed by a blank line), quoted lines starting with `>`, the
+/// "On ... wrote:" line before a quote, and everything after a signature
+/// separator (`-- `) or an "Original Message" or "Forwarded message" line are
+/// skipped.
+///
+/// ```
+/// use zspell::filter::{DocumentFilter, Email};
+///
+/// let text = "\
+/// Subject: Re: lunch
+/// From: Sam <sam@example.com>
+///
+/// Sounds good!
+///
+/// On Monday, Alex wrote:
+/// > Want to get lunch?
+///
+/// --
+/// Sam
+/// ";
+/// let selected: Vec<&str> = Email.ranges(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(selected, ["Sounds good!\n", "\n", "\n"]);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Email;
+
+impl DocumentFilter for Email {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut out = Vec::new();
+        let mut offset = headers_len(text);
+        let mut lines = text[offset..].split_inclusive('\n').peekable();
+
+        while let Some(line) = lines.next() {
+            let start = offset;
+            offset += line.len();
+            let content = line.trim_end_matches(['\n', '\r']);
+
+            if is_signature(content) || is_forward(content) {
+                break;
+            }
+            let before_quote = content.trim_end().ends_with("wrote:")
+                && lines.peek().map_or(false, |next| is_quoted(next));
+            if !is_quoted(content) && !before_quote {
+                push_range(&mut out, start..offset);
+            }
+        }
+
+        out
+    }
+}
+
+/// The length of the headers at the start of `text` and the blank line after
+/// them, or 0 if it does not start with headers
+fn headers_len(text: &str) -> usize {
+    let mut len = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            return if len == 0 { 0 } else { len + line.len() };
+        }
+        // A header, or the continuation of one
+        let is_header = if line.starts_with([' ', '\t']) {
+            len > 0
+        } else {
+            line.split_once(':').map_or(false, |(name, _)| {
+                !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+            })
+        };
+        if !is_header {
+            return 0;
+        }
+        len += line.len();
+    }
+    0
+}
+
+/// Whether a line is quoted from an earlier message
+fn is_quoted(line: &str) -> bool {
+    line.trim_start().starts_with('>')
+}
+
+/// Whether a line starts a signature
+fn is_signature(line: &str) -> bool {
+    line == "-- " || line == "--"
+}
+
+/// Whether a line starts a forwarded or quoted message, as written by clients
+/// that do not quote with `>`
+fn is_forward(line: &str) -> bool {
+    let line = line.trim().to_ascii_lowercase();
+    line.starts_with("-----")
+        && (line.contains("original message") || line.contains("forwarded message"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filter::tests::selected;
+
+    #[test]
+    fn test_headers() {
+        let text = "\
+From: Sam <sam@example.com>
+To: Alex <alex@example.com>,
+ Kim <kim@example.com>
+Subject: Notes
+
+Body: text
+";
+        assert_eq!(selected(&Email, text), ["Body: text\n"]);
+
+        // Without headers, the first line is checked
+        let text = "Hi Alex,\nThanks!";
+        assert_eq!(selected(&Email, text), ["Hi Alex,\n", "Thanks!"]);
+        assert_eq!(selected(&Email, "Note: text"), ["Note: text"]);
+        assert_eq!(
+            selected(&Email, "Note: text\nmore\n\nbody"),
+            ["Note: text\n", "more\n", "\n", "body"]
+        );
+    }
+
+    #[test]
+    fn test_quotes() {
+        let text = "\
+Inline reply
+> quoted
+>> nested
+  > indented
+reply again
+Alex wrote:
+not a quote
+";
+        assert_eq!(
+            selected(&Email, text),
+            [
+                "Inline reply\n",
+                "reply again\n",
+                "Alex wrote:\n",
+                "not a quote\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signature_and_forward() {
+        assert_eq!(selected(&Email, "text\n-- \nSam\n"), ["text\n"]);
+        assert_eq!(selected(&Email, "text\r\n--\r\nSam"), ["text\r\n"]);
+        assert_eq!(
+            selected(
+                &Email,
+                "see below\n---------- Forwarded message ---------\nFrom: x\n"
+            ),
+            ["see below\n"]
+        );
+        assert_eq!(
+            selected(&Email, "ok\n-----Original Message-----\nold"),
+            ["ok\n"]
+        );
+        // Other dashes are text
+        assert_eq!(selected(&Email, "a\n---\nb"), ["a\n", "---\n", "b"]);
+    }
+}
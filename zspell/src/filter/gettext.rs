@@ -0,0 +1,326 @@
+//! Gettext filter, see [`Gettext`]
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::{push_range, DocumentFilter};
+
+/// Characters that may appear between `%` and the conversion of a printf
+/// style placeholder, as in `%-5.2f`, `%1$s`, or `%lu`
+const PRINTF_MODIFIERS: &str = "0123456789$-+#'.*hlLqjzt";
+
+/// A filter for gettext `.po` and `.pot` files that only checks translations.
+///
+/// The text of `msgstr` entries is checked, while `msgid`, `msgctxt`,
+/// comments, and the header entry are skipped. Within translations, escapes
+/// such as `\n`, and placeholders such as `%s`, `%1$d`, `%(name)s`, and
+/// `{name}` are skipped.
+///
+/// Translations are in the language of the file, so they should be checked
+/// with a dictionary for that language, see
+/// [`language`](Self::language).
+///
+/// ```
+/// use zspell::filter::{DocumentFilter, Gettext};
+///
+/// let text = r#"
+/// msgid ""
+/// msgstr ""
+/// "Language: de\n"
+///
+/// #: src/main.c:10
+/// msgid "Hello, %s!"
+/// msgstr "Hallo, %s!"
+///
+/// msgid "{count} files"
+/// msgstr ""
+/// "{count} Dateien\n"
+/// "gefunden"
+/// "#;
+/// assert_eq!(Gettext::language(text), Some("de"));
+///
+/// let selected: Vec<&str> = Gettext.ranges(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(selected, ["Hallo, ", "!", " Dateien", "gefunden"]);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Gettext;
+
+impl Gettext {
+    /// The `Language` field of a `.po` file's header entry, such as `de` or
+    /// `pt_BR`, or `None` if it is missing or empty
+    #[inline]
+    pub fn language(text: &str) -> Option<&str> {
+        Entries::new(text)
+            .skip_while(|(field, _)| *field != Field::Header)
+            .take_while(|(field, _)| *field == Field::Header)
+            .find_map(|(_, range)| text[range].strip_prefix("Language:"))
+            .map(|lang| lang.strip_suffix("\\n").unwrap_or(lang).trim())
+            .filter(|lang| !lang.is_empty())
+    }
+}
+
+impl DocumentFilter for Gettext {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut out = Vec::new();
+        for (kind, range) in Entries::new(text) {
+            if kind == Field::Translation {
+                string_ranges(text, range, &mut out);
+            }
+        }
+        out
+    }
+}
+
+/// What a string in a `.po` file belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    /// A `msgstr` of the header entry
+    Header,
+    /// A `msgstr` of any other entry
+    Translation,
+    /// A `msgid`, `msgctxt`, or `msgid_plural`
+    Other,
+}
+
+/// Iterator over the quoted strings of a `.po` file, returning what each
+/// belongs to and the range of its content without quotes
+struct Entries<'a> {
+    text: &'a str,
+    offset: usize,
+    field: Field,
+    /// Whether the current entry has a `msgctxt`
+    has_context: bool,
+    /// Whether the current entry's `msgid` is empty so far
+    empty_id: bool,
+}
+
+impl<'a> Entries<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            offset: 0,
+            field: Field::Other,
+            has_context: false,
+            empty_id: true,
+        }
+    }
+}
+
+impl Iterator for Entries<'_> {
+    type Item = (Field, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.offset < self.text.len() {
+            let rest = &self.text[self.offset..];
+            let line_len = rest.find('\n').map_or(rest.len(), |p| p + 1);
+            let start = self.offset;
+            self.offset += line_len;
+
+            let line = rest[..line_len].trim();
+            let keyword = line.split(|c: char| c.is_whitespace() || c == '"').next();
+            match keyword {
+                Some("") => (),
+                Some(kw) if kw.starts_with('#') => {
+                    // Comments start an entry
+                    self.field = Field::Other;
+                    self.has_context = false;
+                    continue;
+                }
+                Some("msgctxt") => {
+                    self.field = Field::Other;
+                    self.has_context = true;
+                    self.empty_id = true;
+                }
+                Some("msgid") => {
+                    if self.field != Field::Other {
+                        self.has_context = false;
+                    }
+                    self.field = Field::Other;
+                    self.empty_id = true;
+                }
+                Some(kw) if kw.starts_with("msgstr") => {
+                    self.field = if self.empty_id && !self.has_context {
+                        Field::Header
+                    } else {
+                        Field::Translation
+                    };
+                }
+                _ => self.field = Field::Other,
+            }
+
+            // The string is everything between the first and last quote
+            let raw = &rest[..line_len];
+            let (Some(open), Some(close)) = (raw.find('"'), raw.rfind('"')) else {
+                continue;
+            };
+            if open == close {
+                continue;
+            }
+            let range = start + open + 1..start + close;
+            if keyword == Some("msgid") || (keyword == Some("") && self.field == Field::Other) {
+                self.empty_id &= range.is_empty();
+            }
+            return Some((self.field, range));
+        }
+        None
+    }
+}
+
+/// Add the ranges of a string's content to `out`, skipping escapes and
+/// placeholders
+fn string_ranges(text: &str, range: Range<usize>, out: &mut Vec<Range<usize>>) {
+    let s = &text[range.clone()];
+    let mut last = 0;
+    let mut idx = 0;
+
+    while let Some(pos) = s[idx..].find(['\\', '%', '{']) {
+        let start = idx + pos;
+        let rest = &s[start..];
+        let len = match rest.as_bytes()[0] {
+            b'\\' => 1 + rest[1..].chars().next().map_or(0, char::len_utf8),
+            b'%' => printf_len(rest),
+            _ => brace_len(rest),
+        };
+        if len == 0 {
+            idx = start + 1;
+            continue;
+        }
+        push_range(out, range.start + last..range.start + start);
+        idx = start + len;
+        last = idx;
+    }
+
+    push_range(out, range.start + last..range.end);
+}
+
+/// The length of a printf style placeholder at the start of `rest`, or 0 if
+/// there is not one
+fn printf_len(rest: &str) -> usize {
+    let mut idx = 1;
+    if rest[idx..].starts_with('%') {
+        return 2;
+    }
+    // Python's `%(name)s`
+    if rest[idx..].starts_with('(') {
+        match rest.find(')') {
+            Some(close) => idx = close + 1,
+            None => return 0,
+        }
+    }
+    let modifiers = rest[idx..]
+        .find(|c| !PRINTF_MODIFIERS.contains(c))
+        .unwrap_or(rest.len() - idx);
+    let digits = rest[idx..idx + modifiers]
+        .chars()
+        .all(|c| c.is_ascii_digit());
+    idx += modifiers;
+
+    if rest[idx..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+        idx + 1
+    } else if modifiers > 0 && digits {
+        // Qt's `%1`
+        idx
+    } else {
+        0
+    }
+}
+
+/// The length of a placeholder such as `{name}` or `{0:.2f}` at the start of
+/// `rest`, or of an escaped `{{`, or 0 if there is not one
+fn brace_len(rest: &str) -> usize {
+    if rest.starts_with("{{") {
+        return 2;
+    }
+    let Some(close) = rest.find('}') else {
+        return 0;
+    };
+    let inner = &rest[1..close];
+    if inner
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | ':' | '!' | '-' | '[' | ']'))
+    {
+        close + 1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filter::tests::selected;
+
+    const PO: &str = r#"# Translation of zspell
+msgid ""
+msgstr ""
+"Project-Id-Version: zspell\n"
+"Language: pt_BR\n"
+
+#, fuzzy, c-format
+#| msgid "Old text"
+msgid "Found %d words"
+msgstr "Encontrei %d palavras"
+
+msgctxt ""
+msgid ""
+msgstr "Vazio"
+
+msgid "One file"
+msgid_plural "%(count)s files"
+msgstr[0] "Um arquivo"
+msgstr[1] "%(count)s arquivos"
+
+#~ msgid "Obsolete"
+#~ msgstr "Obsoleto"
+"#;
+
+    #[test]
+    fn test_gettext() {
+        assert_eq!(
+            selected(&Gettext, PO),
+            [
+                "Encontrei ",
+                " palavras",
+                "Vazio",
+                "Um arquivo",
+                " arquivos"
+            ]
+        );
+        assert_eq!(Gettext::language(PO), Some("pt_BR"));
+        assert_eq!(Gettext::language("msgid \"a\"\nmsgstr \"b\""), None);
+        assert_eq!(
+            Gettext::language("msgid \"\"\nmsgstr \"Language: \\n\""),
+            None
+        );
+    }
+
+    #[test]
+    fn test_template() {
+        // Templates have no translations
+        let pot = "msgid \"\"\nmsgstr \"\"\n\"Language: \\n\"\n\nmsgid \"Text\"\nmsgstr \"\"\n";
+        assert!(Gettext.ranges(pot).is_empty());
+    }
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_placeholders() {
+        let cases: &[(&str, &[&str])] = &[
+            (r#"a\nb\tc\"d\\"#, &["a", "b", "c", "d"]),
+            ("%s %5.2f %-3d %1$s %ld %%", &[" ", " ", " ", " ", " "]),
+            ("%(name)s %1 %", &[" ", " %"]),
+            ("50% off", &["50% off"]),
+            (
+                "{name} {0} {0:.2f} {{x}} {not one}",
+                &[" ", " ", " ", "x}} {not one}"],
+            ),
+        ];
+        for (s, expected) in cases {
+            let text = ["msgid \"x\"\nmsgstr \"", s, "\"\n"].concat();
+            let found = selected(&Gettext, &text);
+            assert_eq!(&found, expected, "for {s:?}");
+        }
+    }
+}
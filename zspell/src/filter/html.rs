@@ -0,0 +1,288 @@
+//! HTML and XML filter, see [`Html`]
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::{push_range, DocumentFilter};
+
+/// Elements whose content is code rather than prose
+const SKIP_ELEMENTS: &[&str] = &["code", "kbd", "pre", "samp", "script", "style"];
+
+/// Attributes whose values are shown to readers, so they are checked
+const CHECKED_ATTRIBUTES: &[&str] = &["alt", "aria-label", "placeholder", "title"];
+
+/// A filter for HTML and XML that checks text and the values of attributes
+/// that readers see, such as `alt` and `title`.
+///
+/// Tags, comments, character references such as `&amp;`, and the content of
+/// elements such as `<code>`, `<script>`, and `<style>` are skipped. The
+/// content of `CDATA` sections is checked as text.
+///
+/// ```
+/// use zspell::filter::{DocumentFilter, Html};
+///
+/// let text = r#"<p class="intro">Use <code>zspell</code> &mdash; it's fast</p>"#;
+/// let selected: Vec<&str> = Html.ranges(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(selected, ["Use ", " ", " it's fast"]);
+///
+/// let text = r#"<img src="cat.png" alt="A sleeping cat">"#;
+/// let selected: Vec<&str> = Html.ranges(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(selected, ["A sleeping cat"]);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Html;
+
+impl DocumentFilter for Html {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut out = Vec::new();
+        let mut last = 0;
+        let mut idx = 0;
+
+        while let Some(pos) = text[idx..].find('<') {
+            let start = idx + pos;
+            let rest = &text[start..];
+
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                push_text(&mut out, text, last..start);
+                let content = start + rest.len() - cdata.len();
+                let end = cdata.find("]]>").map_or(text.len(), |p| content + p);
+                push_range(&mut out, content..end);
+                idx = (end + 3).min(text.len());
+                last = idx;
+                continue;
+            }
+
+            let mut attrs = Vec::new();
+            let len = if rest.starts_with("<!--") {
+                rest.find("-->").map_or(rest.len(), |p| p + 3)
+            } else if let Some(len) = element_len(rest, start, &mut attrs) {
+                len
+            } else {
+                idx = start + 1;
+                continue;
+            };
+
+            push_text(&mut out, text, last..start);
+            out.extend(attrs);
+            idx = start + len;
+            last = idx;
+        }
+
+        push_text(&mut out, text, last..text.len());
+        out
+    }
+}
+
+/// The length of a tag at the start of `rest`, including the content and
+/// closing tag of elements in [`SKIP_ELEMENTS`]. The values of attributes in
+/// [`CHECKED_ATTRIBUTES`] are added to `out`, offset by `offset`. Returns
+/// `None` if `rest` does not start with a tag, as in `a < b`.
+fn element_len(rest: &str, offset: usize, out: &mut Vec<Range<usize>>) -> Option<usize> {
+    let after = &rest[1..];
+    if after.starts_with(['!', '?']) {
+        // A declaration or processing instruction, such as `<!DOCTYPE html>`
+        return Some(rest.find('>').map_or(rest.len(), |p| p + 1));
+    }
+    let name_start = usize::from(after.starts_with('/')) + 1;
+    if !rest[name_start..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let name_len = rest[name_start..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')))
+        .unwrap_or(rest.len() - name_start);
+    let name = &rest[name_start..name_start + name_len];
+    let (tag_end, self_closing) = attributes(rest, name_start + name_len, offset, out);
+
+    if name_start > 1
+        || self_closing
+        || !SKIP_ELEMENTS.iter().any(|el| el.eq_ignore_ascii_case(name))
+    {
+        return Some(tag_end);
+    }
+
+    // Skip to the end of the matching closing tag
+    let content = &rest[tag_end..];
+    let close = content.match_indices("</").find(|&(pos, _)| {
+        let close_name = &content[pos + 2..];
+        close_name.len() >= name.len()
+            && close_name.is_char_boundary(name.len())
+            && close_name[..name.len()].eq_ignore_ascii_case(name)
+            && !close_name[name.len()..].starts_with(|c: char| c.is_ascii_alphanumeric())
+    });
+    Some(close.map_or(rest.len(), |(pos, _)| {
+        let close_end = content[pos..]
+            .find('>')
+            .map_or(content.len(), |p| pos + p + 1);
+        tag_end + close_end
+    }))
+}
+
+/// Read the attributes of a tag in `rest`, starting at `idx`. Values of
+/// attributes in [`CHECKED_ATTRIBUTES`] are added to `out`, offset by
+/// `offset`. Returns the length of the tag and whether it ends with `/>`.
+fn attributes(
+    rest: &str,
+    mut idx: usize,
+    offset: usize,
+    out: &mut Vec<Range<usize>>,
+) -> (usize, bool) {
+    loop {
+        let trimmed = rest[idx..].trim_start();
+        idx = rest.len() - trimmed.len();
+        if trimmed.starts_with('>') {
+            return (idx + 1, false);
+        } else if trimmed.starts_with("/>") {
+            return (idx + 2, true);
+        } else if trimmed.is_empty() {
+            return (rest.len(), false);
+        }
+
+        let name_len = trimmed
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+            .unwrap_or(trimmed.len())
+            // A stray `/` inside a tag
+            .max(1);
+        let name = &trimmed[..name_len];
+        idx += name_len;
+
+        let after_name = rest[idx..].trim_start();
+        let Some(value) = after_name.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let value_start = rest.len() - value.len();
+        let value_range = if let Some(quote @ ('"' | '\'')) = value.chars().next() {
+            let end = value[1..].find(quote).map_or(value.len(), |p| p + 1);
+            idx = value_start + (end + 1).min(value.len());
+            value_start + 1..value_start + end
+        } else {
+            let end = value
+                .find(|c: char| c.is_whitespace() || c == '>')
+                .unwrap_or(value.len());
+            idx = value_start + end;
+            value_start..value_start + end
+        };
+
+        if CHECKED_ATTRIBUTES
+            .iter()
+            .any(|attr| attr.eq_ignore_ascii_case(name))
+        {
+            let from = out.len();
+            push_text(out, rest, value_range);
+            for range in &mut out[from..] {
+                *range = offset + range.start..offset + range.end;
+            }
+        }
+    }
+}
+
+/// Add `range` of `text` to `out`, skipping character references
+fn push_text(out: &mut Vec<Range<usize>>, text: &str, range: Range<usize>) {
+    let mut last = range.start;
+    let mut idx = range.start;
+    while let Some(pos) = text[idx..range.end].find('&') {
+        let start = idx + pos;
+        match entity_len(&text[start..range.end]) {
+            Some(len) => {
+                push_range(out, last..start);
+                last = start + len;
+                idx = last;
+            }
+            None => idx = start + 1,
+        }
+    }
+    push_range(out, last..range.end);
+}
+
+/// The length of a character reference such as `&amp;` or `&#8212;` at the
+/// start of `rest`, or `None` if there is not one
+fn entity_len(rest: &str) -> Option<usize> {
+    let after = &rest[1..];
+    let name = after.strip_prefix('#').unwrap_or(after);
+    let name_len = name.find(|c: char| !c.is_ascii_alphanumeric())?;
+    let len = rest.len() - name.len() + name_len;
+    (name_len > 0 && rest[len..].starts_with(';')).then_some(len + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filter::tests::selected;
+
+    #[test]
+    fn test_html() {
+        let text = "\
+<!DOCTYPE html>
+<html><!-- a
+comment --><body>
+<h1 id=\"top\">Title</h1>
+<p>Text with <em>tags</em>, a &lt; b &amp; c, AT&T</p>
+<script type=\"module\">let x = \"<p>\";</script>
+<PRE>
+code</pre><br/>end
+</body></html>
+";
+        assert_eq!(
+            selected(&Html, text),
+            [
+                "\n",
+                "\n",
+                "Title",
+                "\n",
+                "Text with ",
+                "tags",
+                ", a ",
+                " b ",
+                " c, AT&T",
+                "\n",
+                "\n",
+                "end\n",
+                "\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_not_tags() {
+        assert_eq!(selected(&Html, "a < b, 1<2 & 3"), ["a < b, 1<2 & 3"]);
+        // Unclosed tags and comments run to the end
+        assert_eq!(selected(&Html, "text <a href="), ["text "]);
+        assert_eq!(selected(&Html, "text <!-- x"), ["text "]);
+        assert_eq!(selected(&Html, "text <code>x"), ["text "]);
+    }
+
+    #[test]
+    fn test_skip_elements() {
+        // Closing tags must match the whole name
+        assert_eq!(
+            selected(&Html, "<pre>a</prelude>b</pre>c<code/>d"),
+            ["c", "d"]
+        );
+        assert_eq!(selected(&Html, "<preview>a</preview>"), ["a"]);
+        assert_eq!(selected(&Html, "<style a='>'>b</style>c"), ["c"]);
+    }
+
+    #[test]
+    fn test_attributes() {
+        let text = r#"<a href="x.html" title="Tom &amp; Jery" data-x='>'>link</a>"#;
+        assert_eq!(selected(&Html, text), ["Tom ", " Jery", "link"]);
+
+        let text = "a <img alt=unquoted TITLE = 'single' hidden alt=\"\"/> b";
+        assert_eq!(selected(&Html, text), ["a ", "unquoted", "single", " b"]);
+
+        // Unclosed values run to the end
+        assert_eq!(selected(&Html, "<img alt=\"text"), ["text"]);
+    }
+
+    #[test]
+    fn test_xml() {
+        let text = "\
+<?xml version=\"1.0\"?>
+<note xml:lang=\"en\"><to>Tove</to><![CDATA[a <b> & c]]></note>";
+        assert_eq!(selected(&Html, text), ["\n", "Tove", "a <b> & c"]);
+    }
+}
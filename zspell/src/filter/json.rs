@@ -0,0 +1,243 @@
+//! JSON filter, see [`Json`]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::keys::KeyPatterns;
+use super::{push_unescaped, DocumentFilter};
+
+/// A filter for JSON that only checks string values, such as the text of a
+/// file of UI strings.
+///
+/// Keys, numbers, and other syntax are skipped, as are escapes such as `\n`
+/// and `\u00e9` inside strings. `//` and `/* */` comments are skipped too, so
+/// it also works for JSON with comments.
+///
+/// Values can be limited to those under certain keys with
+/// [`with_keys`](Self::with_keys).
+///
+/// ```
+/// use zspell::filter::{DocumentFilter, Json};
+///
+/// let text = r#"{"id": "save", "label": "Save file", "sizes": [1, 2]}"#;
+/// let selected: Vec<&str> = Json::new().ranges(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(selected, ["save", "Save file"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Json {
+    keys: KeyPatterns,
+}
+
+impl Json {
+    /// A filter that checks all string values
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only check string values under keys that match one of `patterns`, in
+    /// which `*` matches any run of characters.
+    ///
+    /// Patterns without a `.` match the nearest key of a value, so `title` or
+    /// `*_label`. Patterns with a `.` match the path of keys to a value joined
+    /// with `.`, so `menu.*` matches everything under a top level `menu` key.
+    /// Arrays do not add to the path. Values that are not under any key are
+    /// not checked.
+    ///
+    /// ```
+    /// use zspell::filter::{DocumentFilter, Json};
+    ///
+    /// let text = r#"{"menu": {"id": "open", "title": "Open"}, "tips": ["Try this"]}"#;
+    /// let filter = Json::new().with_keys(["title", "tips"]);
+    /// let selected: Vec<&str> = filter.ranges(text).into_iter().map(|r| &text[r]).collect();
+    /// assert_eq!(selected, ["Open", "Try this"]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_keys<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.keys = KeyPatterns::new(patterns);
+        self
+    }
+}
+
+/// An object or array that contains the current position
+struct Frame<'a> {
+    is_object: bool,
+    /// The key of the current member of an object
+    key: Option<&'a str>,
+    /// Whether the next string in an object is a key
+    expect_key: bool,
+}
+
+impl DocumentFilter for Json {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut out = Vec::new();
+        let mut stack: Vec<Frame> = Vec::new();
+        let bytes = text.as_bytes();
+        let mut idx = 0;
+
+        while idx < bytes.len() {
+            match bytes[idx] {
+                b'"' => {
+                    let end = string_end(text, idx + 1);
+                    let content = idx + 1..end;
+                    match stack.last_mut() {
+                        Some(frame) if frame.is_object && frame.expect_key => {
+                            frame.key = Some(&text[content]);
+                        }
+                        _ => {
+                            if self.keys.matches(stack.iter().filter_map(|f| f.key)) {
+                                push_string(&mut out, text, content);
+                            }
+                        }
+                    }
+                    idx = end;
+                }
+                b':' => {
+                    if let Some(frame) = stack.last_mut() {
+                        frame.expect_key = false;
+                    }
+                }
+                b',' => {
+                    if let Some(frame) = stack.last_mut().filter(|f| f.is_object) {
+                        frame.expect_key = true;
+                        frame.key = None;
+                    }
+                }
+                open @ (b'{' | b'[') => stack.push(Frame {
+                    is_object: open == b'{',
+                    key: None,
+                    expect_key: open == b'{',
+                }),
+                b'}' | b']' => {
+                    stack.pop();
+                }
+                b'/' if bytes.get(idx + 1) == Some(&b'/') => {
+                    idx = text[idx..].find('\n').map_or(text.len(), |p| idx + p);
+                }
+                b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                    idx = text[idx + 2..]
+                        .find("*/")
+                        .map_or(text.len(), |p| idx + p + 3);
+                }
+                _ => (),
+            }
+            idx += 1;
+        }
+
+        out
+    }
+}
+
+/// The index of the quote that ends a string whose content starts at `start`,
+/// or the end of `text` if it is not closed
+fn string_end(text: &str, start: usize) -> usize {
+    let mut escaped = false;
+    for (idx, c) in text[start..].char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            return start + idx;
+        }
+    }
+    text.len()
+}
+
+/// Add the content of a string to `out`, skipping escapes
+fn push_string(out: &mut Vec<Range<usize>>, text: &str, range: Range<usize>) {
+    push_unescaped(out, text, range, |c| if c == 'u' { 4 } else { 0 });
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filter::tests::selected;
+
+    const TEXT: &str = r#"{
+  // Labels
+  "menu": {
+    "open": {"id": "menu-open", "label": "Open a file"},
+    "recent": ["First", "Second"],
+    /* not "checked" */
+    "count": 3, "shown": true, "none": null
+  },
+  "errors": {"io": "Could not read \"{path}\"\n", "accent": "caf\u00e9 ok"}
+}"#;
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            selected(&Json::new(), TEXT),
+            [
+                "menu-open",
+                "Open a file",
+                "First",
+                "Second",
+                "Could not read ",
+                "{path}",
+                " ok"
+            ]
+        );
+        // Top level values and arrays
+        assert_eq!(
+            selected(&Json::new(), r#"["a", ["b"]] "c""#),
+            ["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn test_keys() {
+        let filter = Json::new().with_keys(["label", "recent"]);
+        assert_eq!(selected(&filter, TEXT), ["Open a file", "First", "Second"]);
+
+        let filter = Json::new().with_keys(["errors.*"]);
+        assert_eq!(
+            selected(&filter, TEXT),
+            ["Could not read ", "{path}", " ok"]
+        );
+
+        let filter = Json::new().with_keys(["*"]);
+        assert_eq!(selected(&filter, r#"["top", {"k": "v"}]"#), ["v"]);
+    }
+
+    #[test]
+    fn test_unclosed() {
+        assert_eq!(selected(&Json::new(), r#"{"a": "text"#), ["text"]);
+        assert_eq!(selected(&Json::new(), r#""a\"#), ["a"]);
+        assert!(Json::new().ranges("/* x").is_empty());
+    }
+
+    #[test]
+    fn test_malformed_escape() {
+        assert_eq!(
+            selected(&Json::new(), r#"{"a": "bad \u000é escape"}"#),
+            ["bad ", " escape"]
+        );
+        assert!(selected(&Json::new(), r#"["\uzz", "\u00"]"#).is_empty());
+    }
+
+    #[test]
+    fn test_escaped_words() {
+        let dict = crate::builder()
+            .config_str("")
+            .dict_str("3\ncafé\ndon't\nit's")
+            .build()
+            .unwrap();
+        let checker = crate::Checker::builder(dict)
+            .filter(Json::new())
+            .build()
+            .unwrap();
+        let text = r#"{"a": "caf\u00e9", "b": "don\u0027t"}"#;
+        assert!(checker.check("a", text).is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+//! Key patterns for the structured filters, see [`Json::with_keys`]
+//!
+//! [`Json::with_keys`]: super::Json::with_keys
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Patterns that select the values of a structured file to check by their
+/// keys. With no patterns, all values are checked.
+#[derive(Clone, Debug, Default)]
+pub(super) struct KeyPatterns(Vec<String>);
+
+impl KeyPatterns {
+    pub(super) fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(patterns.into_iter().map(Into::into).collect())
+    }
+
+    /// Whether a value under `keys`, outermost first, should be checked.
+    /// Patterns with a `.` match the path of keys joined with `.`, others match
+    /// the innermost key.
+    pub(super) fn matches<'a, I>(&self, keys: I) -> bool
+    where
+        I: DoubleEndedIterator<Item = &'a str> + Clone,
+    {
+        if self.0.is_empty() {
+            return true;
+        }
+        let last = keys.clone().next_back();
+        // Joined the first time a pattern needs it
+        let mut keys = Some(keys);
+        let mut path = String::new();
+        self.0.iter().any(|pattern| {
+            if pattern.contains('.') {
+                if let Some(keys) = keys.take() {
+                    path = keys.collect::<Vec<_>>().join(".");
+                }
+                wildcard_match(pattern, &path)
+            } else {
+                last.map_or(false, |key| wildcard_match(pattern, key))
+            }
+        })
+    }
+}
+
+/// Whether `s` matches `pattern`, where `*` matches any run of characters
+fn wildcard_match(pattern: &str, s: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = s.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        let Some(pos) = rest.find(part) else {
+            return false;
+        };
+        rest = &rest[pos + part.len()..];
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("title", "title"));
+        assert!(!wildcard_match("title", "titles"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("*_label", "save_label"));
+        assert!(!wildcard_match("*_label", "label"));
+        assert!(wildcard_match("a*b*c", "abxbc"));
+        assert!(!wildcard_match("a*bc", "abc_"));
+        assert!(!wildcard_match("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn test_matches() {
+        let patterns = KeyPatterns::new(["title", "errors.*"]);
+        assert!(patterns.matches(["menu", "title"].into_iter()));
+        assert!(patterns.matches(["errors", "io", "text"].into_iter()));
+        assert!(!patterns.matches(["menu", "id"].into_iter()));
+        assert!(!patterns.matches(core::iter::empty()));
+        assert!(KeyPatterns::default().matches(core::iter::empty()));
+    }
+}
@@ -0,0 +1,358 @@
+//! LaTeX filter, see [`Latex`]
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::{push_range, DocumentFilter};
+
+/// Environments whose content is code or math rather than prose
+const SKIP_ENVIRONMENTS: &[&str] = &[
+    "align",
+    "align*",
+    "alignat",
+    "alignat*",
+    "comment",
+    "displaymath",
+    "eqnarray",
+    "eqnarray*",
+    "equation",
+    "equation*",
+    "filecontents",
+    "filecontents*",
+    "flalign",
+    "flalign*",
+    "gather",
+    "gather*",
+    "lstlisting",
+    "math",
+    "minted",
+    "multline",
+    "multline*",
+    "tikzpicture",
+    "verbatim",
+    "verbatim*",
+    "Verbatim",
+];
+
+/// Commands whose arguments are names, keys, lengths, or paths rather than
+/// prose, with the number of `{...}` arguments to skip. Optional `[...]`
+/// arguments before each of them are skipped too.
+const SKIP_ARGUMENTS: &[(&str, usize)] = &[
+    ("addbibresource", 1),
+    ("addtolength", 2),
+    ("autoref", 1),
+    ("bibitem", 1),
+    ("bibliography", 1),
+    ("bibliographystyle", 1),
+    ("cite", 1),
+    ("citep", 1),
+    ("citet", 1),
+    ("color", 1),
+    ("cpageref", 1),
+    ("Cref", 1),
+    ("cref", 1),
+    ("definecolor", 3),
+    ("documentclass", 1),
+    ("end", 1),
+    ("eqref", 1),
+    ("graphicspath", 1),
+    ("href", 1),
+    ("hspace", 1),
+    ("hypersetup", 1),
+    ("include", 1),
+    ("includegraphics", 1),
+    ("input", 1),
+    ("inputminted", 2),
+    ("label", 1),
+    ("lstinputlisting", 1),
+    ("nameref", 1),
+    ("newcommand", 2),
+    ("newenvironment", 3),
+    ("nocite", 1),
+    ("pageref", 1),
+    ("pagenumbering", 1),
+    ("pagestyle", 1),
+    ("ref", 1),
+    ("renewcommand", 2),
+    ("renewenvironment", 3),
+    ("RequirePackage", 1),
+    ("setcounter", 2),
+    ("setlength", 2),
+    ("textcolor", 1),
+    ("thispagestyle", 1),
+    ("url", 1),
+    ("usepackage", 1),
+    ("usetikzlibrary", 1),
+    ("vref", 1),
+    ("vspace", 1),
+];
+
+/// Where the body of a full document starts
+const BEGIN_DOCUMENT: &str = "\\begin{document}";
+
+/// Where the body of a full document ends. Anything after it is not typeset.
+const END_DOCUMENT: &str = "\\end{document}";
+
+/// A filter for LaTeX that checks prose, including section titles, captions,
+/// and footnotes.
+///
+/// It skips commands, comments, inline and display math, environments such
+/// as `verbatim` and `equation`, and `\verb`. The arguments of commands like
+/// `\label`, `\ref`, and `\cite` are skipped since they are names rather than
+/// prose. If there is a `\begin{document}`, only the text between it and
+/// `\end{document}` is checked, which skips the preamble.
+///
+/// ```
+/// use zspell::filter::{DocumentFilter, Latex};
+///
+/// let text = r"\section{Intro} See~\cite{knuth}, where $a^2$ holds. % note";
+/// let selected: Vec<&str> = Latex.ranges(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(selected, ["{Intro} See~", ", where ", " holds. "]);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Latex;
+
+impl DocumentFilter for Latex {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut idx = 0;
+        let mut text = text;
+        if let Some(pos) = text.find(BEGIN_DOCUMENT) {
+            idx = pos + BEGIN_DOCUMENT.len();
+            if let Some(end) = text[idx..].find(END_DOCUMENT) {
+                text = &text[..idx + end];
+            }
+        }
+
+        let mut out = Vec::new();
+        let mut last = idx;
+        while let Some(pos) = text[idx..].find(['\\', '%', '$']) {
+            let start = idx + pos;
+            let rest = &text[start..];
+            let len = if rest.starts_with('%') {
+                rest.find('\n').unwrap_or(rest.len())
+            } else if let Some(math) = rest.strip_prefix("$$") {
+                find_end(math, "$$").map_or(rest.len(), |p| p + 4)
+            } else if let Some(math) = rest.strip_prefix('$') {
+                find_end(math, "$").map_or(rest.len(), |p| p + 2)
+            } else {
+                command_len(rest)
+            };
+
+            push_range(&mut out, last..start);
+            idx = start + len;
+            last = idx;
+        }
+
+        push_range(&mut out, last..text.len());
+        out
+    }
+}
+
+/// The length of a command at the start of `rest`, including anything after it
+/// that should be skipped
+fn command_len(rest: &str) -> usize {
+    let name_len = rest[1..]
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len() - 1);
+    let name = &rest[1..=name_len];
+
+    if name.is_empty() {
+        // A control symbol such as `\%`, or math with `\(` or `\[`
+        let close = match rest[1..].chars().next() {
+            Some('(') => "\\)",
+            Some('[') => "\\]",
+            // A line break, with an optional length as in `\\[2pt]`
+            Some('\\') if rest[2..].starts_with('[') => {
+                return 2 + group_len(&rest[2..]).unwrap_or(0);
+            }
+            Some(c) => return 1 + c.len_utf8(),
+            None => return 1,
+        };
+        return find_end(&rest[2..], close).map_or(rest.len(), |p| p + 2 + close.len());
+    }
+
+    let mut len = 1 + name_len;
+    if rest[len..].starts_with('*') {
+        len += 1;
+    }
+
+    if name == "verb" {
+        // The argument is between two of any character, as in `\verb|x|`
+        let Some(delim) = rest[len..].chars().next() else {
+            return len;
+        };
+        let arg = &rest[len + delim.len_utf8()..];
+        return arg.find(delim).map_or(rest.len(), |p| {
+            rest.len() - arg.len() + p + delim.len_utf8()
+        });
+    }
+
+    if name == "begin" {
+        let Some(env_len) = group_len(&rest[len..]) else {
+            return len;
+        };
+        let env = &rest[len + 1..len + env_len - 1];
+        len += env_len;
+        if SKIP_ENVIRONMENTS.contains(&env) {
+            let end = ["\\end{", env, "}"].concat();
+            return rest[len..]
+                .find(&end)
+                .map_or(rest.len(), |p| len + p + end.len());
+        }
+        // Arguments such as the columns of a table
+        while let Some(arg_len) = group_len(&rest[len..]) {
+            len += arg_len;
+        }
+    } else if let Some(&(_, count)) = SKIP_ARGUMENTS.iter().find(|(cmd, _)| *cmd == name) {
+        for _ in 0..count {
+            while rest[len..].starts_with('[') {
+                let Some(arg_len) = group_len(&rest[len..]) else {
+                    break;
+                };
+                len += arg_len;
+            }
+            let Some(arg_len) = group_len(&rest[len..]) else {
+                break;
+            };
+            len += arg_len;
+        }
+    }
+
+    len
+}
+
+/// The length of a `{...}` or `[...]` group at the start of `s`, allowing nested
+/// groups of the same kind. Returns `None` if `s` does not start with a group or
+/// the group is not closed.
+fn group_len(s: &str) -> Option<usize> {
+    let (open, close) = match s.chars().next()? {
+        '{' => ('{', '}'),
+        '[' => ('[', ']'),
+        _ => return None,
+    };
+    let mut depth = 0;
+    let mut escaped = false;
+    for (idx, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(idx + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Find `end` in `s`, skipping any that are escaped with a backslash
+fn find_end(s: &str, end: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (idx, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if s[idx..].starts_with(end) {
+            return Some(idx);
+        } else if c == '\\' {
+            escaped = true;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filter::tests::selected;
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_latex() {
+        let text = r"\documentclass[12pt]{article}
+\usepackage{amsmath} % math
+\begin{document}
+\section*{Introduction}\label{sec:intro}
+Costs 5\% more, see \ref{fig} and \href{https://x.y}{the site}.
+\begin{equation}
+  e = mc^2 \text{ words}
+\end{equation}
+\begin{tabular}{ll} a & b \end{tabular}
+Inline \(x\), display \[ y \] and $$ z $$ or $\$5$.
+\end{document}
+";
+        assert_eq!(
+            selected(&Latex, text),
+            [
+                "\n",
+                "{Introduction}",
+                "\nCosts 5",
+                " more, see ",
+                " and ",
+                "{the site}.\n",
+                "\n",
+                " a & b ",
+                "\nInline ",
+                ", display ",
+                " and ",
+                " or ",
+                ".\n"
+            ]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_commands() {
+        let text = r"\newcommand{\R}{\mathbb{R}}
+\begin{document}
+\begin{figure}[htbp]
+  \includegraphics[width=\linewidth]{plots/errr.pdf}
+  \caption[Short captoin]{A long captoin.}\label{fig:plot}
+\end{figure}
+Use \verb|x_{wrng}| or \verb*+y+ and \textcolor{blue}{colored txt}.\\[2pt]
+\setlength{\parskip}{1em}\vspace*{3mm}See \Cref{fig:plot}.
+\end{document}
+Ignored aftr the end.
+";
+        assert_eq!(
+            selected(&Latex, text),
+            [
+                "\n",
+                "\n  ",
+                "\n  ",
+                "[Short captoin]{A long captoin.}",
+                "\n",
+                "\nUse ",
+                " or ",
+                " and ",
+                "{colored txt}.",
+                "\n",
+                "See ",
+                ".\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unclosed() {
+        assert_eq!(selected(&Latex, r"a $b"), ["a "]);
+        assert_eq!(selected(&Latex, r"a \begin{verbatim} b"), ["a "]);
+        assert_eq!(selected(&Latex, r"a \label{b"), ["a ", "{b"]);
+        assert_eq!(selected(&Latex, "a \\"), ["a "]);
+    }
+
+    #[test]
+    fn test_group_len() {
+        assert_eq!(group_len("{a{b}c}d"), Some(7));
+        assert_eq!(group_len(r"[a\]b]c"), Some(6));
+        assert_eq!(group_len("{a"), None);
+        assert_eq!(group_len("a"), None);
+    }
+}
@@ -0,0 +1,155 @@
+//! Markdown filter, see [`Markdown`]
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::{push_range, DocumentFilter};
+
+/// A filter for Markdown that skips fenced code blocks, code spans, and URLs.
+///
+/// HTML is left as is, chain [`Html`](super::Html) after this filter to skip
+/// tags.
+///
+/// ```
+/// use zspell::filter::{DocumentFilter, Markdown};
+///
+/// let text = "Run `cargo test`, see https://example.com.\n~~~\nfn main() {}\n~~~\n";
+/// let selected: Vec<&str> = Markdown.ranges(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(selected, ["Run ", ", see "]);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Markdown;
+
+impl DocumentFilter for Markdown {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut out = Vec::new();
+        // The character and length of the fence that opened the current code
+        // block, if any
+        let mut fence: Option<(char, usize)> = None;
+        let mut offset = 0;
+
+        for line in text.split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            let line = line.trim_end_matches(['\n', '\r']);
+            let trimmed = line.trim_start();
+            let run = |fence_ch: char| trimmed.chars().take_while(|&c| c == fence_ch).count();
+
+            if let Some((fence_ch, len)) = fence {
+                // A closing fence is at least as long as the opening one
+                if run(fence_ch) >= len && trimmed.trim_end().chars().all(|c| c == fence_ch) {
+                    fence = None;
+                }
+                continue;
+            }
+            if let Some(fence_ch) = ['`', '~'].into_iter().find(|&c| run(c) >= 3) {
+                fence = Some((fence_ch, run(fence_ch)));
+                continue;
+            }
+
+            inline_ranges(line, start, &mut out);
+        }
+
+        out
+    }
+}
+
+/// Add the ranges of a line outside of code spans and URLs to `out`. `offset`
+/// is the position of `line` within the document.
+fn inline_ranges(line: &str, offset: usize, out: &mut Vec<Range<usize>>) {
+    let mut last = 0;
+    let mut idx = 0;
+
+    while idx < line.len() {
+        let rest = &line[idx..];
+        let skip = if rest.starts_with('`') {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            let Some(len) = code_span_len(rest, ticks) else {
+                // Backticks without a match are literal
+                idx += ticks;
+                continue;
+            };
+            len
+        } else if rest.starts_with("http://") || rest.starts_with("https://") {
+            rest.find(|c: char| c.is_whitespace() || matches!(c, ')' | '>' | ']'))
+                .unwrap_or(rest.len())
+        } else {
+            idx += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+
+        push_range(out, offset + last..offset + idx);
+        idx += skip;
+        last = idx;
+    }
+
+    push_range(out, offset + last..offset + line.len());
+}
+
+/// The length of a code span at the start of `rest`, which opens with `ticks`
+/// backticks and closes with the same number, or `None` if it is not closed
+fn code_span_len(rest: &str, ticks: usize) -> Option<usize> {
+    let mut idx = ticks;
+    while let Some(pos) = rest[idx..].find('`') {
+        let start = idx + pos;
+        let len = rest[start..].len() - rest[start..].trim_start_matches('`').len();
+        if len == ticks {
+            return Some(start + len);
+        }
+        idx = start + len;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filter::tests::selected;
+
+    #[test]
+    fn test_markdown() {
+        let input = "\
+Some `inline code` and <b>tags</b>
+See https://exmaple.com/pth or [a lnk](http://x.y/z).
+```rust
+let misspeled = 1;
+``
+```
+~~~~
+```
+~~~~~
+back to txt
+";
+        assert_eq!(
+            selected(&Markdown, input),
+            [
+                "Some ",
+                " and <b>tags</b>",
+                "See ",
+                " or [a lnk](",
+                ").",
+                "back to txt"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_code_spans() {
+        assert_eq!(
+            selected(&Markdown, "a ``x ` y`` b `c` d"),
+            ["a ", " b ", " d"]
+        );
+        // Unclosed spans are not code
+        assert_eq!(selected(&Markdown, "a `` b ` c"), ["a `` b ` c"]);
+        // Spans do not cross lines
+        assert_eq!(selected(&Markdown, "a `b\nc` d\r\n"), ["a `b", "c` d"]);
+    }
+
+    #[test]
+    fn test_unclosed_fence() {
+        assert_eq!(selected(&Markdown, "text\n```\ncode\n``\n"), ["text"]);
+    }
+}
@@ -0,0 +1,477 @@
+//! YAML filter, see [`Yaml`]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::keys::KeyPatterns;
+use super::{push_range, push_unescaped, word_around, DocumentFilter};
+
+/// Plain scalars that are not strings
+const NOT_STRINGS: &[&str] = &[
+    "~", "null", "Null", "NULL", "true", "True", "TRUE", "false", "False", "FALSE", ".inf",
+    "-.inf", ".nan", ".NaN",
+];
+
+/// A filter for YAML that only checks string values, such as the text of a
+/// file of UI strings.
+///
+/// Keys, comments, anchors, tags, numbers, booleans, and `null` are skipped.
+/// Plain, quoted, and block (`|` and `>`) scalars are checked, as are the
+/// values in flow collections such as `[a, b]`. Escapes such as `\n` in
+/// double quoted strings are skipped.
+///
+/// Values can be limited to those under certain keys with
+/// [`with_keys`](Self::with_keys), which works as it does for
+/// [`Json`](super::Json).
+///
+/// ```
+/// use zspell::filter::{DocumentFilter, Yaml};
+///
+/// let text = "\
+/// # Menu strings
+/// open:
+///   id: menu-open
+///   label: \"Open a file\"
+///   help: |
+///     Opens a file
+///     from disk.
+///   shortcut: [Ctrl, O]
+///   order: 1
+/// ";
+/// let filter = Yaml::new().with_keys(["label", "help"]);
+/// let selected: Vec<&str> = filter.ranges(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(selected, ["Open a file", "Opens a file", "from disk."]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Yaml {
+    keys: KeyPatterns,
+}
+
+impl Yaml {
+    /// A filter that checks all string values
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only check string values under keys that match one of `patterns`, see
+    /// [`Json::with_keys`](super::Json::with_keys)
+    #[inline]
+    #[must_use]
+    pub fn with_keys<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.keys = KeyPatterns::new(patterns);
+        self
+    }
+}
+
+impl DocumentFilter for Yaml {
+    #[inline]
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut parser = Parser {
+            text,
+            keys: &self.keys,
+            parents: Vec::new(),
+            out: Vec::new(),
+        };
+        parser.parse();
+        parser.out
+    }
+}
+
+/// State for [`Yaml::ranges`]
+struct Parser<'a> {
+    text: &'a str,
+    keys: &'a KeyPatterns,
+    /// Keys of the enclosing mappings, with the column of each
+    parents: Vec<(usize, &'a str)>,
+    out: Vec<Range<usize>>,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(&mut self) {
+        let text = self.text;
+        // Lines of a block scalar are indented more than this
+        let mut block: Option<usize> = None;
+        let mut offset = 0;
+
+        while offset < text.len() {
+            let line_start = offset;
+            let line_end = text[offset..].find('\n').map_or(text.len(), |p| offset + p);
+            offset = line_end + 1;
+            let line = text[line_start..line_end].trim_end();
+            let content = line.trim_start_matches(' ');
+            let indent = line.len() - content.len();
+
+            if let Some(min) = block {
+                if content.is_empty() || indent > min {
+                    if self.checked() {
+                        push_range(&mut self.out, line_start + indent..line_start + line.len());
+                    }
+                    continue;
+                }
+                block = None;
+            }
+            if content.is_empty()
+                || content.starts_with(['#', '%'])
+                || line.starts_with("---")
+                || line.starts_with("...")
+            {
+                continue;
+            }
+
+            // Sequence entries, possibly several as in `- - a`
+            let mut col = indent;
+            let mut is_item = false;
+            while line[col..].starts_with("- ") || &line[col..] == "-" {
+                is_item = true;
+                col = line.len() - line[col + 1..].trim_start().len();
+            }
+            if is_item {
+                self.pop(indent + 1);
+            }
+
+            let mut pos = line_start + col;
+            let mut parent_col = indent;
+            if let Some((key, value_start)) = self.key(pos, line_start + line.len()) {
+                self.pop(col);
+                self.parents.push((col, key));
+                parent_col = col;
+                pos = value_start;
+            }
+
+            let rest = text[pos..line_start + line.len()].trim_start();
+            pos = line_start + line.len() - rest.len();
+            if rest.starts_with(['|', '>']) {
+                block = Some(parent_col);
+            } else if let Some(end) = self.value(pos) {
+                // A quoted or flow value that ends on a later line
+                offset = text[end..].find('\n').map_or(text.len(), |p| end + p + 1);
+            }
+        }
+    }
+
+    /// Whether values under the current keys are checked
+    fn checked(&self) -> bool {
+        self.keys.matches(self.parents.iter().map(|&(_, key)| key))
+    }
+
+    /// Forget keys at `col` or deeper
+    fn pop(&mut self, col: usize) {
+        while self
+            .parents
+            .last()
+            .map_or(false, |&(key_col, _)| key_col >= col)
+        {
+            self.parents.pop();
+        }
+    }
+
+    /// If there is a mapping key at `pos`, return it and where its value
+    /// starts. `line_end` is the end of the line.
+    fn key(&self, pos: usize, line_end: usize) -> Option<(&'a str, usize)> {
+        let text = self.text;
+        let rest = &text[pos..line_end];
+        let (key, after) = if rest.starts_with(['"', '\'']) {
+            let end = quoted_end(text, pos);
+            let after = end + 1;
+            if after > line_end {
+                return None;
+            }
+            (&text[pos + 1..end], after)
+        } else if rest.starts_with(['[', '{', '&', '*', '!', '#', '|', '>']) {
+            return None;
+        } else {
+            let sep = rest
+                .match_indices(':')
+                .find(|&(p, _)| rest[p + 1..].is_empty() || rest[p + 1..].starts_with([' ', '\t']))?
+                .0;
+            let key = rest[..sep].trim_end();
+            // A `#` after a space starts a comment, so this is not a key
+            if key.contains(" #") {
+                return None;
+            }
+            (key, pos + sep)
+        };
+        let colon = text[after..line_end].trim_start();
+        let colon = colon.strip_prefix(':')?;
+        Some((key, line_end - colon.len()))
+    }
+
+    /// Check the value starting at `pos`, which is not a block scalar. Returns
+    /// the end of the value if it is quoted or a flow collection.
+    fn value(&mut self, mut pos: usize) -> Option<usize> {
+        let text = self.text;
+        // Skip anchors and tags such as `&name` and `!!str`
+        while text[pos..].starts_with(['&', '!']) {
+            let len = text[pos..].find([' ', '\n']).unwrap_or(text.len() - pos);
+            pos += len;
+            pos = text.len() - text[pos..].trim_start_matches(' ').len();
+        }
+
+        let rest = &text[pos..];
+        if rest.starts_with(['"', '\'']) {
+            let end = quoted_end(text, pos);
+            if self.checked() {
+                push_quoted(&mut self.out, text, pos, end);
+            }
+            Some(end)
+        } else if rest.starts_with(['[', '{']) {
+            Some(self.flow(pos))
+        } else {
+            let line_end = rest.find('\n').map_or(text.len(), |p| pos + p);
+            let value = &text[pos..line_end];
+            let value = value.find(" #").map_or(value, |p| &value[..p]).trim_end();
+            self.push_plain(pos..pos + value.len());
+            None
+        }
+    }
+
+    /// Check the values of a flow collection starting at `pos`, returning where
+    /// it ends
+    fn flow(&mut self, pos: usize) -> usize {
+        let text = self.text;
+        let mut depth = 0;
+        let mut idx = pos;
+        while idx < text.len() {
+            let rest = &text[idx..];
+            let c = rest.chars().next().unwrap_or_default();
+            match c {
+                '[' | '{' => depth += 1,
+                ']' | '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return idx;
+                    }
+                }
+                ',' | ':' | ' ' | '\t' | '\r' | '\n' => (),
+                '#' => {
+                    idx = rest.find('\n').map_or(text.len(), |p| idx + p);
+                    continue;
+                }
+                '"' | '\'' => {
+                    let end = quoted_end(text, idx);
+                    if !self.is_flow_key(end + 1) && self.checked() {
+                        push_quoted(&mut self.out, text, idx, end);
+                    }
+                    idx = end + 1;
+                    continue;
+                }
+                _ => {
+                    // A plain scalar runs to an indicator, or to `: `
+                    let mut end = rest
+                        .find([',', '[', ']', '{', '}', '\n', '#'])
+                        .unwrap_or(rest.len());
+                    if let Some(sep) = rest[..end].find(": ") {
+                        end = sep;
+                    }
+                    let end = idx + rest[..end].trim_end().len().max(c.len_utf8());
+                    if !self.is_flow_key(end) {
+                        self.push_plain(idx..end);
+                    }
+                    idx = end;
+                    continue;
+                }
+            }
+            idx += c.len_utf8();
+        }
+        text.len()
+    }
+
+    /// Whether the scalar ending at `end` in a flow collection is a key
+    fn is_flow_key(&self, end: usize) -> bool {
+        let after = self.text.get(end..).unwrap_or_default();
+        after.trim_start_matches([' ', '\t']).starts_with(':')
+    }
+
+    /// Check a plain scalar unless it is a number, boolean, or null
+    fn push_plain(&mut self, range: Range<usize>) {
+        let value = &self.text[range.clone()];
+        let is_number = value
+            .trim_start_matches(['-', '+'])
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || matches!(c, '.' | '_' | 'x' | 'o' | 'e' | 'E'))
+            && value.contains(|c: char| c.is_ascii_digit());
+        if !NOT_STRINGS.contains(&value) && !is_number && !value.starts_with('*') && self.checked()
+        {
+            push_range(&mut self.out, range);
+        }
+    }
+}
+
+/// The index of the quote that ends a quoted scalar starting at `start`, or the
+/// end of `text` if it is not closed
+fn quoted_end(text: &str, start: usize) -> usize {
+    let quote = text.as_bytes()[start];
+    let bytes = text.as_bytes();
+    let mut idx = start + 1;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' if quote == b'"' => idx += 1,
+            // `''` is a quote inside a single quoted scalar
+            b'\'' if quote == b'\'' && bytes.get(idx + 1) == Some(&b'\'') => idx += 1,
+            b if b == quote => return idx,
+            _ => (),
+        }
+        idx += 1;
+    }
+    text.len()
+}
+
+/// Add the content of a quoted scalar from `start` to `end` to `out`, skipping
+/// escapes in double quoted scalars and words with an escaped `''` in single
+/// quoted ones
+fn push_quoted(out: &mut Vec<Range<usize>>, text: &str, start: usize, end: usize) {
+    if text.as_bytes()[start] == b'\'' {
+        let mut last = start + 1;
+        while let Some(pos) = text[last..end].find("''") {
+            let skip = word_around(text, last..end, last + pos..last + pos + 2);
+            push_range(out, last..skip.start);
+            last = skip.end;
+        }
+        push_range(out, last..end);
+        return;
+    }
+    push_unescaped(out, text, start + 1..end, |c| match c {
+        'x' => 2,
+        'u' => 4,
+        'U' => 8,
+        _ => 0,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filter::tests::selected;
+
+    const TEXT: &str = r#"%YAML 1.2
+---
+# Strings for the app
+app:
+  name: ZSpell # the app's name
+  version: 1.2
+  enabled: true
+  empty: ~
+menu:
+  open:
+    label: "Open a file\n"
+    help: >-
+      Opens a file
+
+      from disk.
+  recent: &recent
+  - First one
+  - 'It''s second'
+  - [Third, "Fourth", {k: Fifth}]
+"quoted key": Value
+tags: !!str Tagged
+alias: *recent
+"#;
+
+    #[test]
+    fn test_yaml() {
+        assert_eq!(
+            selected(&Yaml::new(), TEXT),
+            [
+                "ZSpell",
+                "Open a file",
+                "Opens a file",
+                "from disk.",
+                "First one",
+                " second",
+                "Third",
+                "Fourth",
+                "Fifth",
+                "Value",
+                "Tagged"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keys() {
+        let filter = Yaml::new().with_keys(["help", "recent"]);
+        assert_eq!(
+            selected(&filter, TEXT),
+            [
+                "Opens a file",
+                "from disk.",
+                "First one",
+                " second",
+                "Third",
+                "Fourth",
+                "Fifth"
+            ]
+        );
+
+        let filter = Yaml::new().with_keys(["menu.open.*", "quoted key"]);
+        assert_eq!(
+            selected(&filter, TEXT),
+            ["Open a file", "Opens a file", "from disk.", "Value"]
+        );
+    }
+
+    #[test]
+    fn test_sequences() {
+        let text = "\
+- plain
+- key: one
+  other: two
+- - nested
+-
+  after: three
+";
+        assert_eq!(
+            selected(&Yaml::new(), text),
+            ["plain", "one", "two", "nested", "three"]
+        );
+        let filter = Yaml::new().with_keys(["other"]);
+        assert_eq!(selected(&filter, text), ["two"]);
+    }
+
+    #[test]
+    fn test_multiline() {
+        let text = "a: \"first\n  second\"\nb: [x,\n  y]\nc: url://x:y\n";
+        assert_eq!(
+            selected(&Yaml::new(), text),
+            ["first\n  second", "x", "y", "url://x:y"]
+        );
+        assert_eq!(selected(&Yaml::new(), "a: 'open"), ["open"]);
+        assert_eq!(selected(&Yaml::new(), "a: [b"), ["b"]);
+    }
+
+    #[test]
+    fn test_malformed_escape() {
+        assert_eq!(
+            selected(&Yaml::new(), r#"key: "bad \u000é escape""#),
+            ["bad ", " escape"]
+        );
+        assert_eq!(
+            selected(&Yaml::new(), r#"a: "\xé \U0001F600 ok""#),
+            [" ", " ok"]
+        );
+    }
+
+    #[test]
+    fn test_escaped_words() {
+        let dict = crate::builder()
+            .config_str("")
+            .dict_str("3\ncafé\ndon't\nit's")
+            .build()
+            .unwrap();
+        let checker = crate::Checker::builder(dict)
+            .filter(Yaml::new())
+            .build()
+            .unwrap();
+        let text = r#"a: 'it''s'
+b: "caf\u00e9 don\x27t""#;
+        assert!(checker.check("a", text).is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+//! Applying corrections to a document
+//!
+//! A check pass such as [`Dictionary::check_indices`](crate::Dictionary::check_indices)
+//! yields the byte index of each misspelling. Once replacements have been
+//! picked for some of them, the functions here produce the corrected text.
+//! Fixes may be given in any order, and any that overlap an earlier fix are
+//! skipped, so the result is the same no matter how the fixes were gathered.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Replace byte ranges of `text` and return the result.
+///
+/// Fixes are applied in order of where they start, or in the order given if
+/// they start at the same place, so several insertions (empty ranges) at one
+/// index all end up there. A fix whose range overlaps the range of a fix that
+/// was already applied is skipped.
+///
+/// ```
+/// use zspell::apply_fixes;
+///
+/// let text = "teh cat sat on teh mat";
+/// let fixes = [
+///     (15..18, "the".to_owned()),
+///     (0..3, "The".to_owned()),
+///     // Overlaps the fix above, so it is skipped
+///     (1..3, "he".to_owned()),
+/// ];
+/// assert_eq!(apply_fixes(text, &fixes), "The cat sat on the mat");
+/// ```
+///
+/// # Panics
+///
+/// Panics if a range ends before it starts, or if either end is past the end
+/// of `text` or not on a `char` boundary.
+#[inline]
+#[must_use]
+pub fn apply_fixes(text: &str, fixes: &[(Range<usize>, String)]) -> String {
+    let mut order: Vec<&(Range<usize>, String)> = fixes.iter().collect();
+    order.sort_by_key(|(range, _)| range.start);
+
+    let mut ret = String::with_capacity(text.len());
+    let mut pos = 0;
+    for (range, new) in order {
+        assert!(
+            range.start <= range.end
+                && text.is_char_boundary(range.start)
+                && text.is_char_boundary(range.end),
+            "invalid fix range {range:?} for text of length {}",
+            text.len()
+        );
+        if range.start < pos {
+            continue;
+        }
+        ret.push_str(&text[pos..range.start]);
+        ret.push_str(new);
+        pos = range.end;
+    }
+    ret.push_str(&text[pos..]);
+    ret
+}
+
+/// Turn misspellings with their chosen replacements into fixes for [`apply_fixes`].
+///
+/// Each item is the byte index and text of a word, as returned by
+/// [`Dictionary::check_indices`](crate::Dictionary::check_indices), and what to
+/// replace it with.
+///
+/// ```
+/// use zspell::word_fixes;
+///
+/// let fixes = word_fixes([(0, "teh", "the"), (9, "kat", "cat")]);
+/// assert_eq!(fixes, [(0..3, "the".to_owned()), (9..12, "cat".to_owned())]);
+/// ```
+#[inline]
+pub fn word_fixes<'a, I, S>(selections: I) -> Vec<(Range<usize>, String)>
+where
+    I: IntoIterator<Item = (usize, &'a str, S)>,
+    S: Into<String>,
+{
+    selections
+        .into_iter()
+        .map(|(idx, word, new)| (idx..idx + word.len(), new.into()))
+        .collect()
+}
+
+/// Replace misspellings from a check pass and return the corrected text. This
+/// is [`word_fixes`] followed by [`apply_fixes`].
+///
+/// ```
+/// use zspell::apply_word_fixes;
+///
+/// let dict = zspell::builder()
+///     .config_str("")
+///     .dict_str("2\nthe\ncat")
+///     .build()
+///     .unwrap();
+///
+/// let text = "teh cat, teh kat";
+/// let selections = dict.check_indices(text).filter_map(|(idx, word)| {
+///     // Only fix the words that have a replacement picked
+///     let new = match word {
+///         "teh" => "the",
+///         _ => return None,
+///     };
+///     Some((idx, word, new))
+/// });
+/// assert_eq!(apply_word_fixes(text, selections), "the cat, the kat");
+/// ```
+///
+/// # Panics
+///
+/// Panics if a word does not fit in `text` at its index, see [`apply_fixes`].
+#[inline]
+#[must_use]
+pub fn apply_word_fixes<'a, I, S>(text: &str, selections: I) -> String
+where
+    I: IntoIterator<Item = (usize, &'a str, S)>,
+    S: Into<String>,
+{
+    apply_fixes(text, &word_fixes(selections))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::ToOwned;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fix(range: Range<usize>, new: &str) -> (Range<usize>, String) {
+        (range, new.to_owned())
+    }
+
+    #[test]
+    fn test_apply_fixes() {
+        let text = "ab cd ef";
+        assert_eq!(apply_fixes(text, &[]), text);
+        assert_eq!(apply_fixes(text, &[fix(0..8, "")]), "");
+        assert_eq!(
+            apply_fixes(text, &[fix(6..8, "EF"), fix(0..2, "AB")]),
+            "AB cd EF"
+        );
+        // Different lengths shift the rest of the text
+        assert_eq!(
+            apply_fixes(text, &[fix(3..5, "c"), fix(6..8, "efgh")]),
+            "ab c efgh"
+        );
+    }
+
+    #[test]
+    fn test_apply_fixes_overlap() {
+        let text = "ab cd ef";
+        // The fix that starts first wins, then the one given first
+        assert_eq!(
+            apply_fixes(text, &[fix(3..8, "x"), fix(0..4, "y")]),
+            "yd ef"
+        );
+        assert_eq!(
+            apply_fixes(text, &[fix(3..5, "x"), fix(3..4, "y")]),
+            "ab x ef"
+        );
+        // Touching ranges do not overlap
+        assert_eq!(
+            apply_fixes(text, &[fix(2..3, "-"), fix(0..2, "x")]),
+            "x-cd ef"
+        );
+    }
+
+    #[test]
+    fn test_apply_fixes_insert() {
+        let text = "ab";
+        assert_eq!(
+            apply_fixes(text, &[fix(1..1, "1"), fix(1..1, "2"), fix(1..2, "B")]),
+            "a12B"
+        );
+        assert_eq!(apply_fixes(text, &[fix(2..2, "c")]), "abc");
+    }
+
+    #[test]
+    fn test_apply_fixes_unicode() {
+        assert_eq!(
+            apply_fixes("naïve café", &[fix(7..12, "cafe")]),
+            "naïve cafe"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid fix range")]
+    fn test_apply_fixes_char_boundary() {
+        let _ = apply_fixes("naïve", &[fix(0..3, "x")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid fix range")]
+    fn test_apply_fixes_out_of_bounds() {
+        let _ = apply_fixes("ab", &[fix(1..3, "x")]);
+    }
+}
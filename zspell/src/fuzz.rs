@@ -0,0 +1,463 @@
+//! Structured inputs for fuzzing the parsers (requires feature `arbitrary`)
+//!
+//! Affix and dictionary files usually come from third parties, so parsing them
+//! must never panic. Random bytes rarely get past the first keyword of an affix
+//! file, so the types here implement [`Arbitrary`] by assembling files from
+//! plausible lines: known keywords, affix groups whose counts may or may not
+//! match, and tokens drawn from small alphabets that include the characters
+//! the parsers treat specially. Each type is rendered to file contents with
+//! its `Display` implementation.
+//!
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use zspell::fuzz::{AffixFile, DicFile};
+//!
+//! let bytes = [0x5a; 256];
+//! let mut u = Unstructured::new(&bytes);
+//! let aff = AffixFile::arbitrary(&mut u).unwrap();
+//! let dic = DicFile::arbitrary(&mut u).unwrap();
+//!
+//! // Errors are fine, panics are not
+//! let _ = zspell::builder()
+//!     .config_str(&aff.to_string())
+//!     .dict_str(&dic.to_string())
+//!     .build();
+//! ```
+//!
+//! The `fuzz` directory of the repository has `cargo-fuzz` targets that use
+//! these types.
+
+use std::fmt;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Characters for words and affixes, including case pairs, a sharp s, and
+/// separators used by `BREAK` and elision
+const WORD_CHARS: &[char] = &[
+    'a', 'b', 'e', 'i', 'o', 's', 'y', 'A', 'E', 'S', 'é', 'É', 'ß', '\'', '-', '.', '0', '1',
+];
+/// Characters for flags of any flag type
+const FLAG_CHARS: &[char] = &['A', 'B', 'Z', 'a', 'z', '0', '1', '9', ',', 'é', '🙂'];
+/// Characters for affix conditions, which are a subset of regex syntax
+const CONDITION_CHARS: &[char] = &['a', 'e', 'y', '.', '[', ']', '^', '-'];
+/// Morphological tags from the Hunspell documentation
+const MORPH_TAGS: &[&str] = &[
+    "ph", "st", "al", "po", "ds", "is", "ts", "sp", "pa", "ip", "dp", "tp", "xx",
+];
+/// Names for `SET`, including an unsupported one
+const ENCODINGS: &[&str] = &[
+    "UTF-8",
+    "ISO8859-1",
+    "ISO8859-15",
+    "KOI8-R",
+    "microsoft-cp1251",
+    "x",
+];
+/// Names for `FLAG`, including an unsupported one
+const FLAG_TYPES: &[&str] = &["UTF-8", "long", "num", "ascii", "short"];
+/// Options that take a flag
+const FLAG_KEYS: &[&str] = &[
+    "NOSUGGEST",
+    "WARN",
+    "COMPOUNDFLAG",
+    "COMPOUNDBEGIN",
+    "COMPOUNDEND",
+    "COMPOUNDLAST",
+    "COMPOUNDMIDDLE",
+    "ONLYINCOMPOUND",
+    "COMPOUNDPERMITFLAG",
+    "COMPOUNDFORBIDFLAG",
+    "COMPOUNDROOT",
+    "FORCEUCASE",
+    "CIRCUMFIX",
+    "FORBIDDENWORD",
+    "KEEPCASE",
+    "LEMMA_PRESENT",
+    "NEEDAFFIX",
+    "PSEUDOROOT",
+    "SUBSTANDARD",
+];
+/// Options without a value
+const BOOL_KEYS: &[&str] = &[
+    "COMPLEXPREFIXES",
+    "ONLYMAXDIFF",
+    "NOSPLITSUGS",
+    "SUGSWITHDOTS",
+    "FORBIDWARN",
+    "COMPOUNDMORESUFFIXES",
+    "CHECKCOMPOUNDDUP",
+    "CHECKCOMPOUNDREP",
+    "CHECKCOMPOUNDCASE",
+    "CHECKCOMPOUNDTRIPLE",
+    "SIMPLIFIEDTRIPLE",
+    "FULLSTRIP",
+    "CHECKSHARPS",
+];
+/// Options that take a number
+const INT_KEYS: &[&str] = &[
+    "MAXCPDSUGS",
+    "MAXNGRAMSUGS",
+    "MAXDIFF",
+    "COMPOUNDMIN",
+    "COMPOUNDWORDMAX",
+];
+/// Options that take a string
+const TEXT_KEYS: &[&str] = &[
+    "LANG",
+    "IGNORE",
+    "KEY",
+    "TRY",
+    "WORDCHARS",
+    "COMPOUNDSYLLABLE",
+    "SYLLABLENUM",
+    "NAME",
+    "HOME",
+    "VERSION",
+];
+/// Options that are followed by a table of rows
+const TABLE_KEYS: &[&str] = &[
+    "AF",
+    "AM",
+    "REP",
+    "MAP",
+    "PHONE",
+    "BREAK",
+    "COMPOUNDRULE",
+    "CHECKCOMPOUNDPATTERN",
+    "ICONV",
+    "OCONV",
+];
+
+/// A complete affix file
+#[derive(Arbitrary, Clone, Debug, PartialEq, Eq)]
+pub struct AffixFile(Vec<AffixFragment>);
+
+/// One option of an affix file, which may span several lines
+#[non_exhaustive]
+#[derive(Arbitrary, Clone, Debug, PartialEq, Eq)]
+pub enum AffixFragment {
+    /// `SET` with an encoding
+    Encoding(#[arbitrary(with = encoding)] &'static str),
+    /// `FLAG` with a flag type
+    FlagType(#[arbitrary(with = flag_type)] &'static str),
+    /// An option that takes a flag, such as `NOSUGGEST`
+    Flag {
+        #[arbitrary(with = flag_key)]
+        key: &'static str,
+        #[arbitrary(with = flag)]
+        flag: String,
+    },
+    /// An option without a value, such as `FULLSTRIP`
+    Bool(#[arbitrary(with = bool_key)] &'static str),
+    /// An option that takes a number, such as `COMPOUNDMIN`
+    Int {
+        #[arbitrary(with = int_key)]
+        key: &'static str,
+        value: i64,
+    },
+    /// An option that takes a string, such as `TRY`
+    Text {
+        #[arbitrary(with = text_key)]
+        key: &'static str,
+        #[arbitrary(with = word)]
+        value: String,
+    },
+    /// A table such as `REP`. The declared row count may differ from the rows
+    /// that follow.
+    Table {
+        #[arbitrary(with = table_key)]
+        key: &'static str,
+        declared: Option<u8>,
+        #[arbitrary(with = rows)]
+        rows: Vec<Vec<String>>,
+    },
+    /// A group of `PFX` or `SFX` rules
+    Affix(AffixGroup),
+    /// A comment
+    Comment(String),
+    /// Anything at all, which may span several lines
+    Raw(String),
+}
+
+/// A `PFX` or `SFX` header and its rules
+#[derive(Arbitrary, Clone, Debug, PartialEq, Eq)]
+pub struct AffixGroup {
+    suffix: bool,
+    #[arbitrary(with = flag)]
+    flag: String,
+    cross_product: bool,
+    /// The row count in the header, or the number of rules if `None`
+    declared: Option<u8>,
+    rules: Vec<AffixRuleLine>,
+}
+
+/// A single rule within an [`AffixGroup`]
+#[derive(Arbitrary, Clone, Debug, PartialEq, Eq)]
+pub struct AffixRuleLine {
+    /// Use a different flag than the header, which is an error
+    mismatched_flag: bool,
+    #[arbitrary(with = word)]
+    strip: String,
+    #[arbitrary(with = word)]
+    affix: String,
+    #[arbitrary(with = optional_flags)]
+    continuation: Option<String>,
+    #[arbitrary(with = condition)]
+    condition: String,
+    #[arbitrary(with = morphs)]
+    morph: Vec<String>,
+}
+
+/// A complete `.dic` file
+#[derive(Arbitrary, Clone, Debug, PartialEq, Eq)]
+pub struct DicFile {
+    /// The count on the first line, or no count line if `None`. It may differ
+    /// from the number of entries.
+    declared: Option<u16>,
+    lines: Vec<DicLine>,
+}
+
+/// A single line of a `.dic` file, `word[/flags] [morph ...]`
+#[derive(Arbitrary, Clone, Debug, PartialEq, Eq)]
+pub struct DicLine {
+    #[arbitrary(with = word)]
+    stem: String,
+    #[arbitrary(with = optional_flags)]
+    flags: Option<String>,
+    #[arbitrary(with = morphs)]
+    morph: Vec<String>,
+}
+
+impl fmt::Display for AffixFile {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for fragment in &self.0 {
+            writeln!(f, "{fragment}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for AffixFragment {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encoding(name) => write!(f, "SET {name}"),
+            Self::FlagType(name) => write!(f, "FLAG {name}"),
+            Self::Flag { key, flag } => write!(f, "{key} {flag}"),
+            Self::Bool(key) => f.write_str(key),
+            Self::Int { key, value } => write!(f, "{key} {value}"),
+            Self::Text { key, value } => write!(f, "{key} {value}"),
+            Self::Table {
+                key,
+                declared,
+                rows,
+            } => {
+                let count = declared.map_or(rows.len(), usize::from);
+                write!(f, "{key} {count}")?;
+                for row in rows {
+                    write!(f, "\n{key} {}", row.join(" "))?;
+                }
+                Ok(())
+            }
+            Self::Affix(group) => write!(f, "{group}"),
+            Self::Comment(text) => write!(f, "# {}", text.replace('\n', " ")),
+            Self::Raw(text) => f.write_str(text),
+        }
+    }
+}
+
+impl fmt::Display for AffixGroup {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = if self.suffix { "SFX" } else { "PFX" };
+        let cross = if self.cross_product { 'Y' } else { 'N' };
+        let count = self.declared.map_or(self.rules.len(), usize::from);
+        write!(f, "{key} {} {cross} {count}", self.flag)?;
+
+        for rule in &self.rules {
+            let flag = if rule.mismatched_flag {
+                "Q"
+            } else {
+                &self.flag
+            };
+            write!(
+                f,
+                "\n{key} {flag} {} {}",
+                or_zero(&rule.strip),
+                or_zero(&rule.affix)
+            )?;
+            if let Some(continuation) = &rule.continuation {
+                write!(f, "/{continuation}")?;
+            }
+            write!(f, " {}", or_dot(&rule.condition))?;
+            for morph in &rule.morph {
+                write!(f, " {morph}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DicFile {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(count) = self.declared {
+            writeln!(f, "{count}")?;
+        }
+        for line in &self.lines {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DicLine {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.stem)?;
+        if let Some(flags) = &self.flags {
+            write!(f, "/{flags}")?;
+        }
+        for morph in &self.morph {
+            write!(f, " {morph}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Affix files use `0` for an empty strip or affix
+fn or_zero(s: &str) -> &str {
+    if s.is_empty() {
+        "0"
+    } else {
+        s
+    }
+}
+
+/// Affix files use `.` for an empty condition
+fn or_dot(s: &str) -> &str {
+    if s.is_empty() {
+        "."
+    } else {
+        s
+    }
+}
+
+/// A string of up to 8 characters from `chars`
+fn token(u: &mut Unstructured<'_>, chars: &[char]) -> Result<String> {
+    let len = u.int_in_range(0..=8)?;
+    (0..len).map(|_| u.choose(chars).copied()).collect()
+}
+
+fn word(u: &mut Unstructured<'_>) -> Result<String> {
+    token(u, WORD_CHARS)
+}
+
+fn flag(u: &mut Unstructured<'_>) -> Result<String> {
+    let flag = token(u, FLAG_CHARS)?;
+    Ok(if flag.is_empty() {
+        "A".to_owned()
+    } else {
+        flag
+    })
+}
+
+fn optional_flags(u: &mut Unstructured<'_>) -> Result<Option<String>> {
+    if u.arbitrary()? {
+        Ok(Some(token(u, FLAG_CHARS)?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn condition(u: &mut Unstructured<'_>) -> Result<String> {
+    token(u, CONDITION_CHARS)
+}
+
+/// Morph fields such as `po:noun`
+fn morphs(u: &mut Unstructured<'_>) -> Result<Vec<String>> {
+    let len = u.int_in_range(0..=3)?;
+    (0..len)
+        .map(|_| Ok(format!("{}:{}", u.choose(MORPH_TAGS)?, word(u)?)))
+        .collect()
+}
+
+/// Rows of up to three tokens
+fn rows(u: &mut Unstructured<'_>) -> Result<Vec<Vec<String>>> {
+    let len = u.int_in_range(0..=4)?;
+    (0..len)
+        .map(|_| {
+            let width = u.int_in_range(1..=3)?;
+            (0..width).map(|_| word(u)).collect()
+        })
+        .collect()
+}
+
+fn encoding(u: &mut Unstructured<'_>) -> Result<&'static str> {
+    u.choose(ENCODINGS).copied()
+}
+
+fn flag_type(u: &mut Unstructured<'_>) -> Result<&'static str> {
+    u.choose(FLAG_TYPES).copied()
+}
+
+fn flag_key(u: &mut Unstructured<'_>) -> Result<&'static str> {
+    u.choose(FLAG_KEYS).copied()
+}
+
+fn bool_key(u: &mut Unstructured<'_>) -> Result<&'static str> {
+    u.choose(BOOL_KEYS).copied()
+}
+
+fn int_key(u: &mut Unstructured<'_>) -> Result<&'static str> {
+    u.choose(INT_KEYS).copied()
+}
+
+fn text_key(u: &mut Unstructured<'_>) -> Result<&'static str> {
+    u.choose(TEXT_KEYS).copied()
+}
+
+fn table_key(u: &mut Unstructured<'_>) -> Result<&'static str> {
+    u.choose(TABLE_KEYS).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    /// Build dictionaries from many generated files. Errors are expected, but
+    /// nothing should panic.
+    #[test]
+    fn test_generated_files_do_not_panic() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+        let mut bytes = vec![0u8; 1024];
+
+        for _ in 0..500 {
+            rng.fill(bytes.as_mut_slice());
+            let mut u = Unstructured::new(&bytes);
+            let aff = AffixFile::arbitrary(&mut u).unwrap();
+            let dic = DicFile::arbitrary(&mut u).unwrap();
+            let (aff, dic) = (aff.to_string(), dic.to_string());
+
+            let Ok(dict) = crate::builder().config_str(&aff).dict_str(&dic).build() else {
+                continue;
+            };
+            for line in dic.lines() {
+                dict.check(line);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dic_line_display() {
+        let line = DicLine {
+            stem: "band".to_owned(),
+            flags: Some("ESGD".to_owned()),
+            morph: vec!["po:noun".to_owned()],
+        };
+        assert_eq!(line.to_string(), "band/ESGD po:noun");
+    }
+}
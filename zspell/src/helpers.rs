@@ -1,46 +1,62 @@
 //! Various functions that are helpful throughout the crate
 
+use alloc::borrow::Cow;
+use alloc::string::String;
 use core::fmt::Display;
-use std::borrow::Cow;
-use std::hash::Hash;
-use std::ops::Deref;
-use std::rc::Rc;
-use std::sync::Arc;
 
-use hashbrown::Equivalent;
-use regex::Regex;
+use rustc_hash::FxBuildHasher;
 
-use crate::affix::RuleType;
+use crate::casing::Casing;
 
-/// Wrap `Regex` objects so they can be hashed
-#[derive(Clone, Debug)]
-pub struct ReWrapper(Regex);
+#[cfg(feature = "regex")]
+pub use self::re::ReWrapper;
 
-impl ReWrapper {
-    pub fn new(re: &str) -> Result<Self, regex::Error> {
-        Ok(Self(Regex::new(re)?))
+/// Map used for wordlists and other word-keyed storage. Lookups happen for
+/// every checked word, and `FxHash` is much cheaper than the default hasher for
+/// short keys. Keys come from dictionary sources so hash flooding is not a
+/// concern.
+pub type FxHashMap<K, V> = hashbrown::HashMap<K, V, FxBuildHasher>;
+
+/// Set counterpart to [`FxHashMap`]
+pub type FxHashSet<T> = hashbrown::HashSet<T, FxBuildHasher>;
+
+#[cfg(feature = "regex")]
+mod re {
+    use core::hash::Hash;
+    use core::ops::Deref;
+
+    use regex::Regex;
+
+    /// Wrap `Regex` objects so they can be hashed
+    #[derive(Clone, Debug)]
+    pub struct ReWrapper(Regex);
+
+    impl ReWrapper {
+        pub fn new(re: &str) -> Result<Self, regex::Error> {
+            Ok(Self(Regex::new(re)?))
+        }
     }
-}
 
-impl Eq for ReWrapper {}
+    impl Eq for ReWrapper {}
 
-impl PartialEq for ReWrapper {
-    fn eq(&self, other: &Self) -> bool {
-        self.0.as_str() == other.0.as_str()
+    impl PartialEq for ReWrapper {
+        fn eq(&self, other: &Self) -> bool {
+            self.0.as_str() == other.0.as_str()
+        }
     }
-}
 
-impl Hash for ReWrapper {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.0.as_str().hash(state);
+    impl Hash for ReWrapper {
+        fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+            self.0.as_str().hash(state);
+        }
     }
-}
 
-impl Deref for ReWrapper {
-    type Target = Regex;
+    impl Deref for ReWrapper {
+        type Target = Regex;
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
     }
 }
 
@@ -52,50 +68,117 @@ pub fn convertu32<T: TryInto<u32> + Display + Copy>(value: T) -> u32 {
         .unwrap_or_else(|_| panic!("value {value} overflows u32 max of {}", u32::MAX))
 }
 
-/// Compile a regex pattern in the context of an affix. Returns None if
-/// the universal pattern "." is provided
-pub fn compile_re_pattern(
-    condition: &str,
-    kind: RuleType,
-) -> Result<Option<ReWrapper>, regex::Error> {
-    if condition == "." {
-        return Ok(None);
-    }
-    // Escape hyphens in groups
-    let cond = condition.replace('-', r"\-");
-    let re_pattern = match kind {
-        RuleType::Prefix => format!("^{cond}.*$"),
-        RuleType::Suffix => format!("^.*{cond}$"),
-    };
-    ReWrapper::new(re_pattern.as_str()).map(Some)
+/// Words up to this many bytes can be lowercased without allocating
+const LOWER_STACK_LEN: usize = 64;
+
+/// Scratch space for lowercasing words on the check hot path. Words that fit
+/// within [`LOWER_STACK_LEN`] bytes are lowercased on the stack; anything longer
+/// falls back to a heap allocation.
+pub struct LowerBuf {
+    stack: [u8; LOWER_STACK_LEN],
+    heap: String,
+    casing: Casing,
 }
 
-/// Implement a type that derefs to compare to a string
-#[derive(Clone, Debug, PartialEq, Hash, Eq)]
-pub struct StrWrapper<'a>(pub &'a str);
+impl LowerBuf {
+    /// A buffer that lowercases words for the language of a dictionary
+    pub const fn new(casing: Casing) -> Self {
+        Self {
+            stack: [0; LOWER_STACK_LEN],
+            heap: String::new(),
+            casing,
+        }
+    }
 
-impl<'a> StrWrapper<'a> {
-    pub fn new(s: &'a str) -> Self {
-        Self(s)
+    /// Return the lowercase form of `word`, or `None` if it is already lowercase
+    /// (in which case there is no need to do a second lookup).
+    pub fn lower<'a>(&'a mut self, word: &str) -> Option<&'a str> {
+        if self.casing.lowers_specially(word) {
+            self.heap = self.casing.to_lowercase(word);
+            return (self.heap != word).then_some(self.heap.as_str());
+        }
+        if word.is_ascii() {
+            if !word.bytes().any(|b| b.is_ascii_uppercase()) {
+                return None;
+            }
+            if let Some(dst) = self.stack.get_mut(..word.len()) {
+                dst.copy_from_slice(word.as_bytes());
+                dst.make_ascii_lowercase();
+                return Some(core::str::from_utf8(dst).expect("ASCII is valid UTF-8"));
+            }
+        } else if let Some(len) = lower_chars_into(word, &mut self.stack) {
+            let lower =
+                core::str::from_utf8(&self.stack[..len]).expect("encoded chars are valid UTF-8");
+            return (lower != word).then_some(lower);
+        }
+
+        self.heap = word.to_lowercase();
+        (self.heap != word).then_some(self.heap.as_str())
     }
 }
 
-impl Display for StrWrapper<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+/// Write the lowercase chars of `word` to `buf`, returning the number of bytes
+/// written. Returns `None` if the result does not fit, or if the word contains a
+/// capital sigma; its lowercase form depends on context, which only
+/// `str::to_lowercase` takes into account.
+fn lower_chars_into(word: &str, buf: &mut [u8]) -> Option<usize> {
+    let mut len = 0;
+    for ch in word.chars() {
+        if ch.is_ascii() {
+            // ASCII chars are always a single byte
+            #[allow(clippy::cast_possible_truncation)]
+            let byte = ch.to_ascii_lowercase() as u8;
+            *buf.get_mut(len)? = byte;
+            len += 1;
+            continue;
+        }
+        if ch == 'Σ' {
+            return None;
+        }
+        for lower in ch.to_lowercase() {
+            let end = len + lower.len_utf8();
+            lower.encode_utf8(buf.get_mut(len..end)?);
+            len = end;
+        }
     }
+    Some(len)
 }
 
-impl Equivalent<Rc<str>> for StrWrapper<'_> {
-    fn equivalent(&self, key: &Rc<str>) -> bool {
-        self.0 == key.as_ref()
+/// Whether `word` is a number, which may contain single `.`, `,` or `-`
+/// separators between digits. Like Hunspell, these are always accepted.
+pub fn is_number(word: &str) -> bool {
+    let mut prev_digit = false;
+    for ch in word.chars() {
+        match ch {
+            '0'..='9' => prev_digit = true,
+            '.' | ',' | '-' if prev_digit => prev_digit = false,
+            _ => return false,
+        }
     }
+    prev_digit
 }
 
-impl Equivalent<Arc<str>> for StrWrapper<'_> {
-    fn equivalent(&self, key: &Arc<str>) -> bool {
-        self.0 == key.as_ref()
+/// Whether `a` and `b` have a Levenshtein distance of at most one, i.e. one
+/// can be turned into the other by a single insertion, deletion, or
+/// substitution
+pub fn within_one_edit(a: &str, b: &str) -> bool {
+    fn skip_one(s: &str) -> &str {
+        let mut chars = s.chars();
+        chars.next();
+        chars.as_str()
     }
+
+    let Some(idx) = a
+        .char_indices()
+        .zip(b.chars())
+        .find_map(|((idx, ca), cb)| (ca != cb).then_some(idx))
+    else {
+        return a.chars().count().abs_diff(b.chars().count()) <= 1;
+    };
+
+    // Everything before `idx` matches, so it is a boundary in both strings
+    let (a, b) = (&a[idx..], &b[idx..]);
+    skip_one(a) == skip_one(b) || skip_one(a) == b || a == skip_one(b)
 }
 
 #[allow(unused)]
@@ -106,3 +189,60 @@ pub fn replace_cow<'a>(s: &'a str, from: char, to: &str) -> Cow<'a, str> {
         Cow::Borrowed(s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lower_buf() {
+        let mut buf = LowerBuf::new(Casing::Default);
+        assert_eq!(buf.lower("abc"), None);
+        assert_eq!(buf.lower("Abc"), Some("abc"));
+        assert_eq!(buf.lower("ABC's"), Some("abc's"));
+        assert_eq!(buf.lower("straße"), None);
+        assert_eq!(buf.lower("Straße"), Some("straße"));
+        assert_eq!(buf.lower("ǅ"), Some("ǆ"));
+        assert_eq!(buf.lower("ΟΔΟΣ"), Some("οδος"));
+
+        let mut buf = LowerBuf::new(Casing::Turkic);
+        assert_eq!(buf.lower("ILIK"), Some("ılık"));
+        assert_eq!(buf.lower("İzmir"), Some("izmir"));
+        assert_eq!(buf.lower("Ankara"), Some("ankara"));
+        assert_eq!(buf.lower("ılık"), None);
+
+        let long = "A".repeat(LOWER_STACK_LEN * 2);
+        assert_eq!(
+            buf.lower(&long),
+            Some("a".repeat(LOWER_STACK_LEN * 2).as_str())
+        );
+        let long = "Ä".repeat(LOWER_STACK_LEN);
+        assert_eq!(buf.lower(&long), Some("ä".repeat(LOWER_STACK_LEN).as_str()));
+    }
+
+    #[test]
+    fn test_is_number() {
+        assert!(is_number("10"));
+        assert!(is_number("1,000.50"));
+        assert!(is_number("2023-10-17"));
+        assert!(!is_number(""));
+        assert!(!is_number("-1"));
+        assert!(!is_number("1."));
+        assert!(!is_number("1..2"));
+        assert!(!is_number("10th"));
+    }
+
+    #[test]
+    fn test_within_one_edit() {
+        assert!(within_one_edit("word", "word"));
+        assert!(within_one_edit("word", "ward"));
+        assert!(within_one_edit("word", "words"));
+        assert!(within_one_edit("words", "word"));
+        assert!(within_one_edit("word", "wrd"));
+        assert!(within_one_edit("straße", "strase"));
+        assert!(within_one_edit("", "a"));
+        assert!(!within_one_edit("word", "wodr"));
+        assert!(!within_one_edit("word", "wordss"));
+        assert!(!within_one_edit("", "ab"));
+    }
+}
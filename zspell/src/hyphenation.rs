@@ -0,0 +1,277 @@
+//! Hyphenation using Liang-style patterns, as found in `hyph_*.dic` files
+//!
+//! These files are used by `libhyphen` (and therefore most office suites), and
+//! are usually distributed alongside Hunspell dictionaries.
+//!
+//! ```
+//! use zspell::Hyphenator;
+//!
+//! let patterns = "UTF-8
+//! LEFTHYPHENMIN 2
+//! RIGHTHYPHENMIN 3
+//! 1na
+//! 1tio
+//! hy3ph
+//! he2n
+//! hena4
+//! hen5at
+//! n2at
+//! o2n
+//! 2io
+//! ";
+//!
+//! let hyph = Hyphenator::load_from_str(patterns).unwrap();
+//!
+//! // Returns the byte index of each location a hyphen may be inserted
+//! assert_eq!(hyph.hyphenate("hyphenation"), [2, 6]);
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::max;
+
+use hashbrown::HashMap;
+
+use crate::affix::Encoding;
+use crate::error::{Error, ParseError, ParseErrorKind};
+
+/// Minimum number of characters before the first hyphen, if not otherwise specified
+const DEFAULT_LEFT_MIN: usize = 2;
+/// Minimum number of characters after the last hyphen, if not otherwise specified
+const DEFAULT_RIGHT_MIN: usize = 2;
+
+/// A set of hyphenation patterns that can locate hyphenation points in words.
+///
+/// See the [module-level documentation](crate::hyphenation) for an example.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hyphenator {
+    /// The charset specified on the first line of the file
+    encoding: Encoding,
+    /// Map of pattern letters to the priority values before, between, and after
+    /// each letter. The values vector is always one longer than the letter count.
+    patterns: HashMap<Box<str>, Box<[u8]>>,
+    /// Length (in chars) of the longest pattern
+    max_pattern_len: usize,
+    /// `LEFTHYPHENMIN`
+    left_min: usize,
+    /// `RIGHTHYPHENMIN`
+    right_min: usize,
+}
+
+impl Hyphenator {
+    /// Load hyphenation patterns from the contents of a `hyph_*.dic` file.
+    ///
+    /// The first line must specify the charset. After that, each line contains
+    /// either a pattern, a comment starting with `%`, or one of the
+    /// `LEFTHYPHENMIN` / `RIGHTHYPHENMIN` directives. Patterns on both sides of a
+    /// `NEXTLEVEL` directive are merged, and nonstandard hyphenation rules
+    /// (anything after a `/`) are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the charset is not recognized or a directive has an
+    /// invalid value.
+    #[inline]
+    pub fn load_from_str(s: &str) -> Result<Self, Error> {
+        let mut lines = s.lines().enumerate().filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('%')
+        });
+
+        let encoding = match lines.next() {
+            Some((idx, line)) => Encoding::try_from(line.trim())
+                .map_err(|e| ParseError::new_nocol(e, line, idx + 1))?,
+            None => Encoding::default(),
+        };
+
+        let mut ret = Self {
+            encoding,
+            patterns: HashMap::new(),
+            max_pattern_len: 0,
+            left_min: DEFAULT_LEFT_MIN,
+            right_min: DEFAULT_RIGHT_MIN,
+        };
+
+        for (idx, line) in lines {
+            let line = line.trim();
+            let mut split = line.split_whitespace();
+            let first = split.next().unwrap_or_default();
+
+            let min_dest = match first {
+                "LEFTHYPHENMIN" => &mut ret.left_min,
+                "RIGHTHYPHENMIN" => &mut ret.right_min,
+                // We do not track compound boundaries separately
+                "COMPOUNDLEFTHYPHENMIN" | "COMPOUNDRIGHTHYPHENMIN" | "NEXTLEVEL" => continue,
+                pattern => {
+                    ret.add_pattern(pattern);
+                    continue;
+                }
+            };
+
+            let value = split.next().unwrap_or_default();
+            let parsed: usize = value
+                .parse()
+                .map_err(|e| ParseError::new_nocol(ParseErrorKind::Int(e), line, idx + 1))?;
+            // A value of 0 means "use the default"
+            if parsed > 0 {
+                *min_dest = parsed;
+            }
+        }
+
+        Ok(ret)
+    }
+
+    /// Add a single pattern such as `hen5at` or `.ab4`
+    fn add_pattern(&mut self, pattern: &str) {
+        // Drop nonstandard hyphenation information, e.g. `c1k/k=k,1,1`
+        let pattern = pattern.split('/').next().unwrap_or_default();
+
+        let mut letters = String::with_capacity(pattern.len());
+        let mut values = vec![0u8];
+
+        for ch in pattern.chars() {
+            if let Some(digit) = ch.to_digit(10) {
+                // Digits are always < 10 so this will not truncate
+                #[allow(clippy::cast_possible_truncation)]
+                let digit = digit as u8;
+                *values.last_mut().unwrap() = digit;
+            } else {
+                letters.extend(ch.to_lowercase());
+                values.push(0);
+            }
+        }
+
+        if letters.is_empty() {
+            return;
+        }
+
+        self.max_pattern_len = max(self.max_pattern_len, values.len() - 1);
+        self.patterns.insert(letters.into(), values.into());
+    }
+
+    /// The charset specified on the first line of the pattern file
+    #[inline]
+    pub fn encoding(&self) -> &Encoding {
+        &self.encoding
+    }
+
+    /// Locate positions where `word` may be hyphenated.
+    ///
+    /// Each returned value is a byte index into `word`, such that a hyphen may be
+    /// placed between `&word[..idx]` and `&word[idx..]`. Indices are sorted and
+    /// respect the `LEFTHYPHENMIN` and `RIGHTHYPHENMIN` limits. An empty vector is
+    /// returned if the word cannot be hyphenated.
+    #[inline]
+    pub fn hyphenate(&self, word: &str) -> Vec<usize> {
+        let char_count = word.chars().count();
+        if char_count < self.left_min + self.right_min || self.patterns.is_empty() {
+            return Vec::new();
+        }
+
+        // Word surrounded by `.` markers, matched case-insensitively. We keep a
+        // single char per input char so indices line up with the original word.
+        let chars: Vec<char> = core::iter::once('.')
+            .chain(word.chars().map(|c| c.to_lowercase().next().unwrap_or(c)))
+            .chain(core::iter::once('.'))
+            .collect();
+
+        // `points[i]` is the priority of a break before `chars[i]`
+        let mut points = vec![0u8; chars.len() + 1];
+        let mut buf = String::new();
+
+        for start in 0..chars.len() {
+            buf.clear();
+            let end_max = chars.len().min(start + self.max_pattern_len);
+
+            for ch in &chars[start..end_max] {
+                buf.push(*ch);
+                let Some(values) = self.patterns.get(buf.as_str()) else {
+                    continue;
+                };
+                for (offset, val) in values.iter().enumerate() {
+                    let point = &mut points[start + offset];
+                    *point = max(*point, *val);
+                }
+            }
+        }
+
+        // A break before word char `n` is a break before `chars[n + 1]`
+        word.char_indices()
+            .enumerate()
+            .filter(|(n, _)| *n >= self.left_min && char_count - n >= self.right_min)
+            .filter(|(n, _)| points[n + 1] % 2 == 1)
+            .map(|(_, (byte_idx, _))| byte_idx)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const PATTERNS: &str = "\
+UTF-8
+% comment
+LEFTHYPHENMIN 1
+RIGHTHYPHENMIN 1
+1na
+1tio
+hy3ph
+he2n
+hena4
+hen5at
+n2at
+o2n
+2io
+";
+
+    #[test]
+    fn test_load() {
+        let hyph = Hyphenator::load_from_str(PATTERNS).unwrap();
+        assert_eq!(hyph.encoding(), &Encoding::Utf8);
+        assert_eq!(hyph.left_min, 1);
+        assert_eq!(hyph.right_min, 1);
+        assert_eq!(hyph.max_pattern_len, 5);
+        assert_eq!(
+            hyph.patterns.get("henat").unwrap().as_ref(),
+            [0, 0, 0, 5, 0, 0]
+        );
+        assert_eq!(hyph.patterns.get("na").unwrap().as_ref(), [1, 0, 0]);
+    }
+
+    #[test]
+    fn test_load_err() {
+        assert!(Hyphenator::load_from_str("NOT-A-CHARSET\n1na").is_err());
+        assert!(Hyphenator::load_from_str("UTF-8\nLEFTHYPHENMIN x").is_err());
+    }
+
+    #[test]
+    fn test_hyphenate() {
+        let hyph = Hyphenator::load_from_str(PATTERNS).unwrap();
+        assert_eq!(hyph.hyphenate("hyphenation"), [2, 6]);
+        assert_eq!(hyph.hyphenate("Hyphenation"), [2, 6]);
+        assert!(hyph.hyphenate("xyz").is_empty());
+        assert!(hyph.hyphenate("").is_empty());
+    }
+
+    #[test]
+    fn test_hyphenate_limits() {
+        let with_limits = PATTERNS
+            .replace("LEFTHYPHENMIN 1", "LEFTHYPHENMIN 3")
+            .replace("RIGHTHYPHENMIN 1", "RIGHTHYPHENMIN 5");
+        let hyph = Hyphenator::load_from_str(&with_limits).unwrap();
+        assert_eq!(hyph.hyphenate("hyphenation"), [6]);
+    }
+
+    #[test]
+    fn test_hyphenate_multibyte() {
+        let hyph = Hyphenator::load_from_str("UTF-8\n1é").unwrap();
+        // Break is before the `é`, which starts at byte 2
+        assert_eq!(hyph.hyphenate("abéc"), [2]);
+        assert_eq!(hyph.hyphenate("abÉc"), [2]);
+    }
+}
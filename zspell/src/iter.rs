@@ -0,0 +1,119 @@
+//! Spellchecking streams of words with iterator adaptors, see [`SpellcheckExt`]
+
+use core::iter::FusedIterator;
+
+use crate::Dictionary;
+
+/// Adaptors to check the words of any iterator against a [`Dictionary`].
+///
+/// Items can be anything that derefs to a string, such as `&str` or `String`.
+/// Each one is checked as a single word like [`Dictionary::check_word`], as it
+/// is pulled through the iterator.
+///
+/// ```
+/// use zspell::SpellcheckExt;
+///
+/// let dict = zspell::builder()
+///     .config_str("")
+///     .dict_str("2\nthe\ncat")
+///     .build()
+///     .unwrap();
+///
+/// let checked: Vec<_> = "the cta".split(' ').spellcheck(&dict).collect();
+/// assert_eq!(checked, [("the", true), ("cta", false)]);
+///
+/// let words = vec!["teh".to_owned(), "cat".to_owned()];
+/// let errors: Vec<String> = words.into_iter().misspellings(&dict).collect();
+/// assert_eq!(errors, ["teh"]);
+/// ```
+pub trait SpellcheckExt: Iterator + Sized
+where
+    Self::Item: AsRef<str>,
+{
+    /// Pair each word with whether it is correct
+    #[inline]
+    fn spellcheck(self, dict: &Dictionary) -> Spellcheck<'_, Self> {
+        Spellcheck { iter: self, dict }
+    }
+
+    /// Only keep the words that are misspelled
+    #[inline]
+    fn misspellings(self, dict: &Dictionary) -> Misspellings<'_, Self> {
+        Misspellings { iter: self, dict }
+    }
+}
+
+impl<I> SpellcheckExt for I
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+}
+
+/// Iterator of words and whether they are correct, created by
+/// [`SpellcheckExt::spellcheck`]
+#[derive(Clone, Debug)]
+pub struct Spellcheck<'d, I> {
+    iter: I,
+    dict: &'d Dictionary,
+}
+
+impl<I> Iterator for Spellcheck<'_, I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = (I::Item, bool);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let word = self.iter.next()?;
+        let correct = self.dict.check_word(word.as_ref());
+        Some((word, correct))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> FusedIterator for Spellcheck<'_, I>
+where
+    I: FusedIterator,
+    I::Item: AsRef<str>,
+{
+}
+
+/// Iterator of misspelled words, created by [`SpellcheckExt::misspellings`]
+#[derive(Clone, Debug)]
+pub struct Misspellings<'d, I> {
+    iter: I,
+    dict: &'d Dictionary,
+}
+
+impl<I> Iterator for Misspellings<'_, I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let dict = self.dict;
+        self.iter.find(|word| !dict.check_word(word.as_ref()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I> FusedIterator for Misspellings<'_, I>
+where
+    I: FusedIterator,
+    I::Item: AsRef<str>,
+{
+}
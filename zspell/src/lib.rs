@@ -90,7 +90,8 @@
 //! assert_eq!(rust_stems, ["rust"]);
 //! ```
 //!
-//! See [`Dictionary`] and [`DictBuilder`] to get started.
+//! See [`Dictionary`] and [`DictBuilder`] to get started, or [`Checker`] to
+//! combine dictionaries, document filters, and a session in one place.
 //!
 //! # Stability & Feature Flags
 //!
@@ -111,8 +112,38 @@
 //! flags are subject to change, but the need for these flags will be removed as
 //! they are stabalized.
 //!
+//! The following optional features add stable functionality with extra
+//! dependencies:
+//!
+//! - `arbitrary`: The `fuzz` module, which generates affix and dictionary
+//!   files for fuzzing the parsers
+//! - `archive`: The `archive` module, which loads dictionaries directly from
+//!   LibreOffice `.oxt` extensions and Mozilla `.xpi` add-ons
+//! - `fetch`: The `fetch` module, which downloads dictionaries by language and
+//!   caches them
+//! - `icu`: Case mapping from [ICU4X], which puts the first letter of
+//!   capitalized suggestions in titlecase and folds case fully for
+//!   `DictBuilder::case_insensitive` (so "STRASSE" finds "Straße"), and which
+//!   orders equally good suggestions by the alphabet of the dictionary's
+//!   language. Needs Rust 1.67.
+//! - `regex`: `DictBuilder::ignore_regex`, to skip text matching regular
+//!   expressions when checking documents
+//! - `serde`: `Serialize` for analysis, explanation, and stem results and for
+//!   parse errors, plus `Deserialize` for the types that own their data, such
+//!   as `MorphInfo` and `DictStats`
+//! - `std` (enabled by default): Everything that needs the standard library,
+//!   such as [`SharedDictionary`] and the modules above. Without it, the core
+//!   checker only needs `alloc`
+//! - `tokio`: The `stream` module, which checks async readers and streams of
+//!   text as they arrive
+//! - `tracing`: Spans and events from the [`tracing`] crate for building
+//!   dictionaries, checking, and suggesting, to find where time is spent
+//!
 //! [Hunspell]: http://hunspell.github.io/
+//! [ICU4X]: https://github.com/unicode-org/icu4x
 //! [CLI docs]: https://pluots.github.io/zspell/
+//! [`tracing`]: https://docs.rs/tracing
+#![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unsafe_code)]
 #![warn(clippy::pedantic)]
 // #![warn(clippy::cargo)]
@@ -130,30 +161,80 @@
 #![allow(clippy::missing_const_for_fn)]
 #![allow(clippy::derive_partial_eq_without_eq)]
 
+extern crate alloc;
+
 mod affix;
+#[cfg(feature = "archive")]
+pub mod archive;
+mod cancel;
+mod casing;
+mod checker;
 mod dict;
 pub mod error;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod filter;
+mod fix;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
 mod helpers;
+pub mod hyphenation;
+mod iter;
 mod meta;
 mod morph;
+mod normalize;
+mod numeric;
+mod phonet;
+mod progress;
+mod segment;
+mod session;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "tokio")]
+pub mod stream;
+#[cfg(feature = "unstable-suggestions")]
 mod suggestions;
+pub mod thesaurus;
+mod trace;
 
 #[cfg(feature = "unstable-system")]
 pub mod system;
 
 pub(crate) use affix::ParsedCfg;
-pub use affix::PartOfSpeech;
+pub use affix::{Encoding, ParseMode, PartOfSpeech, RuleType};
+pub use cancel::CancelToken;
+pub use checker::{Checker, CheckerBuilder};
 #[doc(inline)]
-pub use dict::{DictBuilder, Dictionary, WordEntry, WordList};
+pub use dict::{
+    AffixInfo, CaseMatch, DictBuilder, DictStats, Dictionary, Explanation, Hint, ListKind, Reason,
+    StemInfo, WordEntry, WordForm, WordList,
+};
 #[doc(inline)]
 pub use error::Error;
-pub use morph::{MorphInfo, MorphStr};
+pub use filter::DocumentFilter;
+pub use fix::{apply_fixes, apply_word_fixes, word_fixes};
+#[doc(inline)]
+pub use hyphenation::Hyphenator;
+pub use iter::{Misspellings, Spellcheck, SpellcheckExt};
+pub use morph::{Analysis, MorphInfo, MorphSource, MorphStr, MorphTag, StemAnalysis};
+pub use normalize::Normalization;
+pub use progress::Progress;
+pub use segment::Segmenter;
+pub use session::Session;
+#[cfg(feature = "std")]
+pub use shared::SharedDictionary;
+#[cfg(feature = "unstable-suggestions")]
+pub use suggestions::{PhoneticSuggestions, SuggestionCacheStats};
+#[doc(inline)]
+pub use thesaurus::Thesaurus;
 
 // Make some things public when benchmarking
 #[cfg(feature = "unstable-bench")]
 pub mod bench {
-    pub use super::affix::{affix_from_str, FlagType};
+    pub use super::affix::{affix_from_str, Condition, FlagType};
     pub use super::dict::DictEntry;
+    #[cfg(feature = "unstable-suggestions")]
+    pub use super::suggestions::EditDistance;
 }
 
 /// Create a new [`DictBuilder`] instance (shortcut for [`DictBuilder::new`])
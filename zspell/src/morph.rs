@@ -1,12 +1,16 @@
 //! Types and implementation of morphological analysis
 
-use std::fmt;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
 
 use crate::affix::PartOfSpeech;
+use crate::dict::AffixInfo;
 
 /// Morphological information about a word, used by analysis methods
 #[non_exhaustive]
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MorphInfo {
     /// `st:` stem word
     Stem(MorphStr),
@@ -22,18 +26,19 @@ pub enum MorphInfo {
     InflecSfx(MorphStr),
     /// `ts:` terminal suffix
     TerminalSfx(MorphStr),
-    /// `dp:` derivational suffix
+    /// `dp:` derivational prefix
     DerivPfx(MorphStr),
-    /// `ip:` inflectional suffix
+    /// `ip:` inflectional prefix
     InflecPfx(MorphStr),
-    /// `tp:` terminal suffix
+    /// `tp:` terminal prefix
     TermPfx(MorphStr),
     /// `sp:` surface prefix
     SurfacePfx(MorphStr),
     /// `pa:` parts of compound words
     CompPart(MorphStr),
     /// Any unrecognized tag. This will be stored as written (e.g. `foo:bar` is stored as
-    /// `foo:bar`, not just `bar`).
+    /// `foo:bar`, not just `bar`), so custom tags used by some dictionaries are kept
+    /// and written back unchanged.
     Other(MorphStr),
 }
 
@@ -101,11 +106,115 @@ impl fmt::Display for MorphInfo {
     }
 }
 
+/// Structured morphological analysis of a word, created by
+/// [`WordEntry::analyze`](crate::WordEntry::analyze).
+///
+/// Morph info is grouped by the stem it belongs to, and each tag records whether it
+/// came from the dictionary entry or from an affix rule. Use [`morphs`](Self::morphs)
+/// if only a flat list of tags is needed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Analysis<'a> {
+    index: usize,
+    stems: Vec<StemAnalysis<'a>>,
+}
+
+/// Morph info that belongs to a single stem, see [`Analysis`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StemAnalysis<'a> {
+    /// The stem word
+    pub stem: &'a str,
+    /// All tags for this stem, along with where they came from
+    pub tags: Vec<MorphTag<'a>>,
+}
+
+/// A single morph info tag along with its source, see [`Analysis`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MorphTag<'a> {
+    /// The morph info
+    pub info: &'a MorphInfo,
+    /// The entry or affix that provided this tag
+    pub source: MorphSource<'a>,
+}
+
+/// Where a [`MorphTag`] came from
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum MorphSource<'a> {
+    /// The tag was written on the stem's entry in the dictionary or the personal
+    /// dictionary
+    Entry,
+    /// The tag was provided by an affix rule that was applied to the stem
+    Affix(AffixInfo<'a>),
+}
+
+impl<'a> Analysis<'a> {
+    pub(crate) fn new(index: usize) -> Self {
+        Self {
+            index,
+            stems: Vec::new(),
+        }
+    }
+
+    /// Add a tag to the group for `stem`, creating the group if needed
+    pub(crate) fn push(&mut self, stem: &'a str, tag: MorphTag<'a>) {
+        let group = if let Some(pos) = self.stems.iter().position(|s| s.stem == stem) {
+            &mut self.stems[pos]
+        } else {
+            self.stems.push(StemAnalysis {
+                stem,
+                tags: Vec::new(),
+            });
+            self.stems.last_mut().unwrap()
+        };
+
+        group.tags.push(tag);
+    }
+
+    /// Make sure a group exists for `stem`, even if it has no tags
+    pub(crate) fn add_stem(&mut self, stem: &'a str) {
+        if !self.stems.iter().any(|s| s.stem == stem) {
+            self.stems.push(StemAnalysis {
+                stem,
+                tags: Vec::new(),
+            });
+        }
+    }
+
+    /// Byte index of the analyzed word within the input string. This is always 0
+    /// for entries created with [`Dictionary::entry`](crate::Dictionary::entry).
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Morph info grouped by stem
+    #[inline]
+    pub fn stems(&self) -> &[StemAnalysis<'a>] {
+        &self.stems
+    }
+
+    /// Iterate over all morph info tags, regardless of stem or source
+    #[inline]
+    pub fn morphs(&self) -> impl Iterator<Item = &'a MorphInfo> + '_ {
+        self.stems
+            .iter()
+            .flat_map(|stem| stem.tags.iter().map(|tag| tag.info))
+    }
+}
+
 /// A string used as part of morphological analysis
 ///
 /// This is a thin wrapper over a native string type to allow us to change
 /// the implementation as needed.
 #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct MorphStr(Box<str>);
 
 impl AsRef<str> for MorphStr {
@@ -154,6 +263,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn morph_round_trip() {
+        let tags = [
+            "po:noun",
+            "po:custom_Part",
+            "st:stem",
+            "al:sang",
+            "ph:fone",
+            "ds:able",
+            "is:ed",
+            "ts:ly",
+            "sp:re",
+            "pa:part",
+            "dp:un",
+            "ip:ge",
+            "tp:a",
+            "xx:custom",
+            "Zz:Mixed:Case",
+            "notatag",
+        ];
+
+        for tag in tags {
+            let parsed = MorphInfo::from(tag);
+            assert_eq!(parsed.to_string(), tag, "round trip failed for {tag}");
+            assert_eq!(MorphInfo::from(parsed.to_string().as_str()), parsed);
+        }
+
+        assert!(matches!(MorphInfo::from("xx:custom"), MorphInfo::Other(_)));
+        assert_eq!(
+            MorphInfo::from("po:Verb"),
+            MorphInfo::Part(PartOfSpeech::Verb)
+        );
+    }
+
     #[test]
     fn morph_string_ok() {
         let input = "st:stem ip:abcd pa:xyz    st:some-stem\tal:def";
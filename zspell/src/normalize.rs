@@ -0,0 +1,71 @@
+//! Unicode normalization of words before lookup
+
+use alloc::borrow::Cow;
+
+use unicode_normalization::{is_nfc_quick, is_nfkc_quick, IsNormalized, UnicodeNormalization};
+
+/// A Unicode normalization form to apply to words, set with
+/// [`DictBuilder::normalization`](crate::DictBuilder::normalization).
+///
+/// The same text can be encoded in more than one way; for example, `é` may be a
+/// single codepoint or an `e` followed by a combining accent. Text that comes from
+/// macOS file names is often in the decomposed form, which would otherwise not
+/// match dictionary entries.
+///
+/// When a form is selected, dictionary and affix sources are normalized at build
+/// time and every input word is normalized before it is looked up.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Normalization {
+    /// Look up words exactly as they are provided. This is the default.
+    #[default]
+    None,
+    /// Canonical composition (NFC)
+    Nfc,
+    /// Compatibility composition (NFKC). In addition to NFC, this folds
+    /// compatibility characters such as ligatures (`ﬁ` -> `fi`) and full-width forms.
+    Nfkc,
+}
+
+impl Normalization {
+    /// Normalize a string, only allocating if it is not already normalized
+    pub(crate) fn apply(self, s: &str) -> Cow<'_, str> {
+        match self {
+            Normalization::None => Cow::Borrowed(s),
+            Normalization::Nfc => {
+                if is_nfc_quick(s.chars()) == IsNormalized::Yes {
+                    Cow::Borrowed(s)
+                } else {
+                    Cow::Owned(s.nfc().collect())
+                }
+            }
+            Normalization::Nfkc => {
+                if is_nfkc_quick(s.chars()) == IsNormalized::Yes {
+                    Cow::Borrowed(s)
+                } else {
+                    Cow::Owned(s.nfkc().collect())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+
+        assert_eq!(Normalization::None.apply(decomposed), decomposed);
+        assert_eq!(Normalization::Nfc.apply(decomposed), composed);
+        assert!(matches!(
+            Normalization::Nfc.apply(composed),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(Normalization::Nfc.apply("\u{fb01}ne"), "\u{fb01}ne");
+        assert_eq!(Normalization::Nfkc.apply("\u{fb01}ne"), "fine");
+    }
+}
@@ -0,0 +1,196 @@
+//! Recognition of numbers, times, dates, and Roman numerals, which may be
+//! accepted without being in the dictionary. See [`DictBuilder::accept_numeric`]
+//! and [`DictBuilder::accept_roman_numerals`].
+//!
+//! [`DictBuilder::accept_numeric`]: crate::DictBuilder::accept_numeric
+//! [`DictBuilder::accept_roman_numerals`]: crate::DictBuilder::accept_roman_numerals
+
+/// Separators that may appear between groups of digits, including the
+/// apostrophe and narrow spaces used by some locales
+const GROUP_SEPARATORS: &[char] = &['.', ',', '\'', '’', '\u{a0}', '\u{202f}'];
+
+/// Roman digits for each place from thousands to units, indexed by value
+const ROMAN_PLACES: [[&str; 10]; 4] = [
+    ["", "M", "MM", "MMM", "", "", "", "", "", ""],
+    ["", "C", "CC", "CCC", "CD", "D", "DC", "DCC", "DCCC", "CM"],
+    ["", "X", "XX", "XXX", "XL", "L", "LX", "LXX", "LXXX", "XC"],
+    ["", "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX"],
+];
+
+/// Whether `word` is a number, time of day, or ISO 8601 date
+pub fn is_numeric_token(word: &str) -> bool {
+    is_decimal(word) || is_time(word) || is_iso_date(word)
+}
+
+/// A number with an optional sign, such as `-3`, `1'000`, `1.000,5` or `.25`.
+/// Groups of digits are separated by at most one separator.
+fn is_decimal(word: &str) -> bool {
+    let word = word
+        .strip_prefix(['-', '+', '−'].as_slice())
+        .unwrap_or(word);
+    // A leading separator is allowed for a decimal like `.5`
+    let mut prev_digit = word.starts_with(['.', ','].as_slice());
+    for ch in word.chars().skip(usize::from(prev_digit)) {
+        match ch {
+            '0'..='9' => prev_digit = true,
+            _ if GROUP_SEPARATORS.contains(&ch) && prev_digit => prev_digit = false,
+            _ => return false,
+        }
+    }
+    prev_digit && word.chars().any(|ch| ch.is_ascii_digit())
+}
+
+/// A time such as `9:05`, `23:59:59` or `12:30:15.250`
+fn is_time(word: &str) -> bool {
+    let mut fields = word.split(':');
+    let (Some(hour), Some(minute)) = (fields.next(), fields.next()) else {
+        return false;
+    };
+    let second = fields.next();
+    if fields.next().is_some() {
+        return false;
+    }
+
+    let valid_second = second.map_or(true, |sec| {
+        let (whole, frac) = sec.split_once(['.', ','].as_slice()).unwrap_or((sec, "0"));
+        whole.len() == 2 && in_range(whole, 0, 60) && all_digits(frac)
+    });
+    // `24:00` is the end of a day, but there is no later time in hour 24
+    let end_of_day = minute == "00"
+        && second.map_or(true, |sec| {
+            sec.chars().all(|ch| matches!(ch, '0' | '.' | ','))
+        });
+
+    (1..=2).contains(&hour.len())
+        && in_range(hour, 0, 24)
+        && (hour != "24" || end_of_day)
+        && minute.len() == 2
+        && in_range(minute, 0, 59)
+        && valid_second
+}
+
+/// An ISO 8601 date such as `2024-02-29`, optionally with a time and offset as
+/// in `2024-02-29T12:30:00Z`
+fn is_iso_date(word: &str) -> bool {
+    let (date, time) = word
+        .split_once(['T', 't'].as_slice())
+        .map_or((word, None), |(date, time)| (date, Some(time)));
+
+    let mut fields = date.split('-');
+    let valid_date = matches!(
+        (fields.next(), fields.next(), fields.next(), fields.next()),
+        (Some(year), Some(month), Some(day), None)
+            if year.len() == 4 && all_digits(year)
+                && month.len() == 2 && in_range(month, 1, 12)
+                && day.len() == 2 && in_range(day, 1, 31)
+    );
+
+    valid_date && time.map_or(true, is_offset_time)
+}
+
+/// A time followed by an optional `Z` or `±hh:mm` offset
+fn is_offset_time(time: &str) -> bool {
+    if let Some(time) = time.strip_suffix(['Z', 'z'].as_slice()) {
+        return is_time(time);
+    }
+    time.rfind(['+', '-'].as_slice()).map_or_else(
+        || is_time(time),
+        |idx| is_time(&time[..idx]) && is_time(&time[idx + 1..]),
+    )
+}
+
+/// Whether `word` is an uppercase Roman numeral in its standard form, from `I`
+/// to `MMMCMXCIX` (3999). Forms such as `IIII` or `IC` are not accepted.
+pub fn is_roman_numeral(word: &str) -> bool {
+    let mut rest = word;
+    for place in &ROMAN_PLACES {
+        // The longest match is the right one, e.g. `CD` rather than `C` in `CDX`
+        let digit = place
+            .iter()
+            .filter(|digit| rest.starts_with(*digit))
+            .max_by_key(|digit| digit.len());
+        if let Some(digit) = digit {
+            rest = &rest[digit.len()..];
+        }
+    }
+    !word.is_empty() && rest.is_empty()
+}
+
+fn all_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether `s` is all digits with a value in `min..=max`
+fn in_range(s: &str, min: u32, max: u32) -> bool {
+    all_digits(s) && s.parse().map_or(false, |v: u32| (min..=max).contains(&v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        for s in [
+            "42",
+            "-3",
+            "+1.5",
+            "1'000",
+            "1\u{202f}000",
+            "1.000,50",
+            ".25",
+        ] {
+            assert!(is_numeric_token(s), "{s}");
+        }
+        for s in ["", "-", "1.", "1..2", "1,,2", "1e5", "abc", "."] {
+            assert!(!is_numeric_token(s), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_times() {
+        for s in ["9:05", "23:59", "23:59:60", "12:30:15.250", "24:00"] {
+            assert!(is_numeric_token(s), "{s}");
+        }
+        for s in ["25:00", "24:01", "9:5", "12:60", "1:2:3:4", ":30"] {
+            assert!(!is_numeric_token(s), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_roman_numerals() {
+        for s in [
+            "I",
+            "IV",
+            "IX",
+            "XIV",
+            "XL",
+            "XC",
+            "CDXLIV",
+            "MMXXIV",
+            "MMMCMXCIX",
+        ] {
+            assert!(is_roman_numeral(s), "{s}");
+        }
+        for s in [
+            "", "IIII", "IC", "VX", "IL", "MMMM", "XIIV", "xiv", "Xiv", "CIVIL",
+        ] {
+            assert!(!is_roman_numeral(s), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_dates() {
+        for s in [
+            "2024-02-29",
+            "2024-02-29T12:30",
+            "2024-02-29T12:30:00Z",
+            "2024-02-29T12:30:00+01:00",
+        ] {
+            assert!(is_numeric_token(s), "{s}");
+        }
+        for s in ["2024-13-01", "2024-1-01", "24-01-01", "2024-01-01T25:00"] {
+            assert!(!is_numeric_token(s), "{s}");
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Phonetic keys from a `PHONE` table
+//!
+//! This follows the algorithm used by Hunspell and Aspell, originally by Björn
+//! Jacke. Each rule has a pattern and a replacement. Patterns are uppercase
+//! letters optionally followed by:
+//!
+//! - `(..)`: one of the listed letters
+//! - `-`: one or more times, the last letters are matched but not replaced
+//! - `<`: the replacement is written back into the word and checked again
+//! - a digit: priority for follow-up rules, default 5
+//! - `^`: only at the start of a word, `^^` also restarts the word afterward
+//! - `$`: only at the end of a word
+//!
+//! A replacement of `_` is empty. Letters without a matching rule are dropped,
+//! and repeated letters in the result are collapsed.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use hashbrown::HashMap;
+
+use crate::affix::Phonetic;
+
+/// Characters that end the literal part of a pattern
+const SPECIAL: &[char] = &['(', '-', '<', '^', '$'];
+
+/// A compiled `PHONE` table
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhoneTable {
+    /// Rules grouped by their first letter, otherwise in table order
+    rules: Vec<PhoneRule>,
+    /// Index of the first rule for each letter
+    first: HashMap<char, usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PhoneRule {
+    pattern: Vec<char>,
+    replace: Vec<char>,
+}
+
+impl PhoneRule {
+    /// Character at `idx` of the pattern, or `'\0'` past the end
+    fn pat(&self, idx: usize) -> char {
+        self.pattern.get(idx).copied().unwrap_or('\0')
+    }
+
+    /// Character at `idx` of the replacement, or `'\0'` past the end
+    fn rep(&self, idx: usize) -> char {
+        self.replace.get(idx).copied().unwrap_or('\0')
+    }
+
+    /// Whether the modifiers after the first letter contain `needle`
+    fn has_modifier(&self, needle: &[char]) -> bool {
+        self.pattern[1..].windows(needle.len()).any(|w| w == needle)
+    }
+
+    /// If the group starting at `s` (an opening parenthesis) contains `ch`,
+    /// return the index after the group
+    fn group_match(&self, s: usize, ch: char) -> Option<usize> {
+        let close = self.pattern[s..]
+            .iter()
+            .position(|&c| c == ')')
+            .map_or(self.pattern.len(), |pos| s + pos);
+        (ch.is_alphabetic() && self.pattern[s + 1..close].contains(&ch)).then_some(close + 1)
+    }
+}
+
+impl PhoneTable {
+    /// Compile the rules of a `PHONE` table, or return `None` if it is empty
+    pub fn new(phonetics: &[Phonetic]) -> Option<Self> {
+        let mut rules: Vec<PhoneRule> = phonetics
+            .iter()
+            .filter(|ph| !ph.pattern().is_empty())
+            .map(|ph| PhoneRule {
+                pattern: ph.pattern().chars().collect(),
+                replace: match ph.replace() {
+                    "_" => Vec::new(),
+                    rep => rep.chars().collect(),
+                },
+            })
+            .collect();
+        if rules.is_empty() {
+            return None;
+        }
+
+        // Rules for a letter are searched in order, so they must be adjacent
+        rules.sort_by_key(|rule| rule.pattern[0]);
+        let mut first = HashMap::new();
+        for (idx, rule) in rules.iter().enumerate() {
+            first.entry(rule.pattern[0]).or_insert(idx);
+        }
+
+        Some(Self { rules, first })
+    }
+
+    /// Iterate the indices of the rules starting with `ch`
+    fn rules_for(&self, ch: char) -> impl Iterator<Item = usize> + '_ {
+        let start = self.first.get(&ch).copied().unwrap_or(self.rules.len());
+        (start..self.rules.len()).take_while(move |&n| self.rules[n].pattern[0] == ch)
+    }
+
+    /// Compute the phonetic key of an uppercase word
+    // Kept close to the reference implementation, including its variable names
+    #[allow(clippy::too_many_lines, clippy::many_single_char_names)]
+    pub fn key(&self, input: &str) -> String {
+        let mut word: Vec<char> = input.chars().collect();
+        let at = |word: &[char], idx: usize| word.get(idx).copied().unwrap_or('\0');
+        let is_alpha = |ch: char| ch.is_alphabetic();
+
+        let mut target: Vec<char> = Vec::with_capacity(word.len());
+        let mut i = 0;
+        // Number of letters matched by the last rule tried
+        let mut k = 0;
+        // Character following the letters of the last rule tried, or whether the
+        // last applied rule had a `<`. A letter is only kept if this is 0.
+        let mut p0: u32 = u32::MAX;
+        // Set after a `<` rule so it cannot apply twice in a row
+        let mut z = false;
+
+        while i < word.len() {
+            let mut c = word[i];
+            // Set if the word was rewritten and `i` should not advance
+            let mut z0 = false;
+            for n in self.rules_for(c) {
+                let rule = &self.rules[n];
+                let mut p = 5;
+                let mut s = 1;
+                k = 1;
+
+                while rule.pat(s) != '\0'
+                    && at(&word, i + k) == rule.pat(s)
+                    && !rule.pat(s).is_ascii_digit()
+                    && !SPECIAL.contains(&rule.pat(s))
+                {
+                    k += 1;
+                    s += 1;
+                }
+                if rule.pat(s) == '(' {
+                    if let Some(after) = rule.group_match(s, at(&word, i + k)) {
+                        k += 1;
+                        s = after;
+                    }
+                }
+
+                p0 = u32::from(rule.pat(s));
+                let k0 = k;
+                while rule.pat(s) == '-' && k > 1 {
+                    k -= 1;
+                    s += 1;
+                }
+                if rule.pat(s) == '<' {
+                    s += 1;
+                }
+                if let Some(digit) = rule.pat(s).to_digit(10) {
+                    p = digit;
+                    s += 1;
+                }
+                if rule.pat(s) == '^' && rule.pat(s + 1) == '^' {
+                    s += 1;
+                }
+
+                let prev_alpha = i > 0 && is_alpha(word[i - 1]);
+                let next_alpha = is_alpha(at(&word, i + k0));
+                let fits = rule.pat(s) == '\0'
+                    || (rule.pat(s) == '^'
+                        && !prev_alpha
+                        && (rule.pat(s + 1) != '$' || !next_alpha))
+                    || (rule.pat(s) == '$' && prev_alpha && !next_alpha);
+                if !fits {
+                    continue;
+                }
+
+                // A rule for the last matched letter may take precedence if it
+                // matches more of the word with at least the same priority
+                if k > 1 && p0 != u32::from('-') && at(&word, i + k) != '\0' {
+                    let c0 = at(&word, i + k - 1);
+                    let followup = self.rules_for(c0).find_map(|n0| {
+                        let rule0 = &self.rules[n0];
+                        let mut k0 = k;
+                        let mut p00 = 5;
+                        let mut s = 1;
+
+                        while rule0.pat(s) != '\0'
+                            && at(&word, i + k0) == rule0.pat(s)
+                            && !rule0.pat(s).is_ascii_digit()
+                            && !SPECIAL.contains(&rule0.pat(s))
+                        {
+                            k0 += 1;
+                            s += 1;
+                        }
+                        if rule0.pat(s) == '(' {
+                            if let Some(after) = rule0.group_match(s, at(&word, i + k0)) {
+                                k0 += 1;
+                                s = after;
+                            }
+                        }
+                        while rule0.pat(s) == '-' {
+                            s += 1;
+                        }
+                        if rule0.pat(s) == '<' {
+                            s += 1;
+                        }
+                        if let Some(digit) = rule0.pat(s).to_digit(10) {
+                            p00 = digit;
+                            s += 1;
+                        }
+
+                        let fits = rule0.pat(s) == '\0'
+                            || (rule0.pat(s) == '$' && !is_alpha(at(&word, i + k0)));
+                        // Rules that only cover the same letters do not count
+                        (fits && k0 != k && p00 >= p).then_some(p00)
+                    });
+                    if let Some(priority) = followup {
+                        p0 = priority;
+                        continue;
+                    }
+                }
+
+                p0 = u32::from(rule.has_modifier(&['<']));
+                if rule.has_modifier(&['<']) && !z {
+                    // Write the replacement into the word and check it again
+                    if rule.rep(0) != '\0'
+                        && matches!(target.last(), Some(&l) if l == c || l == rule.rep(0))
+                    {
+                        target.pop();
+                    }
+                    z0 = true;
+                    z = true;
+                    let mut k0 = 0;
+                    while k0 < rule.replace.len() && i + k0 < word.len() {
+                        word[i + k0] = rule.replace[k0];
+                        k0 += 1;
+                    }
+                    if k > k0 {
+                        word.drain(i + k0..i + k);
+                    }
+                } else {
+                    i += k - 1;
+                    z = false;
+                    // All but the last character are written here, the last one is
+                    // handled as the current letter
+                    let mut s = 0;
+                    while rule.rep(s) != '\0' && rule.rep(s + 1) != '\0' {
+                        if target.last() != Some(&rule.rep(s)) {
+                            target.push(rule.rep(s));
+                        }
+                        s += 1;
+                    }
+                    c = rule.rep(s);
+                    if rule.has_modifier(&['^', '^']) {
+                        if c != '\0' {
+                            target.push(c);
+                        }
+                        word.drain(..=i);
+                        i = 0;
+                        z0 = true;
+                    }
+                }
+                break;
+            }
+
+            if !z0 {
+                if k > 0 && p0 == 0 && c != '\0' && target.last() != Some(&c) {
+                    target.push(c);
+                }
+                i += 1;
+                z = false;
+                k = 0;
+            }
+        }
+
+        target.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rules are tried in order, so longer patterns come first
+    fn table(rules: &[(&str, &str)]) -> PhoneTable {
+        let phonetics: Vec<_> = rules
+            .iter()
+            .map(|(pat, rep)| Phonetic::new(pat, rep))
+            .collect();
+        PhoneTable::new(&phonetics).unwrap()
+    }
+
+    #[test]
+    fn test_simple_rules() {
+        let t = table(&[
+            ("A", "A"),
+            ("E", "_"),
+            ("N", "N"),
+            ("O", "O"),
+            ("PH", "F"),
+            ("P", "P"),
+            ("T", "T"),
+        ]);
+        assert_eq!(t.key("PHONE"), "FON");
+        // Repeated letters collapse
+        assert_eq!(t.key("PPOT"), "POT");
+        // Letters without rules are dropped
+        assert_eq!(t.key("PXOT"), "POT");
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let t = table(&[
+            ("A", "A"),
+            ("I", "I"),
+            ("KN^", "N"),
+            ("K", "K"),
+            ("N", "N"),
+            ("O", "O"),
+            ("TIO-", "X"),
+            ("T", "T"),
+        ]);
+        // `^` only matches at the start
+        assert_eq!(t.key("KNOT"), "NOT");
+        assert_eq!(t.key("AKNOT"), "AKNOT");
+        // `-` leaves the last letter to be matched again
+        assert_eq!(t.key("NATION"), "NAXON");
+    }
+
+    #[test]
+    fn test_empty_table() {
+        assert!(PhoneTable::new(&[]).is_none());
+    }
+}
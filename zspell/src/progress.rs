@@ -0,0 +1,89 @@
+//! Progress reporting for long document checks, see [`Progress`]
+
+/// Bytes of input between progress reports
+const REPORT_INTERVAL: usize = 64 * 1024;
+
+/// How far a document check has got, passed to the callback of
+/// [`Dictionary::check_indices_with_progress`](crate::Dictionary::check_indices_with_progress)
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Bytes of input that have been checked
+    pub bytes: usize,
+    /// Total bytes of input
+    pub total_bytes: usize,
+    /// Number of words that have been checked
+    pub words: usize,
+}
+
+impl Progress {
+    /// The fraction of the input that has been checked, from 0.0 to 1.0
+    #[inline]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            self.bytes as f64 / self.total_bytes as f64
+        }
+    }
+
+    /// Whether the whole input has been checked
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.bytes == self.total_bytes
+    }
+}
+
+/// Wraps an iterator of words and their byte indices, calling `f` every
+/// [`REPORT_INTERVAL`] bytes and once more when the words run out
+pub struct WithProgress<I, F> {
+    words: I,
+    f: F,
+    progress: Progress,
+    next_report: usize,
+    done: bool,
+}
+
+impl<I, F> WithProgress<I, F> {
+    pub fn new(words: I, total_bytes: usize, f: F) -> Self {
+        Self {
+            words,
+            f,
+            progress: Progress {
+                bytes: 0,
+                total_bytes,
+                words: 0,
+            },
+            next_report: REPORT_INTERVAL,
+            done: false,
+        }
+    }
+}
+
+impl<'a, I, F> Iterator for WithProgress<I, F>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+    F: FnMut(Progress),
+{
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some((idx, word)) = self.words.next() else {
+            if !self.done {
+                self.done = true;
+                self.progress.bytes = self.progress.total_bytes;
+                (self.f)(self.progress);
+            }
+            return None;
+        };
+
+        self.progress.words += 1;
+        self.progress.bytes = idx + word.len();
+        if self.progress.bytes >= self.next_report {
+            self.next_report = self.progress.bytes + REPORT_INTERVAL;
+            (self.f)(self.progress);
+        }
+        Some((idx, word))
+    }
+}
@@ -0,0 +1,469 @@
+//! Splitting text into words, including a hook for scripts that do not separate
+//! words with spaces
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::Peekable;
+use core::ops::Range;
+
+use unicode_segmentation::{UWordBoundIndices, UnicodeSegmentation};
+
+#[cfg(feature = "regex")]
+use crate::helpers::ReWrapper;
+use crate::numeric::is_numeric_token;
+
+/// The most word-boundary tokens that are joined into a single numeric token
+const MAX_NUMERIC_TOKENS: usize = 16;
+
+/// A word segmenter for scripts that do not put spaces between words, such as
+/// Thai, Lao, Khmer, Burmese, Chinese, or Japanese.
+///
+/// Unicode word boundaries alone cannot split these scripts correctly, usually
+/// yielding entire runs of text as a single token (or every character as its own
+/// token). By default, a [`Dictionary`](crate::Dictionary) skips such runs when
+/// checking documents rather than reporting them as misspelled. If a segmenter is
+/// provided via [`DictBuilder::segmenter`](crate::DictBuilder::segmenter), each
+/// run is passed to it and the resulting words are checked like any other.
+///
+/// ```
+/// use zspell::{DictBuilder, Segmenter};
+///
+/// /// A segmenter that treats every character as a word
+/// #[derive(Debug)]
+/// struct CharSegmenter;
+///
+/// impl Segmenter for CharSegmenter {
+///     fn segment<'a>(&self, text: &'a str) -> Vec<(usize, &'a str)> {
+///         text.char_indices()
+///             .map(|(idx, ch)| (idx, &text[idx..idx + ch.len_utf8()]))
+///             .collect()
+///     }
+/// }
+///
+/// let aff = "SET UTF-8\n";
+/// let dic = "3\nhello\n日\n本\n";
+///
+/// let dict = DictBuilder::new().config_str(aff).dict_str(dic).build().unwrap();
+/// // Without a segmenter, the Japanese text is skipped
+/// assert!(dict.check("hello 日本語"));
+///
+/// let dict = DictBuilder::new()
+///     .config_str(aff)
+///     .dict_str(dic)
+///     .segmenter(CharSegmenter)
+///     .build()
+///     .unwrap();
+/// let errors: Vec<_> = dict.check_indices("hello 日本語").collect();
+/// assert_eq!(errors, [(12, "語")]);
+/// ```
+pub trait Segmenter: fmt::Debug + Send + Sync {
+    /// Split a run of text into words. Each item is the byte offset of the word
+    /// within `text` along with the word itself, which must be a slice of `text`.
+    fn segment<'a>(&self, text: &'a str) -> Vec<(usize, &'a str)>;
+}
+
+/// Storage for an optional segmenter that can be held by a `Dictionary`
+#[derive(Clone, Default)]
+pub struct SegmenterHook(Option<Arc<dyn Segmenter>>);
+
+impl SegmenterHook {
+    pub fn new(segmenter: Arc<dyn Segmenter>) -> Self {
+        Self(Some(segmenter))
+    }
+
+    pub fn get(&self) -> Option<&dyn Segmenter> {
+        self.0.as_deref()
+    }
+}
+
+impl fmt::Debug for SegmenterHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(seg) => seg.fmt(f),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+impl PartialEq for SegmenterHook {
+    /// Two hooks are equal if they refer to the same segmenter
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Text to skip when checking documents, see
+/// [`DictBuilder::ignore_literal`](crate::DictBuilder::ignore_literal)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IgnoredText {
+    literals: Box<[Box<str>]>,
+    #[cfg(feature = "regex")]
+    pattern: Option<ReWrapper>,
+}
+
+impl IgnoredText {
+    pub fn new(literals: &[String]) -> Self {
+        Self {
+            literals: literals.iter().map(|lit| lit.as_str().into()).collect(),
+            #[cfg(feature = "regex")]
+            pattern: None,
+        }
+    }
+
+    /// Also skip matches of a regular expression
+    #[cfg(feature = "regex")]
+    pub fn with_pattern(mut self, pattern: ReWrapper) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
+
+    /// Byte ranges of `input` to skip
+    pub fn spans(&self, input: &str) -> Vec<Range<usize>> {
+        let spans = self.literals.iter().flat_map(|lit| {
+            input
+                .match_indices(lit.as_ref())
+                .map(|(idx, found)| idx..idx + found.len())
+        });
+        #[cfg(feature = "regex")]
+        let spans = spans.chain(
+            self.pattern
+                .iter()
+                .flat_map(|re| re.find_iter(input).map(|m| m.range())),
+        );
+        spans.collect()
+    }
+}
+
+/// Return true if `c` belongs to a script that is usually written without spaces
+/// between words
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0EFF}' // Thai, Lao
+        | '\u{1000}'..='\u{109F}' // Myanmar
+        | '\u{1780}'..='\u{17FF}' // Khmer
+        | '\u{19E0}'..='\u{19FF}' // Khmer symbols
+        | '\u{3040}'..='\u{30FF}' // Hiragana, Katakana
+        | '\u{31F0}'..='\u{31FF}' // Katakana extensions
+        | '\u{3400}'..='\u{4DBF}' // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}' // Halfwidth katakana
+        | '\u{20000}'..='\u{3FFFF}' // CJK extensions B and later
+    )
+}
+
+/// True if a token from the word splitter is part of an unspaced run
+fn is_unspaced_token(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(is_unspaced_script)
+}
+
+/// True if a token from the word splitter should be checked. This matches the
+/// definition of a word used by [`UnicodeSegmentation::unicode_words`].
+fn is_checkable_token(s: &str) -> bool {
+    s.chars().any(char::is_alphanumeric)
+}
+
+/// True if a `BREAK` separator may appear between two words, rather than only
+/// at the start or end of one
+pub fn is_inner_break(sep: &str) -> bool {
+    !sep.is_empty() && !sep.starts_with('^') && !sep.ends_with('$')
+}
+
+/// Iterator over the words in a string, returned as `(byte_index, word)`.
+///
+/// Runs of unspaced scripts are handed to the segmenter if one is set, or skipped
+/// otherwise.
+pub struct Words<'a, 'd> {
+    input: &'a str,
+    bounds: Peekable<UWordBoundIndices<'a>>,
+    segmenter: Option<&'d dyn Segmenter>,
+    pending: alloc::vec::IntoIter<(usize, &'a str)>,
+    /// Keep numbers, times, and dates together as one word
+    join_numeric: bool,
+    /// `BREAK` separators that keep the words around them together, as in
+    /// `well-known`
+    breaks: &'d [String],
+    /// Byte ranges of the input that are not checked
+    skip: Vec<Range<usize>>,
+}
+
+impl<'a, 'd> Words<'a, 'd> {
+    pub fn new(
+        input: &'a str,
+        segmenter: Option<&'d dyn Segmenter>,
+        join_numeric: bool,
+        breaks: &'d [String],
+    ) -> Self {
+        Self {
+            input,
+            bounds: input.split_word_bound_indices().peekable(),
+            segmenter,
+            pending: Vec::new().into_iter(),
+            join_numeric,
+            breaks,
+            skip: Vec::new(),
+        }
+    }
+
+    /// Skip words that overlap any of `spans` in the input
+    pub fn skip_spans(mut self, spans: Vec<Range<usize>>) -> Self {
+        self.skip = spans;
+        self
+    }
+
+    /// Skip words that overlap `ignored` text
+    pub fn skip_ignored(self, ignored: &IgnoredText) -> Self {
+        let spans = ignored.spans(self.input);
+        self.skip_spans(spans)
+    }
+
+    /// Whether the word at `start` overlaps a skipped span
+    fn is_skipped(&self, start: usize, word: &str) -> bool {
+        let end = start + word.len();
+        self.skip
+            .iter()
+            .any(|span| span.start < end && start < span.end)
+    }
+
+    /// Word boundaries split times and dates such as `12:30` or `2024-01-31`.
+    /// If `token` starts the longest such run, consume the rest of it and return
+    /// the whole run.
+    fn join_numeric(&mut self, start: usize, token: &'a str) -> &'a str {
+        let mut longest = None;
+        let ahead = self.bounds.clone().take(MAX_NUMERIC_TOKENS);
+        for (count, (idx, tok)) in (1..).zip(ahead) {
+            if tok.bytes().any(|b| b.is_ascii_whitespace()) {
+                break;
+            }
+            let end = idx + tok.len();
+            if is_numeric_token(&self.input[start..end]) {
+                longest = Some((count, end));
+            }
+        }
+
+        let Some((count, end)) = longest else {
+            return token;
+        };
+        for _ in 0..count {
+            self.bounds.next();
+        }
+        &self.input[start..end]
+    }
+
+    /// Word boundaries split words at hyphens. If the word `token` is followed
+    /// by a `BREAK` separator and another word, consume them and return the
+    /// whole run, such as `well-known`.
+    fn join_breaks(&mut self, start: usize, token: &'a str) -> &'a str {
+        let mut end = start + token.len();
+        loop {
+            let rest = &self.input[end..];
+            let Some(sep) = self
+                .breaks
+                .iter()
+                .filter(|sep| is_inner_break(sep) && rest.starts_with(sep.as_str()))
+                .max_by_key(|sep| sep.len())
+            else {
+                break;
+            };
+
+            let next = end + sep.len();
+            let Some((_, word)) =
+                self.bounds
+                    .clone()
+                    .find(|(idx, _)| *idx >= next)
+                    .filter(|(idx, tok)| {
+                        *idx == next && is_checkable_token(tok) && !is_unspaced_token(tok)
+                    })
+            else {
+                break;
+            };
+
+            while self.bounds.next_if(|(idx, _)| *idx <= next).is_some() {}
+            end = next + word.len();
+        }
+        &self.input[start..end]
+    }
+}
+
+impl<'a> Iterator for Words<'a, '_> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (start, word) = self.next_word()?;
+            if !self.is_skipped(start, word) {
+                return Some((start, word));
+            }
+        }
+    }
+}
+
+impl<'a> Words<'a, '_> {
+    /// Find the next word, whether or not it is skipped
+    fn next_word(&mut self) -> Option<(usize, &'a str)> {
+        loop {
+            if let Some(item) = self.pending.next() {
+                return Some(item);
+            }
+
+            let (start, token) = self.bounds.next()?;
+
+            if is_unspaced_token(token) {
+                // Collect the entire run, which may have been split into many tokens
+                let mut end = start + token.len();
+                while let Some((idx, tok)) = self.bounds.peek() {
+                    if !is_unspaced_token(tok) {
+                        break;
+                    }
+                    end = idx + tok.len();
+                    self.bounds.next();
+                }
+
+                if let Some(seg) = self.segmenter {
+                    let mut words = seg.segment(&self.input[start..end]);
+                    words.retain(|(_, w)| is_checkable_token(w));
+                    for (idx, _) in &mut words {
+                        *idx += start;
+                    }
+                    self.pending = words.into_iter();
+                }
+
+                continue;
+            }
+
+            if self.join_numeric && token.starts_with(|ch: char| ch.is_ascii_digit()) {
+                return Some((start, self.join_numeric(start, token)));
+            }
+
+            if is_checkable_token(token) {
+                return Some((start, self.join_breaks(start, token)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct CharSegmenter;
+
+    impl Segmenter for CharSegmenter {
+        fn segment<'a>(&self, text: &'a str) -> Vec<(usize, &'a str)> {
+            text.char_indices()
+                .map(|(idx, ch)| (idx, &text[idx..idx + ch.len_utf8()]))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_word_splitter() {
+        let s = "the quick brown.     Fox Jum-ped --\t where? 'over' (the) very--lazy dog";
+        let words: Vec<_> = Words::new(s, None, false, &[]).map(|(_, w)| w).collect();
+        assert_eq!(
+            words,
+            [
+                "the", "quick", "brown", "Fox", "Jum", "ped", "where", "over", "the", "very",
+                "lazy", "dog"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_words_apostrophes() {
+        let input = "don't l’arbre 'quoted'";
+        let words: Vec<_> = Words::new(input, None, false, &[])
+            .map(|(_, w)| w)
+            .collect();
+        assert_eq!(words, ["don't", "l’arbre", "quoted"]);
+    }
+
+    #[test]
+    fn test_words_no_segmenter() {
+        let input = "abc สวัสดีครับ def 日本語 ghi";
+        let words: Vec<_> = Words::new(input, None, false, &[])
+            .map(|(_, w)| w)
+            .collect();
+        assert_eq!(words, ["abc", "def", "ghi"]);
+    }
+
+    #[test]
+    fn test_words_segmenter() {
+        let input = "abc 日本語, ghi";
+        let words: Vec<_> = Words::new(input, Some(&CharSegmenter), false, &[]).collect();
+        assert_eq!(
+            words,
+            [(0, "abc"), (4, "日"), (7, "本"), (10, "語"), (15, "ghi")]
+        );
+    }
+
+    #[test]
+    fn test_words_join_numeric() {
+        let input = "at 12:30, on 2024-01-31T09:00Z or 3.5 times";
+        let words: Vec<_> = Words::new(input, None, true, &[]).collect();
+        assert_eq!(
+            words,
+            [
+                (0, "at"),
+                (3, "12:30"),
+                (10, "on"),
+                (13, "2024-01-31T09:00Z"),
+                (31, "or"),
+                (34, "3.5"),
+                (38, "times")
+            ]
+        );
+
+        let words: Vec<_> = Words::new("12:30", None, false, &[])
+            .map(|(_, w)| w)
+            .collect();
+        assert_eq!(words, ["12", "30"]);
+    }
+
+    #[test]
+    fn test_words_join_breaks() {
+        let breaks = ["-".to_owned(), "^-".to_owned(), "--".to_owned()];
+        let input = "well-known e-mail-address, very--lazy -dog- x-";
+        let words: Vec<_> = Words::new(input, None, false, &breaks).collect();
+        assert_eq!(
+            words,
+            [
+                (0, "well-known"),
+                (11, "e-mail-address"),
+                (27, "very--lazy"),
+                (39, "dog"),
+                (44, "x")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_words_skip_spans() {
+        let input = "see ABC-123 and 0x1f";
+        let words: Vec<_> = Words::new(input, None, false, &[])
+            .skip_spans(vec![4..11, 17..19])
+            .collect();
+        assert_eq!(words, [(0, "see"), (12, "and")]);
+    }
+
+    #[test]
+    fn test_hook_eq() {
+        let seg: Arc<dyn Segmenter> = Arc::new(CharSegmenter);
+        let a = SegmenterHook::new(Arc::clone(&seg));
+        let b = SegmenterHook::new(seg);
+        let c = SegmenterHook::new(Arc::new(CharSegmenter));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(SegmenterHook::default(), SegmenterHook::default());
+    }
+}
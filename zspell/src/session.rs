@@ -0,0 +1,500 @@
+//! Words that the user chose to ignore while checking documents, see [`Session`]
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::fs::{self, File};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+#[cfg(feature = "std")]
+use crate::error::IoError;
+use crate::Dictionary;
+
+/// Shortest time between two saves of a personal word list
+#[cfg(feature = "std")]
+const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A [`Dictionary`] along with the words that the user chose to ignore, as
+/// kept by an interactive checker such as an editor or language server.
+///
+/// A word may be ignored everywhere ("ignore all"), or only at one place in one
+/// document ("ignore once"). Documents are named by any string the caller
+/// picks, such as a path or URI. Ignored words are only kept for the life of
+/// the session; to accept a word for good, add it to the dictionary with
+/// [`dictionary_mut`](Self::dictionary_mut) and save it to a personal word
+/// list, or let the session do that with
+/// [`with_personal_file`](Self::with_personal_file).
+///
+/// ```
+/// use zspell::Session;
+///
+/// let dict = zspell::builder()
+///     .config_str("")
+///     .dict_str("2\nthe\ncat")
+///     .build()
+///     .unwrap();
+/// let mut session = Session::new(dict);
+///
+/// let text = "teh cat, teh kat";
+/// assert_eq!(
+///     session.check_indices("notes.txt", text).collect::<Vec<_>>(),
+///     [(0, "teh"), (9, "teh"), (13, "kat")]
+/// );
+///
+/// session.ignore_once("notes.txt", 0, "teh");
+/// session.ignore_all("kat");
+/// assert_eq!(
+///     session.check_indices("notes.txt", text).collect::<Vec<_>>(),
+///     [(9, "teh")]
+/// );
+///
+/// // Words ignored once only apply to their own document
+/// assert_eq!(session.check_indices("other.txt", text).count(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Session {
+    dict: Dictionary,
+    /// Words that are ignored everywhere
+    ignored: BTreeSet<Box<str>>,
+    /// Words that are ignored once by their byte index, for each document
+    ignored_once: BTreeMap<Box<str>, BTreeMap<usize, Box<str>>>,
+    /// Where words added with `add_word` are saved. Clones of the session
+    /// share it, so that the words are only saved once.
+    #[cfg(feature = "std")]
+    personal: Option<Arc<PersonalFile>>,
+}
+
+/// A personal word list that added words are written to
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct PersonalFile {
+    path: PathBuf,
+    state: Mutex<SaveState>,
+}
+
+/// The words of a [`PersonalFile`] that still need to be saved
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct SaveState {
+    /// Words added since the last save
+    pending: Vec<Box<str>>,
+    /// When the file was last written
+    saved_at: Option<Instant>,
+}
+
+impl Session {
+    /// Start a session with nothing ignored
+    #[inline]
+    pub fn new(dict: Dictionary) -> Self {
+        Self {
+            dict,
+            ignored: BTreeSet::new(),
+            ignored_once: BTreeMap::new(),
+            #[cfg(feature = "std")]
+            personal: None,
+        }
+    }
+
+    /// Save words added with [`add_word`](Self::add_word) to the personal word
+    /// list at `path`, one per line. The file is created if it does not exist,
+    /// and words already in it are not added again. It should also be loaded
+    /// into the dictionary, e.g. with [`DictBuilder::personal_str`], so that
+    /// the words are accepted in later sessions.
+    ///
+    /// Words are written as they are added, but at most once every two
+    /// seconds so that adding many words does not rewrite the file each time.
+    /// Words that are not saved yet are written by
+    /// [`save_if_due`](Self::save_if_due) once that time has passed, by
+    /// [`flush`](Self::flush), or when the session is dropped. Clones of the
+    /// session share the unsaved words, which are saved once the last clone
+    /// is dropped. Each save writes a temporary file next to `path` and renames
+    /// it over `path`, so the file is never left half written.
+    ///
+    /// [`DictBuilder::personal_str`]: crate::DictBuilder::personal_str
+    ///
+    /// ```
+    /// # #![cfg(not(miri))]
+    /// use zspell::Session;
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("words.txt");
+    ///
+    /// let dict = zspell::builder()
+    ///     .config_str("")
+    ///     .dict_str("1\ncat")
+    ///     .build()
+    ///     .unwrap();
+    /// let mut session = Session::new(dict).with_personal_file(&path);
+    ///
+    /// assert!(session.add_word("zspell").unwrap());
+    /// assert!(session.dictionary().check_word("zspell"));
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "zspell\n");
+    ///
+    /// // Words added soon after a save wait for `save_if_due` or `flush`
+    /// session.add_word("hunspell").unwrap();
+    /// assert!(session.has_unsaved_words());
+    /// session.flush().unwrap();
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "zspell\nhunspell\n");
+    /// ```
+    #[inline]
+    #[must_use]
+    #[cfg(feature = "std")]
+    pub fn with_personal_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.personal = Some(Arc::new(PersonalFile {
+            path: path.into(),
+            state: Mutex::default(),
+        }));
+        self
+    }
+
+    /// The personal word list set with
+    /// [`with_personal_file`](Self::with_personal_file)
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn personal_file(&self) -> Option<&Path> {
+        self.personal.as_ref().map(|file| file.path.as_path())
+    }
+
+    /// The dictionary used to check words
+    #[inline]
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dict
+    }
+
+    /// The dictionary used to check words, e.g. to add words to it
+    #[inline]
+    pub fn dictionary_mut(&mut self) -> &mut Dictionary {
+        &mut self.dict
+    }
+
+    /// End the session and return its dictionary
+    #[inline]
+    pub fn into_dictionary(self) -> Dictionary {
+        self.dict
+    }
+
+    /// Ignore `word` wherever it appears. Only this exact spelling is
+    /// ignored, so ignoring "teh" does not ignore "Teh".
+    #[inline]
+    pub fn ignore_all(&mut self, word: &str) {
+        self.ignored.insert(word.into());
+    }
+
+    /// Ignore `word` at byte index `idx` of document `doc`, as returned by
+    /// [`check_indices`](Self::check_indices).
+    ///
+    /// These are not moved when the text changes, so a frontend that edits the
+    /// document should ignore the word again at its new index, or call
+    /// [`forget_document`](Self::forget_document).
+    #[inline]
+    pub fn ignore_once(&mut self, doc: &str, idx: usize, word: &str) {
+        self.ignored_once
+            .entry(doc.into())
+            .or_default()
+            .insert(idx, word.into());
+    }
+
+    /// Accept `word` from now on, like [`Dictionary::add_word`], and save it to
+    /// the personal word list if there is one. Returns `true` if the word was
+    /// not in the dictionary already.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the personal word list could not be written. The
+    /// word is still added to the dictionary, and saving it is tried again
+    /// next time.
+    #[inline]
+    pub fn add_word(&mut self, word: &str) -> Result<bool, Error> {
+        let added = self.dict.add_word(word);
+        #[cfg(feature = "std")]
+        if let Some(file) = &self.personal {
+            let mut state = file.state();
+            if added {
+                state.pending.push(word.into());
+            }
+            if state.is_due() {
+                state.save(&file.path)?;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Whether some added words are not saved to the personal word list yet
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn has_unsaved_words(&self) -> bool {
+        self.personal
+            .as_ref()
+            .map_or(false, |file| !file.state().pending.is_empty())
+    }
+
+    /// Save added words to the personal word list if enough time has passed
+    /// since the last save. Long-running frontends can call this on a timer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the personal word list could not be written.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn save_if_due(&mut self) -> Result<(), Error> {
+        let Some(file) = &self.personal else {
+            return Ok(());
+        };
+        let mut state = file.state();
+        if state.is_due() {
+            state.save(&file.path)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Save added words to the personal word list now
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the personal word list could not be written.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.personal.as_deref().map_or(Ok(()), PersonalFile::save)
+    }
+
+    /// Stop ignoring `word` everywhere. Returns `true` if it was ignored.
+    #[inline]
+    pub fn unignore_all(&mut self, word: &str) -> bool {
+        self.ignored.remove(word)
+    }
+
+    /// Forget the words ignored once in `doc`, e.g. when it is closed
+    #[inline]
+    pub fn forget_document(&mut self, doc: &str) {
+        self.ignored_once.remove(doc);
+    }
+
+    /// Words that are ignored everywhere, in sorted order
+    #[inline]
+    pub fn ignored_words(&self) -> impl Iterator<Item = &str> {
+        self.ignored.iter().map(AsRef::as_ref)
+    }
+
+    /// Whether `word` is correct or ignored everywhere
+    #[inline]
+    pub fn check_word(&self, word: &str) -> bool {
+        self.ignored.contains(word) || self.dict.check_word(word)
+    }
+
+    /// Like [`Dictionary::check_indices`], but without the words that are
+    /// ignored everywhere or at their place in `doc`
+    #[inline]
+    pub fn check_indices<'a: 'd, 'd>(
+        &'d self,
+        doc: &str,
+        input: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a str)> + 'd {
+        let once = self.ignored_once.get(doc);
+        self.dict
+            .check_indices(input)
+            .filter(move |&(idx, word)| !self.is_ignored_in(once, idx, word))
+    }
+
+    /// Whether the misspelled `word` at byte index `idx` of `doc` is ignored
+    pub(crate) fn is_ignored_at(&self, doc: &str, idx: usize, word: &str) -> bool {
+        self.is_ignored_in(self.ignored_once.get(doc), idx, word)
+    }
+
+    fn is_ignored_in(
+        &self,
+        once: Option<&BTreeMap<usize, Box<str>>>,
+        idx: usize,
+        word: &str,
+    ) -> bool {
+        self.ignored.contains(word)
+            || once
+                .and_then(|once| once.get(&idx))
+                .map_or(false, |ignored| ignored.as_ref() == word)
+    }
+}
+
+#[cfg(feature = "std")]
+impl PersonalFile {
+    fn state(&self) -> MutexGuard<'_, SaveState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Save the pending words now
+    fn save(&self) -> Result<(), Error> {
+        self.state().save(&self.path)
+    }
+}
+
+#[cfg(feature = "std")]
+impl SaveState {
+    /// Whether there are words to save and the last save was long enough ago
+    fn is_due(&self) -> bool {
+        !self.pending.is_empty()
+            && self
+                .saved_at
+                .map_or(true, |saved_at| saved_at.elapsed() >= SAVE_INTERVAL)
+    }
+
+    /// Add the pending words to the end of the file at `path`, if it does not
+    /// have them
+    fn save(&mut self, path: &Path) -> Result<(), Error> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let io_err = |e: io::Error| IoError::new(&path.to_string_lossy(), e.kind());
+        let mut content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(io_err(e).into()),
+        };
+        let new: Vec<&str> = self
+            .pending
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|word| !content.lines().any(|line| line.trim() == *word))
+            .collect();
+
+        if !new.is_empty() {
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            for word in new {
+                content.push_str(word);
+                content.push('\n');
+            }
+
+            // Write to a temporary file first so that an interrupted save does
+            // not lose the words that were already there
+            let mut tmp = path.to_path_buf().into_os_string();
+            tmp.push(".tmp");
+            File::create(&tmp)
+                .and_then(|mut f| f.write_all(content.as_bytes()).and_then(|()| f.sync_all()))
+                .map_err(io_err)?;
+            fs::rename(&tmp, path).map_err(io_err)?;
+        }
+
+        self.pending.clear();
+        self.saved_at = Some(Instant::now());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+/// Runs once the last clone of the session is dropped
+impl Drop for PersonalFile {
+    fn drop(&mut self) {
+        // There is no way to report errors here; callers that care should
+        // use `Session::flush`
+        _ = self.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_session() {
+        let dict = crate::builder()
+            .config_str("")
+            .dict_str("1\ncat")
+            .build()
+            .unwrap();
+        let mut session = Session::new(dict);
+        let errors = |session: &Session, doc| session.check_indices(doc, "teh cat teh").count();
+
+        session.ignore_once("a", 0, "teh");
+        // The word must match too, in case the text has changed
+        session.ignore_once("a", 8, "kat");
+        assert_eq!(errors(&session, "a"), 1);
+        session.forget_document("a");
+        assert_eq!(errors(&session, "a"), 2);
+
+        session.ignore_all("teh");
+        assert!(session.check_word("teh"));
+        assert!(!session.check_word("Teh"));
+        assert_eq!(errors(&session, "a"), 0);
+        assert_eq!(session.ignored_words().collect::<Vec<_>>(), ["teh"]);
+        assert!(session.unignore_all("teh"));
+        assert!(!session.unignore_all("teh"));
+        assert_eq!(errors(&session, "a"), 2);
+
+        session.dictionary_mut().add_word("teh");
+        assert_eq!(errors(&session, "a"), 0);
+        assert!(session.into_dictionary().check_word("teh"));
+    }
+
+    #[test]
+    fn test_personal_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        std::fs::write(&path, "# my words\nfoo").unwrap();
+        let dict = crate::builder()
+            .config_str("")
+            .dict_str("1\ncat")
+            .build()
+            .unwrap();
+
+        let mut session = Session::new(dict).with_personal_file(&path);
+        assert_eq!(session.personal_file(), Some(path.as_path()));
+        // Words that are already accepted are not saved
+        assert!(!session.add_word("cat").unwrap());
+        assert!(!session.has_unsaved_words());
+
+        session.add_word("bar").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# my words\nfoo\nbar\n"
+        );
+
+        // Saves are spaced out, and words already in the file are skipped
+        session.add_word("foo").unwrap();
+        session.add_word("baz").unwrap();
+        session.save_if_due().unwrap();
+        assert!(session.has_unsaved_words());
+        let dict = session.into_dictionary();
+        assert!(dict.check_word("baz"));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# my words\nfoo\nbar\nbaz\n"
+        );
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        // Clones share the unsaved words, which are saved when the last one
+        // is dropped
+        let mut session = Session::new(dict).with_personal_file(&path);
+        session.add_word("qux").unwrap();
+        session.add_word("quux").unwrap();
+        let clone = session.clone();
+        assert!(clone.has_unsaved_words());
+        drop(clone);
+        assert!(session.has_unsaved_words());
+        let dict = session.into_dictionary();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# my words\nfoo\nbar\nbaz\nqux\nquux\n"
+        );
+
+        // Errors are reported, and the word is kept to save later
+        let mut session = Session::new(dict).with_personal_file(dir.path());
+        assert!(session.add_word("quuz").is_err());
+        assert!(session.has_unsaved_words());
+    }
+}
@@ -0,0 +1,138 @@
+//! A dictionary that can be read and updated from many threads at once
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::Dictionary;
+
+/// A [`Dictionary`] that can be updated while other threads are checking text.
+///
+/// Readers take a snapshot with [`load`](Self::load), which never blocks and is
+/// unaffected by later updates. Writers use [`update`](Self::update) to apply a
+/// change to a copy of the current dictionary and then publish it; readers pick up
+/// the new version the next time they call `load`. This makes it suitable for
+/// long-running processes such as language servers, where words may be added to
+/// the dictionary while documents are being checked.
+///
+/// Each update copies the compiled wordlists (see [`Dictionary::add_word`]), so it
+/// is best to batch changes into a single `update` call where possible.
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// use zspell::SharedDictionary;
+///
+/// let dict = zspell::builder()
+///     .config_str("")
+///     .dict_str("1\nfoo")
+///     .build()
+///     .unwrap();
+/// let shared = Arc::new(SharedDictionary::new(dict));
+///
+/// let reader = {
+///     let shared = Arc::clone(&shared);
+///     thread::spawn(move || {
+///         // This snapshot stays the same even if the dictionary is updated
+///         let snapshot = shared.load();
+///         snapshot.check("foo")
+///     })
+/// };
+///
+/// shared.update(|dict| {
+///     dict.add_word("bar");
+/// });
+///
+/// assert!(reader.join().unwrap());
+/// assert!(shared.load().check("foo bar"));
+/// ```
+#[derive(Debug)]
+pub struct SharedDictionary(ArcSwap<Dictionary>);
+
+impl SharedDictionary {
+    /// Wrap a dictionary so it can be shared
+    #[inline]
+    pub fn new(dict: Dictionary) -> Self {
+        Self(ArcSwap::from_pointee(dict))
+    }
+
+    /// Get a snapshot of the current dictionary. This does not block, and the
+    /// returned dictionary does not change when updates are made.
+    #[inline]
+    pub fn load(&self) -> Arc<Dictionary> {
+        self.0.load_full()
+    }
+
+    /// Replace the dictionary entirely, e.g. after reloading it from disk
+    #[inline]
+    pub fn store(&self, dict: Dictionary) {
+        self.0.store(Arc::new(dict));
+    }
+
+    /// Apply `f` to a copy of the current dictionary and publish the result.
+    ///
+    /// If another thread publishes a new version while `f` is running, `f` is
+    /// called again on that version so that no update is lost. It should
+    /// therefore not have side effects other than modifying the dictionary.
+    #[inline]
+    pub fn update<F>(&self, mut f: F)
+    where
+        F: FnMut(&mut Dictionary),
+    {
+        self.0.rcu(|current| {
+            let mut dict = Dictionary::clone(current);
+            f(&mut dict);
+            dict
+        });
+    }
+}
+
+impl From<Dictionary> for SharedDictionary {
+    #[inline]
+    fn from(dict: Dictionary) -> Self {
+        Self::new(dict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::DictBuilder;
+
+    #[test]
+    fn test_concurrent_updates() {
+        let dict = DictBuilder::new()
+            .config_str("")
+            .dict_str("1\nfoo")
+            .build()
+            .unwrap();
+        let shared = SharedDictionary::new(dict);
+        let before = shared.load();
+
+        thread::scope(|s| {
+            for i in 0..4 {
+                let shared = &shared;
+                s.spawn(move || {
+                    shared.update(|dict| {
+                        dict.add_word(&format!("word{i}"));
+                    });
+                    assert!(shared.load().check("foo"));
+                });
+            }
+        });
+
+        let after = shared.load();
+        assert!(after.check("foo word0 word1 word2 word3"));
+        // The old snapshot is unchanged
+        assert!(!before.check("word0"));
+
+        shared.update(|dict| {
+            dict.remove_word("word0");
+        });
+        assert!(!shared.load().check_word("word0"));
+        assert!(after.check_word("word0"));
+    }
+}
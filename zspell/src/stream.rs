@@ -0,0 +1,335 @@
+//! Spellchecking async readers and streams, for use with [`tokio`].
+//!
+//! [`CheckLines`] reads lines from an [`AsyncBufRead`], such as an upload in
+//! a web service, and [`CheckStream`] checks each item of a [`Stream`] of
+//! text. Both are streams of the [`Misspelling`]s they find. Checking happens
+//! as the stream is polled, and both give the runtime a chance to run other
+//! tasks every few kilobytes of correct text, so there is no need to move the
+//! work to a blocking task.
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use tokio_stream::StreamExt;
+//! use zspell::stream::CheckLines;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let dict = zspell::builder()
+//!     .config_str("")
+//!     .dict_str("3\nthe\ncat\nsat")
+//!     .build()
+//!     .unwrap();
+//! let dict = Arc::new(dict);
+//!
+//! // Any `AsyncBufRead` works, such as a `tokio::io::BufReader` around a socket
+//! let upload: &[u8] = b"the cat\nteh cat sat\n";
+//! let mut errors = CheckLines::new(upload, dict);
+//!
+//! let first = errors.next().await.unwrap().unwrap();
+//! assert_eq!((first.line, first.index, first.word.as_str()), (2, 0, "teh"));
+//! assert!(errors.next().await.is_none());
+//! # }
+//! ```
+//!
+//! [`tokio`]: https://docs.rs/tokio
+
+use std::borrow::Borrow;
+use std::collections::VecDeque;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+pub use futures_core::Stream;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, Lines};
+
+use crate::Dictionary;
+
+/// Bytes of correct text to check before yielding to the runtime
+const YIELD_INTERVAL: usize = 16 * 1024;
+
+/// A misspelled word found by [`CheckLines`] or [`CheckStream`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Misspelling {
+    /// Line the word was found on, or the item of a [`CheckStream`], starting
+    /// from 1
+    pub line: usize,
+    /// Byte index of the word in its line
+    pub index: usize,
+    /// The misspelled word
+    pub word: String,
+}
+
+/// Misspellings found in checked lines that have not yet been returned
+#[derive(Debug)]
+struct Pending<D> {
+    dict: D,
+    line: usize,
+    queue: VecDeque<Misspelling>,
+    /// Bytes checked since the stream last yielded
+    checked: usize,
+}
+
+impl<D: Borrow<Dictionary>> Pending<D> {
+    fn new(dict: D) -> Self {
+        Self {
+            dict,
+            line: 0,
+            queue: VecDeque::new(),
+            checked: 0,
+        }
+    }
+
+    /// Check the next line, queueing any misspellings
+    fn check(&mut self, text: &str) {
+        self.line += 1;
+        self.checked += text.len();
+        let line = self.line;
+        self.queue
+            .extend(
+                self.dict
+                    .borrow()
+                    .check_indices(text)
+                    .map(|(index, word)| Misspelling {
+                        line,
+                        index,
+                        word: word.to_owned(),
+                    }),
+            );
+    }
+
+    /// Take the next misspelling to return, if any
+    fn pop(&mut self) -> Option<Misspelling> {
+        let next = self.queue.pop_front();
+        if next.is_some() {
+            self.checked = 0;
+        }
+        next
+    }
+
+    /// If enough text has been checked without returning anything, wake the
+    /// task and return `true` so the caller can yield
+    fn should_yield(&mut self, cx: &Context<'_>) -> bool {
+        if self.checked < YIELD_INTERVAL {
+            return false;
+        }
+        self.checked = 0;
+        cx.waker().wake_by_ref();
+        true
+    }
+}
+
+/// A [`Stream`] of the misspellings in each line of an [`AsyncBufRead`]
+///
+/// The dictionary can be anything that borrows as a [`Dictionary`], such as
+/// `&Dictionary` or an `Arc<Dictionary>` for streams that must be `'static`.
+/// A [`SharedDictionary`](crate::SharedDictionary) can be checked with a
+/// snapshot from [`load`](crate::SharedDictionary::load).
+///
+/// Errors reading from the input, including input that is not valid UTF-8,
+/// are returned as items of the stream.
+#[derive(Debug)]
+pub struct CheckLines<R, D> {
+    lines: Lines<R>,
+    pending: Pending<D>,
+}
+
+impl<R, D> CheckLines<R, D>
+where
+    R: AsyncBufRead + Unpin,
+    D: Borrow<Dictionary>,
+{
+    /// Check the lines of `reader` against `dict`
+    #[inline]
+    pub fn new(reader: R, dict: D) -> Self {
+        Self {
+            lines: reader.lines(),
+            pending: Pending::new(dict),
+        }
+    }
+}
+
+impl<R, D> Stream for CheckLines<R, D>
+where
+    R: AsyncBufRead + Unpin,
+    D: Borrow<Dictionary> + Unpin,
+{
+    type Item = io::Result<Misspelling>;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(found) = this.pending.pop() {
+                return Poll::Ready(Some(Ok(found)));
+            }
+            if this.pending.should_yield(cx) {
+                return Poll::Pending;
+            }
+            match ready!(Pin::new(&mut this.lines).poll_next_line(cx)) {
+                Ok(Some(line)) => this.pending.check(&line),
+                Ok(None) => return Poll::Ready(None),
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+    }
+}
+
+/// A [`Stream`] of the misspellings in each item of another stream of text
+///
+/// Each item is checked on its own like [`Dictionary::check_indices`], so
+/// items should not split words; lines or paragraphs work well. The
+/// [`line`](Misspelling::line) of each misspelling is the number of the item
+/// it was found in. See [`CheckLines`] for the dictionaries that can be used.
+#[derive(Debug)]
+pub struct CheckStream<S, D> {
+    stream: S,
+    pending: Pending<D>,
+}
+
+impl<S, D> CheckStream<S, D>
+where
+    S: Stream + Unpin,
+    S::Item: AsRef<str>,
+    D: Borrow<Dictionary>,
+{
+    /// Check the items of `stream` against `dict`
+    #[inline]
+    pub fn new(stream: S, dict: D) -> Self {
+        Self {
+            stream,
+            pending: Pending::new(dict),
+        }
+    }
+}
+
+impl<S, D> Stream for CheckStream<S, D>
+where
+    S: Stream + Unpin,
+    S::Item: AsRef<str>,
+    D: Borrow<Dictionary> + Unpin,
+{
+    type Item = Misspelling;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(found) = this.pending.pop() {
+                return Poll::Ready(Some(found));
+            }
+            if this.pending.should_yield(cx) {
+                return Poll::Pending;
+            }
+            match ready!(Pin::new(&mut this.stream).poll_next(cx)) {
+                Some(text) => this.pending.check(text.as_ref()),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// A stream of the items of an iterator
+    struct Iter<I>(I);
+
+    impl<I: Iterator + Unpin> Stream for Iter<I> {
+        type Item = I::Item;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+            Poll::Ready(self.0.next())
+        }
+    }
+
+    fn dict() -> Dictionary {
+        crate::builder()
+            .config_str("")
+            .dict_str("3\nthe\ncat\nsat")
+            .build()
+            .unwrap()
+    }
+
+    /// Collect a stream, also counting how many times it yielded
+    async fn collect<S: Stream + Unpin>(mut stream: S) -> (Vec<S::Item>, usize) {
+        let mut items = Vec::new();
+        let mut yields = 0;
+        loop {
+            let next = poll_fn(|cx| match Pin::new(&mut stream).poll_next(cx) {
+                Poll::Pending => Poll::Ready(None),
+                Poll::Ready(item) => Poll::Ready(Some(item)),
+            })
+            .await;
+            match next {
+                Some(Some(item)) => items.push(item),
+                Some(None) => return (items, yields),
+                None => yields += 1,
+            }
+        }
+    }
+
+    fn misspelling(line: usize, index: usize, word: &str) -> Misspelling {
+        Misspelling {
+            line,
+            index,
+            word: word.to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_lines() {
+        let dict = dict();
+        let input: &[u8] = b"the cat\nteh cat sta\n\ncat teh";
+        let (found, _) = collect(CheckLines::new(input, &dict)).await;
+        let found: Vec<_> = found.into_iter().map(Result::unwrap).collect();
+        assert_eq!(
+            found,
+            [
+                misspelling(2, 0, "teh"),
+                misspelling(2, 8, "sta"),
+                misspelling(4, 4, "teh"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_lines_invalid_utf8() {
+        let input: &[u8] = b"teh\n\xff\xfe\n";
+        let (found, _) = collect(CheckLines::new(input, Arc::new(dict()))).await;
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].as_ref().unwrap(), &misspelling(1, 0, "teh"));
+        assert_eq!(
+            found[1].as_ref().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_stream() {
+        let dict = dict();
+        let items = vec!["the cat sat".to_owned(), "cat teh".to_owned()];
+        let (found, yields) = collect(CheckStream::new(Iter(items.into_iter()), &dict)).await;
+        assert_eq!(found, [misspelling(2, 4, "teh")]);
+        assert_eq!(yields, 0);
+    }
+
+    #[tokio::test]
+    async fn test_yields_on_long_input() {
+        let dict = dict();
+        let lines = core::iter::repeat("the cat sat")
+            .take(YIELD_INTERVAL)
+            .chain(["teh"]);
+        let (found, yields) = collect(CheckStream::new(Iter(lines), &dict)).await;
+        assert_eq!(found, [misspelling(YIELD_INTERVAL + 1, 0, "teh")]);
+        assert!(yields > 0);
+    }
+}
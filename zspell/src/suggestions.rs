@@ -1 +1,377 @@
 //! Types and implementation of suggestion logic
+
+mod distance;
+mod metaphone;
+
+use std::cell::Cell;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use hashbrown::HashMap;
+
+pub use self::distance::EditDistance;
+use self::metaphone::double_metaphone;
+use crate::cancel::CancelToken;
+use crate::casing::Casing;
+use crate::phonet::PhoneTable;
+
+/// Number of suggestions returned by `WordEntry::suggest`
+pub const MAX_SUGGESTIONS: usize = 10;
+
+/// Number of suggestions found by sound rather than spelling, like Hunspell's
+/// `MAXPHONSUGS`
+pub const MAX_PHONETIC_SUGGESTIONS: usize = 2;
+
+/// Number of suggestions that are other forms of the same stem as a
+/// misspelling
+pub const MAX_STEM_SUGGESTIONS: usize = 4;
+
+/// How suggestions find words that sound like a misspelling, set with
+/// [`DictBuilder::phonetic_suggestions`](crate::DictBuilder::phonetic_suggestions).
+/// Feature gated behind `unstable-suggestions`.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PhoneticSuggestions {
+    /// Do not look for words that sound alike
+    Disabled,
+    /// Use the dictionary's `PHONE` table, if it has one
+    #[default]
+    PhoneTable,
+    /// Use the dictionary's `PHONE` table if it has one, otherwise the built-in
+    /// Double Metaphone algorithm for English. Most English dictionaries do not
+    /// have a `PHONE` table.
+    DoubleMetaphone,
+}
+
+/// Computes codes for words so that words that sound alike can be matched
+#[derive(Clone, Copy, Debug)]
+pub enum PhoneticCoder<'a> {
+    Table(&'a PhoneTable, Casing),
+    DoubleMetaphone,
+}
+
+impl PhoneticCoder<'_> {
+    /// Primary and alternate codes for `word`. A `PHONE` table has only one code,
+    /// which is returned twice.
+    pub fn codes(self, word: &str) -> [String; 2] {
+        match self {
+            Self::Table(table, casing) => {
+                let key = table.key(&casing.to_uppercase(word));
+                [key.clone(), key]
+            }
+            Self::DoubleMetaphone => {
+                let (primary, alternate) = double_metaphone(word);
+                [primary, alternate]
+            }
+        }
+    }
+
+    /// Whether `word` has a nonempty code in common with `codes`
+    pub fn sounds_like(self, codes: &[String; 2], word: &str) -> bool {
+        let other = self.codes(word);
+        codes.iter().any(|c| !c.is_empty() && other.contains(c))
+    }
+}
+
+/// Upper bound on the number of variants that `MAP` substitution will produce for
+/// a single word. The count grows exponentially with the number of mappable
+/// characters, so long words could otherwise take a very long time.
+const MAP_CANDIDATE_LIMIT: usize = 10_000;
+
+/// Call `f` with each variant of `word` that can be created by swapping items
+/// within the same `MAP` group, e.g. `uber` -> `über` for the group `uü`. The
+/// original word is never passed to `f`.
+pub fn map_related(word: &str, maps: &[Vec<String>], mut f: impl FnMut(&str)) {
+    if maps.is_empty() {
+        return;
+    }
+
+    let mut buf = String::with_capacity(word.len() + 4);
+    let mut remaining = MAP_CANDIDATE_LIMIT;
+    map_related_inner(word, maps, &mut buf, false, &mut remaining, &mut f);
+}
+
+/// Recursive helper for [`map_related`]. `buf` holds the variant created so far
+/// and `rest` is the part of the original word that has not yet been visited.
+fn map_related_inner(
+    rest: &str,
+    maps: &[Vec<String>],
+    buf: &mut String,
+    changed: bool,
+    remaining: &mut usize,
+    f: &mut impl FnMut(&str),
+) {
+    if *remaining == 0 {
+        return;
+    }
+
+    let Some(next_ch) = rest.chars().next() else {
+        if changed {
+            *remaining -= 1;
+            f(buf);
+        }
+        return;
+    };
+
+    let base_len = buf.len();
+
+    // Replace anything that matches at this position with its related items
+    for group in maps {
+        for item in group.iter().filter(|item| rest.starts_with(item.as_str())) {
+            let after = &rest[item.len()..];
+            for other in group.iter().filter(|other| *other != item) {
+                buf.push_str(other);
+                map_related_inner(after, maps, buf, true, remaining, f);
+                buf.truncate(base_len);
+            }
+        }
+    }
+
+    // Keep the current character as-is
+    buf.push(next_ch);
+    map_related_inner(
+        &rest[next_ch.len_utf8()..],
+        maps,
+        buf,
+        changed,
+        remaining,
+        f,
+    );
+    buf.truncate(base_len);
+}
+
+/// A bounded cache of suggestion results, keyed on the misspelled word. When full,
+/// the least recently used entry is evicted.
+///
+/// Eviction scans every entry, which is insignificant next to the cost of
+/// generating suggestions for a miss.
+/// How many checks of a [`Deadline`] go by between reading the clock
+const DEADLINE_CHECK_INTERVAL: u32 = 256;
+
+/// A time after which suggestion passes stop and keep what they have found,
+/// which also passes if its [`CancelToken`] is cancelled. Reading the clock
+/// costs about as much as scoring a candidate, so it is only read every
+/// [`DEADLINE_CHECK_INTERVAL`] checks.
+#[derive(Debug)]
+pub struct Deadline<'a> {
+    at: Option<Instant>,
+    cancel: Option<&'a CancelToken>,
+    checks: Cell<u32>,
+    expired: Cell<bool>,
+}
+
+impl<'a> Deadline<'a> {
+    /// A deadline `budget` from now, or one that only passes if cancelled
+    pub fn new(budget: Option<Duration>, cancel: Option<&'a CancelToken>) -> Self {
+        Self {
+            at: budget.and_then(|budget| Instant::now().checked_add(budget)),
+            cancel,
+            checks: Cell::new(0),
+            expired: Cell::new(false),
+        }
+    }
+
+    /// Whether the deadline has passed. Once this returns `true`, it always
+    /// will.
+    pub fn expired(&self) -> bool {
+        if self.expired.get() {
+            return true;
+        }
+        if self.cancel.map_or(false, CancelToken::is_cancelled) {
+            self.expired.set(true);
+            return true;
+        }
+        let Some(at) = self.at else {
+            return false;
+        };
+        let checks = self.checks.get();
+        self.checks.set(checks.wrapping_add(1));
+        if checks % DEADLINE_CHECK_INTERVAL == 0 && Instant::now() >= at {
+            self.expired.set(true);
+        }
+        self.expired.get()
+    }
+
+    /// Whether any pass was cut short, without reading the clock
+    pub fn was_hit(&self) -> bool {
+        self.expired.get()
+    }
+}
+
+#[derive(Debug)]
+pub struct SuggestionCache {
+    capacity: usize,
+    inner: Mutex<CacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    map: HashMap<Box<str>, CacheItem>,
+    /// Incremented on every access, used to find the least recently used item
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Debug)]
+struct CacheItem {
+    suggestions: Box<[Box<str>]>,
+    last_used: u64,
+}
+
+impl SuggestionCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::default(),
+        }
+    }
+
+    /// Create an empty cache with the same capacity as this one
+    pub fn new_empty(&self) -> Self {
+        Self::new(self.capacity)
+    }
+
+    /// Call `f` with the cached suggestions for `word`, if any. This records a hit
+    /// or a miss.
+    pub fn get<R>(&self, word: &str, f: impl FnOnce(&[Box<str>]) -> R) -> Option<R> {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        if let Some(item) = inner.map.get_mut(word) {
+            item.last_used = tick;
+            let ret = f(&item.suggestions);
+            inner.hits += 1;
+            Some(ret)
+        } else {
+            inner.misses += 1;
+            None
+        }
+    }
+
+    /// Store the suggestions for `word`, evicting the least recently used entry if
+    /// the cache is full
+    pub fn insert(&self, word: &str, suggestions: &[&str]) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.tick += 1;
+
+        if inner.map.len() >= self.capacity && !inner.map.contains_key(word) {
+            let oldest = inner
+                .map
+                .iter()
+                .min_by_key(|(_, item)| item.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                inner.map.remove(&oldest);
+            }
+        }
+
+        let item = CacheItem {
+            suggestions: suggestions.iter().map(|s| (*s).into()).collect(),
+            last_used: inner.tick,
+        };
+        inner.map.insert(word.into(), item);
+    }
+
+    pub fn stats(&self) -> SuggestionCacheStats {
+        let inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        SuggestionCacheStats {
+            hits: inner.hits,
+            misses: inner.misses,
+            len: inner.map.len(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+/// Caches are only equal if they are the same instance
+impl PartialEq for SuggestionCache {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+/// Statistics about a dictionary's suggestion cache, see
+/// [`DictBuilder::suggestion_cache`](crate::DictBuilder::suggestion_cache).
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuggestionCacheStats {
+    /// Number of lookups that were answered from the cache
+    pub hits: u64,
+    /// Number of lookups that had to generate suggestions
+    pub misses: u64,
+    /// Number of words currently cached
+    pub len: usize,
+    /// Maximum number of words that will be cached
+    pub capacity: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_map_related(word: &str, maps: &[Vec<String>]) -> Vec<String> {
+        let mut res = Vec::new();
+        map_related(word, maps, |s| res.push(s.to_owned()));
+        res.sort_unstable();
+        res
+    }
+
+    fn groups(v: &[&[&str]]) -> Vec<Vec<String>> {
+        v.iter()
+            .map(|g| g.iter().map(|s| (*s).to_owned()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_map_related_single() {
+        let maps = groups(&[&["u", "ü"]]);
+        assert_eq!(collect_map_related("uber", &maps), ["über"]);
+        assert!(collect_map_related("abc", &maps).is_empty());
+    }
+
+    #[test]
+    fn test_map_related_multiple_positions() {
+        let maps = groups(&[&["u", "ú", "ü"]]);
+        let res = collect_map_related("usu", &maps);
+        assert_eq!(res.len(), 8);
+        assert!(res.contains(&"üsü".to_owned()));
+        assert!(res.contains(&"úsu".to_owned()));
+        assert!(!res.contains(&"usu".to_owned()));
+    }
+
+    #[test]
+    fn test_map_related_multichar() {
+        let maps = groups(&[&["ß", "ss"]]);
+        assert_eq!(collect_map_related("gross", &maps), ["groß"]);
+        assert_eq!(collect_map_related("groß", &maps), ["gross"]);
+    }
+
+    #[test]
+    fn test_cache_lru() {
+        let cache = SuggestionCache::new(2);
+        assert_eq!(cache.get("a", <[_]>::len), None);
+        cache.insert("a", &["aa"]);
+        cache.insert("b", &["bb", "bbb"]);
+        assert_eq!(cache.get("a", <[_]>::len), Some(1));
+
+        // `b` is the least recently used, so it gets evicted
+        cache.insert("c", &[]);
+        assert_eq!(cache.get("b", <[_]>::len), None);
+        assert_eq!(cache.get("a", |s| s[0].clone()), Some("aa".into()));
+        assert_eq!(cache.get("c", <[_]>::len), Some(0));
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 3);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.len, 2);
+        assert_eq!(stats.capacity, 2);
+    }
+}
@@ -0,0 +1,320 @@
+//! Edit distance for scoring suggestion candidates
+//!
+//! Suggestions compare one misspelled word against every word in the list, so
+//! [`EditDistance`] prepares the misspelling once. Words of up to 64 characters
+//! use the bit-parallel algorithm of Myers and Hyyrö, which computes a whole
+//! column of the distance matrix with a handful of operations on a `u64`.
+//! Longer words fall back to a scalar implementation that only fills a band of
+//! the matrix around its diagonal.
+//!
+//! Distances count characters rather than bytes, so `straße` and `strase` are
+//! one edit apart.
+
+/// Longest word handled by the bit-parallel algorithm, one bit per character
+const MAX_BITS: usize = u64::BITS as usize;
+
+/// Distance matrix rows up to this length are kept on the stack
+const STACK_ROW: usize = 64;
+
+/// Levenshtein distances from one word to many others
+#[derive(Clone, Debug)]
+pub struct EditDistance<'a> {
+    word: &'a str,
+    /// Length of `word` in characters
+    len: usize,
+    /// Bit `i` of `ascii[c]` is set if the `i`th character of `word` is `c`
+    ascii: [u64; 128],
+    /// Same as `ascii`, for other characters
+    other: Vec<(char, u64)>,
+}
+
+impl<'a> EditDistance<'a> {
+    #[inline]
+    pub fn new(word: &'a str) -> Self {
+        let mut ascii = [0; 128];
+        let mut other: Vec<(char, u64)> = Vec::new();
+        let mut len = 0;
+        for (i, ch) in word.chars().enumerate() {
+            len += 1;
+            if i >= MAX_BITS {
+                continue;
+            }
+            let bit = 1 << i;
+            if ch.is_ascii() {
+                ascii[ch as usize] |= bit;
+            } else if let Some((_, mask)) = other.iter_mut().find(|(c, _)| *c == ch) {
+                *mask |= bit;
+            } else {
+                other.push((ch, bit));
+            }
+        }
+        Self {
+            word,
+            len,
+            ascii,
+            other,
+        }
+    }
+
+    /// The distance to `other`, or `None` if it is greater than `limit`
+    #[inline]
+    pub fn within(&self, other: &str, limit: u32) -> Option<u32> {
+        let other_len = if other.is_ascii() {
+            other.len()
+        } else {
+            other.chars().count()
+        };
+        if self.len.abs_diff(other_len) > to_usize(limit) {
+            return None;
+        }
+        if self.len > MAX_BITS {
+            return banded_str(self.word, other, limit);
+        }
+        self.bit_parallel(other, other_len, limit)
+    }
+
+    /// The distance to `other`
+    #[inline]
+    pub fn distance(&self, other: &str) -> u32 {
+        self.within(other, u32::MAX)
+            .expect("every distance is within the maximum")
+    }
+
+    /// Bits set where `word` has `ch`
+    fn mask(&self, ch: char) -> u64 {
+        if ch.is_ascii() {
+            self.ascii[ch as usize]
+        } else {
+            self.other
+                .iter()
+                .find(|(c, _)| *c == ch)
+                .map_or(0, |(_, mask)| *mask)
+        }
+    }
+
+    /// Myers' algorithm as described by Hyyrö, for a word of at most 64
+    /// characters. Each step moves one character through `other` and keeps the
+    /// vertical differences of that column of the matrix as bit vectors.
+    fn bit_parallel(&self, other: &str, other_len: usize, limit: u32) -> Option<u32> {
+        if self.len == 0 {
+            return Some(to_u32(other_len)).filter(|dist| *dist <= limit);
+        }
+
+        let last = 1u64 << (self.len - 1);
+        // Vertical positive and negative deltas, initially all +1
+        let mut pos_v = u64::MAX;
+        let mut neg_v = 0u64;
+        let mut score = self.len;
+        let limit_usize = to_usize(limit);
+
+        for (j, ch) in other.chars().enumerate() {
+            let eq = self.mask(ch);
+            let x_v = eq | neg_v;
+            let x_h = ((eq & pos_v).wrapping_add(pos_v) ^ pos_v) | eq;
+            let mut pos_h = neg_v | !(x_h | pos_v);
+            let mut neg_h = pos_v & x_h;
+
+            if pos_h & last != 0 {
+                score += 1;
+            } else if neg_h & last != 0 {
+                score -= 1;
+            }
+
+            // Each remaining character can lower the score by at most one
+            if score.saturating_sub(other_len - j - 1) > limit_usize {
+                return None;
+            }
+
+            // The first row of the matrix counts up from zero
+            pos_h = (pos_h << 1) | 1;
+            neg_h <<= 1;
+            pos_v = neg_h | !(x_v | pos_h);
+            neg_v = pos_h & x_v;
+        }
+
+        Some(to_u32(score)).filter(|dist| *dist <= limit)
+    }
+}
+
+/// Banded Levenshtein distance between strings, see [`banded`]
+fn banded_str(a: &str, b: &str, limit: u32) -> Option<u32> {
+    if a.is_ascii() && b.is_ascii() {
+        return banded(a.as_bytes(), b.as_bytes(), limit);
+    }
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    banded(&a, &b, limit)
+}
+
+/// Banded Levenshtein distance. Only cells within `limit` of the diagonal are
+/// computed, and the search stops as soon as a whole row exceeds `limit`.
+fn banded<T: Copy + Eq>(a: &[T], b: &[T], limit: u32) -> Option<u32> {
+    // Shared ends never add edits, so only the differing middle is compared
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let (a, b) = (&a[prefix..], &b[prefix..]);
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a, b) = (&a[..a.len() - suffix], &b[..b.len() - suffix]);
+
+    // The distance is never more than the longer length
+    let (m, n) = (a.len(), b.len());
+    let limit = limit.min(to_u32(m.max(n)));
+    let band = to_usize(limit);
+    if m.abs_diff(n) > band {
+        return None;
+    }
+    if m == 0 || n == 0 {
+        return Some(limit);
+    }
+
+    // Anything over the limit is stored as `big` so the row stays small
+    let big = limit + 1;
+    let mut stack = [0u32; STACK_ROW];
+    let mut heap = Vec::new();
+    let row: &mut [u32] = if n < STACK_ROW {
+        &mut stack[..=n]
+    } else {
+        heap.resize(n + 1, 0);
+        &mut heap
+    };
+
+    // `row[j]` is the distance between the first `i` items of `a` and the
+    // first `j` of `b`
+    for (j, cell) in row.iter_mut().enumerate() {
+        *cell = if j <= band { to_u32(j) } else { big };
+    }
+
+    for i in 1..=m {
+        let lo = i.saturating_sub(band).max(1);
+        let hi = i.saturating_add(band).min(n);
+
+        // Cells left of the band are out of reach
+        let mut diag = row[lo - 1];
+        row[lo - 1] = if lo == 1 { to_u32(i).min(big) } else { big };
+        let mut row_min = row[lo - 1];
+
+        let item = a[i - 1];
+        for j in lo..=hi {
+            let up = row[j];
+            let replace = diag + u32::from(item != b[j - 1]);
+            let val = replace.min(up + 1).min(row[j - 1] + 1).min(big);
+            diag = up;
+            row[j] = val;
+            row_min = row_min.min(val);
+        }
+
+        // The next row reads this cell from above, and it is out of reach
+        if hi < n {
+            row[hi + 1] = big;
+        }
+        if row_min > limit {
+            return None;
+        }
+    }
+
+    Some(row[n]).filter(|dist| *dist <= limit)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn to_u32(n: usize) -> u32 {
+    n.min(u32::MAX as usize) as u32
+}
+
+fn to_usize(n: u32) -> usize {
+    usize::try_from(n).unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Full matrix Levenshtein distance over characters
+    fn reference(a: &str, b: &str) -> u32 {
+        let b: Vec<char> = b.chars().collect();
+        let mut prev: Vec<u32> = (0..=to_u32(b.len())).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut cur = vec![to_u32(i) + 1];
+            for (j, cb) in b.iter().enumerate() {
+                let val = (prev[j] + u32::from(ca != *cb))
+                    .min(prev[j + 1] + 1)
+                    .min(cur[j] + 1);
+                cur.push(val);
+            }
+            prev = cur;
+        }
+        prev[b.len()]
+    }
+
+    #[test]
+    fn test_distance() {
+        let dist = |a, b| EditDistance::new(a).distance(b);
+        assert_eq!(dist("kitten", "sitting"), 3);
+        assert_eq!(dist("", "abc"), 3);
+        assert_eq!(dist("abc", ""), 3);
+        assert_eq!(dist("abc", "abc"), 0);
+        assert_eq!(dist("straße", "strase"), 1);
+        assert_eq!(dist("über", "uber"), 1);
+    }
+
+    #[test]
+    fn test_within() {
+        let word = EditDistance::new("word");
+        assert_eq!(word.within("ward", 1), Some(1));
+        assert_eq!(word.within("wodr", 1), None);
+        assert_eq!(word.within("wodr", 2), Some(2));
+        assert_eq!(word.within("wordss", 1), None);
+        assert_eq!(EditDistance::new("").within("a", 1), Some(1));
+        assert_eq!(EditDistance::new("").within("ab", 1), None);
+        assert_eq!(EditDistance::new("naïve").within("naive", 1), Some(1));
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        let long = "a".repeat(70) + "bc";
+        let long_typo = "a".repeat(69) + "cb";
+        let words = [
+            "",
+            "a",
+            "ab",
+            "ba",
+            "abc",
+            "acb",
+            "abcc",
+            "babcbc",
+            "kitten",
+            "sitting",
+            "sittin",
+            "saturday",
+            "sunday",
+            "straße",
+            "strasse",
+            "accommodation",
+            "acommodation",
+            "accomodation",
+            "internationalization",
+            "internationalisation",
+            "interpretationalization",
+            "électricité",
+            "electricite",
+            "pneumonoultramicroscopicsilicovolcanoconiosispneumonoultramicroscopic",
+            "pneumonoultramicroscopicsilicovolcanoconiosispneumonoultramicroscopy",
+            &long,
+            &long_typo,
+        ];
+        for a in words {
+            let from_a = EditDistance::new(a);
+            for b in words {
+                let expected = reference(a, b);
+                assert_eq!(from_a.distance(b), expected, "{a} {b}");
+                for limit in 0..4 {
+                    let within = (expected <= limit).then_some(expected);
+                    assert_eq!(from_a.within(b, limit), within, "{a} {b} {limit}");
+                }
+            }
+        }
+    }
+}
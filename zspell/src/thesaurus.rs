@@ -0,0 +1,284 @@
+//! Synonym lookup using MyThes thesaurus files, as found in `th_*.dat` files
+//!
+//! These files are used by most office suites, and are usually distributed
+//! alongside Hunspell dictionaries. Each entry is a word and a count, followed
+//! by that many meanings: a part of speech and related terms.
+//!
+//! ```
+//! use zspell::Thesaurus;
+//!
+//! let dat = "UTF-8
+//! happy|2
+//! (adj)|felicitous|glad|unhappy (antonym)
+//! (adj)|well-chosen|fortunate (similar term)
+//! ";
+//!
+//! let thes = Thesaurus::load_from_str(dat).unwrap();
+//!
+//! // Antonyms are left out, and notes such as "(similar term)" are removed
+//! assert_eq!(
+//!     thes.synonyms("happy"),
+//!     ["felicitous", "glad", "well-chosen", "fortunate"]
+//! );
+//!
+//! let meanings = thes.meanings("happy").unwrap();
+//! assert_eq!(meanings[0].part_of_speech(), Some("adj"));
+//! assert_eq!(meanings[0].terms().last(), Some("unhappy (antonym)"));
+//! ```
+//!
+//! The `th_*.idx` file that comes with each `.dat` file is an index of byte
+//! offsets, used to read entries without loading the whole file. It is not
+//! needed here, since the `.dat` file is read in full.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use hashbrown::HashMap;
+
+use crate::affix::Encoding;
+use crate::error::{Error, ParseError, ParseErrorKind};
+
+/// A thesaurus that can list the synonyms of a word.
+///
+/// See the [module-level documentation](crate::thesaurus) for an example.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Thesaurus {
+    /// The charset specified on the first line of the file
+    encoding: Encoding,
+    /// Meanings of each word, in the order of the file
+    entries: HashMap<Box<str>, Vec<Meaning>>,
+}
+
+/// One meaning of a word in a [`Thesaurus`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Meaning {
+    /// Part of speech without parentheses, if there is one
+    part_of_speech: Option<Box<str>>,
+    /// Related terms as written, possibly with a note such as `(antonym)`
+    terms: Box<[Box<str>]>,
+}
+
+impl Thesaurus {
+    /// Load a thesaurus from the contents of a MyThes `.dat` file.
+    ///
+    /// The first line must specify the charset. After that, each entry is a
+    /// line such as `word|2`, followed by that many lines such as
+    /// `(noun)|term|other term (generic term)`. If a word has several entries,
+    /// their meanings are combined.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the charset is not recognized, an entry is not
+    /// `word|count`, or the file ends before all meanings of an entry.
+    #[inline]
+    pub fn load_from_str(s: &str) -> Result<Self, Error> {
+        let mut lines = s.lines().enumerate();
+
+        let encoding = match lines.next() {
+            Some((idx, line)) => Encoding::try_from(line.trim())
+                .map_err(|e| ParseError::new_nocol(e, line, idx + 1))?,
+            None => Encoding::default(),
+        };
+
+        let mut entries: HashMap<Box<str>, Vec<Meaning>> = HashMap::new();
+
+        while let Some((idx, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let Some((word, count)) = line.split_once('|') else {
+                return Err(
+                    ParseError::new_nocol(ParseErrorKind::ThesaurusEntry, line, idx + 1).into(),
+                );
+            };
+            let count: u32 = count
+                .trim()
+                .parse()
+                .map_err(|e| ParseError::new_nocol(ParseErrorKind::Int(e), line, idx + 1))?;
+
+            let meanings = entries.entry(word.trim().into()).or_default();
+            for actual in 0..count {
+                let Some((_, meaning)) = lines.next() else {
+                    let kind = ParseErrorKind::TableCount {
+                        expected: count,
+                        actual,
+                    };
+                    return Err(ParseError::new_nocol(kind, line, idx + 1).into());
+                };
+                meanings.push(Meaning::parse(meaning));
+            }
+        }
+
+        Ok(Self { encoding, entries })
+    }
+
+    /// The charset specified on the first line of the thesaurus file
+    #[inline]
+    pub fn encoding(&self) -> &Encoding {
+        &self.encoding
+    }
+
+    /// The meanings of `word`, or `None` if it is not in the thesaurus. Like
+    /// MyThes, a word that is not found is looked up again in lowercase.
+    #[inline]
+    pub fn meanings(&self, word: &str) -> Option<&[Meaning]> {
+        self.entries
+            .get(word)
+            .or_else(|| self.entries.get(word.to_lowercase().as_str()))
+            .map(Vec::as_slice)
+    }
+
+    /// Synonyms of `word` from all of its meanings, without repeats and in the
+    /// order of the file. Antonyms are left out, see [`Meaning::synonyms`].
+    /// Returns an empty vector if the word is not in the thesaurus.
+    #[inline]
+    pub fn synonyms(&self, word: &str) -> Vec<&str> {
+        let mut ret: Vec<&str> = Vec::new();
+        for syn in self
+            .meanings(word)
+            .unwrap_or_default()
+            .iter()
+            .flat_map(Meaning::synonyms)
+        {
+            if syn != word && !ret.contains(&syn) {
+                ret.push(syn);
+            }
+        }
+        ret
+    }
+}
+
+impl Meaning {
+    /// Parse a line such as `(noun)|term|other term (generic term)`
+    fn parse(line: &str) -> Self {
+        let mut fields = line.trim().split('|').map(str::trim);
+        let part_of_speech = fields
+            .next()
+            .map(|pos| pos.trim_start_matches('(').trim_end_matches(')').trim())
+            .filter(|pos| !pos.is_empty() && *pos != "-")
+            .map(Into::into);
+        let terms = fields
+            .filter(|term| !term.is_empty())
+            .map(Into::into)
+            .collect();
+
+        Self {
+            part_of_speech,
+            terms,
+        }
+    }
+
+    /// The part of speech of this meaning without parentheses, such as `noun`,
+    /// or `None` if it is empty or `-`
+    #[inline]
+    pub fn part_of_speech(&self) -> Option<&str> {
+        self.part_of_speech.as_deref()
+    }
+
+    /// The related terms of this meaning as written in the file. They may end
+    /// with a note such as `(generic term)`, `(similar term)`,
+    /// `(related term)`, or `(antonym)`.
+    #[inline]
+    pub fn terms(&self) -> impl Iterator<Item = &str> {
+        self.terms.iter().map(AsRef::as_ref)
+    }
+
+    /// The terms of this meaning that are not antonyms, without their notes
+    #[inline]
+    pub fn synonyms(&self) -> impl Iterator<Item = &str> {
+        self.terms().filter_map(|term| match split_note(term) {
+            (_, Some("antonym")) => None,
+            (term, _) => Some(term),
+        })
+    }
+}
+
+/// Split a term such as `animal (generic term)` into the term and its note
+fn split_note(term: &str) -> (&str, Option<&str>) {
+    term.strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" ("))
+        .map_or((term, None), |(term, note)| (term.trim_end(), Some(note)))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const DAT: &str = "\
+ISO8859-1
+'hood|1
+(noun)|vicinity|locality|neighborhood|neck of the woods
+Bank|1
+(noun)|financial institution (generic term)
+bank|2
+(noun)|depository financial institution|bank|banking concern
+-|side (generic term)|riverbank (related term)
+";
+
+    #[test]
+    fn test_load() {
+        let thes = Thesaurus::load_from_str(DAT).unwrap();
+        assert_eq!(thes.encoding(), &Encoding::Iso8859t1);
+        assert_eq!(thes.entries.len(), 3);
+
+        let meanings = thes.meanings("bank").unwrap();
+        assert_eq!(meanings.len(), 2);
+        assert_eq!(meanings[0].part_of_speech(), Some("noun"));
+        assert_eq!(meanings[1].part_of_speech(), None);
+        assert_eq!(
+            meanings[1].terms().collect::<Vec<_>>(),
+            ["side (generic term)", "riverbank (related term)"]
+        );
+    }
+
+    #[test]
+    fn test_load_err() {
+        assert!(Thesaurus::load_from_str("NOT-A-CHARSET\nword|1\n(noun)|term").is_err());
+        assert!(Thesaurus::load_from_str("UTF-8\nword\n(noun)|term").is_err());
+        assert!(Thesaurus::load_from_str("UTF-8\nword|x\n(noun)|term").is_err());
+
+        let err = Thesaurus::load_from_str("UTF-8\nword|2\n(noun)|term").unwrap_err();
+        let crate::Error::Parse(err) = err else {
+            panic!("expected a parse error, got {err:?}");
+        };
+        assert_eq!(
+            err.err(),
+            &ParseErrorKind::TableCount {
+                expected: 2,
+                actual: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_synonyms() {
+        let thes = Thesaurus::load_from_str(DAT).unwrap();
+        assert_eq!(
+            thes.synonyms("bank"),
+            [
+                "depository financial institution",
+                "banking concern",
+                "side",
+                "riverbank"
+            ]
+        );
+        // An exact match is preferred, then the lowercase word
+        assert_eq!(thes.synonyms("Bank"), ["financial institution"]);
+        assert_eq!(thes.synonyms("'HOOD").len(), 4);
+        assert!(thes.synonyms("river").is_empty());
+    }
+
+    #[test]
+    fn test_split_note() {
+        assert_eq!(
+            split_note("animal (generic term)"),
+            ("animal", Some("generic term"))
+        );
+        assert_eq!(split_note("animal"), ("animal", None));
+        assert_eq!(split_note("(animal)"), ("(animal)", None));
+    }
+}
@@ -0,0 +1,24 @@
+//! Instrumentation with the `tracing` crate, enabled by the `tracing` feature
+//!
+//! These macros expand to nothing without the feature, so call sites don't
+//! need their own `cfg` attributes. Arguments are passed through to `tracing`
+//! unchanged, so they are not evaluated when the feature is disabled.
+
+/// Enter a span at the given level (e.g. `DEBUG`) until the end of the
+/// enclosing scope
+macro_rules! enter_span {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::span!(tracing::Level::$level, $($arg)+).entered();
+    };
+}
+
+/// Emit an event at the given level (e.g. `DEBUG`)
+macro_rules! event {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::event!(tracing::Level::$level, $($arg)+);
+    };
+}
+
+pub(crate) use {enter_span, event};
@@ -0,0 +1,304 @@
+//! Compare zspell against an installed `hunspell` binary
+//!
+//! This is opt in: set `HUNSPELL_BIN` to the binary (e.g. `hunspell`) to
+//! enable it. Words are sent to Hunspell's pipe mode (`hunspell -a`) and the
+//! check and suggestion results are compared with zspell's for the same
+//! dictionary files. Differences are collected into a [`DiffReport`] rather
+//! than asserted, since some are expected while zspell is incomplete.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use zspell::Dictionary;
+
+/// Environment variable naming the `hunspell` binary to compare against
+pub const HUNSPELL_BIN_VAR: &str = "HUNSPELL_BIN";
+
+/// How Hunspell answered for a single word
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    /// The word is correct
+    Correct,
+    /// The word is misspelled, with these suggestions
+    Misspelled(Vec<String>),
+}
+
+/// A word where Hunspell and zspell disagree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Divergence {
+    /// One accepts the word and the other does not
+    Check {
+        word: String,
+        hunspell: bool,
+        zspell: bool,
+    },
+    /// Both reject the word, but suggest different words. Order is ignored.
+    Suggest {
+        word: String,
+        hunspell: Vec<String>,
+        zspell: Vec<String>,
+    },
+}
+
+/// All divergences found for one dictionary
+#[derive(Clone, Debug, Default)]
+pub struct DiffReport {
+    pub dictionary: String,
+    /// Number of words compared
+    pub words: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+/// A `hunspell` binary and the dictionary it should load
+#[derive(Clone, Debug)]
+pub struct Reference {
+    bin: PathBuf,
+    /// Path to the dictionary without the `.aff` or `.dic` extension
+    dict_base: PathBuf,
+}
+
+impl Reference {
+    /// Use the binary at `bin` with the dictionary at `dict_base`, which is
+    /// the path to the `.aff` and `.dic` files without the extension
+    pub fn new(bin: impl Into<PathBuf>, dict_base: impl Into<PathBuf>) -> Self {
+        Self {
+            bin: bin.into(),
+            dict_base: dict_base.into(),
+        }
+    }
+
+    /// Use the binary named by [`HUNSPELL_BIN_VAR`], if it is set
+    pub fn from_env(dict_base: impl Into<PathBuf>) -> Option<Self> {
+        let bin = std::env::var_os(HUNSPELL_BIN_VAR)?;
+        Some(Self::new(bin, dict_base))
+    }
+
+    /// Ask Hunspell about each word. The result has one answer per word.
+    pub fn query(&self, words: &[&str]) -> io::Result<Vec<Answer>> {
+        let mut child = Command::new(&self.bin)
+            .arg("-a")
+            .arg("-i")
+            .arg("utf-8")
+            .arg("-d")
+            .arg(&self.dict_base)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        // `^` marks a line as text to check, so words are never read as commands
+        let mut input = String::new();
+        for word in words {
+            input.push('^');
+            input.push_str(word);
+            input.push('\n');
+        }
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+        let output = child.wait_with_output()?;
+        writer.join().expect("writer thread panicked")?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let answers = parse_pipe_output(&stdout);
+        if answers.len() != words.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "expected {} answers from hunspell, got {}",
+                    words.len(),
+                    answers.len()
+                ),
+            ));
+        }
+        Ok(answers)
+    }
+
+    /// Build zspell's dictionary from the same files
+    pub fn zspell_dict(&self) -> Dictionary {
+        let read = |ext: &str| {
+            let path = self.dict_base.with_extension(ext);
+            std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+        };
+        zspell::builder()
+            .config_str(&read("aff"))
+            .dict_str(&read("dic"))
+            .build()
+            .expect("error building dictionary")
+    }
+
+    /// Compare check and suggestion results for each word
+    pub fn diff(&self, words: &[&str]) -> io::Result<DiffReport> {
+        let answers = self.query(words)?;
+        let dict = self.zspell_dict();
+
+        let divergences = words
+            .iter()
+            .zip(answers)
+            .filter_map(|(word, answer)| diverges(&dict, word, answer))
+            .collect();
+
+        Ok(DiffReport {
+            dictionary: self.dict_base.display().to_string(),
+            words: words.len(),
+            divergences,
+        })
+    }
+}
+
+/// Compare zspell's result for `word` with Hunspell's
+fn diverges(dict: &Dictionary, word: &str, answer: Answer) -> Option<Divergence> {
+    let zspell_ok = dict.check_word(word);
+    let Answer::Misspelled(mut hunspell) = answer else {
+        return (!zspell_ok).then(|| Divergence::Check {
+            word: word.to_owned(),
+            hunspell: true,
+            zspell: false,
+        });
+    };
+    if zspell_ok {
+        return Some(Divergence::Check {
+            word: word.to_owned(),
+            hunspell: false,
+            zspell: true,
+        });
+    }
+
+    let mut zspell: Vec<String> = dict
+        .entry(word)
+        .suggest()
+        .unwrap_or_default()
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    hunspell.sort_unstable();
+    zspell.sort_unstable();
+    (hunspell != zspell).then(|| Divergence::Suggest {
+        word: word.to_owned(),
+        hunspell,
+        zspell,
+    })
+}
+
+/// Parse the output of `hunspell -a`, with one answer per checked line
+///
+/// The first line is a version banner. Each checked line then gets one line
+/// per word, followed by an empty line. `*`, `+`, and `-` mean the word is
+/// correct; `& word count offset: sug, sug` and `# word offset` mean it is
+/// misspelled.
+fn parse_pipe_output(output: &str) -> Vec<Answer> {
+    output
+        .lines()
+        .skip(1)
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.as_bytes()[0] {
+            b'&' => {
+                let sugs = line.split_once(": ").map_or("", |(_, sugs)| sugs);
+                Answer::Misspelled(sugs.split(", ").map(ToOwned::to_owned).collect())
+            }
+            b'#' => Answer::Misspelled(Vec::new()),
+            _ => Answer::Correct,
+        })
+        .collect()
+}
+
+/// Collect distinct words from text, splitting on anything that is not
+/// alphabetic or an apostrophe
+pub fn words_from_text(text: &str) -> Vec<&str> {
+    let mut words: Vec<&str> = text
+        .split(|ch: char| !(ch.is_alphabetic() || ch == '\''))
+        .map(|word| word.trim_matches('\''))
+        .filter(|word| !word.is_empty())
+        .collect();
+    words.sort_unstable();
+    words.dedup();
+    words
+}
+
+impl DiffReport {
+    /// Number of words that one accepts and the other rejects
+    pub fn check_divergences(&self) -> usize {
+        self.divergences
+            .iter()
+            .filter(|div| matches!(div, Divergence::Check { .. }))
+            .count()
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = |ok: bool| if ok { "correct" } else { "misspelled" };
+        match self {
+            Self::Check {
+                word,
+                hunspell,
+                zspell,
+            } => write!(
+                f,
+                "check   {word}: hunspell {}, zspell {}",
+                verdict(*hunspell),
+                verdict(*zspell)
+            ),
+            Self::Suggest {
+                word,
+                hunspell,
+                zspell,
+            } => write!(
+                f,
+                "suggest {word}: hunspell [{}], zspell [{}]",
+                hunspell.join(", "),
+                zspell.join(", ")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} words, {} check and {} suggestion divergences",
+            self.dictionary,
+            self.words,
+            self.check_divergences(),
+            self.divergences.len() - self.check_divergences()
+        )?;
+        for div in &self.divergences {
+            writeln!(f, "  {div}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Path to a dictionary in the workspace `dictionaries` directory, without the
+/// extension. Returns `None` if its files have not been downloaded.
+pub fn workspace_dict(root: &Path, name: &str) -> Option<PathBuf> {
+    let base = root.join("dictionaries").join(name);
+    (base.with_extension("aff").exists() && base.with_extension("dic").exists()).then_some(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pipe_output() {
+        let output = "@(#) International Ispell Version 3.2.06 (but really Hunspell 1.7.2)\n\
+                      *\n\n\
+                      + walk\n\n\
+                      & tiem 3 0: time, item, tie\n\n\
+                      # xyzzy 0\n\n";
+        assert_eq!(
+            parse_pipe_output(output),
+            [
+                Answer::Correct,
+                Answer::Correct,
+                Answer::Misspelled(vec!["time".into(), "item".into(), "tie".into()]),
+                Answer::Misspelled(Vec::new()),
+            ]
+        );
+    }
+}
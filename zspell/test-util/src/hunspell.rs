@@ -0,0 +1,285 @@
+//! Run Hunspell's own test suite against zspell
+//!
+//! Each Hunspell test is a set of files sharing a name, usually named after the
+//! feature it covers: `name.aff` and `name.dic` make up the dictionary,
+//! `name.good` and `name.wrong` list words that should pass or fail, and
+//! `name.sug` has the expected suggestions for each wrong word that has any,
+//! one comma-separated line per word.
+//!
+//! Results are collected into a [`Scorecard`] rather than asserted, since
+//! zspell does not support every Hunspell feature yet.
+
+use std::fmt;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use zspell::Dictionary;
+
+/// Environment variable with the path to Hunspell's `tests` directory
+pub const HUNSPELL_TESTS_VAR: &str = "HUNSPELL_TESTS";
+
+/// The files for a single Hunspell test
+#[derive(Clone, Debug, Default)]
+pub struct HunspellCase {
+    pub name: String,
+    pub aff: String,
+    pub dic: String,
+    pub good: Vec<String>,
+    pub wrong: Vec<String>,
+    /// Expected suggestion lines, if the test has a `.sug` file
+    pub sug: Option<Vec<String>>,
+}
+
+/// How many items of one kind passed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+    pub passed: usize,
+    pub total: usize,
+}
+
+/// The outcome of a single Hunspell test
+#[derive(Clone, Debug)]
+pub struct CaseResult {
+    pub name: String,
+    /// Set if the dictionary could not be built or checking panicked
+    pub error: Option<String>,
+    pub good: Score,
+    pub wrong: Score,
+    pub sug: Option<Score>,
+}
+
+/// Results for every test in a suite
+#[derive(Clone, Debug, Default)]
+pub struct Scorecard {
+    pub results: Vec<CaseResult>,
+}
+
+impl HunspellCase {
+    /// Load the test with the given name from `dir`. Returns `None` if there is
+    /// no `.aff` or `.dic` file.
+    pub fn load(dir: &Path, name: &str) -> Option<Self> {
+        let read = |ext: &str| read_lossy(&dir.join(format!("{name}.{ext}")));
+        let words = |ext: &str| {
+            read(ext).map_or_else(Vec::new, |s| {
+                s.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+        };
+
+        Some(Self {
+            name: name.to_owned(),
+            aff: read("aff")?,
+            dic: read("dic")?,
+            good: words("good"),
+            wrong: words("wrong"),
+            sug: read("sug").map(|s| s.lines().map(ToOwned::to_owned).collect()),
+        })
+    }
+
+    /// Load every test in `dir`, sorted by name
+    pub fn load_all(dir: &Path) -> Vec<Self> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap_or_else(|e| panic!("failed to read {}: {e}", dir.display()))
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                (path.extension()? == "dic")
+                    .then(|| path.file_stem()?.to_str().map(ToOwned::to_owned))
+                    .flatten()
+            })
+            .collect();
+        names.sort_unstable();
+        names
+            .iter()
+            .filter_map(|name| Self::load(dir, name))
+            .collect()
+    }
+
+    /// Build the dictionary and score each word. Panics are caught and
+    /// recorded as errors.
+    pub fn run(&self) -> CaseResult {
+        let mut result = CaseResult {
+            name: self.name.clone(),
+            error: None,
+            good: Score::default(),
+            wrong: Score::default(),
+            sug: None,
+        };
+
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            let dict = zspell::builder()
+                .config_str(&self.aff)
+                .dict_str(&self.dic)
+                .build()
+                .map_err(|e| format!("build failed: {e}"))?;
+
+            result.good = Score::count(&self.good, |word| dict.check_word(word));
+            result.wrong = Score::count(&self.wrong, |word| !dict.check_word(word));
+            result.sug = self
+                .sug
+                .as_ref()
+                .map(|expected| self.score_sug(&dict, expected));
+            Ok::<_, String>(())
+        }));
+
+        result.error = match outcome {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e),
+            Err(_) => Some("panicked".to_owned()),
+        };
+        result
+    }
+
+    /// Compare suggestion lines for wrong words that have suggestions
+    fn score_sug(&self, dict: &Dictionary, expected: &[String]) -> Score {
+        let actual: Vec<String> = self
+            .wrong
+            .iter()
+            .filter_map(|word| dict.entry(word).suggest())
+            .filter(|sugs| !sugs.is_empty())
+            .map(|sugs| sugs.join(", "))
+            .collect();
+
+        let total = expected.len().max(actual.len());
+        let passed = expected
+            .iter()
+            .zip(&actual)
+            .filter(|(exp, act)| exp == act)
+            .count();
+        Score { passed, total }
+    }
+}
+
+impl Score {
+    fn count(items: &[String], f: impl Fn(&str) -> bool) -> Self {
+        Self {
+            passed: items.iter().filter(|item| f(item)).count(),
+            total: items.len(),
+        }
+    }
+
+    fn is_perfect(self) -> bool {
+        self.passed == self.total
+    }
+}
+
+impl CaseResult {
+    /// Whether the dictionary built and every item matched Hunspell
+    pub fn is_pass(&self) -> bool {
+        self.error.is_none()
+            && self.good.is_perfect()
+            && self.wrong.is_perfect()
+            && self.sug.iter().all(|sug| sug.is_perfect())
+    }
+}
+
+impl Scorecard {
+    /// Run every test in `dir`
+    pub fn run_dir(dir: &Path) -> Self {
+        Self {
+            results: HunspellCase::load_all(dir)
+                .iter()
+                .map(HunspellCase::run)
+                .collect(),
+        }
+    }
+
+    /// Run the suite at the path in [`HUNSPELL_TESTS_VAR`], if it is set
+    pub fn run_from_env() -> Option<Self> {
+        let dir = PathBuf::from(std::env::var_os(HUNSPELL_TESTS_VAR)?);
+        Some(Self::run_dir(&dir))
+    }
+
+    /// Number of tests that fully pass
+    pub fn passing(&self) -> usize {
+        self.results.iter().filter(|res| res.is_pass()).count()
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.total == 0 {
+            write!(f, "-")
+        } else {
+            write!(f, "{}/{}", self.passed, self.total)
+        }
+    }
+}
+
+impl fmt::Display for Scorecard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .results
+            .iter()
+            .map(|res| res.name.len())
+            .fold("test".len(), usize::max);
+        writeln!(
+            f,
+            "{:width$}  {:>9}  {:>9}  {:>9}  status",
+            "test", "good", "wrong", "sug"
+        )?;
+        for res in &self.results {
+            let sug = res.sug.map_or_else(|| "-".to_owned(), |s| s.to_string());
+            let status = match (&res.error, res.is_pass()) {
+                (Some(e), _) => e.as_str(),
+                (None, true) => "pass",
+                (None, false) => "fail",
+            };
+            writeln!(
+                f,
+                "{:width$}  {:>9}  {:>9}  {sug:>9}  {status}",
+                res.name,
+                res.good.to_string(),
+                res.wrong.to_string(),
+            )?;
+        }
+        write!(f, "{}/{} tests pass", self.passing(), self.results.len())
+    }
+}
+
+/// Read a file, decoding it as Latin-1 if it is not UTF-8 since many Hunspell
+/// tests use legacy encodings. Returns `None` if the file does not exist.
+fn read_lossy(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    Some(
+        String::from_utf8(bytes)
+            .unwrap_or_else(|e| e.into_bytes().into_iter().map(char::from).collect()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_case() {
+        let case = HunspellCase {
+            name: "sample".to_owned(),
+            aff: "SFX S Y 1\nSFX S 0 s .".to_owned(),
+            dic: "1\nword/S".to_owned(),
+            good: vec!["word".to_owned(), "words".to_owned(), "wordes".to_owned()],
+            wrong: vec!["wordss".to_owned()],
+            sug: None,
+        };
+        let result = case.run();
+        assert_eq!(result.error, None);
+        assert_eq!(
+            result.good,
+            Score {
+                passed: 2,
+                total: 3
+            }
+        );
+        assert_eq!(
+            result.wrong,
+            Score {
+                passed: 1,
+                total: 1
+            }
+        );
+        assert!(!result.is_pass());
+    }
+}
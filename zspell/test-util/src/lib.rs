@@ -1,6 +1,10 @@
 //! Utilities intended to help with test collection
 #![forbid(unsafe_code)]
 
+pub mod differential;
+pub mod hunspell;
+pub mod strategies;
+
 use std::collections::BTreeMap;
 use std::fmt::Write;
 use std::fs;
@@ -372,6 +376,7 @@ impl TestManager {
                 .unwrap_or_else(|| {
                     self.panic_with_dict(dict, &format!("no analysis for '{input}'"))
                 })
+                .morphs()
                 .collect();
             let mut morph_exp: Vec<_> = expected.iter().collect();
             morph_dict.sort_unstable();
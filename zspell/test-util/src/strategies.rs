@@ -0,0 +1,247 @@
+//! Proptest strategies for dictionaries with a known expansion
+//!
+//! [`dictionaries`] generates affix and dictionary files along with the
+//! wordlist they should expand to, which is computed by a straightforward
+//! reference implementation rather than by `zspell`. Rules are kept simple
+//! enough that Hunspell and `zspell` agree on them:
+//!
+//! - Conditions are single characters or character classes, and always include
+//!   the stripped characters
+//! - Stems are long enough that prefix and suffix conditions never overlap
+//! - Affixes are never empty, since `zspell` reads an affix of `0` literally
+//! - Rules have no continuation flags or morphological info
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use proptest::prelude::*;
+use proptest::sample::subsequence;
+
+/// Letters used for stems, affixes, and conditions. A small alphabet makes
+/// conditions match often.
+const LETTERS: &[char] = &['a', 'e', 'i', 'o', 's', 't', 'y'];
+/// Flags given to affix groups, in order
+const FLAGS: &[char] = &['A', 'B', 'C', 'D'];
+
+/// Affix and dictionary files, and the words they should produce
+#[derive(Clone, Debug)]
+pub struct GeneratedDict {
+    /// Contents of the affix file
+    pub afx: String,
+    /// Contents of the dictionary file
+    pub dic: String,
+    /// Every word in the expanded wordlist, sorted and without duplicates
+    pub expected: Vec<String>,
+}
+
+/// One character of a condition
+#[derive(Clone, Debug)]
+enum CondChar {
+    Is(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl CondChar {
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            Self::Is(c) => *c == ch,
+            Self::OneOf(chars) => chars.contains(&ch),
+            Self::NoneOf(chars) => !chars.contains(&ch),
+        }
+    }
+
+    fn render(&self) -> String {
+        match self {
+            Self::Is(c) => c.to_string(),
+            Self::OneOf(chars) => format!("[{}]", chars.iter().collect::<String>()),
+            Self::NoneOf(chars) => format!("[^{}]", chars.iter().collect::<String>()),
+        }
+    }
+}
+
+/// A single `PFX` or `SFX` rule
+#[derive(Clone, Debug)]
+struct Rule {
+    strip: String,
+    affix: String,
+    /// Conditions for characters from the start of the stem for prefixes, or
+    /// up to the end for suffixes. This includes the stripped characters.
+    condition: Vec<CondChar>,
+}
+
+/// A `PFX` or `SFX` group
+#[derive(Clone, Debug)]
+struct Group {
+    suffix: bool,
+    cross_product: bool,
+    rules: Vec<Rule>,
+}
+
+impl Rule {
+    /// Apply this rule to a stem, if the condition matches
+    fn apply(&self, stem: &str, suffix: bool) -> Option<String> {
+        let chars: Vec<char> = stem.chars().collect();
+        let len = self.condition.len();
+        if chars.len() < len {
+            return None;
+        }
+        let checked = if suffix {
+            &chars[chars.len() - len..]
+        } else {
+            &chars[..len]
+        };
+        if !self
+            .condition
+            .iter()
+            .zip(checked)
+            .all(|(c, ch)| c.matches(*ch))
+        {
+            return None;
+        }
+
+        Some(if suffix {
+            let rest = stem.strip_suffix(self.strip.as_str())?;
+            format!("{rest}{}", self.affix)
+        } else {
+            let rest = stem.strip_prefix(self.strip.as_str())?;
+            format!("{}{rest}", self.affix)
+        })
+    }
+}
+
+fn letter() -> impl Strategy<Value = char> {
+    proptest::sample::select(LETTERS)
+}
+
+fn cond_char() -> impl Strategy<Value = CondChar> {
+    prop_oneof![
+        letter().prop_map(CondChar::Is),
+        subsequence(LETTERS, 1..=2).prop_map(CondChar::OneOf),
+        subsequence(LETTERS, 1..=2).prop_map(CondChar::NoneOf),
+    ]
+}
+
+fn rule(suffix: bool) -> impl Strategy<Value = Rule> {
+    let strip = proptest::option::of(letter());
+    let affix = proptest::collection::vec(letter(), 1..=3);
+    let extra = proptest::option::of(cond_char());
+    (strip, affix, extra).prop_map(move |(strip, affix, extra)| {
+        // The condition always covers the stripped character, with an optional
+        // character before a suffix or after a prefix
+        let strip_cond = strip.map(CondChar::Is);
+        let condition = if suffix {
+            extra.into_iter().chain(strip_cond).collect()
+        } else {
+            strip_cond.into_iter().chain(extra).collect()
+        };
+        Rule {
+            strip: strip.map(String::from).unwrap_or_default(),
+            affix: affix.into_iter().collect(),
+            condition,
+        }
+    })
+}
+
+fn group() -> impl Strategy<Value = Group> {
+    (any::<bool>(), any::<bool>()).prop_flat_map(|(suffix, cross_product)| {
+        proptest::collection::vec(rule(suffix), 1..=3).prop_map(move |rules| Group {
+            suffix,
+            cross_product,
+            rules,
+        })
+    })
+}
+
+/// A stem of four to seven letters, so prefix and suffix conditions of up to
+/// two characters never overlap
+fn stem() -> impl Strategy<Value = String> {
+    proptest::collection::vec(letter(), 4..=7).prop_map(|chars| chars.into_iter().collect())
+}
+
+/// Generate affix and dictionary files with up to four affix groups and eight
+/// stems, along with the wordlist they expand to
+pub fn dictionaries() -> impl Strategy<Value = GeneratedDict> {
+    proptest::collection::vec(group(), 0..=FLAGS.len()).prop_flat_map(|groups| {
+        let indices: Vec<usize> = (0..groups.len()).collect();
+        let entry = (stem(), subsequence(indices, 0..=groups.len()));
+        proptest::collection::vec(entry, 1..=8)
+            .prop_map(move |entries| GeneratedDict::new(&groups, &entries))
+    })
+}
+
+impl GeneratedDict {
+    fn new(groups: &[Group], entries: &[(String, Vec<usize>)]) -> Self {
+        let mut afx = String::new();
+        for (group, flag) in groups.iter().zip(FLAGS) {
+            let kind = if group.suffix { "SFX" } else { "PFX" };
+            let cross = if group.cross_product { 'Y' } else { 'N' };
+            writeln!(afx, "{kind} {flag} {cross} {}", group.rules.len()).unwrap();
+            for rule in &group.rules {
+                let strip = if rule.strip.is_empty() {
+                    "0"
+                } else {
+                    &rule.strip
+                };
+                let condition: String = rule.condition.iter().map(CondChar::render).collect();
+                let condition = if condition.is_empty() {
+                    "."
+                } else {
+                    &condition
+                };
+                let affix = &rule.affix;
+                writeln!(afx, "{kind} {flag} {strip} {affix} {condition}").unwrap();
+            }
+        }
+
+        let mut dic = format!("{}\n", entries.len());
+        for (stem, group_idx) in entries {
+            let flags: String = group_idx.iter().map(|&idx| FLAGS[idx]).collect();
+            if flags.is_empty() {
+                writeln!(dic, "{stem}").unwrap();
+            } else {
+                writeln!(dic, "{stem}/{flags}").unwrap();
+            }
+        }
+
+        let mut expected = BTreeSet::new();
+        for (stem, group_idx) in entries {
+            let groups: Vec<&Group> = group_idx.iter().map(|&idx| &groups[idx]).collect();
+            expand(stem, &groups, &mut expected);
+        }
+
+        Self {
+            afx,
+            dic,
+            expected: expected.into_iter().collect(),
+        }
+    }
+}
+
+/// Reference expansion: the stem, each affix applied alone, and each prefix
+/// combined with each suffix if both groups allow cross products
+fn expand(stem: &str, groups: &[&Group], out: &mut BTreeSet<String>) {
+    out.insert(stem.to_owned());
+
+    for group in groups {
+        out.extend(
+            group
+                .rules
+                .iter()
+                .filter_map(|rule| rule.apply(stem, group.suffix)),
+        );
+    }
+
+    let combinable = |suffix: bool| {
+        groups
+            .iter()
+            .filter(move |group| group.cross_product && group.suffix == suffix)
+            .flat_map(|group| &group.rules)
+    };
+    for sfx_rule in combinable(true) {
+        let Some(suffixed) = sfx_rule.apply(stem, true) else {
+            continue;
+        };
+        out.extend(combinable(false).filter_map(|pfx_rule| pfx_rule.apply(&suffixed, false)));
+    }
+}
@@ -0,0 +1,62 @@
+//! Property tests comparing affix expansion against a reference implementation
+
+use proptest::prelude::*;
+use test_util::strategies::dictionaries;
+
+proptest! {
+    #[test]
+    fn expansion_matches_reference(generated in dictionaries()) {
+        let dict = zspell::builder()
+            .config_str(&generated.afx)
+            .dict_str(&generated.dic)
+            .build()
+            .expect("generated files should be valid");
+
+        let mut actual: Vec<String> = dict
+            .wordlist()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        actual.sort_unstable();
+
+        prop_assert_eq!(
+            actual,
+            generated.expected,
+            "\naffix file:\n{}\ndictionary file:\n{}",
+            generated.afx,
+            generated.dic
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn lazy_expansion_matches_reference(generated in dictionaries()) {
+        let dict = zspell::builder()
+            .config_str(&generated.afx)
+            .dict_str(&generated.dic)
+            .lazy_affixes(true)
+            .build()
+            .expect("generated files should be valid");
+
+        // Words one letter away from an expected word are likely to be reached by
+        // stripping an affix that does not apply
+        let near_misses = generated.expected.iter().flat_map(|word| {
+            let trimmed = [word[1..].to_owned(), word[..word.len() - 1].to_owned()];
+            let extended = ["a", "s", "y"].map(|letter| format!("{word}{letter}"));
+            trimmed.into_iter().chain(extended)
+        });
+
+        for word in generated.expected.iter().cloned().chain(near_misses) {
+            let expected = generated.expected.binary_search(&word).is_ok();
+            prop_assert_eq!(
+                dict.check_word(&word),
+                expected,
+                "checking '{}'\naffix file:\n{}\ndictionary file:\n{}",
+                word,
+                generated.afx,
+                generated.dic
+            );
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Compare check and suggestion results with an installed Hunspell. Set
+//! `HUNSPELL_BIN` to the `hunspell` binary to run this, and download the
+//! dictionaries with `update_dictionaries.py`.
+
+use std::fs;
+use std::path::Path;
+
+use test_util::differential::{words_from_text, workspace_dict, Reference, HUNSPELL_BIN_VAR};
+
+#[test]
+fn hunspell_diff_en_us() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let Some(dict_base) = workspace_dict(root, "en_US") else {
+        eprintln!("skipping Hunspell comparison; en_US dictionary not found");
+        return;
+    };
+    let Some(reference) = Reference::from_env(dict_base) else {
+        eprintln!("skipping Hunspell comparison; {HUNSPELL_BIN_VAR} is not set");
+        return;
+    };
+
+    let text = ["odyssey.txt", "tortoise_hare_misspelled.txt"]
+        .map(|name| fs::read_to_string(Path::new("tests/files").join(name)).unwrap())
+        .join("\n");
+    let words = words_from_text(&text);
+
+    let report = reference.diff(&words).expect("failed to run hunspell");
+    println!("{report}");
+}
@@ -0,0 +1,13 @@
+//! Score zspell against Hunspell's own test suite. Set `HUNSPELL_TESTS` to the
+//! `tests` directory of a Hunspell checkout to run it.
+
+use test_util::hunspell::{Scorecard, HUNSPELL_TESTS_VAR};
+
+#[test]
+fn hunspell_scorecard() {
+    let Some(scorecard) = Scorecard::run_from_env() else {
+        eprintln!("skipping Hunspell suite; {HUNSPELL_TESTS_VAR} is not set");
+        return;
+    };
+    println!("{scorecard}");
+}